@@ -117,6 +117,9 @@ export_scenes!(
     fn image_extend_modes_nearest_neighbor(impls::image_extend_modes(ImageQuality::Low), "image_extend_modes (nearest neighbor)", false)
     fn luminance_mask(luminance_mask)
     fn image_luminance_mask(image_luminance_mask)
+    fn mask_layers(mask_layers)
+    fn filter_layers(filter_layers)
+    fn filter_layer_chains(filter_layer_chains)
 );
 
 /// Implementations for the test scenes.
@@ -2347,4 +2350,222 @@ mod impls {
         scene.pop_layer();
         scene.pop_layer();
     }
+
+    pub(super) fn mask_layers(scene: &mut Scene, params: &mut SceneParams<'_>) {
+        params.resolution = Some((400., 200.).into());
+        let bounds = Rect::new(0., 0., 200., 200.);
+        let fill_content = |scene: &mut Scene, transform: Affine| {
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                palette::css::ROYAL_BLUE,
+                None,
+                &bounds,
+            );
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                palette::css::ORANGE,
+                None,
+                &Rect::new(40., 40., 160., 160.),
+            );
+        };
+
+        // Left: alpha mask built from a scene containing a translucent ring and an opaque star.
+        let mut mask = Scene::new();
+        mask.stroke(
+            &Stroke::new(30.),
+            Affine::IDENTITY,
+            Color::from_rgba8(0, 0, 0, 128),
+            None,
+            &Circle::new((100., 100.), 70.),
+        );
+        mask.fill(
+            Fill::NonZero,
+            Affine::IDENTITY,
+            Color::BLACK,
+            None,
+            &star_path(),
+        );
+        scene.push_mask_layer(
+            Fill::NonZero,
+            MaskType::Alpha,
+            &mask,
+            Affine::IDENTITY,
+            1.0,
+            Affine::IDENTITY,
+            &bounds,
+        );
+        fill_content(scene, Affine::IDENTITY);
+        scene.pop_layer();
+
+        // Right: luminance mask built from a gradient image.
+        let mut blob: Vec<u8> = Vec::new();
+        for _ in 0..4 {
+            for x in 0..4_u8 {
+                let v = x * 85;
+                blob.extend([v, v, v, 255]);
+            }
+        }
+        let gradient_image = ImageData {
+            data: Blob::new(Arc::new(blob)),
+            format: ImageFormat::Rgba8,
+            alpha_type: ImageAlphaType::Alpha,
+            width: 4,
+            height: 4,
+        };
+        let right = Affine::translate((200., 0.));
+        scene.push_image_mask_layer(
+            Fill::NonZero,
+            MaskType::Luminance,
+            &ImageBrush::new(gradient_image).with_quality(ImageQuality::Low),
+            right * Affine::scale(50.),
+            1.0,
+            right,
+            &bounds,
+        );
+        fill_content(scene, right);
+        scene.pop_layer();
+    }
+
+    pub(super) fn filter_layers(scene: &mut Scene, params: &mut SceneParams<'_>) {
+        use vello::filter_effects::{EdgeMode, Filter, FilterPrimitive};
+
+        params.resolution = Some((400., 400.).into());
+        params.base_color = Some(Color::WHITE);
+        let bounds = Rect::new(0., 0., 200., 200.);
+        let filters = [
+            // Top left: gaussian blur.
+            FilterPrimitive::GaussianBlur {
+                std_deviation: 6.,
+                edge_mode: EdgeMode::None,
+            },
+            // Top right: drop shadow.
+            FilterPrimitive::DropShadow {
+                dx: 10.,
+                dy: 10.,
+                std_deviation: 4.,
+                color: Color::from_rgba8(0, 0, 0, 160),
+                edge_mode: EdgeMode::None,
+            },
+            // Bottom left: offset.
+            FilterPrimitive::Offset { dx: 20., dy: -20. },
+            // Bottom right: flood, which replaces the content of the layer.
+            FilterPrimitive::Flood {
+                color: palette::css::SEA_GREEN.with_alpha(0.5),
+            },
+        ];
+        for (i, primitive) in filters.into_iter().enumerate() {
+            let transform = Affine::translate((200. * (i % 2) as f64, 200. * (i / 2) as f64));
+            scene.push_filter_layer(
+                Fill::NonZero,
+                Filter::from_primitive(primitive),
+                transform,
+                &bounds.inset(-10.),
+            );
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                palette::css::ORANGE,
+                None,
+                &star_path(),
+            );
+            scene.stroke(
+                &Stroke::new(4.),
+                transform,
+                palette::css::ROYAL_BLUE,
+                None,
+                &star_path(),
+            );
+            scene.pop_layer();
+        }
+    }
+
+    pub(super) fn filter_layer_chains(scene: &mut Scene, params: &mut SceneParams<'_>) {
+        use vello::filter_effects::{EdgeMode, Filter, FilterGraph, FilterPrimitive};
+
+        params.resolution = Some((400., 400.).into());
+        params.base_color = Some(Color::WHITE);
+        let bounds = Rect::new(0., 0., 200., 200.);
+        let flood = FilterPrimitive::Flood {
+            color: palette::css::SEA_GREEN,
+        };
+        let chains = [
+            // Top left: a blur, which is then offset.
+            [
+                FilterPrimitive::GaussianBlur {
+                    std_deviation: 4.,
+                    edge_mode: EdgeMode::None,
+                },
+                FilterPrimitive::Offset { dx: 20., dy: 20. },
+            ],
+            // Top right: an offset, followed by a drop shadow of the offset content.
+            [
+                FilterPrimitive::Offset { dx: -20., dy: 0. },
+                FilterPrimitive::DropShadow {
+                    dx: 0.,
+                    dy: 15.,
+                    std_deviation: 3.,
+                    color: Color::from_rgba8(0, 0, 0, 160),
+                    edge_mode: EdgeMode::None,
+                },
+            ],
+            // Bottom left: a blurred flood, which fades out at the edges of the layer.
+            [
+                flood.clone(),
+                FilterPrimitive::GaussianBlur {
+                    std_deviation: 8.,
+                    edge_mode: EdgeMode::None,
+                },
+            ],
+            // Bottom right: a blurred flood, which keeps its edges due to the edge mode.
+            [
+                flood,
+                FilterPrimitive::GaussianBlur {
+                    std_deviation: 8.,
+                    edge_mode: EdgeMode::Duplicate,
+                },
+            ],
+        ];
+        for (i, chain) in chains.into_iter().enumerate() {
+            let transform = Affine::translate((200. * (i % 2) as f64, 200. * (i / 2) as f64));
+            let mut graph = FilterGraph::new();
+            for primitive in chain {
+                let id = graph.add(primitive, None);
+                graph.set_output(id);
+            }
+            scene.push_filter_layer(
+                Fill::NonZero,
+                Filter {
+                    graph: Arc::new(graph),
+                },
+                transform,
+                &bounds.inset(-40.),
+            );
+            scene.fill(
+                Fill::NonZero,
+                transform,
+                palette::css::ORANGE,
+                None,
+                &star_path(),
+            );
+            scene.pop_layer();
+        }
+    }
+
+    fn star_path() -> BezPath {
+        let mut path = BezPath::new();
+        for i in 0..10 {
+            let angle = PI / 5. * i as f64 - PI / 2.;
+            let radius = if i % 2 == 0 { 60. } else { 25. };
+            let point = Point::new(100. + radius * angle.cos(), 100. + radius * angle.sin());
+            if i == 0 {
+                path.move_to(point);
+            } else {
+                path.line_to(point);
+            }
+        }
+        path.close_path();
+        path
+    }
 }
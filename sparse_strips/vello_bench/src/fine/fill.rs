@@ -8,7 +8,9 @@ use vello_common::encode::EncodedPaint;
 use vello_common::fearless_simd::Simd;
 use vello_common::paint::{NoOpImageResolver, Paint, PremulColor};
 use vello_common::peniko::BlendMode;
-use vello_cpu::fine::{Fine, FineKernel, FineResources, PaintFillAttrs, Span};
+use vello_cpu::fine::{
+    CustomPaintRegistry, Fine, FineKernel, FineResources, PaintFillAttrs, Span,
+};
 use vello_dev_macros::vello_bench;
 
 pub fn fill(c: &mut Criterion) {
@@ -76,6 +78,9 @@ pub(crate) fn fill_single<S: Simd, N: FineKernel<S>>(
                 encoded_paints,
                 filter_paints: &[],
                 image_resolver: &NoOpImageResolver,
+                custom_paints: &CustomPaintRegistry::default(),
+                textures: None,
+                dithering: false,
             },
             None,
         );
@@ -12,7 +12,9 @@ use vello_common::encode::EncodedPaint;
 use vello_common::fearless_simd::Simd;
 use vello_common::paint::{NoOpImageResolver, Paint, PremulColor};
 use vello_common::tile::Tile;
-use vello_cpu::fine::{Fine, FineKernel, FineResources, PaintFillAttrs, Span};
+use vello_cpu::fine::{
+    CustomPaintRegistry, Fine, FineKernel, FineResources, PaintFillAttrs, Span,
+};
 use vello_dev_macros::vello_bench;
 
 pub fn strip(c: &mut Criterion) {
@@ -85,6 +87,9 @@ fn strip_single<S: Simd, N: FineKernel<S>>(
                 encoded_paints,
                 filter_paints: &[],
                 image_resolver: &NoOpImageResolver,
+                custom_paints: &CustomPaintRegistry::default(),
+                textures: None,
+                dithering: false,
             },
             Some(&alphas),
         );
@@ -0,0 +1,9 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use vello_common_fuzz::input::FillInput;
+
+fuzz_target!(|input: FillInput| input.check());
@@ -0,0 +1,397 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Invariants of the flatten → tile → strip pipeline.
+
+use vello_common::fearless_simd::Level;
+use vello_common::flatten::{self, FlattenCtx, Line};
+use vello_common::geometry::RectU16;
+use vello_common::kurbo::{
+    self, Affine, BezPath, PathEl, Point, Shape as _, Stroke, StrokeCtx, StrokeOpts, Vec2,
+};
+use vello_common::peniko::Fill;
+use vello_common::strip::{AntialiasingMode, Strip};
+use vello_common::strip_generator::{StripGenerator, StripStorage};
+use vello_common::tile::Tile;
+
+/// The tolerance used by Vello for flattening curves, in device pixels.
+const FLATTEN_TOLERANCE: f64 = 0.25;
+/// The tolerance used for flattening the reference geometry, in device pixels.
+const REFERENCE_TOLERANCE: f64 = 0.01;
+/// Coordinates beyond this magnitude are only checked for panics and structural
+/// invariants, since the precision of `f32` is too low to compare coverage.
+const MAX_COORDINATE: f64 = 50_000.0;
+/// The maximum number of point-to-line distance computations for the coverage check.
+const MAX_DISTANCE_CHECKS: usize = 20_000_000;
+
+const LEVELS: [Level; 2] = [Level::fallback(), Level::baseline()];
+const MODES: [AntialiasingMode; 3] = [
+    AntialiasingMode::Area,
+    AntialiasingMode::Msaa8,
+    AntialiasingMode::Msaa16,
+];
+
+/// Run a filled path through the pipeline and check all invariants.
+pub fn check_fill(path: &BezPath, transform: Affine, fill_rule: Fill, width: u16, height: u16) {
+    let device = transform * path.clone();
+    let reference = Reference::new(&device, fill_rule, 0.0);
+
+    for level in levels() {
+        for mode in MODES {
+            let mut generator = StripGenerator::new(width, height, level);
+            generator.set_antialiasing_mode(mode);
+            let mut storage = StripStorage::default();
+            generator.generate_filled_path(path, fill_rule, transform, None, &mut storage, None);
+            check_strips(&storage, width, height);
+            if let Some(reference) = &reference {
+                reference.check_coverage(&coverage(&storage, width, height), width, height);
+            }
+        }
+    }
+
+    check_flattening(&device);
+}
+
+/// Run a stroked path through the pipeline and check all invariants.
+pub fn check_stroke(path: &BezPath, stroke: &Stroke, transform: Affine, width: u16, height: u16) {
+    // Vello expands the stroke with a tolerance that is only scaled by the diagonal of the
+    // transform, so the deviation of the outline can be larger for rotations and skews.
+    let [a, b, c, d, _, _] = transform.as_coeffs();
+    let scale = a.abs().max(b.abs()).max(c.abs()).max(d.abs()) * 2.0;
+    let outline_error = FLATTEN_TOLERANCE * scale / a.abs().max(d.abs()).max(1.0);
+    // Expanding the reference outline of huge paths is expensive and pointless, since their
+    // coverage is not checked anyway.
+    let reference = is_bounded(&(transform * path.clone()))
+        .then(|| {
+            let tolerance = stroke_tolerance(transform);
+            kurbo::stroke(path, stroke, &StrokeOpts::default(), tolerance)
+        })
+        .and_then(|outline| Reference::new(&(transform * outline), Fill::NonZero, outline_error));
+
+    for level in levels() {
+        for mode in MODES {
+            let mut generator = StripGenerator::new(width, height, level);
+            generator.set_antialiasing_mode(mode);
+            let mut storage = StripStorage::default();
+            generator.generate_stroked_path(path, stroke, transform, None, &mut storage, None);
+            check_strips(&storage, width, height);
+            if let Some(reference) = &reference {
+                reference.check_coverage(&coverage(&storage, width, height), width, height);
+            }
+        }
+    }
+
+    let mut stroke_ctx = StrokeCtx::default();
+    flatten::expand_stroke(path, stroke, stroke_tolerance(transform), &mut stroke_ctx);
+    check_flattening(&(transform * stroke_ctx.output().clone()));
+}
+
+/// The scalar fallback and the SIMD level of the current machine.
+fn levels() -> impl Iterator<Item = Level> {
+    let detected = Level::try_detect().unwrap_or(Level::baseline());
+    LEVELS.into_iter().chain(Some(detected))
+}
+
+/// The tolerance Vello uses for expanding strokes, see `flatten::stroke`.
+fn stroke_tolerance(transform: Affine) -> f64 {
+    let [a, _, _, d, _, _] = transform.as_coeffs();
+    FLATTEN_TOLERANCE / a.abs().max(d.abs()).max(1.0)
+}
+
+/// Check that the strips are sorted and that their alphas are laid out correctly.
+fn check_strips(storage: &StripStorage, width: u16, height: u16) {
+    let strips = &storage.strips;
+    let alphas = &storage.alphas;
+
+    for strip in strips {
+        assert_eq!(
+            strip.y % Tile::HEIGHT,
+            0,
+            "strip not aligned to a row: {strip:?}"
+        );
+        assert_eq!(
+            strip.alpha_idx() % u32::from(Tile::HEIGHT),
+            0,
+            "alpha index not aligned to a column: {strip:?}"
+        );
+        assert!(
+            strip.alpha_idx() as usize <= alphas.len(),
+            "alpha index out of bounds: {strip:?}, {} alphas",
+            alphas.len()
+        );
+        if !strip.is_sentinel() {
+            assert!(
+                strip.x < width.next_multiple_of(Tile::WIDTH)
+                    && strip.y < height.next_multiple_of(Tile::HEIGHT),
+                "strip outside of the viewport: {strip:?}"
+            );
+        }
+    }
+
+    for pair in strips.windows(2) {
+        let (strip, next) = (pair[0], pair[1]);
+        assert!(
+            next.alpha_idx() >= strip.alpha_idx(),
+            "alpha indices not monotonic: {strip:?}, {next:?}"
+        );
+        assert!(
+            next.strip_y() >= strip.strip_y(),
+            "strips not sorted by row: {strip:?}, {next:?}"
+        );
+        if next.strip_y() == strip.strip_y() && !strip.is_sentinel() {
+            let end = u32::from(strip.x) + u32::from(strip.width_to(&next));
+            assert!(
+                u32::from(next.x) >= end,
+                "overlapping strips in the same row: {strip:?}, {next:?}"
+            );
+        }
+    }
+}
+
+/// Reconstruct the coverage of each pixel in the viewport from the strips.
+fn coverage(storage: &StripStorage, width: u16, height: u16) -> Vec<u8> {
+    let mut coverage = vec![0_u8; usize::from(width) * usize::from(height)];
+    let mut set = |x: u16, y: u16, alpha: u8| {
+        if x < width && y < height {
+            coverage[usize::from(y) * usize::from(width) + usize::from(x)] = alpha;
+        }
+    };
+
+    for pair in storage.strips.windows(2) {
+        let (strip, next): (Strip, Strip) = (pair[0], pair[1]);
+        if strip.is_sentinel() {
+            continue;
+        }
+
+        let strip_width = strip.width_to(&next);
+        let alpha_idx = strip.alpha_idx() as usize;
+        for col in 0..strip_width {
+            for row in 0..Tile::HEIGHT {
+                let idx = alpha_idx + usize::from(col * Tile::HEIGHT + row);
+                set(strip.x + col, strip.y + row, storage.alphas[idx]);
+            }
+        }
+
+        if next.fill_gap() && next.strip_y() == strip.strip_y() {
+            let x0 = strip.x.saturating_add(strip_width);
+            for x in x0..next.x.min(width) {
+                for row in 0..Tile::HEIGHT {
+                    set(x, strip.y + row, 255);
+                }
+            }
+        }
+    }
+
+    coverage
+}
+
+/// The exact geometry of a path in device space, used to check the winding of pixels
+/// that are far enough away from any edge.
+struct Reference {
+    fill_rule: Fill,
+    /// The flattened reference path, with all subpaths closed.
+    lines: Vec<(Point, Point)>,
+    /// The minimum distance of a pixel center to the edges of the reference for its
+    /// coverage to be unambiguous.
+    margin: f64,
+}
+
+impl Reference {
+    fn new(path: &BezPath, fill_rule: Fill, additional_error: f64) -> Option<Self> {
+        if !is_bounded(path) {
+            return None;
+        }
+
+        let lines = flatten_closed(path, REFERENCE_TOLERANCE);
+        // Half the diagonal of a pixel, plus the flattening error of Vello and the reference,
+        // plus some leeway for the limited precision of `f32`.
+        let margin = core::f64::consts::FRAC_1_SQRT_2
+            + FLATTEN_TOLERANCE
+            + REFERENCE_TOLERANCE
+            + additional_error
+            + 0.25;
+        Some(Self {
+            fill_rule,
+            lines,
+            margin,
+        })
+    }
+
+    /// Check that all pixels that are clearly inside are fully covered, and that all pixels
+    /// that are clearly outside are not covered at all.
+    fn check_coverage(&self, coverage: &[u8], width: u16, height: u16) {
+        if self.lines.len() * coverage.len() > MAX_DISTANCE_CHECKS {
+            return;
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let center = Point::new(f64::from(x) + 0.5, f64::from(y) + 0.5);
+                if self.distance(center) <= self.margin {
+                    continue;
+                }
+
+                let winding = self.winding(center);
+                let inside = match self.fill_rule {
+                    Fill::NonZero => winding != 0,
+                    Fill::EvenOdd => winding % 2 != 0,
+                };
+                let alpha = coverage[usize::from(y) * usize::from(width) + usize::from(x)];
+                if inside {
+                    assert!(
+                        alpha >= 253,
+                        "pixel ({x}, {y}) with winding {winding} has coverage {alpha}"
+                    );
+                } else {
+                    assert!(
+                        alpha <= 2,
+                        "pixel ({x}, {y}) with winding {winding} has coverage {alpha}"
+                    );
+                }
+            }
+        }
+    }
+
+    fn winding(&self, point: Point) -> i32 {
+        let mut winding = 0;
+        for (p0, p1) in &self.lines {
+            if (p0.y <= point.y) != (p1.y <= point.y) {
+                let t = (point.y - p0.y) / (p1.y - p0.y);
+                if p0.x + t * (p1.x - p0.x) < point.x {
+                    winding += if p1.y > p0.y { 1 } else { -1 };
+                }
+            }
+        }
+        winding
+    }
+
+    fn distance(&self, point: Point) -> f64 {
+        self.lines
+            .iter()
+            .map(|(p0, p1)| distance_to_segment(point, *p0, *p1))
+            .fold(f64::INFINITY, f64::min)
+    }
+}
+
+/// Check that flattening with each SIMD level approximates the same geometry as kurbo,
+/// by comparing the enclosed area.
+fn check_flattening(device: &BezPath) {
+    if !is_bounded(device) {
+        return;
+    }
+
+    // Move the path into the positive quadrant, so that no geometry is culled.
+    let bbox = device.bounding_box();
+    let shift = Affine::translate(
+        Vec2::new(f64::from(Tile::WIDTH), f64::from(Tile::HEIGHT)) - bbox.origin().to_vec2(),
+    );
+    let center = shift * bbox.center();
+    let cull_bbox = RectU16::new(0, 0, u16::MAX, u16::MAX);
+
+    let reference_lines = flatten_closed(device, REFERENCE_TOLERANCE);
+    let reference_area = signed_area(
+        reference_lines
+            .iter()
+            .map(|(p0, p1)| (shift * *p0 - center, shift * *p1 - center)),
+    );
+    let length: f64 = reference_lines
+        .iter()
+        .map(|(p0, p1)| (*p1 - *p0).hypot())
+        .sum();
+    let allowance = 2.0 * FLATTEN_TOLERANCE * length + 1e-3 * (1.0 + reference_area.abs());
+
+    let mut lines = Vec::new();
+    let mut ctx = FlattenCtx::default();
+    for level in levels() {
+        flatten::fill(level, device, shift, &mut lines, &mut ctx, cull_bbox);
+        let area = signed_area(lines.iter().map(|line: &Line| {
+            let p0 = Point::new(f64::from(line.p0.x), f64::from(line.p0.y));
+            let p1 = Point::new(f64::from(line.p1.x), f64::from(line.p1.y));
+            (p0 - center, p1 - center)
+        }));
+        assert!(
+            (area - reference_area).abs() <= allowance,
+            "flattened area {area} differs from reference area {reference_area} \
+             (allowed difference {allowance}) with {level:?}"
+        );
+    }
+}
+
+/// Whether all coordinates of the path are finite and small enough to be compared.
+fn is_bounded(path: &BezPath) -> bool {
+    // Control points are checked as well, since the bounding box of a curve doesn't
+    // necessarily include them.
+    path.elements().iter().all(|el| {
+        let points: &[Point] = match el {
+            PathEl::MoveTo(p) | PathEl::LineTo(p) => core::slice::from_ref(p),
+            PathEl::QuadTo(p1, p2) => &[*p1, *p2],
+            PathEl::CurveTo(p1, p2, p3) => &[*p1, *p2, *p3],
+            PathEl::ClosePath => &[],
+        };
+        points
+            .iter()
+            .all(|p| p.is_finite() && p.x.abs() <= MAX_COORDINATE && p.y.abs() <= MAX_COORDINATE)
+    })
+}
+
+/// Flatten a path into lines, closing all subpaths like Vello does for fills.
+fn flatten_closed(path: &BezPath, tolerance: f64) -> Vec<(Point, Point)> {
+    // A drawing command after `ClosePath` continues from the start of the closed subpath,
+    // which `kurbo::flatten` doesn't account for, so make it an explicit new subpath.
+    let mut normalized = BezPath::new();
+    let mut subpath_start = Point::ZERO;
+    let mut after_close = false;
+    for el in path.elements() {
+        match el {
+            PathEl::MoveTo(p) => subpath_start = *p,
+            PathEl::ClosePath => {}
+            _ if after_close => normalized.move_to(subpath_start),
+            _ => {}
+        }
+        after_close = *el == PathEl::ClosePath;
+        normalized.push(*el);
+    }
+
+    let mut lines = Vec::new();
+    let mut start = Point::ZERO;
+    let mut last = Point::ZERO;
+    kurbo::flatten(&normalized, tolerance, |el| match el {
+        PathEl::MoveTo(p) => {
+            if last != start {
+                lines.push((last, start));
+            }
+            start = p;
+            last = p;
+        }
+        PathEl::LineTo(p) => {
+            lines.push((last, p));
+            last = p;
+        }
+        PathEl::ClosePath => {
+            if last != start {
+                lines.push((last, start));
+            }
+            last = start;
+        }
+        _ => unreachable!("flattening only produces lines"),
+    });
+    if last != start {
+        lines.push((last, start));
+    }
+    lines
+}
+
+fn signed_area(lines: impl Iterator<Item = (Vec2, Vec2)>) -> f64 {
+    lines.map(|(p0, p1)| p0.cross(p1)).sum::<f64>() / 2.0
+}
+
+fn distance_to_segment(point: Point, p0: Point, p1: Point) -> f64 {
+    let segment = p1 - p0;
+    let length_squared = segment.hypot2();
+    let t = if length_squared > 0.0 {
+        ((point - p0).dot(segment) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (point - (p0 + segment * t)).hypot()
+}
@@ -0,0 +1,246 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Fuzzer inputs and their conversion into kurbo types.
+
+use crate::check::{check_fill, check_stroke};
+use arbitrary::Arbitrary;
+use vello_common::kurbo::{Affine, BezPath, Cap, Join, Point, Shape as _, Stroke};
+use vello_common::peniko::Fill;
+
+/// The maximum number of path commands, to keep the run time of a single input low.
+const MAX_COMMANDS: usize = 64;
+/// The maximum number of dashes a stroke may be split into, to keep the run time and
+/// memory usage of a single input low.
+const MAX_DASHES: f64 = 10_000.0;
+
+/// A coordinate or other scalar.
+///
+/// Most inputs use small values with a fixed precision, for which all invariants are
+/// checked, but arbitrary `f32` values are generated as well to exercise extreme
+/// magnitudes, infinities and NaN.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Scalar {
+    /// A value in the range of ±128, in steps of 1/256.
+    Small(i16),
+    /// An arbitrary value.
+    Raw(f32),
+}
+
+impl Scalar {
+    fn value(self) -> f64 {
+        match self {
+            Self::Small(value) => f64::from(value) / 256.0,
+            Self::Raw(value) => f64::from(value),
+        }
+    }
+}
+
+/// A point.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub struct PointInput(Scalar, Scalar);
+
+impl PointInput {
+    fn point(self) -> Point {
+        Point::new(self.0.value(), self.1.value())
+    }
+}
+
+/// A path command.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum Command {
+    /// Start a new subpath.
+    MoveTo(PointInput),
+    /// Draw a line.
+    LineTo(PointInput),
+    /// Draw a quadratic Bézier curve.
+    QuadTo(PointInput, PointInput),
+    /// Draw a cubic Bézier curve.
+    CurveTo(PointInput, PointInput, PointInput),
+    /// Close the current subpath.
+    ClosePath,
+}
+
+/// A path.
+#[derive(Arbitrary, Debug, Clone)]
+pub struct PathInput(Vec<Command>);
+
+impl PathInput {
+    /// Convert the commands into a path, which always starts with a `MoveTo`.
+    pub fn path(&self) -> BezPath {
+        let mut path = BezPath::new();
+        for (idx, command) in self.0.iter().take(MAX_COMMANDS).enumerate() {
+            if idx == 0 && !matches!(command, Command::MoveTo(_)) {
+                path.move_to(Point::ZERO);
+            }
+            match *command {
+                Command::MoveTo(p) => path.move_to(p.point()),
+                Command::LineTo(p) => path.line_to(p.point()),
+                Command::QuadTo(p1, p2) => path.quad_to(p1.point(), p2.point()),
+                Command::CurveTo(p1, p2, p3) => path.curve_to(p1.point(), p2.point(), p3.point()),
+                Command::ClosePath => path.close_path(),
+            }
+        }
+        path
+    }
+}
+
+/// A transform.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum TransformInput {
+    /// The identity transform.
+    Identity,
+    /// A translation.
+    Translate(Scalar, Scalar),
+    /// A non-uniform scale.
+    Scale(Scalar, Scalar),
+    /// A rotation, in units of 1/65536 of a full turn.
+    Rotate(u16),
+    /// An arbitrary affine transform.
+    Matrix([Scalar; 6]),
+}
+
+impl TransformInput {
+    fn transform(self) -> Affine {
+        match self {
+            Self::Identity => Affine::IDENTITY,
+            Self::Translate(x, y) => Affine::translate((x.value(), y.value())),
+            Self::Scale(x, y) => Affine::scale_non_uniform(x.value(), y.value()),
+            Self::Rotate(angle) => {
+                Affine::rotate(f64::from(angle) / 65536.0 * core::f64::consts::TAU)
+            }
+            Self::Matrix(coeffs) => Affine::new(coeffs.map(Scalar::value)),
+        }
+    }
+}
+
+/// The size of the viewport, which is between 1x1 and 64x64 pixels.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub struct Viewport {
+    width: u8,
+    height: u8,
+}
+
+impl Viewport {
+    fn size(self) -> (u16, u16) {
+        (
+            u16::from(self.width % 64) + 1,
+            u16::from(self.height % 64) + 1,
+        )
+    }
+}
+
+/// The input of the `fill` target.
+#[derive(Arbitrary, Debug, Clone)]
+pub struct FillInput {
+    path: PathInput,
+    transform: TransformInput,
+    even_odd: bool,
+    viewport: Viewport,
+}
+
+impl FillInput {
+    /// Run the input through the pipeline and check all invariants.
+    pub fn check(&self) {
+        let fill_rule = if self.even_odd {
+            Fill::EvenOdd
+        } else {
+            Fill::NonZero
+        };
+        let (width, height) = self.viewport.size();
+        check_fill(
+            &self.path.path(),
+            self.transform.transform(),
+            fill_rule,
+            width,
+            height,
+        );
+    }
+}
+
+/// A line join.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum JoinInput {
+    /// A bevel join.
+    Bevel,
+    /// A miter join.
+    Miter,
+    /// A round join.
+    Round,
+}
+
+/// A line cap.
+#[derive(Arbitrary, Debug, Clone, Copy)]
+pub enum CapInput {
+    /// A butt cap.
+    Butt,
+    /// A square cap.
+    Square,
+    /// A round cap.
+    Round,
+}
+
+/// The input of the `stroke` target.
+#[derive(Arbitrary, Debug, Clone)]
+pub struct StrokeInput {
+    path: PathInput,
+    transform: TransformInput,
+    width: Scalar,
+    join: JoinInput,
+    miter_limit: Scalar,
+    start_cap: CapInput,
+    end_cap: CapInput,
+    dashes: Vec<Scalar>,
+    dash_offset: Scalar,
+    viewport: Viewport,
+}
+
+impl StrokeInput {
+    /// Run the input through the pipeline and check all invariants.
+    pub fn check(&self) {
+        let path = self.path.path();
+        let (width, height) = self.viewport.size();
+        check_stroke(
+            &path,
+            &self.stroke(&path),
+            self.transform.transform(),
+            width,
+            height,
+        );
+    }
+
+    fn stroke(&self, path: &BezPath) -> Stroke {
+        let cap = |cap| match cap {
+            CapInput::Butt => Cap::Butt,
+            CapInput::Square => Cap::Square,
+            CapInput::Round => Cap::Round,
+        };
+        let stroke = Stroke {
+            width: self.width.value(),
+            join: match self.join {
+                JoinInput::Bevel => Join::Bevel,
+                JoinInput::Miter => Join::Miter,
+                JoinInput::Round => Join::Round,
+            },
+            miter_limit: self.miter_limit.value(),
+            start_cap: cap(self.start_cap),
+            end_cap: cap(self.end_cap),
+            ..Stroke::default()
+        };
+
+        // Dashing a long path with a short pattern produces an excessive number of
+        // segments, which would only make the fuzzer run out of time or memory. Negative
+        // dash lengths make `kurbo::dash` loop forever, so those are skipped as well.
+        let dashes = self
+            .dashes
+            .iter()
+            .map(|dash| dash.value())
+            .collect::<Vec<_>>();
+        let period = dashes.iter().sum::<f64>();
+        if dashes.iter().all(|dash| *dash >= 0.0) && path.perimeter(1.0) / period <= MAX_DASHES {
+            stroke.with_dashes(self.dash_offset.value(), dashes)
+        } else {
+            stroke
+        }
+    }
+}
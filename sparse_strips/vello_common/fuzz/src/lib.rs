@@ -0,0 +1,7 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Fuzz targets for the flatten → tile → strip pipeline of `vello_common`.
+
+pub mod check;
+pub mod input;
@@ -2040,6 +2040,8 @@ fn paint_name(paint: &Paint, encoded_paints: &[EncodedPaint]) -> String {
                     EncodedPaint::Image(_) => "Image",
                     EncodedPaint::ExternalTexture(_) => "ExternalTexture",
                     EncodedPaint::BlurredRoundedRect(_) => "BlurredRoundedRect",
+                    EncodedPaint::MeshGradient(_) => "MeshGradient",
+                    EncodedPaint::Custom(_) => "Custom",
                 };
                 format!("{}[{}]", kind, index)
             } else {
@@ -0,0 +1,216 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Recognizing the color space of decoded images from their embedded color profiles.
+//!
+//! Instead of implementing full ICC color management, the color profiles of images are matched
+//! against the RGB color spaces supported by the [`color`](crate::color) crate, which covers
+//! the profiles that are commonly embedded into images (sRGB, Display P3, Adobe RGB, ...).
+
+use crate::color::ColorSpaceTag;
+
+/// The RGB color spaces that color profiles are matched against.
+const CANDIDATES: [ColorSpaceTag; 5] = [
+    ColorSpaceTag::Srgb,
+    ColorSpaceTag::DisplayP3,
+    ColorSpaceTag::A98Rgb,
+    ColorSpaceTag::ProphotoRgb,
+    ColorSpaceTag::Rec2020,
+];
+
+/// The maximum difference between the primaries of a color profile and a color space for
+/// them to be considered equal.
+///
+/// This accounts for the limited precision of ICC profiles and slight differences in how the
+/// primaries have been adapted to the D50 white point.
+const TOLERANCE: f32 = 0.01;
+
+/// Return the color space described by an ICC profile, or `None` if the profile is invalid or
+/// does not correspond to a supported color space.
+///
+/// The color space is determined from the primaries of the profile. The tone response curves
+/// are only used to distinguish linear profiles.
+pub(crate) fn color_space_from_icc(profile: &[u8]) -> Option<ColorSpaceTag> {
+    // Only RGB profiles can be matched against RGB color spaces.
+    if profile.get(16..20)? != b"RGB " {
+        return None;
+    }
+
+    let primaries = [
+        read_xyz(tag(profile, *b"rXYZ")?)?,
+        read_xyz(tag(profile, *b"gXYZ")?)?,
+        read_xyz(tag(profile, *b"bXYZ")?)?,
+    ];
+    let color_space = CANDIDATES.into_iter().find(|candidate| {
+        [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+            .into_iter()
+            .zip(primaries)
+            .all(|(primary, actual)| {
+                let expected = candidate.convert(ColorSpaceTag::XyzD50, primary);
+                expected
+                    .into_iter()
+                    .zip(actual)
+                    .all(|(e, a)| (e - a).abs() < TOLERANCE)
+            })
+    })?;
+
+    let is_linear = [*b"rTRC", *b"gTRC", *b"bTRC"]
+        .into_iter()
+        .all(|signature| tag(profile, signature).is_some_and(is_linear_curve));
+    match (color_space, is_linear) {
+        (ColorSpaceTag::Srgb, true) => Some(ColorSpaceTag::LinearSrgb),
+        // The color crate has no linear variants of the other color spaces.
+        (_, true) => None,
+        (color_space, false) => Some(color_space),
+    }
+}
+
+/// Return the data of the tag with the given signature.
+fn tag(profile: &[u8], signature: [u8; 4]) -> Option<&[u8]> {
+    let count = usize::try_from(read_u32(profile, 128)?).ok()?;
+    // The tag count comes from the profile itself, so don't look past the entries that
+    // actually fit into it.
+    let count = count.min(profile.len().saturating_sub(132) / 12);
+    (0..count).find_map(|i| {
+        let entry = i.checked_mul(12)?.checked_add(132)?;
+        if profile.get(entry..entry + 4)? != signature {
+            return None;
+        }
+        let offset = usize::try_from(read_u32(profile, entry + 4)?).ok()?;
+        let size = usize::try_from(read_u32(profile, entry + 8)?).ok()?;
+        profile.get(offset..offset.checked_add(size)?)
+    })
+}
+
+/// Read the value of an `XYZ ` tag.
+fn read_xyz(data: &[u8]) -> Option<[f32; 3]> {
+    if data.get(0..4)? != b"XYZ " {
+        return None;
+    }
+
+    Some([
+        read_s15_fixed16(data, 8)?,
+        read_s15_fixed16(data, 12)?,
+        read_s15_fixed16(data, 16)?,
+    ])
+}
+
+/// Whether a `curv` or `para` tag describes the identity function.
+fn is_linear_curve(data: &[u8]) -> bool {
+    match data.get(0..4) {
+        Some(b"curv") => match read_u32(data, 8) {
+            Some(0) => true,
+            // A single entry is a gamma value, encoded as `u8Fixed8Number`.
+            Some(1) => data.get(12..14) == Some(&[1, 0]),
+            _ => false,
+        },
+        // Function type 0 only has a gamma value.
+        Some(b"para") => {
+            data.get(8..10) == Some(&[0, 0])
+                && read_s15_fixed16(data, 12).is_some_and(|gamma| gamma == 1.0)
+        }
+        _ => false,
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_s15_fixed16(data: &[u8], offset: usize) -> Option<f32> {
+    #[expect(
+        clippy::cast_possible_wrap,
+        reason = "the value is a signed two's complement number"
+    )]
+    let value = read_u32(data, offset)? as i32;
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "ICC values don't need more than f32 precision"
+    )]
+    Some(value as f32 / 65536.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    /// Build an RGB profile with the primaries of the given color space and the given
+    /// tone response curve.
+    fn profile(color_space: ColorSpaceTag, curve: &[u8]) -> Vec<u8> {
+        let mut tags = Vec::new();
+        for (signature, primary) in [
+            (b"rXYZ", [1.0, 0.0, 0.0]),
+            (b"gXYZ", [0.0, 1.0, 0.0]),
+            (b"bXYZ", [0.0, 0.0, 1.0]),
+        ] {
+            let mut data = b"XYZ \0\0\0\0".to_vec();
+            for value in color_space.convert(ColorSpaceTag::XyzD50, primary) {
+                data.extend(((value * 65536.0).round() as i32).to_be_bytes());
+            }
+            tags.push((*signature, data));
+        }
+        for signature in [b"rTRC", b"gTRC", b"bTRC"] {
+            tags.push((*signature, curve.to_vec()));
+        }
+
+        let mut profile = vec![0; 128];
+        profile[16..20].copy_from_slice(b"RGB ");
+        profile.extend((tags.len() as u32).to_be_bytes());
+        let mut offset = 132 + 12 * tags.len();
+        for (signature, data) in &tags {
+            profile.extend(signature);
+            profile.extend((offset as u32).to_be_bytes());
+            profile.extend((data.len() as u32).to_be_bytes());
+            offset += data.len();
+        }
+        for (_, data) in tags {
+            profile.extend(data);
+        }
+        profile
+    }
+
+    const GAMMA_2_2: &[u8] = b"curv\0\0\0\0\0\0\0\x01\x02\x33";
+    const LINEAR: &[u8] = b"curv\0\0\0\0\0\0\0\0";
+
+    #[test]
+    fn recognize_color_spaces() {
+        for color_space in CANDIDATES {
+            assert_eq!(
+                color_space_from_icc(&profile(color_space, GAMMA_2_2)),
+                Some(color_space)
+            );
+        }
+        assert_eq!(
+            color_space_from_icc(&profile(ColorSpaceTag::Srgb, LINEAR)),
+            Some(ColorSpaceTag::LinearSrgb)
+        );
+    }
+
+    #[test]
+    fn reject_invalid_profiles() {
+        let valid = profile(ColorSpaceTag::DisplayP3, GAMMA_2_2);
+        assert_eq!(color_space_from_icc(&valid[..200]), None);
+        assert_eq!(color_space_from_icc(&[]), None);
+
+        let mut gray = valid;
+        gray[16..20].copy_from_slice(b"GRAY");
+        assert_eq!(color_space_from_icc(&gray), None);
+    }
+
+    #[test]
+    fn bogus_tag_count() {
+        let mut profile = profile(ColorSpaceTag::DisplayP3, GAMMA_2_2);
+        profile[128..132].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(
+            color_space_from_icc(&profile),
+            Some(ColorSpaceTag::DisplayP3)
+        );
+
+        // Only the header and a huge tag count, but no tags.
+        assert_eq!(color_space_from_icc(&profile[..132]), None);
+    }
+}
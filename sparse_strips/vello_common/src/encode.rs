@@ -5,12 +5,16 @@
 
 use crate::TextureId;
 use crate::blurred_rounded_rect::BlurredRoundedRectangle;
-use crate::color::palette::css::BLACK;
+use crate::color::palette::css::{BLACK, WHITE};
 use crate::color::{ColorSpaceTag, HueDirection, Srgb, gradient};
 use crate::geometry::RectU16;
 use crate::kurbo::{Affine, Point, Vec2};
 use crate::math::{FloatExt, compute_erf7};
-use crate::paint::{Image, ImageSource, IndexedPaint, Paint, PremulColor, Tint};
+use crate::mesh_gradient::{EncodedMeshGradient, MeshGradient};
+use crate::paint::{
+    CustomPaintId, ExternalTexture, Image, ImageSource, IndexedPaint, Paint, PremulColor, Tint,
+    TintMode,
+};
 use crate::peniko::{ColorStop, ColorStops, Extend, Gradient, GradientKind, ImageQuality};
 use alloc::borrow::Cow;
 use alloc::fmt::Debug;
@@ -535,6 +539,71 @@ impl EncodeExt for Image {
     }
 }
 
+impl private::Sealed for ExternalTexture {}
+
+impl EncodeExt for ExternalTexture {
+    fn encode_into(
+        &self,
+        paints: &mut Vec<EncodedPaint>,
+        transform: Affine,
+        tint: Option<Tint>,
+    ) -> Paint {
+        let idx = paints.len();
+
+        let mut sampler = self.sampler;
+        let mut tint = tint;
+
+        if sampler.alpha != 1.0 {
+            // The tint is premultiplied before it is applied, so scaling its alpha scales the
+            // sampled colors in both tint modes. Without a tint, a white one that is multiplied
+            // with the texture applies just the opacity.
+            let tint = tint.get_or_insert(Tint {
+                color: WHITE,
+                mode: TintMode::Multiply,
+            });
+            tint.color = tint.color.multiply_alpha(sampler.alpha);
+            sampler.alpha = 1.0;
+        }
+
+        let transform = transform.inverse();
+        let (x_advance, y_advance) = x_y_advances(&transform);
+
+        let encoded = EncodedExternalTexture {
+            texture_id: self.texture_id,
+            source_region: self.source_region,
+            sampler,
+            // The contents of the texture are unknown until render time.
+            may_have_transparency: true,
+            transform,
+            x_advance,
+            y_advance,
+            tint,
+        };
+
+        paints.push(EncodedPaint::ExternalTexture(encoded));
+
+        Paint::Indexed(IndexedPaint::new(idx))
+    }
+}
+
+impl private::Sealed for MeshGradient {}
+
+impl EncodeExt for MeshGradient {
+    fn encode_into(
+        &self,
+        paints: &mut Vec<EncodedPaint>,
+        transform: Affine,
+        _tint: Option<Tint>,
+    ) -> Paint {
+        let idx = paints.len();
+        paints.push(EncodedPaint::MeshGradient(EncodedMeshGradient::new(
+            self, transform,
+        )));
+
+        Paint::Indexed(IndexedPaint::new(idx))
+    }
+}
+
 /// An encoded paint.
 #[derive(Debug)]
 pub enum EncodedPaint {
@@ -546,6 +615,10 @@ pub enum EncodedPaint {
     ExternalTexture(EncodedExternalTexture),
     /// A blurred, rounded rectangle.
     BlurredRoundedRect(EncodedBlurredRoundedRectangle),
+    /// An encoded mesh gradient.
+    MeshGradient(EncodedMeshGradient),
+    /// An encoded custom paint.
+    Custom(EncodedCustomPaint),
 }
 
 impl EncodedPaint {
@@ -556,6 +629,10 @@ impl EncodedPaint {
             Self::Image(image) => image.may_have_transparency,
             Self::ExternalTexture(texture) => texture.may_have_transparency,
             Self::BlurredRoundedRect(_) => true,
+            // Pixels outside of the patches are transparent.
+            Self::MeshGradient(_) => true,
+            // Custom paints are only resolved during rasterization.
+            Self::Custom(_) => true,
         }
     }
 }
@@ -601,6 +678,52 @@ pub struct EncodedImage {
     pub tint: Option<Tint>,
 }
 
+impl EncodedImage {
+    /// Returns the level of the image's mip pyramid that should be sampled from.
+    ///
+    /// Level 0 is the image itself, and each following level halves its size. The level is
+    /// chosen such that one step in device space advances between one and two pixels in the
+    /// level, so that bilinear and bicubic filtering don't skip any pixels. Images with
+    /// [`ImageQuality::Low`] always use level 0, as nearest-neighbor sampling is usually
+    /// requested to keep pixels sharp.
+    pub fn mip_level(&self) -> u8 {
+        if self.sampler.quality == ImageQuality::Low {
+            return 0;
+        }
+
+        let mut footprint = self.x_advance.hypot().max(self.y_advance.hypot());
+        let mut level = 0;
+        while footprint >= 2.0 && level < MAX_MIP_LEVEL {
+            footprint /= 2.0;
+            level += 1;
+        }
+
+        level
+    }
+
+    /// Returns a copy of the image that samples from a version of it which was downscaled
+    /// from `base_size` to `level_size`, for example a level of its mip pyramid.
+    pub fn with_level_size(&self, base_size: (u16, u16), level_size: (u16, u16)) -> Self {
+        let scale_x = f64::from(level_size.0) / f64::from(base_size.0);
+        let scale_y = f64::from(level_size.1) / f64::from(base_size.1);
+        let scale = |advance: Vec2| Vec2::new(advance.x * scale_x, advance.y * scale_y);
+
+        Self {
+            source: self.source.clone(),
+            sampler: self.sampler,
+            may_have_transparency: self.may_have_transparency,
+            transform: Affine::scale_non_uniform(scale_x, scale_y) * self.transform,
+            x_advance: scale(self.x_advance),
+            y_advance: scale(self.y_advance),
+            tint: self.tint,
+        }
+    }
+}
+
+/// The highest mip level that is ever selected by [`EncodedImage::mip_level`], which is
+/// enough for images of the maximum size of 65535x65535 pixels.
+pub const MAX_MIP_LEVEL: u8 = 16;
+
 /// An encoded external texture.
 ///
 /// The texture must be bound by the user at render-time in order for us to be able to sample from
@@ -617,6 +740,10 @@ pub struct EncodedExternalTexture {
     pub may_have_transparency: bool,
     /// Inverse destination transform, mapping scene coordinates to local source-rect space.
     pub transform: Affine,
+    /// The advance in texture coordinates for one step in the x direction.
+    pub x_advance: Vec2,
+    /// The advance in texture coordinates for one step in the y direction.
+    pub y_advance: Vec2,
     /// Optional tint applied to the sampled color.
     pub tint: Option<Tint>,
 }
@@ -856,6 +983,15 @@ pub struct GradientRange {
     pub interpolation_alpha_space: InterpolationAlphaSpace,
 }
 
+/// An encoded custom paint.
+#[derive(Debug, Clone, Copy)]
+pub struct EncodedCustomPaint {
+    /// The handle of the custom paint.
+    pub id: CustomPaintId,
+    /// The transform from device coordinates to the coordinate system of the paint.
+    pub transform: Affine,
+}
+
 /// An encoded blurred, rounded rectangle.
 #[derive(Debug)]
 pub struct EncodedBlurredRoundedRectangle {
@@ -1105,7 +1241,9 @@ impl<T: FromF32Color> GradientLut<T> {
                 // values either become greater than 1 or the RGB channels
                 // become greater than the alpha channel. To prevent overflows
                 // in later parts of the pipeline, we need to take the minimum here.
-                result = result.min(1.0).min(alphas);
+                // Similarly, samples close to a hard stop can be extrapolated to
+                // slightly negative values, which would wrap around when converted to u8.
+                result = result.min(1.0).min(alphas).max(0.0);
                 let (im1, im2) = simd.split_f32x16(result);
                 let (r1, r2) = simd.split_f32x8(im1);
                 let (r3, r4) = simd.split_f32x8(im2);
@@ -1193,11 +1331,14 @@ mod private {
 
 #[cfg(test)]
 mod tests {
-    use super::{EncodeExt, Gradient};
+    use super::{EncodeExt, EncodedPaint, Gradient};
+    use crate::TextureId;
     use crate::color::DynamicColor;
     use crate::color::palette::css::{BLACK, BLUE, GREEN};
+    use crate::geometry::RectU16;
     use crate::kurbo::{Affine, Point};
-    use crate::peniko::{ColorStop, ColorStops};
+    use crate::paint::{ExternalTexture, Image, ImageId, ImageSource, IndexedPaint, Paint};
+    use crate::peniko::{ColorStop, ColorStops, Extend, ImageQuality, ImageSampler};
     use alloc::vec;
     use peniko::{LinearGradientPosition, RadialGradientPosition};
     use smallvec::smallvec;
@@ -1393,4 +1534,72 @@ mod tests {
             GREEN.into()
         );
     }
+
+    fn encode_image(transform: Affine, quality: ImageQuality) -> super::EncodedImage {
+        let mut buf = vec![];
+        let image = Image {
+            image: ImageSource::opaque_id(ImageId::new(0)),
+            sampler: ImageSampler::new().with_quality(quality),
+        };
+        image.encode_into(&mut buf, transform, None);
+
+        match buf.pop() {
+            Some(EncodedPaint::Image(image)) => image,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn image_mip_level() {
+        let level = |transform| encode_image(transform, ImageQuality::Medium).mip_level();
+
+        assert_eq!(level(Affine::IDENTITY), 0);
+        assert_eq!(level(Affine::scale(2.0)), 0);
+        assert_eq!(level(Affine::scale(0.6)), 0);
+        assert_eq!(level(Affine::scale(0.5)), 1);
+        assert_eq!(level(Affine::scale(0.3)), 1);
+        assert_eq!(level(Affine::scale(0.125)), 3);
+        // The direction with the strongest minification determines the level.
+        assert_eq!(level(Affine::scale_non_uniform(1.0, 0.25)), 2);
+        assert_eq!(level(Affine::rotate(1.0) * Affine::scale(0.25)), 2);
+        assert_eq!(
+            encode_image(Affine::scale(0.125), ImageQuality::Low).mip_level(),
+            0
+        );
+    }
+
+    #[test]
+    fn image_with_level_size() {
+        let image = encode_image(Affine::scale(0.25), ImageQuality::High);
+        let level = image.with_level_size((64, 32), (16, 8));
+
+        assert_eq!(level.mip_level(), 0);
+        assert_eq!(level.transform * Point::new(4.0, 8.0), Point::new(4.0, 8.0));
+        assert_eq!(level.x_advance, super::Vec2::new(1.0, 0.0));
+        assert_eq!(level.y_advance, super::Vec2::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn external_texture() {
+        let mut buf = vec![];
+        let texture = ExternalTexture::new(TextureId(7), RectU16::new(8, 8, 24, 16))
+            .with_x_extend(Extend::Repeat)
+            .with_quality(ImageQuality::Low);
+        let paint = texture.encode_into(&mut buf, Affine::translate((10.0, 20.0)), None);
+
+        assert_eq!(paint, Paint::Indexed(IndexedPaint::new(0)));
+        let Some(EncodedPaint::ExternalTexture(encoded)) = buf.pop() else {
+            unreachable!()
+        };
+        assert_eq!(encoded.texture_id, TextureId(7));
+        assert_eq!(encoded.sampler.x_extend, Extend::Repeat);
+        assert_eq!(encoded.sampler.y_extend, Extend::Pad);
+        assert!(encoded.may_have_transparency);
+        assert_eq!(
+            encoded.transform * Point::new(10.0, 20.0),
+            Point::new(0.0, 0.0)
+        );
+        assert_eq!(encoded.x_advance, super::Vec2::new(1.0, 0.0));
+        assert_eq!(encoded.y_advance, super::Vec2::new(0.0, 1.0));
+    }
 }
@@ -40,7 +40,18 @@ impl PreparedFilter {
             unimplemented!("Multi-primitive filter graphs are not yet supported");
         }
 
-        match &filter.graph.primitives[0] {
+        Self::from_primitive(&filter.graph.primitives[0], transform).unwrap_or_else(|| {
+            // Other primitives like Blend, ColorMatrix, ComponentTransfer, etc.
+            // are not yet implemented
+            unimplemented!("Other filter primitives not yet implemented")
+        })
+    }
+
+    /// Build a new prepared filter from a single primitive for the given transform.
+    ///
+    /// Returns `None` if the primitive is not supported yet.
+    pub fn from_primitive(primitive: &FilterPrimitive, transform: &Affine) -> Option<Self> {
+        let filter = match primitive {
             FilterPrimitive::Flood { color } => {
                 let flood = Flood::new(*color);
                 Self::Flood(flood)
@@ -73,12 +84,9 @@ impl PreparedFilter {
 
                 Self::Offset(offset)
             }
-            _ => {
-                // Other primitives like Blend, ColorMatrix, ComponentTransfer, etc.
-                // are not yet implemented
-                unimplemented!("Other filter primitives not yet implemented");
-            }
-        }
+            _ => return None,
+        };
+        Some(filter)
     }
 }
 
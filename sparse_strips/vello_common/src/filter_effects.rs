@@ -149,6 +149,11 @@ impl Filter {
 pub struct FilterGraph {
     /// All filter primitives in the graph, stored in insertion order.
     pub primitives: SmallVec<[FilterPrimitive; 1]>,
+    /// The inputs of each primitive, in the same order as `primitives`.
+    ///
+    /// `None` means that the primitive uses the default input, i.e. the
+    /// result of the previous primitive (or `SourceGraphic` for the first one).
+    pub inputs: SmallVec<[Option<FilterInputs>; 1]>,
     /// The final output filter ID whose result is the output of this graph.
     pub output: FilterId,
     /// Next available filter ID (monotonically increasing counter).
@@ -170,6 +175,7 @@ impl FilterGraph {
     pub fn new() -> Self {
         Self {
             primitives: SmallVec::new(),
+            inputs: SmallVec::new(),
             output: FilterId(0),
             next_id: 0,
             filter_expansion: Rect::ZERO,
@@ -181,7 +187,7 @@ impl FilterGraph {
     ///
     /// Returns a `FilterId` that can be referenced by other primitives.
     /// Automatically updates the accumulated source and filter expansion requirements.
-    pub fn add(&mut self, primitive: FilterPrimitive, _inputs: Option<FilterInputs>) -> FilterId {
+    pub fn add(&mut self, primitive: FilterPrimitive, inputs: Option<FilterInputs>) -> FilterId {
         let id = FilterId(self.next_id);
         self.next_id += 1;
 
@@ -189,10 +195,18 @@ impl FilterGraph {
         self.source_expansion = self.source_expansion.union(primitive.source_expansion());
 
         self.primitives.push(primitive);
+        self.inputs.push(inputs);
 
         id
     }
 
+    /// The primitive with the given ID, together with its inputs.
+    pub fn get(&self, id: FilterId) -> Option<(&FilterPrimitive, Option<&FilterInputs>)> {
+        let index = usize::from(id.0);
+        let primitive = self.primitives.get(index)?;
+        Some((primitive, self.inputs.get(index)?.as_ref()))
+    }
+
     /// Set the output filter for the graph.
     pub fn set_output(&mut self, output: FilterId) {
         self.output = output;
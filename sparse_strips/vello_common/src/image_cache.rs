@@ -50,6 +50,9 @@ pub struct ImageCache {
     slots: Vec<Option<ImageResource>>,
     /// Stack of free indices.
     free_idxs: Vec<usize>,
+    /// The mip levels that were allocated for each image, starting with level 1 and indexed
+    /// like `slots`.
+    mip_levels: Vec<Vec<ImageId>>,
 }
 
 impl core::fmt::Debug for ImageCache {
@@ -59,6 +62,7 @@ impl core::fmt::Debug for ImageCache {
         f.debug_struct("ImageCache")
             .field("slots", &self.slots)
             .field("free_idxs", &self.free_idxs)
+            .field("mip_levels", &self.mip_levels)
             .field("atlas_count", &self.atlas_manager.atlas_count())
             .field("atlas_stats", &atlas_stats)
             .finish()
@@ -72,6 +76,7 @@ impl ImageCache {
             atlas_manager: MultiAtlasManager::new(config),
             slots: Vec::new(),
             free_idxs: Vec::new(),
+            mip_levels: Vec::new(),
         }
     }
 
@@ -143,11 +148,68 @@ impl ImageCache {
             atlas_alloc_id: atlas_alloc.allocation.id,
         };
         self.slots[slot_idx] = Some(image_resource);
+        if let Some(mip_levels) = self.mip_levels.get_mut(slot_idx) {
+            mip_levels.clear();
+        }
 
         Ok(image_id)
     }
 
+    /// Allocate the next mip level of an image, which is a downscaled version of it with the
+    /// given size.
+    ///
+    /// The level is allocated like a normal image, so its resource can be accessed through
+    /// [`ImageCache::get`] using the returned Id as well.
+    pub fn allocate_mip_level(
+        &mut self,
+        id: ImageId,
+        width: u32,
+        height: u32,
+        padding: u16,
+    ) -> Result<ImageId, AtlasError> {
+        let level_id = self.allocate(width, height, padding)?;
+        let index = id.as_u32() as usize;
+        if self.mip_levels.len() <= index {
+            self.mip_levels.resize_with(index + 1, Vec::new);
+        }
+        self.mip_levels[index].push(level_id);
+
+        Ok(level_id)
+    }
+
+    /// Get the Ids of the mip levels that have been allocated for an image so far, starting
+    /// with level 1.
+    pub fn mip_levels(&self, id: ImageId) -> &[ImageId] {
+        self.mip_levels
+            .get(id.as_u32() as usize)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Get the image resource of the given mip level of an image.
+    ///
+    /// If the level hasn't been allocated, the highest allocated level below it is returned
+    /// instead, which is the image itself in case there are no mip levels.
+    pub fn get_mip_level(&self, id: ImageId, level: u8) -> Option<&ImageResource> {
+        let mip_levels = self.mip_levels(id);
+        match usize::from(level).min(mip_levels.len()) {
+            0 => self.get(id),
+            level => self.get(mip_levels[level - 1]),
+        }
+    }
+
+    /// Remove the mip levels of an image from its bookkeeping and return their Ids, so that
+    /// they can be deallocated.
+    pub fn take_mip_levels(&mut self, id: ImageId) -> Vec<ImageId> {
+        self.mip_levels
+            .get_mut(id.as_u32() as usize)
+            .map(core::mem::take)
+            .unwrap_or_default()
+    }
+
     /// Deallocate an image from the cache, returning the image resource if it existed.
+    ///
+    /// This doesn't deallocate the mip levels of the image, use [`ImageCache::take_mip_levels`]
+    /// to deallocate them as well.
     pub fn deallocate(&mut self, id: ImageId) -> Option<ImageResource> {
         let index = id.as_u32() as usize;
         if let Some(image_resource) = self.slots.get_mut(index).and_then(Option::take) {
@@ -339,4 +401,34 @@ mod tests {
         assert_eq!(new_id2.as_u32(), 1);
         assert_ne!(new_id1.as_u32(), new_id2.as_u32());
     }
+
+    #[test]
+    fn test_mip_levels() {
+        let mut cache = ImageCache::new_with_config(AtlasConfig {
+            atlas_size: (ATLAS_SIZE, ATLAS_SIZE),
+            ..Default::default()
+        });
+
+        let id = cache.allocate(100, 60, 1).unwrap();
+        assert!(cache.mip_levels(id).is_empty());
+        assert_eq!(cache.get_mip_level(id, 3).unwrap().width, 100);
+
+        let level1 = cache.allocate_mip_level(id, 50, 30, 1).unwrap();
+        let level2 = cache.allocate_mip_level(id, 25, 15, 1).unwrap();
+        assert_eq!(cache.mip_levels(id), [level1, level2]);
+        assert_eq!(cache.get_mip_level(id, 0).unwrap().width, 100);
+        assert_eq!(cache.get_mip_level(id, 1).unwrap().width, 50);
+        // Levels that haven't been allocated fall back to the highest available one.
+        assert_eq!(cache.get_mip_level(id, 5).unwrap().width, 25);
+
+        for level in cache.take_mip_levels(id) {
+            cache.deallocate(level);
+        }
+        cache.deallocate(id);
+        assert!(cache.mip_levels(id).is_empty());
+
+        // A new image reusing the slot doesn't inherit any mip levels.
+        let new_id = cache.allocate(10, 10, 0).unwrap();
+        assert!(cache.mip_levels(new_id).is_empty());
+    }
 }
@@ -23,6 +23,10 @@
 //! - `png` (enabled by default): Allow loading [`Pixmap`][crate::pixmap::Pixmap]s from PNG images.
 //!   Also required for rendering glyphs with an embedded PNG.
 //!   Implies `std`.
+//! - `jpeg`: Allow loading [`Pixmap`][crate::pixmap::Pixmap]s from JPEG images.
+//!   Implies `std`.
+//! - `webp`: Allow loading [`Pixmap`][crate::pixmap::Pixmap]s from WebP images.
+//!   Implies `std`.
 //!
 //! At least one of `std` and `libm` is required; `std` overrides `libm`.
 //!
@@ -66,6 +70,8 @@ extern crate std;
 pub mod blurred_rounded_rect;
 pub mod clip;
 pub mod coarse;
+#[cfg(any(feature = "png", feature = "jpeg", feature = "webp"))]
+pub(crate) mod color_profile;
 pub mod encode;
 pub mod filter;
 pub mod filter_effects;
@@ -75,8 +81,10 @@ pub mod geometry;
 pub mod image_cache;
 pub mod mask;
 pub mod math;
+pub mod mesh_gradient;
 pub mod multi_atlas;
 pub mod paint;
+pub mod pattern;
 #[doc(hidden)]
 #[cfg(feature = "pico_svg")]
 pub mod pico_svg;
@@ -84,12 +92,15 @@ pub mod pixmap;
 #[doc(hidden)]
 #[cfg(feature = "probe")]
 pub mod probe;
+pub mod raw_image;
 pub mod rect;
 pub mod render_graph;
 pub mod render_state;
 pub mod simd;
 pub mod strip;
 pub mod strip_generator;
+#[cfg(feature = "pico_svg")]
+pub mod svg_filter;
 pub mod tile;
 pub mod util;
 
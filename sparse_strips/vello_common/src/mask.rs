@@ -99,6 +99,12 @@ impl Mask {
         self.0.height
     }
 
+    /// Return the raw mask values, stored in row-major order.
+    #[inline(always)]
+    pub fn data(&self) -> &[u8] {
+        &self.0.data
+    }
+
     /// Sample the value at a specific location.
     ///
     /// This function might panic or yield a wrong result if the location
@@ -0,0 +1,502 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Mesh gradients made of Coons and tensor-product patches.
+//!
+//! A mesh gradient consists of a number of patches, each of which is a surface bounded by four
+//! cubic Bézier curves with a color at each of its corners. The colors are interpolated across
+//! the surface, which makes it possible to represent the shading types 4 to 7 of PDF as well
+//! as the mesh gradients of Illustrator and Inkscape.
+//!
+//! For rendering, the patches are tessellated into triangles in device space, whose colors are
+//! interpolated linearly. To quickly find the triangles that cover a pixel, they are sorted
+//! into the cells of a grid.
+
+use crate::color::{AlphaColor, PremulColor, Srgb};
+use crate::kurbo::{Affine, Point, Rect};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use crate::kurbo::common::FloatFuncs as _;
+
+/// The maximum length, in device pixels, of the control polygon of a sub-patch produced by
+/// tessellation.
+const MAX_SUB_PATCH_LENGTH: f64 = 4.0;
+/// The maximum number of subdivisions of a patch in each direction.
+const MAX_SUBDIVISIONS: usize = 64;
+/// The size of a cell in the grid the triangles are sorted into, in device pixels.
+const CELL_SIZE: f64 = 16.0;
+/// The maximum number of cells of the grid in each direction.
+const MAX_CELLS: usize = 256;
+
+/// A mesh gradient, consisting of a list of patches.
+///
+/// Patches are painted in order, so later patches cover earlier ones where they overlap.
+/// Anything outside of the patches is transparent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshGradient {
+    patches: Arc<[MeshPatch]>,
+}
+
+impl MeshGradient {
+    /// Create a new mesh gradient from a list of patches.
+    pub fn new(patches: impl Into<Arc<[MeshPatch]>>) -> Self {
+        Self {
+            patches: patches.into(),
+        }
+    }
+
+    /// Return the patches of the mesh gradient.
+    pub fn patches(&self) -> &[MeshPatch] {
+        &self.patches
+    }
+
+    /// Return the average of the corner colors of all patches.
+    ///
+    /// This can be used as a fallback by renderers that don't support mesh gradients.
+    pub fn average_color(&self) -> AlphaColor<Srgb> {
+        let mut sum = [0.0; 4];
+        let mut count = 0.0;
+        for color in self.patches.iter().flat_map(|patch| patch.colors) {
+            let components = color.premultiply().components;
+            for (sum, component) in sum.iter_mut().zip(components) {
+                *sum += component;
+            }
+            count += 1.0;
+        }
+
+        if count == 0.0 {
+            return AlphaColor::TRANSPARENT;
+        }
+
+        PremulColor::<Srgb>::new(sum.map(|sum| sum / count)).un_premultiply()
+    }
+}
+
+/// A tensor-product patch of a mesh gradient.
+///
+/// The patch is defined by a grid of 4x4 control points `p[i][j]`, where `i` is the index in
+/// the `u` direction and `j` the index in the `v` direction of the parametric surface, as
+/// well as the colors at its four corners. The colors are interpolated bilinearly in the
+/// parameter space, using premultiplied sRGB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshPatch {
+    /// The control points of the patch, indexed by `[i][j]`.
+    pub points: [[Point; 4]; 4],
+    /// The colors at the corners `p[0][0]`, `p[0][3]`, `p[3][3]` and `p[3][0]`.
+    pub colors: [AlphaColor<Srgb>; 4],
+}
+
+impl MeshPatch {
+    /// Create a tensor-product patch from its control points, indexed by `[i][j]`, and its
+    /// corner colors.
+    ///
+    /// The colors belong to the corners `p[0][0]`, `p[0][3]`, `p[3][3]` and `p[3][0]`, in that
+    /// order.
+    pub fn tensor(points: [[Point; 4]; 4], colors: [AlphaColor<Srgb>; 4]) -> Self {
+        Self { points, colors }
+    }
+
+    /// Create a Coons patch from the 12 control points of its boundary and its corner colors.
+    ///
+    /// The boundary consists of four cubic Bézier curves, with the points listed in the order
+    /// in which they appear along the boundary, starting at a corner. This means that the
+    /// corners are at the indices 0, 3, 6 and 9, and the colors belong to these corners in the
+    /// same order. This is the same layout as used by the shading type 6 of PDF.
+    ///
+    /// The interior control points are derived from the boundary, as described in section
+    /// 8.7.4.5.8 of the PDF specification.
+    pub fn coons(boundary: [Point; 12], colors: [AlphaColor<Srgb>; 4]) -> Self {
+        let b = boundary;
+        let mut p = [[Point::ZERO; 4]; 4];
+        // The left edge, going up.
+        p[0] = [b[0], b[1], b[2], b[3]];
+        // The top and bottom edges, without their corners.
+        p[1][3] = b[4];
+        p[2][3] = b[5];
+        p[2][0] = b[10];
+        p[1][0] = b[11];
+        // The right edge, going down.
+        p[3] = [b[9], b[8], b[7], b[6]];
+
+        let v = p.map(|row| row.map(Point::to_vec2));
+        let interior = |corner, adjacent_1, adjacent_2, far_1, far_2, next_1, next_2, opposite| {
+            ((-4.0 * corner) + 6.0 * (adjacent_1 + adjacent_2) - 2.0 * (far_1 + far_2)
+                + 3.0 * (next_1 + next_2)
+                - opposite)
+                / 9.0
+        };
+        p[1][1] = interior(
+            v[0][0], v[0][1], v[1][0], v[0][3], v[3][0], v[3][1], v[1][3], v[3][3],
+        )
+        .to_point();
+        p[1][2] = interior(
+            v[0][3], v[0][2], v[1][3], v[0][0], v[3][3], v[3][2], v[1][0], v[3][0],
+        )
+        .to_point();
+        p[2][2] = interior(
+            v[3][3], v[3][2], v[2][3], v[3][0], v[0][3], v[2][0], v[0][2], v[0][0],
+        )
+        .to_point();
+        p[2][1] = interior(
+            v[3][0], v[3][1], v[2][0], v[3][3], v[0][0], v[0][1], v[2][3], v[0][3],
+        )
+        .to_point();
+
+        Self { points: p, colors }
+    }
+
+    /// Evaluate the position of the patch at the parameters `u` and `v`, which are in the
+    /// range from 0 to 1.
+    pub fn eval(&self, u: f64, v: f64) -> Point {
+        let bu = bernstein(u);
+        let bv = bernstein(v);
+        let mut result = Point::ZERO.to_vec2();
+        for (i, row) in self.points.iter().enumerate() {
+            for (j, point) in row.iter().enumerate() {
+                result += point.to_vec2() * (bu[i] * bv[j]);
+            }
+        }
+
+        result.to_point()
+    }
+
+    /// Evaluate the premultiplied color of the patch at the parameters `u` and `v`.
+    fn premul_color(&self, u: f32, v: f32) -> [f32; 4] {
+        let [c00, c03, c33, c30] = self.colors.map(|c| c.premultiply().components);
+        core::array::from_fn(|idx| {
+            let left = c00[idx] + (c03[idx] - c00[idx]) * v;
+            let right = c30[idx] + (c33[idx] - c30[idx]) * v;
+            left + (right - left) * u
+        })
+    }
+
+    fn transform(&self, transform: Affine) -> Self {
+        Self {
+            points: self.points.map(|row| row.map(|p| transform * p)),
+            colors: self.colors,
+        }
+    }
+
+    /// The number of subdivisions in each direction that are needed so that the sub-patches
+    /// are small enough to be approximated by two triangles.
+    fn subdivisions(&self) -> usize {
+        let mut max_length: f64 = 0.0;
+        for idx in 0..4 {
+            let mut length_u = 0.0;
+            let mut length_v = 0.0;
+            for step in 0..3 {
+                length_u += (self.points[step + 1][idx] - self.points[step][idx]).hypot();
+                length_v += (self.points[idx][step + 1] - self.points[idx][step]).hypot();
+            }
+            max_length = max_length.max(length_u).max(length_v);
+        }
+
+        if !max_length.is_finite() {
+            return 0;
+        }
+
+        ((max_length / MAX_SUB_PATCH_LENGTH).ceil() as usize).clamp(1, MAX_SUBDIVISIONS)
+    }
+}
+
+fn bernstein(t: f64) -> [f64; 4] {
+    let mt = 1.0 - t;
+    [mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t]
+}
+
+/// A triangle of a tessellated mesh gradient in device space.
+///
+/// Instead of the vertices, it stores the edge functions for testing whether a point is
+/// inside of the triangle and the coefficients of the color as an affine function of the
+/// position.
+#[derive(Debug, Clone, Copy)]
+pub struct EncodedMeshTriangle {
+    /// The coefficients `[a, b, c]` of the three edge functions `a * x + b * y + c`, which are
+    /// non-negative for points inside of the triangle.
+    pub edges: [[f32; 3]; 3],
+    /// The coefficients `[a, b, c]` of each premultiplied color component, which is
+    /// `a * x + b * y + c` at the point `(x, y)`.
+    pub color: [[f32; 3]; 4],
+}
+
+impl EncodedMeshTriangle {
+    fn new(points: [Point; 3], colors: [[f32; 4]; 3]) -> Option<Self> {
+        let [p0, p1, p2] = points;
+        let area = (p1 - p0).cross(p2 - p0);
+        if !area.is_finite() || area.abs() < 1e-9 {
+            return None;
+        }
+
+        // Edge function of the edge from `a` to `b`, which is positive on the side of the
+        // triangle.
+        let edge = |a: Point, b: Point| {
+            let a_coeff = (a.y - b.y) / area;
+            let b_coeff = (b.x - a.x) / area;
+            let c_coeff = (a.x * b.y - a.y * b.x) / area;
+            [a_coeff, b_coeff, c_coeff]
+        };
+        // The barycentric coordinates of a point with respect to each vertex.
+        let weights = [edge(p1, p2), edge(p2, p0), edge(p0, p1)];
+
+        let color = core::array::from_fn(|component| {
+            core::array::from_fn(|coeff| {
+                (0..3)
+                    .map(|vertex| weights[vertex][coeff] * f64::from(colors[vertex][component]))
+                    .sum::<f64>() as f32
+            })
+        });
+
+        Some(Self {
+            edges: weights.map(|weight| weight.map(|coeff| coeff as f32)),
+            color,
+        })
+    }
+
+    /// Return the premultiplied color at the given point if it is inside of the triangle.
+    #[inline(always)]
+    pub fn sample(&self, x: f32, y: f32) -> Option<[f32; 4]> {
+        // Allow for a small tolerance, so that there are no gaps on shared edges.
+        const TOLERANCE: f32 = -1e-4;
+
+        for [a, b, c] in self.edges {
+            if a * x + b * y + c < TOLERANCE {
+                return None;
+            }
+        }
+
+        let [r, g, b, alpha] = self.color.map(|[a, b, c]| a * x + b * y + c);
+        let alpha = alpha.clamp(0.0, 1.0);
+        Some([
+            r.clamp(0.0, alpha),
+            g.clamp(0.0, alpha),
+            b.clamp(0.0, alpha),
+            alpha,
+        ])
+    }
+}
+
+/// An encoded mesh gradient, tessellated into triangles in device space.
+#[derive(Debug)]
+pub struct EncodedMeshGradient {
+    /// The triangles, in painting order.
+    pub triangles: Vec<EncodedMeshTriangle>,
+    /// The bounds of the grid, in device space.
+    pub grid_bounds: Rect,
+    /// The number of cells of the grid in each direction.
+    pub grid_size: (u16, u16),
+    /// For each cell of the grid (in row-major order), the start index of its triangles in
+    /// `cell_triangles`, followed by the end index for the last cell.
+    pub cell_offsets: Vec<u32>,
+    /// The indices of the triangles that overlap each cell, in painting order.
+    pub cell_triangles: Vec<u32>,
+}
+
+impl EncodedMeshGradient {
+    /// Tessellate a mesh gradient, using the given transform from its coordinates to device
+    /// space.
+    pub(crate) fn new(mesh: &MeshGradient, transform: Affine) -> Self {
+        let mut triangles = Vec::new();
+        let mut bounds = Vec::new();
+
+        for patch in mesh.patches.iter() {
+            let patch = patch.transform(transform);
+            let subdivisions = patch.subdivisions();
+            if subdivisions == 0 {
+                continue;
+            }
+
+            let n = subdivisions as f64;
+            let vertex = |i: usize, j: usize| {
+                let (u, v) = (i as f64 / n, j as f64 / n);
+                (patch.eval(u, v), patch.premul_color(u as f32, v as f32))
+            };
+            let row_len = subdivisions + 1;
+            let vertices = (0..row_len)
+                .flat_map(|j| (0..row_len).map(move |i| (i, j)))
+                .map(|(i, j)| vertex(i, j))
+                .collect::<Vec<_>>();
+
+            // Iterating over `v` in the outer loop means that parts of the patch with higher
+            // `v` (and then higher `u`) win in case the patch folds over itself, as required
+            // by PDF.
+            for j in 0..subdivisions {
+                for i in 0..subdivisions {
+                    let [v00, v10, v01, v11] = [
+                        j * row_len + i,
+                        j * row_len + i + 1,
+                        (j + 1) * row_len + i,
+                        (j + 1) * row_len + i + 1,
+                    ]
+                    .map(|idx| vertices[idx]);
+                    for [a, b, c] in [[v00, v10, v11], [v00, v11, v01]] {
+                        if let Some(triangle) =
+                            EncodedMeshTriangle::new([a.0, b.0, c.0], [a.1, b.1, c.1])
+                        {
+                            triangles.push(triangle);
+                            bounds
+                                .push(Rect::from_points(a.0, b.0).union_pt(c.0).inflate(0.5, 0.5));
+                        }
+                    }
+                }
+            }
+        }
+
+        let grid_bounds = bounds
+            .iter()
+            .copied()
+            .reduce(|a, b| a.union(b))
+            .unwrap_or(Rect::ZERO);
+        let cells = |length: f64| ((length / CELL_SIZE).ceil() as usize).clamp(1, MAX_CELLS) as u16;
+        let grid_size = (cells(grid_bounds.width()), cells(grid_bounds.height()));
+        let cell_width = grid_bounds.width() / f64::from(grid_size.0);
+        let cell_height = grid_bounds.height() / f64::from(grid_size.1);
+        let cell_range = |min: f64, max: f64, origin: f64, size: f64, count: u16| {
+            let to_cell = |value: f64| {
+                (((value - origin) / size).floor().max(0.0) as usize).min(usize::from(count) - 1)
+            };
+            to_cell(min)..=to_cell(max)
+        };
+
+        let num_cells = usize::from(grid_size.0) * usize::from(grid_size.1);
+        let mut cells_of_triangles = vec![Vec::new(); num_cells];
+        for (idx, bounds) in bounds.iter().enumerate() {
+            let columns = cell_range(
+                bounds.x0,
+                bounds.x1,
+                grid_bounds.x0,
+                cell_width,
+                grid_size.0,
+            );
+            let rows = cell_range(
+                bounds.y0,
+                bounds.y1,
+                grid_bounds.y0,
+                cell_height,
+                grid_size.1,
+            );
+            for row in rows {
+                for column in columns.clone() {
+                    cells_of_triangles[row * usize::from(grid_size.0) + column].push(idx as u32);
+                }
+            }
+        }
+
+        let mut cell_offsets = Vec::with_capacity(num_cells + 1);
+        let mut cell_triangles = Vec::new();
+        for cell in cells_of_triangles {
+            cell_offsets.push(cell_triangles.len() as u32);
+            cell_triangles.extend(cell);
+        }
+        cell_offsets.push(cell_triangles.len() as u32);
+
+        Self {
+            triangles,
+            grid_bounds,
+            grid_size,
+            cell_offsets,
+            cell_triangles,
+        }
+    }
+
+    /// Return the premultiplied color of the mesh gradient at the given point in device
+    /// space, which is transparent outside of the patches.
+    #[inline]
+    pub fn sample(&self, x: f32, y: f32) -> [f32; 4] {
+        const TRANSPARENT: [f32; 4] = [0.0; 4];
+
+        let bounds = self.grid_bounds;
+        let (x_64, y_64) = (f64::from(x), f64::from(y));
+        if x_64 < bounds.x0 || x_64 >= bounds.x1 || y_64 < bounds.y0 || y_64 >= bounds.y1 {
+            return TRANSPARENT;
+        }
+
+        let column = ((x_64 - bounds.x0) / bounds.width() * f64::from(self.grid_size.0)) as usize;
+        let row = ((y_64 - bounds.y0) / bounds.height() * f64::from(self.grid_size.1)) as usize;
+        let cell = row.min(usize::from(self.grid_size.1) - 1) * usize::from(self.grid_size.0)
+            + column.min(usize::from(self.grid_size.0) - 1);
+        let start = self.cell_offsets[cell] as usize;
+        let end = self.cell_offsets[cell + 1] as usize;
+
+        // Later triangles are painted on top, so the last one containing the point wins.
+        self.cell_triangles[start..end]
+            .iter()
+            .rev()
+            .find_map(|idx| self.triangles[*idx as usize].sample(x, y))
+            .unwrap_or(TRANSPARENT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncodedMeshGradient, MeshGradient, MeshPatch};
+    use crate::color::palette::css::{BLACK, BLUE, LIME, RED, WHITE};
+    use crate::kurbo::{Affine, Point};
+    use alloc::vec;
+
+    fn square_patch() -> MeshPatch {
+        MeshPatch::coons(
+            [
+                (0.0, 0.0),
+                (0.0, 10.0),
+                (0.0, 20.0),
+                (0.0, 30.0),
+                (10.0, 30.0),
+                (20.0, 30.0),
+                (30.0, 30.0),
+                (30.0, 20.0),
+                (30.0, 10.0),
+                (30.0, 0.0),
+                (20.0, 0.0),
+                (10.0, 0.0),
+            ]
+            .map(Point::from),
+            [RED, LIME, BLUE, WHITE],
+        )
+    }
+
+    #[test]
+    fn coons_patch_of_square_is_bilinear() {
+        let patch = square_patch();
+
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = Point::new(i as f64 * 10.0, j as f64 * 10.0);
+                assert!((patch.points[i][j] - expected).hypot() < 1e-9);
+            }
+        }
+        assert_eq!(patch.eval(0.5, 0.25), Point::new(15.0, 7.5));
+    }
+
+    #[test]
+    fn mesh_gradient_sampling() {
+        let mesh = MeshGradient::new(vec![square_patch()]);
+        let encoded = EncodedMeshGradient::new(&mesh, Affine::translate((5.0, 5.0)));
+
+        let assert_color = |x: f32, y: f32, expected: [f32; 4]| {
+            let color = encoded.sample(x, y);
+            for (actual, expected) in color.iter().zip(expected) {
+                assert!((actual - expected).abs() < 1e-3, "{color:?} at {x}, {y}");
+            }
+        };
+
+        assert_color(5.0, 5.0, [1.0, 0.0, 0.0, 1.0]);
+        assert_color(5.0, 35.0, [0.0, 1.0, 0.0, 1.0]);
+        assert_color(34.99, 34.99, [0.0, 0.0, 1.0, 1.0]);
+        assert_color(20.0, 20.0, [0.5, 0.5, 0.5, 1.0]);
+        assert_color(4.0, 20.0, [0.0; 4]);
+        assert_color(40.0, 20.0, [0.0; 4]);
+    }
+
+    #[test]
+    fn later_patches_win() {
+        let mut top = square_patch();
+        top.colors = [BLACK; 4];
+        let mesh = MeshGradient::new(vec![square_patch(), top]);
+        let encoded = EncodedMeshGradient::new(&mesh, Affine::IDENTITY);
+
+        assert_eq!(encoded.sample(15.0, 15.0), [0.0, 0.0, 0.0, 1.0]);
+    }
+}
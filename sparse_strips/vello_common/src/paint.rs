@@ -3,12 +3,17 @@
 
 //! Types for paints.
 
+use crate::TextureId;
+use crate::geometry::RectU16;
+use crate::mesh_gradient::MeshGradient;
+use crate::pattern::Pattern;
 use crate::pixmap::Pixmap;
+use crate::raw_image::RawImage;
 use alloc::sync::Arc;
 pub use peniko::Color;
 use peniko::{
-    Gradient,
-    color::{AlphaColor, PremulRgba8, Srgb},
+    Extend, Gradient, ImageQuality, ImageSampler,
+    color::{AlphaColor, ColorSpace, ColorSpaceTag, DynamicColor, OpaqueColor, PremulRgba8, Srgb},
 };
 
 /// A paint that needs to be resolved via its index.
@@ -69,11 +74,32 @@ impl ImageId {
     }
 }
 
+/// Opaque handle of a custom paint.
+///
+/// Custom paints are provided by the user and registered with a renderer, which
+/// assigns them their handle.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
+pub struct CustomPaintId(u32);
+
+impl CustomPaintId {
+    /// Create a new custom paint id from a u32.
+    pub fn new(value: u32) -> Self {
+        Self(value)
+    }
+
+    /// Return the custom paint id as a u32.
+    pub fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
 /// Bitmap source used by `Image`.
 #[derive(Debug, Clone)]
 pub enum ImageSource {
     /// Pixmap pixels travel with the scene packet.
     Pixmap(Arc<Pixmap>),
+    /// Pixels in their native format, which are converted when they are sampled.
+    Raw(Arc<RawImage>),
     /// Pixmap pixels were registered earlier; this is just a handle.
     OpaqueId {
         /// The image handle.
@@ -108,6 +134,7 @@ impl ImageSource {
     pub fn may_have_transparency(&self) -> bool {
         match self {
             Self::Pixmap(p) => p.may_have_transparency(),
+            Self::Raw(r) => r.may_have_transparency(),
             Self::OpaqueId {
                 may_have_transparency,
                 ..
@@ -115,17 +142,27 @@ impl ImageSource {
         }
     }
 
+    /// Returns the color space of the pixels of this image source.
+    ///
+    /// Registered images are only resolved by the renderer, so `None` is returned for them.
+    pub fn color_space(&self) -> Option<ColorSpaceTag> {
+        match self {
+            Self::Pixmap(p) => Some(p.color_space()),
+            Self::Raw(_) => Some(ColorSpaceTag::Srgb),
+            Self::OpaqueId { .. } => None,
+        }
+    }
+
     /// Convert a [`peniko::ImageData`] to an [`ImageSource`].
     ///
     /// This is a somewhat lossy conversion, as the image data data is transformed to
-    /// [premultiplied RGBA8](`PremulRgba8`).
+    /// [premultiplied RGBA8](`PremulRgba8`). Image formats that are not supported yet are
+    /// logged and converted to a fully transparent image instead.
     ///
     /// # Panics
     ///
     /// This panics if `image` has a `width` or `height` greater than `u16::MAX`.
     pub fn from_peniko_image_data(image: &peniko::ImageData) -> Self {
-        // TODO: how do we deal with `peniko::ImageFormat` growing? See also
-        // <https://github.com/linebender/vello/pull/996#discussion_r2080510863>.
         let do_alpha_multiply = image.alpha_type != peniko::ImageAlphaType::AlphaPremultiplied;
 
         assert!(
@@ -136,6 +173,17 @@ impl ImageSource {
         let width = image.width.try_into().unwrap();
         let height = image.height.try_into().unwrap();
 
+        // `peniko::ImageFormat` is non-exhaustive, so formats added in later versions end up
+        // here. See also <https://github.com/linebender/vello/pull/996#discussion_r2080510863>.
+        let to_rgba: fn(&[u8]) -> [u8; 4] = match image.format {
+            peniko::ImageFormat::Rgba8 => |pixel| pixel.try_into().unwrap(),
+            peniko::ImageFormat::Bgra8 => |pixel| [pixel[2], pixel[1], pixel[0], pixel[3]],
+            format => {
+                log::warn!("Unsupported image format {format:?}, using a transparent image");
+                return Self::Pixmap(Arc::new(Pixmap::new(width, height)));
+            }
+        };
+
         // TODO: SIMD
         #[expect(clippy::cast_possible_truncation, reason = "This cannot overflow.")]
         let pixels = image
@@ -143,11 +191,7 @@ impl ImageSource {
             .data()
             .chunks_exact(4)
             .map(|pixel| {
-                let rgba: [u8; 4] = match image.format {
-                    peniko::ImageFormat::Rgba8 => pixel.try_into().unwrap(),
-                    peniko::ImageFormat::Bgra8 => [pixel[2], pixel[1], pixel[0], pixel[3]],
-                    format => unimplemented!("Unsupported image format: {format:?}"),
-                };
+                let rgba = to_rgba(pixel);
                 let alpha = u16::from(rgba[3]);
                 let multiply = |component| ((alpha * u16::from(component)) / 255) as u8;
                 if do_alpha_multiply {
@@ -176,6 +220,69 @@ impl ImageSource {
 /// An image.
 pub type Image = peniko::ImageBrush<ImageSource>;
 
+/// A paint that samples from an external, user-provided texture.
+///
+/// The texture is resolved at render time from the [`TextureId`], see the binding types of the
+/// renderer backends. In the paint's coordinate system, the origin is the top-left corner of
+/// the source region, and the extend modes of the sampler determine how the region is tiled
+/// beyond its bounds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExternalTexture {
+    /// The handle of the texture.
+    pub texture_id: TextureId,
+    /// The region of the texture to sample from, in texel coordinates.
+    pub source_region: RectU16,
+    /// The sampler parameters.
+    pub sampler: ImageSampler,
+}
+
+impl ExternalTexture {
+    /// Create a paint sampling the given region of a texture, using the default sampler.
+    pub fn new(texture_id: TextureId, source_region: RectU16) -> Self {
+        Self {
+            texture_id,
+            source_region,
+            sampler: ImageSampler::default(),
+        }
+    }
+
+    /// Builder method for setting the extend mode in both directions.
+    #[must_use]
+    pub fn with_extend(mut self, mode: Extend) -> Self {
+        self.sampler.x_extend = mode;
+        self.sampler.y_extend = mode;
+        self
+    }
+
+    /// Builder method for setting the extend mode in the horizontal direction.
+    #[must_use]
+    pub fn with_x_extend(mut self, mode: Extend) -> Self {
+        self.sampler.x_extend = mode;
+        self
+    }
+
+    /// Builder method for setting the extend mode in the vertical direction.
+    #[must_use]
+    pub fn with_y_extend(mut self, mode: Extend) -> Self {
+        self.sampler.y_extend = mode;
+        self
+    }
+
+    /// Builder method for setting the sampling quality.
+    #[must_use]
+    pub fn with_quality(mut self, quality: ImageQuality) -> Self {
+        self.sampler.quality = quality;
+        self
+    }
+
+    /// Builder method for setting the opacity the texture is drawn with.
+    #[must_use]
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.sampler.alpha = alpha;
+        self
+    }
+}
+
 /// Trait for resolving opaque image IDs to pixmaps at rasterization time.
 ///
 /// This allows delaying the resolution of `ImageSource::OpaqueId` until the
@@ -190,6 +297,16 @@ pub trait ImageResolver: Send + Sync {
     ///
     /// Returns `None` if the image ID is not found in the registry.
     fn resolve(&self, id: ImageId) -> Option<Arc<Pixmap>>;
+
+    /// Resolve an `ImageId` to a level of its mip pyramid, where level 0 is the image itself
+    /// and each following level halves its size.
+    ///
+    /// Returns `None` if the level is not available, in which case the image is sampled
+    /// from level 0 instead. The default implementation doesn't provide any mip levels.
+    fn resolve_mip_level(&self, id: ImageId, level: u8) -> Option<Arc<Pixmap>> {
+        let _ = (id, level);
+        None
+    }
 }
 
 /// A no-op image resolver that always returns `None`.
@@ -273,4 +390,111 @@ pub struct Tint {
 }
 
 /// A kind of paint that can be used for filling and stroking shapes.
-pub type PaintType = peniko::Brush<Image, Gradient>;
+///
+/// New kinds of paints may be added in the future, so matches on it need a wildcard arm.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum PaintType {
+    /// A solid color.
+    Solid(AlphaColor<Srgb>),
+    /// A gradient.
+    Gradient(Gradient),
+    /// An image.
+    Image(Image),
+    /// A mesh gradient.
+    MeshGradient(MeshGradient),
+    /// A pattern.
+    Pattern(Pattern),
+    /// A custom paint that has been registered with the renderer.
+    Custom(CustomPaintId),
+    /// An external texture that is bound at render time.
+    ExternalTexture(ExternalTexture),
+}
+
+impl Default for PaintType {
+    fn default() -> Self {
+        Self::Solid(AlphaColor::TRANSPARENT)
+    }
+}
+
+impl<CS: ColorSpace> From<AlphaColor<CS>> for PaintType {
+    fn from(color: AlphaColor<CS>) -> Self {
+        Self::Solid(color.convert())
+    }
+}
+
+impl<CS: ColorSpace> From<&AlphaColor<CS>> for PaintType {
+    fn from(color: &AlphaColor<CS>) -> Self {
+        Self::from(*color)
+    }
+}
+
+impl<CS: ColorSpace> From<OpaqueColor<CS>> for PaintType {
+    fn from(color: OpaqueColor<CS>) -> Self {
+        Self::Solid(color.with_alpha(1.).convert())
+    }
+}
+
+impl<CS: ColorSpace> From<&OpaqueColor<CS>> for PaintType {
+    fn from(color: &OpaqueColor<CS>) -> Self {
+        Self::from(*color)
+    }
+}
+
+impl From<DynamicColor> for PaintType {
+    fn from(color: DynamicColor) -> Self {
+        Self::Solid(color.to_alpha_color())
+    }
+}
+
+impl From<&DynamicColor> for PaintType {
+    fn from(color: &DynamicColor) -> Self {
+        Self::from(*color)
+    }
+}
+
+impl From<Gradient> for PaintType {
+    fn from(gradient: Gradient) -> Self {
+        Self::Gradient(gradient)
+    }
+}
+
+impl From<Image> for PaintType {
+    fn from(image: Image) -> Self {
+        Self::Image(image)
+    }
+}
+
+impl From<MeshGradient> for PaintType {
+    fn from(mesh: MeshGradient) -> Self {
+        Self::MeshGradient(mesh)
+    }
+}
+
+impl From<Pattern> for PaintType {
+    fn from(pattern: Pattern) -> Self {
+        Self::Pattern(pattern)
+    }
+}
+
+impl From<CustomPaintId> for PaintType {
+    fn from(id: CustomPaintId) -> Self {
+        Self::Custom(id)
+    }
+}
+
+impl From<ExternalTexture> for PaintType {
+    fn from(texture: ExternalTexture) -> Self {
+        Self::ExternalTexture(texture)
+    }
+}
+
+impl From<peniko::Brush<Image, Gradient>> for PaintType {
+    fn from(brush: peniko::Brush<Image, Gradient>) -> Self {
+        match brush {
+            peniko::Brush::Solid(color) => Self::Solid(color),
+            peniko::Brush::Gradient(gradient) => Self::Gradient(gradient),
+            peniko::Brush::Image(image) => Self::Image(image),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Patterns, which tile a recorded scene.
+//!
+//! Unlike an image with [`Extend::Repeat`](crate::peniko::Extend::Repeat), the content of a
+//! pattern is stored as drawing commands, so that renderers can rasterize it at the resolution
+//! it is displayed at.
+
+use crate::filter_effects::Filter;
+use crate::kurbo::{Affine, BezPath, Rect};
+use crate::peniko::{BlendMode, Fill};
+use crate::render_state::RenderState;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// A paint that repeats the content of a scene in a grid of tiles.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    /// The content of the pattern.
+    pub scene: Arc<PatternScene>,
+    /// The tile that is repeated, in the coordinate system of the scene.
+    ///
+    /// Content outside of the tile is clipped.
+    pub tile: Rect,
+    /// The transform from the coordinate system of the scene to the coordinate system of
+    /// the paint.
+    pub transform: Affine,
+}
+
+impl Pattern {
+    /// Create a new pattern that repeats the given tile of the scene.
+    pub fn new(scene: impl Into<Arc<PatternScene>>, tile: Rect) -> Self {
+        Self {
+            scene: scene.into(),
+            tile,
+            transform: Affine::IDENTITY,
+        }
+    }
+
+    /// Set the transform from the coordinate system of the scene to the coordinate system
+    /// of the paint.
+    #[must_use]
+    pub fn with_transform(mut self, transform: Affine) -> Self {
+        self.transform = transform;
+        self
+    }
+}
+
+/// A recorded scene that is used as the content of a [`Pattern`].
+#[derive(Debug, Clone, Default)]
+pub struct PatternScene {
+    commands: Vec<PatternCommand>,
+}
+
+impl PatternScene {
+    /// Create a new, empty scene.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a command to the scene.
+    pub fn push(&mut self, command: PatternCommand) {
+        self.commands.push(command);
+    }
+
+    /// Fill a path, using the paint, fill rule, blend mode and transforms of the given state.
+    pub fn fill_path(&mut self, path: BezPath, state: &RenderState) {
+        self.push(PatternCommand::Fill {
+            path,
+            state: state.clone(),
+        });
+    }
+
+    /// Stroke a path, using the paint, stroke, blend mode and transforms of the given state.
+    pub fn stroke_path(&mut self, path: BezPath, state: &RenderState) {
+        self.push(PatternCommand::Stroke {
+            path,
+            state: state.clone(),
+        });
+    }
+
+    /// Return the recorded commands.
+    pub fn commands(&self) -> &[PatternCommand] {
+        &self.commands
+    }
+}
+
+impl From<Vec<PatternCommand>> for PatternScene {
+    fn from(commands: Vec<PatternCommand>) -> Self {
+        Self { commands }
+    }
+}
+
+/// A drawing command of a [`PatternScene`].
+#[derive(Debug, Clone)]
+pub enum PatternCommand {
+    /// Fill a path.
+    Fill {
+        /// The path to fill.
+        path: BezPath,
+        /// The state to draw the path with.
+        state: RenderState,
+    },
+    /// Stroke a path.
+    Stroke {
+        /// The path to stroke.
+        path: BezPath,
+        /// The state to draw the path with.
+        state: RenderState,
+    },
+    /// Push a new layer, which needs to be popped again by a [`PatternCommand::PopLayer`].
+    PushLayer {
+        /// The path the layer is clipped to, if any.
+        clip_path: Option<BezPath>,
+        /// The fill rule of the clip path.
+        fill_rule: Fill,
+        /// The transform of the clip path.
+        transform: Affine,
+        /// The blend mode the layer is composited with.
+        blend_mode: BlendMode,
+        /// The opacity of the layer.
+        opacity: f32,
+        /// The filter effect applied to the content of the layer, if any.
+        ///
+        /// The filter is applied in the coordinate system given by `transform`.
+        filter: Option<Filter>,
+    },
+    /// Pop the last-pushed layer.
+    PopLayer,
+}
@@ -7,23 +7,67 @@
 //!
 //! This module provides a simple SVG parser to load and render SVG files
 //! for demonstration purposes. It supports basic SVG features like paths,
-//! fill, stroke, and grouping.
-
-#![expect(
-    clippy::print_stderr,
-    reason = "We don't have a better way to handle these errors, plus this code is not public API"
-)]
-
-extern crate std;
+//! fill, stroke, grouping and filters (see [`crate::svg_filter`]). Unsupported
+//! elements are ignored.
+//!
+//! The attribute parsers in this module are also used by the full SVG importer
+//! of Vello CPU.
 
 use crate::color::{AlphaColor, DynamicColor, Srgb, palette};
-use crate::kurbo::{Affine, BezPath, Point, Size, Vec2};
-use alloc::boxed::Box;
+use crate::filter_effects::Filter;
+#[cfg(not(feature = "std"))]
+use crate::kurbo::common::FloatFuncs as _;
+use crate::kurbo::{Affine, BezPath, Rect, Shape as _, Size, SvgParseError};
+use crate::svg_filter::SvgFilter;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
-use core::str::FromStr;
 use roxmltree::{Document, Node};
-use std::eprintln;
+use thiserror::Error;
+
+/// An error that occurred while loading an SVG document.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SvgError {
+    /// The document is not well-formed XML.
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] roxmltree::Error),
+    /// The root element of the document is not an `svg` element.
+    #[error("The root element is not an `svg` element")]
+    NotAnSvg,
+    /// An element is missing a required attribute.
+    #[error("Element `{element}` is missing the `{attribute}` attribute")]
+    MissingAttribute {
+        /// The name of the element.
+        element: String,
+        /// The name of the missing attribute.
+        attribute: &'static str,
+    },
+    /// An attribute or style property has a value that could not be parsed.
+    #[error("Invalid value `{value}` for `{attribute}`")]
+    InvalidAttribute {
+        /// The name of the attribute or style property.
+        attribute: String,
+        /// The value that could not be parsed.
+        value: String,
+    },
+    /// The path data of an element is malformed.
+    #[error("Invalid path data: {0}")]
+    InvalidPath(#[from] SvgParseError),
+    /// An element references itself, either directly or indirectly.
+    #[error("Element `#{0}` references itself")]
+    CyclicReference(String),
+}
+
+impl SvgError {
+    /// Create an [`SvgError::InvalidAttribute`] for the given attribute and value.
+    pub fn invalid_attribute(attribute: &str, value: &str) -> Self {
+        Self::InvalidAttribute {
+            attribute: attribute.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
 
 /// A simplified representation of an SVG document
 #[derive(Debug)]
@@ -32,6 +76,9 @@ pub struct PicoSvg {
     pub items: Vec<Item>,
     /// The size of the SVG document
     pub size: Size,
+    /// Problems with the document which didn't prevent it from being loaded, such as
+    /// unsupported colors which were replaced by a placeholder
+    pub warnings: Vec<SvgError>,
 }
 
 /// Represents a single item in an SVG document
@@ -72,34 +119,28 @@ pub struct GroupItem {
     pub affine: Affine,
     /// The child items in this group
     pub children: Vec<Item>,
+    /// The filter to apply to the group, in the coordinate system of its children
+    pub filter: Option<Filter>,
 }
 
 struct Parser {
     scale: f64,
+    warnings: Vec<SvgError>,
 }
 
 impl PicoSvg {
     /// Load an SVG document from a string
-    pub fn load(xml_string: &str, scale: f64) -> Result<Self, Box<dyn core::error::Error>> {
+    pub fn load(xml_string: &str, scale: f64) -> Result<Self, SvgError> {
         let doc = Document::parse(xml_string)?;
         let root = doc.root_element();
         let mut parser = Parser::new(scale);
-        let root_width = root.attribute("width").and_then(|s| f64::from_str(s).ok());
-        let root_height = root.attribute("height").and_then(|s| f64::from_str(s).ok());
-        let (origin, viewbox_size) = root
+        let root_width = parse_root_length(root, "width")?;
+        let root_height = parse_root_length(root, "height")?;
+        let view_box = root
             .attribute("viewBox")
-            .and_then(|vb_attr| {
-                let vs: Vec<f64> = vb_attr
-                    .split(' ')
-                    .map(|s| f64::from_str(s).unwrap())
-                    .collect();
-                if let &[x, y, width, height] = vs.as_slice() {
-                    Some((Point { x, y }, Size { width, height }))
-                } else {
-                    None
-                }
-            })
-            .unzip();
+            .map(|vb| parse_view_box(vb).ok_or_else(|| SvgError::invalid_attribute("viewBox", vb)))
+            .transpose()?;
+        let (origin, viewbox_size) = view_box.map(|vb| (vb.origin(), vb.size())).unzip();
 
         let mut transform = if let Some(origin) = origin {
             Affine::translate(origin.to_vec2() * -1.0)
@@ -150,14 +191,31 @@ impl PicoSvg {
         let root_group = Item::Group(GroupItem {
             affine: transform,
             children: items,
+            filter: None,
         });
         Ok(Self {
             items: vec![root_group],
             size,
+            warnings: parser.warnings,
         })
     }
 }
 
+/// Parse the `width` or `height` attribute of the root element.
+///
+/// Percentages are relative to an unknown viewport, so they are treated as if the
+/// attribute wasn't specified.
+fn parse_root_length(root: Node<'_, '_>, attribute: &'static str) -> Result<Option<f64>, SvgError> {
+    match root.attribute(attribute) {
+        None => Ok(None),
+        Some(value) => match parse_length(value) {
+            Some(Length::Absolute(length)) => Ok(Some(length)),
+            Some(Length::Percent(_)) => Ok(None),
+            None => Err(SvgError::invalid_attribute(attribute, value)),
+        },
+    }
+}
+
 #[derive(Clone)]
 struct RecursiveProperties {
     fill: Option<AlphaColor<Srgb>>,
@@ -165,7 +223,10 @@ struct RecursiveProperties {
 
 impl Parser {
     fn new(scale: f64) -> Self {
-        Self { scale }
+        Self {
+            scale,
+            warnings: Vec::new(),
+        }
     }
 
     fn rec_parse(
@@ -173,17 +234,17 @@ impl Parser {
         node: Node<'_, '_>,
         properties: &RecursiveProperties,
         items: &mut Vec<Item>,
-    ) -> Result<(), Box<dyn core::error::Error>> {
+    ) -> Result<(), SvgError> {
         if node.is_element() {
             let mut properties = properties.clone();
             if let Some(fill_color) = node.attribute("fill") {
                 if fill_color == "none" {
                     properties.fill = None;
                 } else {
-                    let color = parse_color(fill_color);
-                    let color = modify_opacity(color, "fill-opacity", node);
+                    let color = self.parse_paint_color("fill", fill_color);
+                    let color = self.modify_opacity(color, "fill-opacity", node);
                     // TODO: Handle recursive opacity properly
-                    let color = modify_opacity(color, "opacity", node);
+                    let color = self.modify_opacity(color, "opacity", node);
                     properties.fill = Some(color);
                 }
             }
@@ -192,17 +253,27 @@ impl Parser {
                     let mut children = Vec::new();
                     let mut affine = Affine::default();
                     if let Some(transform) = node.attribute("transform") {
-                        affine = parse_transform(transform);
+                        affine = parse_transform(transform)
+                            .ok_or_else(|| SvgError::invalid_attribute("transform", transform))?;
                     }
                     for child in node.children() {
                         self.rec_parse(child, &properties, &mut children)?;
                     }
-                    items.push(Item::Group(GroupItem { affine, children }));
+                    let filter = parse_filter(node, &children)?;
+                    items.push(Item::Group(GroupItem {
+                        affine,
+                        children,
+                        filter,
+                    }));
                 }
                 "path" => {
-                    let d = node.attribute("d").ok_or("missing 'd' attribute")?;
+                    let d = node.attribute("d").ok_or(SvgError::MissingAttribute {
+                        element: "path".to_string(),
+                        attribute: "d",
+                    })?;
                     let bp = BezPath::from_svg(d)?;
                     let path = bp;
+                    let start = items.len();
                     if let Some(color) = properties.fill {
                         items.push(Item::Fill(FillItem {
                             color,
@@ -214,116 +285,278 @@ impl Parser {
                     {
                         let width = node
                             .attribute("stroke-width")
-                            .map(|a| f64::from_str(a).unwrap_or(1.0))
+                            .and_then(parse_length)
+                            .map(|l| l.resolve(1.0))
                             .unwrap_or(1.0)
                             * self.scale.abs();
-                        let color = parse_color(stroke_color);
-                        let color = modify_opacity(color, "stroke-opacity", node);
+                        let color = self.parse_paint_color("stroke", stroke_color);
+                        let color = self.modify_opacity(color, "stroke-opacity", node);
                         // TODO: Handle recursive opacity properly
-                        let color = modify_opacity(color, "opacity", node);
+                        let color = self.modify_opacity(color, "opacity", node);
                         items.push(Item::Stroke(StrokeItem { width, color, path }));
                     }
+                    let filter = parse_filter(node, &items[start..])?;
+                    if filter.is_some() {
+                        let children = items.split_off(start);
+                        items.push(Item::Group(GroupItem {
+                            affine: Affine::IDENTITY,
+                            children,
+                            filter,
+                        }));
+                    }
                 }
-                other => eprintln!("Unhandled node type {other}"),
+                // Other elements are not supported by this minimal parser.
+                _ => {}
             }
         }
         Ok(())
     }
-}
 
-fn parse_transform(transform: &str) -> Affine {
-    let mut nt = Affine::IDENTITY;
-    for ts in transform.split(')').map(str::trim) {
-        nt *= if let Some(s) = ts.strip_prefix("matrix(") {
-            let vals = s
-                .split([',', ' '])
-                .map(str::parse)
-                .collect::<Result<Vec<f64>, _>>()
-                .expect("Could parse all values of 'matrix' as floats");
-            Affine::new(
-                vals.try_into()
-                    .expect("Should be six arguments to `matrix`"),
-            )
-        } else if let Some(s) = ts.strip_prefix("translate(") {
-            if let Ok(vals) = s
-                .split([',', ' '])
-                .map(str::trim)
-                .map(str::parse)
-                .collect::<Result<Vec<f64>, _>>()
-            {
-                match vals.as_slice() {
-                    &[x, y] => Affine::translate(Vec2 { x, y }),
-                    _ => Affine::IDENTITY,
-                }
-            } else {
-                Affine::IDENTITY
+    /// Parse the color of a `fill` or `stroke` attribute.
+    ///
+    /// Paint servers and colors which can't be parsed (like `currentColor`) are replaced by
+    /// a placeholder color. The latter are also reported as warnings.
+    fn parse_paint_color(&mut self, attribute: &str, value: &str) -> AlphaColor<Srgb> {
+        const PLACEHOLDER: AlphaColor<Srgb> = palette::css::FUCHSIA.with_alpha(0.5);
+        if value.trim_start().starts_with("url(") {
+            return PLACEHOLDER;
+        }
+        parse_color(value).unwrap_or_else(|| {
+            self.warnings
+                .push(SvgError::invalid_attribute(attribute, value));
+            PLACEHOLDER
+        })
+    }
+
+    /// Apply the opacity attribute `attr_name` of `node` to `color`.
+    ///
+    /// Malformed opacities are reported as warnings and ignored.
+    fn modify_opacity(
+        &mut self,
+        color: AlphaColor<Srgb>,
+        attr_name: &str,
+        node: Node<'_, '_>,
+    ) -> AlphaColor<Srgb> {
+        let Some(opacity) = node.attribute(attr_name) else {
+            return color;
+        };
+        match parse_opacity(opacity) {
+            Some(alpha) => color.with_alpha(alpha),
+            None => {
+                self.warnings
+                    .push(SvgError::invalid_attribute(attr_name, opacity));
+                color
             }
-        } else if let Some(s) = ts.strip_prefix("scale(") {
-            if let Ok(vals) = s
-                .split([',', ' '])
-                .map(str::trim)
-                .map(str::parse)
-                .collect::<Result<Vec<f64>, _>>()
-            {
-                match *vals.as_slice() {
-                    [x, y] => Affine::scale_non_uniform(x, y),
-                    [x] => Affine::scale(x),
-                    _ => Affine::IDENTITY,
-                }
-            } else {
-                Affine::IDENTITY
+        }
+    }
+}
+
+/// Resolve the `filter` attribute of an element with the given children.
+///
+/// Filters which reference unknown elements or cannot be rendered are ignored.
+fn parse_filter(node: Node<'_, '_>, children: &[Item]) -> Result<Option<Filter>, SvgError> {
+    let Some(value) = node.attribute("filter") else {
+        return Ok(None);
+    };
+    let Some(id) = value
+        .trim()
+        .strip_prefix("url(#")
+        .and_then(|url| url.strip_suffix(')'))
+    else {
+        return Ok(None);
+    };
+    let Some(element) = node
+        .document()
+        .descendants()
+        .find(|n| n.attribute("id") == Some(id))
+    else {
+        return Ok(None);
+    };
+    let Some(filter) = SvgFilter::parse(element)? else {
+        return Ok(None);
+    };
+    Ok(filter.resolve(bounding_box(children)))
+}
+
+/// The bounding box of the outlines of some items, in their parent coordinate system.
+fn bounding_box(items: &[Item]) -> Option<Rect> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Fill(FillItem { path, .. }) | Item::Stroke(StrokeItem { path, .. }) => {
+                Some(path.bounding_box())
             }
-        } else if let Some(s) = ts.strip_prefix("scaleX(") {
-            s.trim()
-                .parse()
-                .ok()
-                .map(|x| Affine::scale_non_uniform(x, 1.0))
-                .unwrap_or(Affine::IDENTITY)
-        } else if let Some(s) = ts.strip_prefix("scaleY(") {
-            s.trim()
-                .parse()
-                .ok()
-                .map(|y| Affine::scale_non_uniform(1.0, y))
-                .unwrap_or(Affine::IDENTITY)
-        } else {
-            if !ts.is_empty() {
-                eprintln!("Did not understand transform attribute {ts:?})");
+            Item::Group(group) => {
+                bounding_box(&group.children).map(|bbox| group.affine.transform_rect_bbox(bbox))
             }
-            Affine::IDENTITY
+        })
+        .reduce(|a, b| a.union(b))
+}
+
+/// A length, as used by attributes like `width` or `stroke-width`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Length {
+    /// An absolute length, in user units.
+    Absolute(f64),
+    /// A percentage (with 100% being `1.0`) of some reference length.
+    Percent(f64),
+}
+
+impl Length {
+    /// Resolve the length, given the length that percentages are relative to.
+    pub fn resolve(self, reference: f64) -> f64 {
+        match self {
+            Self::Absolute(length) => length,
+            Self::Percent(fraction) => fraction * reference,
+        }
+    }
+}
+
+/// Parse a length with an optional unit.
+///
+/// Font-relative units assume a font size of 16 pixels.
+pub fn parse_length(length: &str) -> Option<Length> {
+    let length = length.trim();
+    let len = number_len(length)?;
+    let value: f64 = length[..len].parse().ok()?;
+    let absolute = match &length[len..] {
+        "%" => return Some(Length::Percent(value * 0.01)),
+        "" | "px" => value,
+        "pt" => value * 4.0 / 3.0,
+        "pc" => value * 16.0,
+        "in" => value * 96.0,
+        "cm" => value * 96.0 / 2.54,
+        "mm" => value * 96.0 / 25.4,
+        "em" => value * 16.0,
+        "ex" => value * 8.0,
+        _ => return None,
+    };
+    Some(Length::Absolute(absolute))
+}
+
+/// Parse a list of numbers separated by whitespace and/or commas, like in the `points`
+/// attribute of a `polygon`.
+///
+/// As allowed by SVG, separators can be omitted if the start of the next number
+/// is unambiguous, e.g. `1-2.5.5` is parsed as `[1, -2.5, 0.5]`.
+pub fn parse_number_list(list: &str) -> Option<Vec<f64>> {
+    let mut numbers = Vec::new();
+    let mut rest = list;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            return Some(numbers);
+        }
+        let len = number_len(rest)?;
+        numbers.push(rest[..len].parse().ok()?);
+        rest = &rest[len..];
+    }
+}
+
+/// Return the length of the number at the start of `s`, if there is one.
+fn number_len(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let count_digits = |start: usize| {
+        bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut len = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let mut digits = count_digits(len);
+    len += digits;
+    if bytes.get(len) == Some(&b'.') {
+        let fraction_digits = count_digits(len + 1);
+        digits += fraction_digits;
+        len += 1 + fraction_digits;
+    }
+    if digits == 0 {
+        return None;
+    }
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(len + 1), Some(b'+' | b'-')));
+        let exponent_digits = count_digits(len + 1 + sign);
+        // Otherwise, the `e` is the start of a unit like `em`.
+        if exponent_digits > 0 {
+            len += 1 + sign + exponent_digits;
+        }
+    }
+    Some(len)
+}
+
+/// Parse the value of a `viewBox` attribute.
+///
+/// Returns `None` if the value is malformed or has a negative width or height.
+pub fn parse_view_box(view_box: &str) -> Option<Rect> {
+    match *parse_number_list(view_box)?.as_slice() {
+        [x, y, width, height] if width >= 0.0 && height >= 0.0 => {
+            Some(Rect::new(x, y, x + width, y + height))
+        }
+        _ => None,
+    }
+}
+
+/// Parse the value of a `transform` attribute.
+///
+/// Returns `None` if any of the transform functions is malformed.
+pub fn parse_transform(transform: &str) -> Option<Affine> {
+    let mut nt = Affine::IDENTITY;
+    let mut rest = transform;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+        if rest.is_empty() {
+            return Some(nt);
+        }
+        let (name, args) = rest.split_once('(')?;
+        let (args, tail) = args.split_once(')')?;
+        rest = tail;
+        nt *= match (name.trim(), parse_number_list(args)?.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Affine::new([a, b, c, d, e, f]),
+            ("translate", &[x]) => Affine::translate((x, 0.0)),
+            ("translate", &[x, y]) => Affine::translate((x, y)),
+            ("scale", &[s]) => Affine::scale(s),
+            ("scale", &[x, y]) => Affine::scale_non_uniform(x, y),
+            ("scaleX", &[x]) => Affine::scale_non_uniform(x, 1.0),
+            ("scaleY", &[y]) => Affine::scale_non_uniform(1.0, y),
+            ("rotate", &[angle]) => Affine::rotate(angle.to_radians()),
+            ("rotate", &[angle, cx, cy]) => Affine::rotate_about(angle.to_radians(), (cx, cy)),
+            ("skewX", &[angle]) => Affine::new([1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0]),
+            ("skewY", &[angle]) => Affine::new([1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+            _ => return None,
         };
     }
-    nt
 }
 
-fn parse_color(color: &str) -> AlphaColor<Srgb> {
-    let color = color.trim();
+/// Parse a CSS color.
+pub fn parse_color(color: &str) -> Option<AlphaColor<Srgb>> {
     crate::color::parse_color(color.trim())
+        .ok()
         .map(DynamicColor::to_alpha_color)
-        .unwrap_or(palette::css::FUCHSIA.with_alpha(0.5))
 }
 
-fn modify_opacity(
-    color: AlphaColor<Srgb>,
-    attr_name: &str,
-    node: Node<'_, '_>,
-) -> AlphaColor<Srgb> {
-    if let Some(opacity) = node.attribute(attr_name) {
-        let alpha: f32 = if let Some(o) = opacity.strip_suffix('%') {
-            let pctg = o.parse().unwrap_or(100.0);
-            pctg * 0.01
-        } else {
-            opacity.parse().unwrap_or(1.0)
-        };
-        color.with_alpha(alpha.clamp(0., 1.))
+/// Parse an opacity value, either as a number or a percentage.
+///
+/// The result is clamped to the range `0.0..=1.0`.
+pub fn parse_opacity(opacity: &str) -> Option<f32> {
+    let opacity = opacity.trim();
+    let alpha: f32 = if let Some(o) = opacity.strip_suffix('%') {
+        o.parse::<f32>().ok()? * 0.01
     } else {
-        color
-    }
+        opacity.parse().ok()?
+    };
+    Some(alpha.clamp(0., 1.))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_color;
+    use super::{
+        Item, Length, PicoSvg, SvgError, parse_color, parse_length, parse_number_list,
+        parse_transform, parse_view_box,
+    };
     use crate::color::{AlphaColor, Srgb, palette};
+    use crate::kurbo::{Affine, Rect};
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     fn assert_close_color(c1: AlphaColor<Srgb>, c2: AlphaColor<Srgb>) {
         const EPSILON: f32 = 1e-4;
@@ -338,19 +571,118 @@ mod tests {
         let lime = palette::css::LIME;
         let lime_a = lime.with_alpha(0.4);
 
-        let named = parse_color("lime");
+        let named = parse_color("lime").unwrap();
         assert_close_color(lime, named);
 
-        let hex = parse_color("#00ff00");
+        let hex = parse_color("#00ff00").unwrap();
         assert_close_color(lime, hex);
 
-        let rgb = parse_color("rgb(0, 255, 0)");
+        let rgb = parse_color("rgb(0, 255, 0)").unwrap();
         assert_close_color(lime, rgb);
 
-        let modern = parse_color("color(srgb 0 1 0)");
+        let modern = parse_color("color(srgb 0 1 0)").unwrap();
         assert_close_color(lime, modern);
 
-        let modern_a = parse_color("color(srgb 0 1 0 / 0.4)");
+        let modern_a = parse_color("color(srgb 0 1 0 / 0.4)").unwrap();
         assert_close_color(lime_a, modern_a);
     }
+
+    #[test]
+    fn parse_numbers() {
+        assert_eq!(
+            parse_number_list(" 1,2 3.5e1-4.5.5 "),
+            Some(vec![1.0, 2.0, 35.0, -4.5, 0.5])
+        );
+        assert_eq!(parse_number_list(""), Some(vec![]));
+        assert_eq!(parse_number_list("1 a"), None);
+        assert_eq!(parse_length("2em"), Some(Length::Absolute(32.0)));
+        assert_eq!(parse_length("50%"), Some(Length::Percent(0.5)));
+        assert_eq!(parse_length("1in"), Some(Length::Absolute(96.0)));
+        assert_eq!(parse_length("1furlong"), None);
+    }
+
+    #[test]
+    fn parse_transforms() {
+        assert_eq!(
+            parse_transform("translate(10) scale(2, 3)"),
+            Some(Affine::translate((10.0, 0.0)) * Affine::scale_non_uniform(2.0, 3.0))
+        );
+        assert_eq!(
+            parse_transform("matrix(1 2 3 4 5 6),rotate(0)"),
+            Some(Affine::new([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]))
+        );
+        assert_eq!(parse_transform(""), Some(Affine::IDENTITY));
+        assert_eq!(parse_transform("rotate(1, 2)"), None);
+        assert_eq!(parse_transform("translate(1"), None);
+    }
+
+    #[test]
+    fn malformed_view_box() {
+        assert_eq!(
+            parse_view_box("0,0 10 20"),
+            Some(Rect::new(0.0, 0.0, 10.0, 20.0))
+        );
+        assert_eq!(parse_view_box("0 0 -10 20"), None);
+
+        let err = PicoSvg::load(r#"<svg viewBox="0 0 ten 10"/>"#, 1.0).unwrap_err();
+        assert!(
+            matches!(err, SvgError::InvalidAttribute { attribute, .. } if attribute == "viewBox")
+        );
+    }
+
+    #[test]
+    fn path_filter() {
+        let svg = PicoSvg::load(
+            r##"<svg width="10" height="10">
+                <filter id="blur"><feGaussianBlur stdDeviation="3"/></filter>
+                <path d="M 0 0 L 5 5 Z" filter="url(#blur)"/>
+            </svg>"##,
+            1.0,
+        )
+        .unwrap();
+        let Item::Group(root) = &svg.items[0] else {
+            panic!("expected the root group");
+        };
+        let [Item::Group(group)] = root.children.as_slice() else {
+            panic!("expected the path to be wrapped in a group");
+        };
+        assert!(group.filter.is_some());
+        assert!(matches!(group.children.as_slice(), [Item::Fill(_)]));
+    }
+
+    #[test]
+    fn unsupported_colors_are_warnings() {
+        let svg = PicoSvg::load(
+            r##"<svg width="10" height="10">
+                <path d="M 0 0 L 5 5 Z" fill="currentColor" fill-opacity="half"/>
+                <path d="M 0 0 L 5 5 Z" fill="lime" stroke="inherit"/>
+            </svg>"##,
+            1.0,
+        )
+        .unwrap();
+        let Item::Group(root) = &svg.items[0] else {
+            panic!("expected the root group");
+        };
+        let [
+            Item::Fill(placeholder),
+            Item::Fill(lime),
+            Item::Stroke(stroke_placeholder),
+        ] = root.children.as_slice()
+        else {
+            panic!("expected all paths to be drawn");
+        };
+        let placeholder_color = palette::css::FUCHSIA.with_alpha(0.5);
+        assert_close_color(placeholder.color, placeholder_color);
+        assert_close_color(lime.color, palette::css::LIME);
+        assert_close_color(stroke_placeholder.color, placeholder_color);
+        let attributes: Vec<_> = svg
+            .warnings
+            .iter()
+            .map(|warning| match warning {
+                SvgError::InvalidAttribute { attribute, .. } => attribute.as_str(),
+                _ => panic!("unexpected warning {warning}"),
+            })
+            .collect();
+        assert_eq!(attributes, ["fill", "fill-opacity", "stroke"]);
+    }
 }
@@ -3,14 +3,15 @@
 
 //! A simple pixmap type.
 
+use alloc::borrow::Cow;
 use alloc::vec;
 use alloc::vec::Vec;
-#[cfg(feature = "png")]
+#[cfg(any(feature = "png", feature = "jpeg", feature = "webp"))]
 use std::io::{BufRead, Seek};
 
-use crate::peniko::color::{PremulRgba8, Rgba8};
+use crate::peniko::color::{ColorSpaceTag, PremulRgba8, Rgba8};
 
-#[cfg(feature = "png")]
+#[cfg(any(feature = "png", feature = "jpeg", feature = "webp"))]
 extern crate std;
 
 /// A pixmap of premultiplied RGBA8 values backed by [`u8`][core::u8].
@@ -27,6 +28,8 @@ pub struct Pixmap {
     /// Note: This may become stale if pixels are modified via [`data_mut()`](Self::data_mut),
     /// [`data_as_u8_slice_mut()`](Self::data_as_u8_slice_mut), or [`set_pixel()`](Self::set_pixel).
     may_have_transparency: bool,
+    /// The color space of the pixels, which is converted from when the pixmap is drawn.
+    color_space: ColorSpaceTag,
 }
 
 /// A mutable view into premultiplied RGBA8 pixmap data.
@@ -66,6 +69,15 @@ impl<'a> PixmapMut<'a> {
     pub fn data_mut(&mut self) -> &mut [u8] {
         self.buf
     }
+
+    /// Create a shorter-lived view into the same pixmap data.
+    pub fn reborrow(&mut self) -> PixmapMut<'_> {
+        PixmapMut {
+            width: self.width,
+            height: self.height,
+            buf: self.buf,
+        }
+    }
 }
 
 impl<'a> From<&'a mut Pixmap> for PixmapMut<'a> {
@@ -74,6 +86,66 @@ impl<'a> From<&'a mut Pixmap> for PixmapMut<'a> {
     }
 }
 
+/// A view into premultiplied RGBA8 pixmap data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixmapRef<'a> {
+    /// Width of the pixmap in pixels.
+    width: u16,
+    /// Height of the pixmap in pixels.
+    height: u16,
+    /// Buffer of the pixmap in RGBA8 format.
+    buf: &'a [PremulRgba8],
+}
+
+impl<'a> PixmapRef<'a> {
+    /// Create a new pixmap view.
+    ///
+    /// Returns `None` if `buf` is not exactly `width * height * 4` bytes long.
+    pub fn new(width: u16, height: u16, buf: &'a [u8]) -> Option<Self> {
+        if buf.len() == usize::from(width) * usize::from(height) * 4 {
+            Some(Self {
+                width,
+                height,
+                buf: bytemuck::cast_slice(buf),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Return the width of the pixmap.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the height of the pixmap.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns a reference to the underlying data as premultiplied RGBA8.
+    ///
+    /// The pixels are in row-major order.
+    pub fn data(&self) -> &'a [PremulRgba8] {
+        self.buf
+    }
+
+    /// Sample a pixel from the pixmap.
+    ///
+    /// The pixel data is [premultiplied RGBA8][PremulRgba8].
+    #[inline(always)]
+    pub fn sample(&self, x: u16, y: u16) -> PremulRgba8 {
+        let idx = self.width as usize * y as usize + x as usize;
+        self.buf[idx]
+    }
+}
+
+impl<'a> From<&'a Pixmap> for PixmapRef<'a> {
+    fn from(pixmap: &'a Pixmap) -> Self {
+        pixmap.as_ref()
+    }
+}
+
 impl Pixmap {
     /// Create a new pixmap with the given width and height in pixels.
     ///
@@ -85,6 +157,7 @@ impl Pixmap {
             height,
             buf,
             may_have_transparency: true,
+            color_space: ColorSpaceTag::Srgb,
         }
     }
 
@@ -133,6 +206,7 @@ impl Pixmap {
             height,
             buf: data,
             may_have_transparency,
+            color_space: ColorSpaceTag::Srgb,
         }
     }
 
@@ -206,6 +280,52 @@ impl Pixmap {
         self.may_have_transparency = self.buf.iter().any(|pixel| pixel.a != 255);
     }
 
+    /// Returns the color space of the pixels.
+    ///
+    /// This is [sRGB](ColorSpaceTag::Srgb) unless the pixmap was decoded from an image with an
+    /// embedded color profile, or it has been changed with
+    /// [`set_color_space()`](Self::set_color_space).
+    pub fn color_space(&self) -> ColorSpaceTag {
+        self.color_space
+    }
+
+    /// Set the color space that the pixels are in, without changing them.
+    ///
+    /// Pixmaps in a color space other than sRGB are converted to sRGB when they are drawn.
+    /// Only RGB-like color spaces, such as [Display P3](ColorSpaceTag::DisplayP3), are
+    /// supported.
+    pub fn set_color_space(&mut self, color_space: ColorSpaceTag) {
+        self.color_space = color_space;
+    }
+
+    /// Convert the pixels into the given color space.
+    ///
+    /// Colors outside of the gamut of the target color space are clipped.
+    pub fn convert_color_space(&mut self, color_space: ColorSpaceTag) {
+        if self.color_space == color_space {
+            return;
+        }
+
+        for pixel in &mut self.buf {
+            *pixel = convert_premul_pixel(*pixel, self.color_space, color_space);
+        }
+        self.color_space = color_space;
+    }
+
+    /// Return the pixmap with its pixels in sRGB, converting a copy of it if necessary.
+    ///
+    /// This is useful for passing the pixels on to consumers that don't support other color
+    /// spaces.
+    pub fn to_srgb(&self) -> Cow<'_, Self> {
+        if self.color_space == ColorSpaceTag::Srgb {
+            Cow::Borrowed(self)
+        } else {
+            let mut pixmap = self.clone();
+            pixmap.convert_color_space(ColorSpaceTag::Srgb);
+            Cow::Owned(pixmap)
+        }
+    }
+
     /// Apply an alpha value to the whole pixmap.
     pub fn multiply_alpha(&mut self, alpha: u8) {
         #[expect(
@@ -230,6 +350,10 @@ impl Pixmap {
     }
 
     /// Create a pixmap from a PNG file.
+    ///
+    /// The [color space](Self::color_space) of the pixmap is determined from the `cICP`,
+    /// `iCCP`, `sRGB` and `gAMA` chunks of the image. Images with a color space that isn't
+    /// supported are treated as sRGB.
     #[cfg(feature = "png")]
     pub fn from_png(data: impl BufRead + Seek) -> Result<Self, png::DecodingError> {
         let mut decoder = png::Decoder::new(data);
@@ -248,7 +372,9 @@ impl Pixmap {
                 .height
                 .try_into()
                 .map_err(|_| png::DecodingError::LimitsExceeded)?;
-            Self::new(width, height)
+            let mut pixmap = Self::new(width, height);
+            pixmap.color_space = png_color_space(info);
+            pixmap
         };
 
         // Note `reader.info()` returns the pre-transformation color type output, whereas
@@ -319,6 +445,97 @@ impl Pixmap {
         Ok(pixmap)
     }
 
+    /// Create a pixmap from a JPEG file.
+    ///
+    /// Images stored as grayscale, YCbCr, CMYK or YCCK are all converted to RGB. JPEG has no
+    /// alpha channel, so the resulting pixmap is always opaque.
+    ///
+    /// The [color space](Self::color_space) of the pixmap is determined from the embedded
+    /// color profile, if there is one.
+    #[cfg(feature = "jpeg")]
+    pub fn from_jpeg(data: impl BufRead + Seek) -> Result<Self, zune_jpeg::errors::DecodeErrors> {
+        use zune_jpeg::zune_core::colorspace::ColorSpace;
+        use zune_jpeg::zune_core::options::DecoderOptions;
+
+        let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
+        let mut decoder = zune_jpeg::JpegDecoder::new_with_options(data, options);
+        decoder.decode_headers()?;
+        let info = decoder
+            .info()
+            .expect("headers have been decoded successfully");
+
+        let mut pixmap = Self::new(info.width, info.height);
+        if let Some(color_space) = decoder
+            .icc_profile()
+            .and_then(|profile| crate::color_profile::color_space_from_icc(&profile))
+        {
+            pixmap.color_space = color_space;
+        }
+        // The alpha channel of RGBA output is always fully opaque, so the pixels are already
+        // premultiplied.
+        decoder.decode_into(pixmap.data_as_u8_slice_mut())?;
+        pixmap.may_have_transparency = false;
+
+        Ok(pixmap)
+    }
+
+    /// Create a pixmap from a WebP file.
+    ///
+    /// Both lossy and lossless images are supported. For animated images, only the first
+    /// frame is decoded.
+    ///
+    /// The [color space](Self::color_space) of the pixmap is determined from the embedded
+    /// color profile, if there is one.
+    #[cfg(feature = "webp")]
+    pub fn from_webp(data: impl BufRead + Seek) -> Result<Self, image_webp::DecodingError> {
+        let mut decoder = image_webp::WebPDecoder::new(data)?;
+        let (width, height) = decoder.dimensions();
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+            return Err(image_webp::DecodingError::ImageTooLarge);
+        };
+
+        let mut pixmap = Self::new(width, height);
+        if let Some(color_space) = decoder
+            .icc_profile()?
+            .and_then(|profile| crate::color_profile::color_space_from_icc(&profile))
+        {
+            pixmap.color_space = color_space;
+        }
+
+        if decoder.has_alpha() {
+            decoder.read_image(pixmap.data_as_u8_slice_mut())?;
+
+            let mut may_have_transparency = false;
+            for pixel in pixmap.data_mut() {
+                let alpha = pixel.a;
+                if alpha != 255 {
+                    may_have_transparency = true;
+                }
+                let alpha_u16 = u16::from(alpha);
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "Overflow should be impossible."
+                )]
+                let premultiply = |e: u8| ((u16::from(e) * alpha_u16) / 255) as u8;
+                pixel.r = premultiply(pixel.r);
+                pixel.g = premultiply(pixel.g);
+                pixel.b = premultiply(pixel.b);
+            }
+            pixmap.may_have_transparency = may_have_transparency;
+        } else {
+            let mut rgb_data = vec![0; decoder.output_buffer_size().unwrap_or_default()];
+            decoder.read_image(&mut rgb_data)?;
+
+            for (rgb_pixel, pixmap_pixel) in rgb_data.chunks_exact(3).zip(pixmap.data_mut()) {
+                let [r, g, b] = rgb_pixel.try_into().unwrap();
+                *pixmap_pixel = PremulRgba8 { r, g, b, a: 255 };
+            }
+            pixmap.may_have_transparency = false;
+        }
+
+        Ok(pixmap)
+    }
+
     /// Return the current content of the pixmap as a PNG.
     #[cfg(feature = "png")]
     pub fn into_png(self) -> Result<Vec<u8>, png::EncodingError> {
@@ -338,9 +555,8 @@ impl Pixmap {
         &self.buf
     }
 
-    // TODO: Now that we have `as_mut`, maybe we don't need the
-    // mutable methods. If we add a `PixmapRef` we can also remove the
-    // non-mutable ones.
+    // TODO: Now that we have `as_mut` and `as_ref`, maybe we don't need the
+    // mutable and non-mutable methods.
 
     /// Returns a mutable reference to the underlying data as premultiplied RGBA8.
     ///
@@ -374,6 +590,61 @@ impl Pixmap {
         }
     }
 
+    /// Return a view into this pixmap's pixel data.
+    pub fn as_ref(&self) -> PixmapRef<'_> {
+        PixmapRef {
+            width: self.width,
+            height: self.height,
+            buf: &self.buf,
+        }
+    }
+
+    /// Create a copy of the pixmap downscaled by a factor of two in each direction, which is
+    /// the next level of a mip pyramid.
+    ///
+    /// Each pixel is the average of a 2x2 block of the source pixels. Odd dimensions are
+    /// rounded up, in which case the last row or column is averaged with itself.
+    pub fn downsample(&self) -> Self {
+        let width = self.width.div_ceil(2);
+        let height = self.height.div_ceil(2);
+        let max_x = self.width.saturating_sub(1);
+        let max_y = self.height.saturating_sub(1);
+
+        let mut buf = Vec::with_capacity(usize::from(width) * usize::from(height));
+        for y in 0..height {
+            let y0 = (y * 2).min(max_y);
+            let y1 = (y * 2 + 1).min(max_y);
+            for x in 0..width {
+                let x0 = (x * 2).min(max_x);
+                let x1 = (x * 2 + 1).min(max_x);
+                let pixels = [
+                    self.sample(x0, y0),
+                    self.sample(x1, y0),
+                    self.sample(x0, y1),
+                    self.sample(x1, y1),
+                ];
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "the average of four u8 values fits into a u8"
+                )]
+                let average = |component: fn(&PremulRgba8) -> u8| {
+                    ((pixels.iter().map(|p| u16::from(component(p))).sum::<u16>() + 2) / 4) as u8
+                };
+                buf.push(PremulRgba8 {
+                    r: average(|p| p.r),
+                    g: average(|p| p.g),
+                    b: average(|p| p.b),
+                    a: average(|p| p.a),
+                });
+            }
+        }
+
+        let mut level =
+            Self::from_parts_with_opacity(buf, width, height, self.may_have_transparency);
+        level.color_space = self.color_space;
+        level
+    }
+
     /// Sample a pixel from the pixmap.
     ///
     /// The pixel data is [premultiplied RGBA8][PremulRgba8].
@@ -434,3 +705,168 @@ impl Pixmap {
             .collect()
     }
 }
+
+/// Determine the color space of a PNG image from its metadata.
+#[cfg(feature = "png")]
+fn png_color_space(info: &png::Info<'_>) -> ColorSpaceTag {
+    // The chunks are checked in the order of precedence defined by the PNG specification.
+    if let Some(cicp) = info.coding_independent_code_points {
+        // The color primaries and transfer characteristics are defined in ITU-T H.273.
+        match (cicp.color_primaries, cicp.transfer_function) {
+            (1, 13) => return ColorSpaceTag::Srgb,
+            (1, 8) => return ColorSpaceTag::LinearSrgb,
+            (12, 13) => return ColorSpaceTag::DisplayP3,
+            (9, 1 | 14 | 15) => return ColorSpaceTag::Rec2020,
+            _ => {}
+        }
+    }
+    if let Some(profile) = &info.icc_profile {
+        return crate::color_profile::color_space_from_icc(profile).unwrap_or(ColorSpaceTag::Srgb);
+    }
+    if info.srgb.is_none()
+        && info
+            .gama_chunk
+            .is_some_and(|gamma| gamma.into_scaled() == 100_000)
+    {
+        return ColorSpaceTag::LinearSrgb;
+    }
+
+    ColorSpaceTag::Srgb
+}
+
+/// Convert a premultiplied pixel between two RGB-like color spaces.
+fn convert_premul_pixel(
+    pixel: PremulRgba8,
+    source: ColorSpaceTag,
+    target: ColorSpaceTag,
+) -> PremulRgba8 {
+    if pixel.a == 0 {
+        return pixel;
+    }
+
+    let alpha = f32::from(pixel.a) / 255.0;
+    let unpremultiply = |component: u8| f32::from(component) / 255.0 / alpha;
+    let [r, g, b] = source.convert(
+        target,
+        [
+            unpremultiply(pixel.r),
+            unpremultiply(pixel.g),
+            unpremultiply(pixel.b),
+        ],
+    );
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "the value is clamped to the range of a u8"
+    )]
+    let premultiply = |component: f32| (component.clamp(0.0, 1.0) * alpha * 255.0 + 0.5) as u8;
+
+    PremulRgba8 {
+        r: premultiply(r),
+        g: premultiply(g),
+        b: premultiply(b),
+        a: pixel.a,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pixmap;
+    use crate::color::{ColorSpaceTag, PremulRgba8};
+    use alloc::vec;
+
+    #[test]
+    fn convert_color_space() {
+        let gray = PremulRgba8 {
+            r: 64,
+            g: 64,
+            b: 64,
+            a: 128,
+        };
+        let mut pixmap = Pixmap::from_parts(vec![gray; 4], 2, 2);
+        pixmap.set_color_space(ColorSpaceTag::LinearSrgb);
+
+        let converted = pixmap.to_srgb();
+        assert_eq!(converted.color_space(), ColorSpaceTag::Srgb);
+        // Linear mid gray at half opacity is brighter in sRGB.
+        assert_eq!(converted.sample(0, 0).r, 94);
+        assert_eq!(converted.sample(1, 1).a, 128);
+        // The original pixmap isn't modified.
+        assert_eq!(pixmap.sample(0, 0), gray);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_gamma() {
+        let pixmap = Pixmap::new(1, 1);
+        let mut data = vec![];
+        let mut encoder = png::Encoder::new(&mut data, 1, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_source_gamma(png::ScaledFloat::new(1.0));
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixmap.data_as_u8_slice()).unwrap();
+        writer.finish().unwrap();
+
+        let decoded = Pixmap::from_png(std::io::Cursor::new(data)).unwrap();
+        assert_eq!(decoded.color_space(), ColorSpaceTag::LinearSrgb);
+    }
+
+    #[cfg(feature = "jpeg")]
+    #[test]
+    fn decode_jpeg() {
+        let data = include_bytes!("../../../examples/assets/splash-flower.jpg");
+        let pixmap = Pixmap::from_jpeg(std::io::Cursor::new(data)).unwrap();
+
+        assert_eq!((pixmap.width(), pixmap.height()), (640, 480));
+        assert!(!pixmap.may_have_transparency());
+        assert!(pixmap.data().iter().all(|pixel| pixel.a == 255));
+        assert!(pixmap.data().iter().any(|pixel| pixel.r != pixel.b));
+    }
+
+    #[cfg(feature = "webp")]
+    #[test]
+    fn decode_webp() {
+        let encode = |pixels: &[u8], color| {
+            let mut data = vec![];
+            image_webp::WebPEncoder::new(&mut data)
+                .encode(pixels, 2, 1, color)
+                .unwrap();
+            Pixmap::from_webp(std::io::Cursor::new(data)).unwrap()
+        };
+
+        let opaque = encode(&[255, 0, 0, 0, 0, 255], image_webp::ColorType::Rgb8);
+        assert_eq!((opaque.width(), opaque.height()), (2, 1));
+        assert!(!opaque.may_have_transparency());
+        assert_eq!(
+            opaque.data(),
+            [
+                PremulRgba8 {
+                    r: 255,
+                    g: 0,
+                    b: 0,
+                    a: 255
+                },
+                PremulRgba8 {
+                    r: 0,
+                    g: 0,
+                    b: 255,
+                    a: 255
+                }
+            ]
+        );
+
+        let transparent = encode(
+            &[255, 0, 0, 255, 0, 0, 255, 0],
+            image_webp::ColorType::Rgba8,
+        );
+        assert!(transparent.may_have_transparency());
+        assert_eq!(
+            transparent.data()[1],
+            PremulRgba8 {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 0
+            }
+        );
+    }
+}
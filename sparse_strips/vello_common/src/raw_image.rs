@@ -0,0 +1,470 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Images that keep the pixel format they were created with.
+//!
+//! A [`Pixmap`] always stores premultiplied RGBA8 pixels, so data in any other format needs to
+//! be converted before it can be drawn. For content that changes every frame, like video,
+//! that conversion is a significant cost. A [`RawImage`] instead stores the data as is, and
+//! renderers convert the pixels when sampling them.
+
+use crate::color::PremulRgba8;
+use crate::peniko::{Blob, ImageAlphaType};
+use crate::pixmap::Pixmap;
+use alloc::vec::Vec;
+use thiserror::Error;
+
+/// The pixel format of a [`RawImage`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawImageFormat {
+    /// A single 8-bit channel, which is drawn as an opaque gray level.
+    ///
+    /// This is useful for masks, since the luminance of each pixel is the value of the
+    /// channel.
+    R8,
+    /// Two 8-bit channels per pixel, the gray level followed by the alpha value.
+    Rg8,
+    /// Four 16-bit channels per pixel in RGBA order, each stored in little-endian byte
+    /// order.
+    Rgba16,
+    /// 8-bit YUV with a plane of luma samples, followed by a plane of interleaved U and V
+    /// chroma samples at half the horizontal and vertical resolution.
+    Nv12(YuvEncoding),
+    /// 8-bit YUV with a plane of luma samples, followed by a plane of U and a plane of V
+    /// chroma samples, both at half the horizontal and vertical resolution.
+    I420(YuvEncoding),
+}
+
+impl RawImageFormat {
+    /// Return the number of planes of an image in this format.
+    pub fn plane_count(self) -> usize {
+        match self {
+            Self::R8 | Self::Rg8 | Self::Rgba16 => 1,
+            Self::Nv12(_) => 2,
+            Self::I420(_) => 3,
+        }
+    }
+
+    /// Return the number of bytes per row and the number of rows of the given plane, for an
+    /// image of the given size.
+    fn plane_size(self, plane: usize, width: u16, height: u16) -> (usize, usize) {
+        let (width, height) = (usize::from(width), usize::from(height));
+        let (chroma_width, chroma_height) = (width.div_ceil(2), height.div_ceil(2));
+        match (self, plane) {
+            (Self::R8, _) => (width, height),
+            (Self::Rg8, _) => (2 * width, height),
+            (Self::Rgba16, _) => (8 * width, height),
+            (Self::Nv12(_) | Self::I420(_), 0) => (width, height),
+            (Self::Nv12(_), _) => (2 * chroma_width, chroma_height),
+            (Self::I420(_), _) => (chroma_width, chroma_height),
+        }
+    }
+}
+
+/// How the colors of a YUV image are encoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct YuvEncoding {
+    /// The matrix used to convert between YUV and RGB.
+    pub matrix: YuvMatrix,
+    /// The range of the sample values.
+    pub range: YuvRange,
+}
+
+/// The matrix used to convert between YUV and RGB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YuvMatrix {
+    /// The matrix of ITU-R BT.601, used for standard-definition video.
+    Bt601,
+    /// The matrix of ITU-R BT.709, used for high-definition video.
+    #[default]
+    Bt709,
+}
+
+/// The range of the sample values of a YUV image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YuvRange {
+    /// Luma samples range from 16 to 235, and chroma samples from 16 to 240.
+    #[default]
+    Limited,
+    /// All samples use the full range from 0 to 255.
+    Full,
+}
+
+/// The location of a plane of a [`RawImage`] in its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaneLayout {
+    /// The offset of the first row of the plane, in bytes.
+    pub offset: usize,
+    /// The distance between the starts of two consecutive rows of the plane, in bytes.
+    pub stride: usize,
+}
+
+/// An error when creating a [`RawImage`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawImageError {
+    /// The number of plane layouts doesn't match the format.
+    #[error("Expected {expected} planes, got {actual}")]
+    PlaneCount {
+        /// The number of planes of the format.
+        expected: usize,
+        /// The number of planes that were provided.
+        actual: usize,
+    },
+    /// The stride of a plane is smaller than the size of one of its rows.
+    #[error("The stride of plane {0} is smaller than its rows")]
+    StrideTooSmall(usize),
+    /// The data is too short to contain a plane.
+    #[error("The data is too short to contain plane {0}")]
+    DataTooShort(usize),
+}
+
+/// An image that stores its pixels in one of the [`RawImageFormat`]s.
+#[derive(Debug, Clone)]
+pub struct RawImage {
+    data: Blob<u8>,
+    format: RawImageFormat,
+    alpha_type: ImageAlphaType,
+    width: u16,
+    height: u16,
+    planes: [PlaneLayout; 3],
+    may_have_transparency: bool,
+}
+
+impl RawImage {
+    /// Create a new image whose planes are stored one after another without any padding.
+    ///
+    /// Colors with an alpha channel are treated as not premultiplied. Use
+    /// [`with_alpha_type`](Self::with_alpha_type) to change that.
+    pub fn new(
+        data: impl Into<Blob<u8>>,
+        format: RawImageFormat,
+        width: u16,
+        height: u16,
+    ) -> Result<Self, RawImageError> {
+        let mut planes = Vec::with_capacity(format.plane_count());
+        let mut offset = 0;
+        for plane in 0..format.plane_count() {
+            let (row_size, rows) = format.plane_size(plane, width, height);
+            planes.push(PlaneLayout {
+                offset,
+                stride: row_size,
+            });
+            offset += row_size * rows;
+        }
+        Self::with_planes(data, format, width, height, &planes)
+    }
+
+    /// Create a new image with the given location of each plane in the data.
+    ///
+    /// This allows drawing frames whose rows are padded, as is common for video, without
+    /// copying them first.
+    pub fn with_planes(
+        data: impl Into<Blob<u8>>,
+        format: RawImageFormat,
+        width: u16,
+        height: u16,
+        planes: &[PlaneLayout],
+    ) -> Result<Self, RawImageError> {
+        let data = data.into();
+        if planes.len() != format.plane_count() {
+            return Err(RawImageError::PlaneCount {
+                expected: format.plane_count(),
+                actual: planes.len(),
+            });
+        }
+        for (idx, plane) in planes.iter().enumerate() {
+            let (row_size, rows) = format.plane_size(idx, width, height);
+            if plane.stride < row_size {
+                return Err(RawImageError::StrideTooSmall(idx));
+            }
+            let end = match rows {
+                0 => plane.offset,
+                rows => plane
+                    .stride
+                    .checked_mul(rows - 1)
+                    .and_then(|len| len.checked_add(plane.offset))
+                    .and_then(|len| len.checked_add(row_size))
+                    .ok_or(RawImageError::DataTooShort(idx))?,
+            };
+            if end > data.data().len() {
+                return Err(RawImageError::DataTooShort(idx));
+            }
+        }
+
+        let mut layouts = [PlaneLayout {
+            offset: 0,
+            stride: 0,
+        }; 3];
+        layouts[..planes.len()].copy_from_slice(planes);
+        let mut image = Self {
+            data,
+            format,
+            alpha_type: ImageAlphaType::Alpha,
+            width,
+            height,
+            planes: layouts,
+            may_have_transparency: false,
+        };
+        image.may_have_transparency = image.compute_may_have_transparency();
+        Ok(image)
+    }
+
+    /// Set whether the colors are premultiplied by their alpha value.
+    ///
+    /// This only has an effect on formats with an alpha channel.
+    #[must_use]
+    pub fn with_alpha_type(mut self, alpha_type: ImageAlphaType) -> Self {
+        self.alpha_type = alpha_type;
+        self
+    }
+
+    /// Return the width of the image in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the height of the image in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Return the pixel format of the image.
+    pub fn format(&self) -> RawImageFormat {
+        self.format
+    }
+
+    /// Return the data of the image.
+    pub fn data(&self) -> &Blob<u8> {
+        &self.data
+    }
+
+    /// Return whether the image may contain pixels that are not fully opaque.
+    pub fn may_have_transparency(&self) -> bool {
+        self.may_have_transparency
+    }
+
+    fn compute_may_have_transparency(&self) -> bool {
+        let alpha_is_max = |y: u16| match self.format {
+            RawImageFormat::Rg8 => self.row(0, y).chunks_exact(2).all(|p| p[1] == u8::MAX),
+            RawImageFormat::Rgba16 => self.row(0, y).chunks_exact(8).all(|p| p[6..] == [255; 2]),
+            _ => true,
+        };
+        !(0..self.height).all(alpha_is_max)
+    }
+
+    /// Return the bytes of a row of a plane.
+    #[inline(always)]
+    fn row(&self, plane: usize, y: u16) -> &[u8] {
+        let layout = self.planes[plane];
+        let (row_size, _) = self.format.plane_size(plane, self.width, self.height);
+        let start = layout.offset + layout.stride * usize::from(y);
+        &self.data.data()[start..start + row_size]
+    }
+
+    /// Sample the pixel at the given position, converted to premultiplied RGBA8.
+    #[inline]
+    pub fn sample(&self, x: u16, y: u16) -> PremulRgba8 {
+        let x = usize::from(x);
+        match self.format {
+            RawImageFormat::R8 => {
+                let gray = self.row(0, y)[x];
+                PremulRgba8 {
+                    r: gray,
+                    g: gray,
+                    b: gray,
+                    a: u8::MAX,
+                }
+            }
+            RawImageFormat::Rg8 => {
+                let pixel = &self.row(0, y)[2 * x..2 * x + 2];
+                let gray = self.premultiply_u8(pixel[0], pixel[1]);
+                PremulRgba8 {
+                    r: gray,
+                    g: gray,
+                    b: gray,
+                    a: pixel[1],
+                }
+            }
+            RawImageFormat::Rgba16 => {
+                let pixel = &self.row(0, y)[8 * x..8 * x + 8];
+                let [r, g, b, a] =
+                    core::array::from_fn(|i| u16::from_le_bytes([pixel[2 * i], pixel[2 * i + 1]]));
+                PremulRgba8 {
+                    r: self.premultiply_u16(r, a),
+                    g: self.premultiply_u16(g, a),
+                    b: self.premultiply_u16(b, a),
+                    a: u16_to_u8(a),
+                }
+            }
+            RawImageFormat::Nv12(encoding) => {
+                let luma = self.row(0, y)[x];
+                let chroma = &self.row(1, y / 2)[x / 2 * 2..x / 2 * 2 + 2];
+                yuv_to_rgb(luma, chroma[0], chroma[1], encoding)
+            }
+            RawImageFormat::I420(encoding) => {
+                let luma = self.row(0, y)[x];
+                let u = self.row(1, y / 2)[x / 2];
+                let v = self.row(2, y / 2)[x / 2];
+                yuv_to_rgb(luma, u, v, encoding)
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn premultiply_u8(&self, component: u8, alpha: u8) -> u8 {
+        match self.alpha_type {
+            ImageAlphaType::Alpha => {
+                #[expect(clippy::cast_possible_truncation, reason = "This cannot overflow.")]
+                let premultiplied = ((u16::from(component) * u16::from(alpha)) / 255) as u8;
+                premultiplied
+            }
+            ImageAlphaType::AlphaPremultiplied => component,
+        }
+    }
+
+    #[inline(always)]
+    fn premultiply_u16(&self, component: u16, alpha: u16) -> u8 {
+        match self.alpha_type {
+            ImageAlphaType::Alpha => {
+                #[expect(clippy::cast_possible_truncation, reason = "This cannot overflow.")]
+                let premultiplied = ((u32::from(component) * u32::from(alpha)) / 65535) as u16;
+                u16_to_u8(premultiplied)
+            }
+            ImageAlphaType::AlphaPremultiplied => u16_to_u8(component),
+        }
+    }
+
+    /// Convert the image to a pixmap.
+    pub fn to_pixmap(&self) -> Pixmap {
+        let mut pixels = Vec::with_capacity(usize::from(self.width) * usize::from(self.height));
+        for y in 0..self.height {
+            pixels.extend((0..self.width).map(|x| self.sample(x, y)));
+        }
+        Pixmap::from_parts_with_opacity(pixels, self.width, self.height, self.may_have_transparency)
+    }
+}
+
+/// Convert a 16-bit component to 8 bits, rounding to the nearest value.
+#[inline(always)]
+fn u16_to_u8(value: u16) -> u8 {
+    #[expect(clippy::cast_possible_truncation, reason = "This cannot overflow.")]
+    let value = ((u32::from(value) * 255 + 32767) / 65535) as u8;
+    value
+}
+
+/// Convert a YUV sample to an opaque RGBA8 color.
+#[inline(always)]
+fn yuv_to_rgb(y: u8, u: u8, v: u8, encoding: YuvEncoding) -> PremulRgba8 {
+    let (y, u, v) = (f32::from(y), f32::from(u) - 128.0, f32::from(v) - 128.0);
+    let (y, u, v) = match encoding.range {
+        YuvRange::Limited => (
+            (y - 16.0) * (255.0 / 219.0),
+            u * (255.0 / 224.0),
+            v * (255.0 / 224.0),
+        ),
+        YuvRange::Full => (y, u, v),
+    };
+    let (r, g, b) = match encoding.matrix {
+        YuvMatrix::Bt601 => (
+            y + 1.402 * v,
+            y - 0.344_136 * u - 0.714_136 * v,
+            y + 1.772 * u,
+        ),
+        YuvMatrix::Bt709 => (
+            y + 1.5748 * v,
+            y - 0.187_324 * u - 0.468_124 * v,
+            y + 1.8556 * u,
+        ),
+    };
+    #[expect(clippy::cast_possible_truncation, reason = "The value is clamped.")]
+    let to_u8 = |value: f32| (value.clamp(0.0, 255.0) + 0.5) as u8;
+    PremulRgba8 {
+        r: to_u8(r),
+        g: to_u8(g),
+        b: to_u8(b),
+        a: u8::MAX,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn invalid_layouts_are_rejected() {
+        let data = vec![0_u8; 16];
+        assert_eq!(
+            RawImage::new(data.clone(), RawImageFormat::Rg8, 4, 4).unwrap_err(),
+            RawImageError::DataTooShort(0)
+        );
+        assert_eq!(
+            RawImage::with_planes(
+                data.clone(),
+                RawImageFormat::R8,
+                4,
+                4,
+                &[PlaneLayout {
+                    offset: 0,
+                    stride: 2
+                }]
+            )
+            .unwrap_err(),
+            RawImageError::StrideTooSmall(0)
+        );
+        assert_eq!(
+            RawImage::with_planes(
+                data,
+                RawImageFormat::I420(YuvEncoding::default()),
+                2,
+                2,
+                &[]
+            )
+            .unwrap_err(),
+            RawImageError::PlaneCount {
+                expected: 3,
+                actual: 0
+            }
+        );
+    }
+
+    #[test]
+    fn gray_and_alpha_are_premultiplied() {
+        let image = RawImage::new(vec![200, 128, 100, 255], RawImageFormat::Rg8, 2, 1).unwrap();
+        assert!(image.may_have_transparency());
+        assert_eq!(
+            image.sample(0, 0),
+            PremulRgba8 {
+                r: 100,
+                g: 100,
+                b: 100,
+                a: 128
+            }
+        );
+        assert_eq!(image.sample(1, 0).r, 100);
+    }
+
+    #[test]
+    fn nv12_with_padded_rows() {
+        // A 2x2 frame with rows padded to 4 bytes, the chroma plane being red in BT.709
+        // limited range.
+        let data = vec![
+            63, 63, 0, 0, //
+            63, 63, 0, 0, //
+            102, 240, 0, 0,
+        ];
+        let layout = |offset| PlaneLayout { offset, stride: 4 };
+        let image = RawImage::with_planes(
+            data,
+            RawImageFormat::Nv12(YuvEncoding::default()),
+            2,
+            2,
+            &[layout(0), layout(8)],
+        )
+        .unwrap();
+        assert!(!image.may_have_transparency());
+        let pixel = image.sample(1, 1);
+        assert!(pixel.r >= 253 && pixel.g <= 2 && pixel.b <= 2, "{pixel:?}");
+        assert_eq!(image.to_pixmap().sample(0, 1), pixel);
+    }
+}
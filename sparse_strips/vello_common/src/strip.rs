@@ -9,6 +9,8 @@ use crate::tile::{Tile, Tiles};
 use crate::util::f32_to_u8;
 use alloc::vec::Vec;
 use fearless_simd::*;
+#[cfg(not(feature = "std"))]
+use peniko::kurbo::common::FloatFuncs as _;
 
 /// A strip.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -132,6 +134,53 @@ impl Strip {
     }
 }
 
+/// The method used to compute the coverage of pixels along the edges of a path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AntialiasingMode {
+    /// Exact analytic area coverage.
+    ///
+    /// This gives the highest quality for isolated edges, but leads to conflation artifacts
+    /// where edges of a path coincide, for example when a path consists of abutting polygons.
+    #[default]
+    Area,
+    /// Coverage is estimated by testing 8 sample points per pixel.
+    ///
+    /// As each sample point is either inside or outside of the path, coinciding edges
+    /// don't lead to conflation artifacts.
+    Msaa8,
+    /// Coverage is estimated by testing 16 sample points per pixel.
+    ///
+    /// This is more precise but slower than [`AntialiasingMode::Msaa8`].
+    Msaa16,
+}
+
+impl AntialiasingMode {
+    /// The horizontal offsets of the sample points in a pixel for the given number of samples.
+    ///
+    /// Sample `i` lies on the horizontal line `(i + 0.5) / n` within a pixel, and the offsets form
+    /// a permutation so that no two samples share the same vertical line ("n-rooks" pattern).
+    fn sample_offsets(self) -> &'static [f32] {
+        const fn offsets<const N: usize>(permutation: [u8; N]) -> [f32; N] {
+            let mut offsets = [0.0; N];
+            let mut i = 0;
+            while i < N {
+                offsets[i] = (permutation[i] as f32 + 0.5) / N as f32;
+                i += 1;
+            }
+            offsets
+        }
+
+        const MSAA8: [f32; 8] = offsets([5, 2, 7, 0, 3, 6, 1, 4]);
+        const MSAA16: [f32; 16] = offsets([11, 6, 1, 12, 3, 14, 9, 4, 15, 8, 0, 10, 5, 13, 2, 7]);
+
+        match self {
+            Self::Area => &[],
+            Self::Msaa8 => &MSAA8,
+            Self::Msaa16 => &MSAA16,
+        }
+    }
+}
+
 /// Render the tiles stored in `tiles` into the strip and alpha buffer.
 pub fn render(
     level: Level,
@@ -543,3 +592,158 @@ fn render_impl<S: Simd>(
         );
     }
 }
+
+/// The maximum number of sample rows in a tile.
+const MAX_SAMPLE_ROWS: usize = 16 * Tile::HEIGHT as usize;
+
+/// Render the tiles stored in `tiles` into the strip and alpha buffer, computing coverage
+/// by sampling each pixel at multiple points.
+///
+/// The tiles must have been generated by [`Tiles::make_tiles_msaa`], and `mode` must be
+/// one of the MSAA modes.
+pub fn render_msaa(
+    tiles: &Tiles,
+    strip_buf: &mut Vec<Strip>,
+    alpha_buf: &mut Vec<u8>,
+    fill_rule: Fill,
+    aliasing_threshold: Option<u8>,
+    lines: &[Line],
+    mode: AntialiasingMode,
+) {
+    let offsets = mode.sample_offsets();
+    assert!(!offsets.is_empty(), "`render_msaa` requires an MSAA mode");
+
+    if tiles.is_empty() {
+        return;
+    }
+
+    let n_samples = offsets.len();
+    let n_rows = n_samples * Tile::HEIGHT as usize;
+
+    let should_fill = |winding: i32| match fill_rule {
+        Fill::NonZero => winding != 0,
+        Fill::EvenOdd => winding % 2 != 0,
+    };
+
+    // The winding of each sample at the location we're currently at, indexed by the column of
+    // the pixel and the sample row. Each sample row contains one sample per pixel.
+    let mut location_winding = [[0_i32; MAX_SAMPLE_ROWS]; Tile::WIDTH as usize];
+    // The winding of each sample row at the right edge of this location.
+    let mut accumulated_winding = [0_i32; MAX_SAMPLE_ROWS];
+    // The winding to the right of the current location. As no geometry lies between strips,
+    // this is the same for all sample rows there, so we track the winding of the first one.
+    let mut winding_delta: i32 = 0;
+
+    let mut prev_tile = *tiles.get(0);
+    let mut strip = Strip::new(
+        prev_tile.x * Tile::WIDTH,
+        prev_tile.y * Tile::HEIGHT,
+        alpha_buf.len() as u32,
+        false,
+    );
+
+    for (tile_idx, tile) in tiles.iter().copied().chain([Tile::SENTINEL]).enumerate() {
+        // Push out the coverage as an alpha mask when we move to the next location.
+        if !prev_tile.same_loc(&tile) {
+            for column in &mut location_winding {
+                for samples in column[..n_rows].chunks_exact(n_samples) {
+                    let covered = samples.iter().filter(|w| should_fill(**w)).count();
+                    let mut alpha = ((covered * 255 + n_samples / 2) / n_samples) as u8;
+                    if let Some(aliasing_threshold) = aliasing_threshold {
+                        alpha = if alpha >= aliasing_threshold { 255 } else { 0 };
+                    }
+                    alpha_buf.push(alpha);
+                }
+                column[..n_rows].copy_from_slice(&accumulated_winding[..n_rows]);
+            }
+        }
+
+        // Push out the strip if we're moving to a next strip.
+        if !prev_tile.same_loc(&tile) && !prev_tile.prev_loc(&tile) {
+            strip_buf.push(strip);
+
+            let is_sentinel = tile_idx == tiles.len() as usize;
+            if !prev_tile.same_row(&tile) {
+                // Emit a final strip in the row if there is non-zero winding for the sparse fill,
+                // or unconditionally if we've reached the sentinel tile to end the path (the
+                // `alpha_idx` field is used for width calculations).
+                if winding_delta != 0 || is_sentinel {
+                    strip_buf.push(Strip::new(
+                        u16::MAX,
+                        prev_tile.y * Tile::HEIGHT,
+                        alpha_buf.len() as u32,
+                        should_fill(winding_delta),
+                    ));
+                }
+
+                winding_delta = 0;
+                accumulated_winding = [0; MAX_SAMPLE_ROWS];
+                location_winding = [[0; MAX_SAMPLE_ROWS]; Tile::WIDTH as usize];
+            }
+
+            if is_sentinel {
+                break;
+            }
+
+            strip = Strip::new(
+                tile.x * Tile::WIDTH,
+                tile.y * Tile::HEIGHT,
+                alpha_buf.len() as u32,
+                should_fill(winding_delta),
+            );
+        }
+        prev_tile = tile;
+
+        let line = lines[tile.line_idx() as usize];
+        let tile_left_x = f32::from(tile.x) * f32::from(Tile::WIDTH);
+        let tile_top_y = f32::from(tile.y) * f32::from(Tile::HEIGHT);
+        let p0_x = line.p0.x - tile_left_x;
+        let p0_y = line.p0.y - tile_top_y;
+        let p1_x = line.p1.x - tile_left_x;
+        let p1_y = line.p1.y - tile_top_y;
+
+        // Horizontal geometry has no impact on winding.
+        if p0_y == p1_y {
+            continue;
+        }
+
+        // Lines moving upwards (in a y-down coordinate system) add to winding; lines moving
+        // downwards subtract from winding.
+        let sign = if p0_y > p1_y { 1 } else { -1 };
+        let (line_top_y, line_top_x, line_bottom_y) = if p0_y < p1_y {
+            (p0_y, p0_x, p1_y)
+        } else {
+            (p1_y, p1_x, p0_y)
+        };
+        let x_slope = (p1_x - p0_x) / (p1_y - p0_y);
+
+        // Each sample row is crossed by the line if its center lies in the half-open range
+        // `[line_top_y, line_bottom_y)`, so that rows are never counted twice for lines sharing
+        // an end point.
+        let first_row =
+            ((line_top_y * n_samples as f32 - 0.5).ceil().max(0.0) as usize).min(n_rows);
+        let end_row =
+            ((line_bottom_y * n_samples as f32 - 0.5).ceil().max(0.0) as usize).min(n_rows);
+
+        for row in first_row..end_row {
+            let y = (row as f32 + 0.5) / n_samples as f32;
+            let x = line_top_x + (y - line_top_y) * x_slope;
+
+            // Crossings belong to the tile they are located in. Lines left of the viewport are
+            // assigned to the first column of tiles, so crossings left of it need to be counted
+            // there.
+            if x >= f32::from(Tile::WIDTH) || (x < 0.0 && tile.x != 0) {
+                continue;
+            }
+
+            let sample_x = offsets[row % n_samples];
+            for (px, column) in location_winding.iter_mut().enumerate() {
+                if px as f32 + sample_x > x {
+                    column[row] += sign;
+                }
+            }
+            accumulated_winding[row] += sign;
+        }
+        winding_delta = accumulated_winding[0];
+    }
+}
@@ -9,7 +9,7 @@ use crate::flatten::{FlattenCtx, Line};
 use crate::geometry::RectU16;
 use crate::kurbo::{Affine, PathEl, Rect, Stroke};
 use crate::peniko::Fill;
-use crate::strip::Strip;
+use crate::strip::{AntialiasingMode, Strip};
 use crate::tile::Tiles;
 use crate::{flatten, rect, strip};
 use alloc::vec::Vec;
@@ -85,6 +85,7 @@ pub struct StripGenerator {
     stroke_ctx: StrokeCtx,
     temp_storage: StripStorage,
     tiles: Tiles,
+    antialiasing_mode: AntialiasingMode,
     width: u16,
     height: u16,
 }
@@ -99,11 +100,23 @@ impl StripGenerator {
             flatten_ctx: FlattenCtx::default(),
             stroke_ctx: StrokeCtx::default(),
             temp_storage: StripStorage::default(),
+            antialiasing_mode: AntialiasingMode::default(),
             width,
             height,
         }
     }
 
+    /// Get the antialiasing mode used for generating strips.
+    #[inline(always)]
+    pub fn antialiasing_mode(&self) -> AntialiasingMode {
+        self.antialiasing_mode
+    }
+
+    /// Set the antialiasing mode used for generating strips.
+    pub fn set_antialiasing_mode(&mut self, mode: AntialiasingMode) {
+        self.antialiasing_mode = mode;
+    }
+
     /// Get this strip generator's viewport width.
     #[inline(always)]
     pub fn width(&self) -> u16 {
@@ -174,8 +187,14 @@ impl StripGenerator {
         fill_rule: Fill,
         clip_path: Option<PathDataRef<'_>>,
     ) {
-        self.tiles
-            .make_tiles_analytic_aa(self.level, &self.line_buf, self.width, self.height);
+        let antialiasing_mode = self.antialiasing_mode;
+        if antialiasing_mode == AntialiasingMode::Area {
+            self.tiles
+                .make_tiles_analytic_aa(self.level, &self.line_buf, self.width, self.height);
+        } else {
+            self.tiles
+                .make_tiles_msaa(&self.line_buf, self.width, self.height);
+        }
 
         self.tiles.sort_tiles();
 
@@ -188,15 +207,27 @@ impl StripGenerator {
             strip_storage,
             clip_path,
             |strips, alphas| {
-                strip::render(
-                    level,
-                    tiles,
-                    strips,
-                    alphas,
-                    fill_rule,
-                    aliasing_threshold,
-                    line_buf,
-                );
+                if antialiasing_mode == AntialiasingMode::Area {
+                    strip::render(
+                        level,
+                        tiles,
+                        strips,
+                        alphas,
+                        fill_rule,
+                        aliasing_threshold,
+                        line_buf,
+                    );
+                } else {
+                    strip::render_msaa(
+                        tiles,
+                        strips,
+                        alphas,
+                        fill_rule,
+                        aliasing_threshold,
+                        line_buf,
+                        antialiasing_mode,
+                    );
+                }
             },
         );
     }
@@ -283,11 +314,15 @@ fn render_with_clip(
 #[cfg(test)]
 mod tests {
     use alloc::format;
+    use alloc::vec;
+    use alloc::vec::Vec;
 
     use crate::fearless_simd::Level;
-    use crate::kurbo::{Affine, Rect, Shape};
+    use crate::kurbo::{Affine, BezPath, Circle, Rect, Shape};
     use crate::peniko::Fill;
+    use crate::strip::AntialiasingMode;
     use crate::strip_generator::{StripGenerator, StripStorage};
+    use crate::tile::Tile;
 
     #[test]
     fn reset() {
@@ -437,4 +472,95 @@ mod tests {
     fn rect_inverted_both_axes() {
         assert_rect_fast_eq_path(Rect::new(18.0, 18.0, 2.0, 2.0), "inverted_both_axes");
     }
+
+    /// Resolve the strips in `storage` into a `width` x `height` coverage mask.
+    fn coverage_mask(storage: &StripStorage, width: usize, height: usize) -> Vec<u8> {
+        let mut mask = vec![0_u8; width * height];
+        for pair in storage.strips.windows(2) {
+            let (strip, next) = (&pair[0], &pair[1]);
+            let x0 = usize::from(strip.x);
+            let y0 = usize::from(strip.y);
+            let strip_width = usize::from(strip.width_to(next));
+            let alphas = &storage.alphas[strip.alpha_idx() as usize..];
+            for col in 0..strip_width {
+                for row in 0..usize::from(Tile::HEIGHT) {
+                    let (x, y) = (x0 + col, y0 + row);
+                    if x < width && y < height {
+                        mask[y * width + x] = alphas[col * usize::from(Tile::HEIGHT) + row];
+                    }
+                }
+            }
+            if next.fill_gap() && next.strip_y() == strip.strip_y() {
+                for x in x0 + strip_width..usize::from(next.x).min(width) {
+                    for y in y0..(y0 + usize::from(Tile::HEIGHT)).min(height) {
+                        mask[y * width + x] = 255;
+                    }
+                }
+            }
+        }
+        mask
+    }
+
+    fn render_mask(
+        path: &BezPath,
+        fill_rule: Fill,
+        antialiasing_mode: AntialiasingMode,
+    ) -> Vec<u8> {
+        let mut generator = StripGenerator::new(32, 32, Level::baseline());
+        generator.set_antialiasing_mode(antialiasing_mode);
+        let mut storage = StripStorage::default();
+        generator.generate_filled_path(path, fill_rule, Affine::IDENTITY, None, &mut storage, None);
+        coverage_mask(&storage, 32, 32)
+    }
+
+    #[test]
+    fn msaa_pixel_aligned_rect_matches_area() {
+        let path = Rect::new(3.0, 5.0, 21.0, 14.0).to_path(0.1);
+        let expected = render_mask(&path, Fill::NonZero, AntialiasingMode::Area);
+        for mode in [AntialiasingMode::Msaa8, AntialiasingMode::Msaa16] {
+            assert_eq!(
+                render_mask(&path, Fill::NonZero, mode),
+                expected,
+                "{mode:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn msaa_approximates_area_coverage() {
+        let path = Circle::new((15.3, 16.7), 11.2).to_path(0.1);
+        let expected = render_mask(&path, Fill::NonZero, AntialiasingMode::Area);
+        for (mode, samples) in [
+            (AntialiasingMode::Msaa8, 8_u16),
+            (AntialiasingMode::Msaa16, 16),
+        ] {
+            let actual = render_mask(&path, Fill::NonZero, mode);
+            for (i, (&a, &e)) in actual.iter().zip(&expected).enumerate() {
+                // Each sample is worth `255 / samples`. Along an edge, a pixel can
+                // deviate from its exact coverage by a couple of samples.
+                let tolerance = (3 * 255 / samples) as u8;
+                assert!(
+                    a.abs_diff(e) <= tolerance,
+                    "{mode:?}: pixel {i} has coverage {a}, expected {e}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn msaa_even_odd() {
+        // Two overlapping squares with the same winding direction.
+        let mut path = Rect::new(2.0, 2.0, 18.0, 18.0).to_path(0.1);
+        path.extend(Rect::new(10.0, 10.0, 26.0, 26.0).to_path(0.1));
+        for fill_rule in [Fill::NonZero, Fill::EvenOdd] {
+            let expected = render_mask(&path, fill_rule, AntialiasingMode::Area);
+            for mode in [AntialiasingMode::Msaa8, AntialiasingMode::Msaa16] {
+                assert_eq!(
+                    render_mask(&path, fill_rule, mode),
+                    expected,
+                    "{fill_rule:?}, {mode:?}"
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,787 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Translation of SVG `filter` elements into [`FilterGraph`]s.
+//!
+//! The following filter primitives are supported: `feBlend`, `feColorMatrix`,
+//! `feComponentTransfer`, `feComposite`, `feConvolveMatrix`, `feDisplacementMap`,
+//! `feDropShadow`, `feFlood`, `feGaussianBlur`, `feMerge`, `feMorphology`, `feOffset`,
+//! `feTile` and `feTurbulence`. `feMerge` is expressed as a chain of `over` composites.
+//!
+//! Note that the renderers currently only support filter graphs consisting of a single
+//! primitive. [`SvgFilter::resolve`] therefore also recognizes the common ways of
+//! building a drop shadow out of several primitives, and turns them into a single
+//! [`FilterPrimitive::DropShadow`].
+
+use crate::color::palette::css::BLACK;
+use crate::color::{AlphaColor, Srgb};
+use crate::filter_effects::{
+    ColorChannel, CompositeOperator, ConvolutionKernel, EdgeMode, Filter, FilterGraph, FilterId,
+    FilterInput, FilterInputs, FilterPrimitive, FilterSource, MorphologyOperator, TransferFunction,
+    TurbulenceType,
+};
+use crate::kurbo::Rect;
+#[cfg(not(feature = "std"))]
+use crate::kurbo::common::FloatFuncs as _;
+use crate::peniko::Mix;
+use crate::pico_svg::{SvgError, parse_color, parse_number_list, parse_opacity};
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use roxmltree::Node;
+
+/// The coordinate system of the lengths in filter primitives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrimitiveUnits {
+    /// Lengths are in the user space of the filtered element.
+    UserSpaceOnUse,
+    /// Lengths are fractions of the bounding box of the filtered element.
+    ObjectBoundingBox,
+}
+
+/// An SVG `filter` element, translated into a filter graph.
+///
+/// The filter region is not taken into account.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgFilter {
+    /// The filter graph, with lengths in `primitive_units`.
+    pub graph: FilterGraph,
+    /// The coordinate system of the lengths in `graph`.
+    pub primitive_units: PrimitiveUnits,
+}
+
+impl SvgFilter {
+    /// Translate a `filter` element into a filter graph.
+    ///
+    /// Returns `Ok(None)` if `node` is not a `filter` element, if it has no primitives,
+    /// or if it uses primitives which are not supported.
+    pub fn parse(node: Node<'_, '_>) -> Result<Option<Self>, SvgError> {
+        if !node.has_tag_name("filter") {
+            return Ok(None);
+        }
+        let primitive_units = match node.attribute("primitiveUnits") {
+            None | Some("userSpaceOnUse") => PrimitiveUnits::UserSpaceOnUse,
+            Some("objectBoundingBox") => PrimitiveUnits::ObjectBoundingBox,
+            Some(value) => return Err(SvgError::invalid_attribute("primitiveUnits", value)),
+        };
+
+        let mut builder = GraphBuilder::default();
+        for child in node.children().filter(Node::is_element) {
+            let Some(id) = builder.add_primitive(child)? else {
+                return Ok(None);
+            };
+            if let Some(result) = child.attribute("result") {
+                builder.results.insert(result, id);
+            }
+            builder.previous = Some(id);
+        }
+        let Some(output) = builder.previous else {
+            return Ok(None);
+        };
+        let mut graph = builder.graph;
+        graph.set_output(output);
+        Ok(Some(Self {
+            graph,
+            primitive_units,
+        }))
+    }
+
+    /// Resolve the filter for an element with the given bounding box.
+    ///
+    /// Returns `None` if the filter cannot be rendered, either because the bounding box
+    /// is needed but empty, or because the graph cannot be simplified into a single
+    /// primitive.
+    pub fn resolve(&self, bbox: Option<Rect>) -> Option<Filter> {
+        let primitive = match self.primitive_units {
+            PrimitiveUnits::UserSpaceOnUse => simplify(&self.graph)?,
+            PrimitiveUnits::ObjectBoundingBox => {
+                let bbox = bbox.filter(|bbox| bbox.width() > 0.0 && bbox.height() > 0.0)?;
+                let (sx, sy) = (bbox.width() as f32, bbox.height() as f32);
+                let mut graph = FilterGraph::new();
+                for (primitive, inputs) in self.graph.primitives.iter().zip(&self.graph.inputs) {
+                    graph.add(scale_primitive(primitive, sx, sy), inputs.clone());
+                }
+                graph.set_output(self.graph.output);
+                simplify(&graph)?
+            }
+        };
+        Some(Filter::from_primitive(primitive))
+    }
+}
+
+#[derive(Default)]
+struct GraphBuilder<'a> {
+    graph: FilterGraph,
+    results: HashMap<&'a str, FilterId>,
+    previous: Option<FilterId>,
+}
+
+impl<'a> GraphBuilder<'a> {
+    /// Resolve an `in` or `in2` attribute.
+    fn input(&self, node: Node<'a, '_>, attribute: &str) -> FilterInput {
+        let source = match node.attribute(attribute) {
+            Some("SourceGraphic") => FilterSource::SourceGraphic,
+            Some("SourceAlpha") => FilterSource::SourceAlpha,
+            Some("BackgroundImage") => FilterSource::BackgroundImage,
+            Some("BackgroundAlpha") => FilterSource::BackgroundAlpha,
+            Some("FillPaint") => FilterSource::FillPaint,
+            Some("StrokePaint") => FilterSource::StrokePaint,
+            // References to non-existent results are treated as if no input was given.
+            name => match name
+                .and_then(|name| self.results.get(name))
+                .or(self.previous.as_ref())
+            {
+                Some(id) => return FilterInput::Result(*id),
+                None => FilterSource::SourceGraphic,
+            },
+        };
+        FilterInput::Source(source)
+    }
+
+    fn add(&mut self, primitive: FilterPrimitive, inputs: FilterInputs) -> FilterId {
+        self.graph.add(primitive, Some(inputs))
+    }
+
+    /// Add the primitive described by `node`, returning `Ok(None)` if it is not supported.
+    fn add_primitive(&mut self, node: Node<'a, '_>) -> Result<Option<FilterId>, SvgError> {
+        let single = FilterInputs::single(self.input(node, "in"));
+        let dual = || FilterInputs::dual(self.input(node, "in"), self.input(node, "in2"));
+        let primitive = match node.tag_name().name() {
+            "feFlood" => FilterPrimitive::Flood {
+                color: flood_color(node)?,
+            },
+            "feGaussianBlur" => FilterPrimitive::GaussianBlur {
+                std_deviation: std_deviation(node, 0.0)?,
+                edge_mode: edge_mode(node, EdgeMode::None)?,
+            },
+            "feOffset" => FilterPrimitive::Offset {
+                dx: number(node, "dx", 0.0)?,
+                dy: number(node, "dy", 0.0)?,
+            },
+            "feDropShadow" => FilterPrimitive::DropShadow {
+                dx: number(node, "dx", 2.0)?,
+                dy: number(node, "dy", 2.0)?,
+                std_deviation: std_deviation(node, 2.0)?,
+                color: flood_color(node)?,
+                edge_mode: EdgeMode::None,
+            },
+            "feComposite" => {
+                let operator = match node.attribute("operator") {
+                    None | Some("over") => CompositeOperator::Over,
+                    Some("in") => CompositeOperator::In,
+                    Some("out") => CompositeOperator::Out,
+                    Some("atop") => CompositeOperator::Atop,
+                    Some("xor") => CompositeOperator::Xor,
+                    Some("arithmetic") => CompositeOperator::Arithmetic {
+                        k1: number(node, "k1", 0.0)?,
+                        k2: number(node, "k2", 0.0)?,
+                        k3: number(node, "k3", 0.0)?,
+                        k4: number(node, "k4", 0.0)?,
+                    },
+                    Some(value) => return Err(SvgError::invalid_attribute("operator", value)),
+                };
+                let inputs = dual();
+                return Ok(Some(
+                    self.add(FilterPrimitive::Composite { operator }, inputs),
+                ));
+            }
+            "feBlend" => {
+                let mode = blend_mode(node)?;
+                let inputs = dual();
+                return Ok(Some(self.add(FilterPrimitive::Blend { mode }, inputs)));
+            }
+            "feDisplacementMap" => {
+                let primitive = FilterPrimitive::DisplacementMap {
+                    scale: number(node, "scale", 0.0)?,
+                    x_channel: channel(node, "xChannelSelector")?,
+                    y_channel: channel(node, "yChannelSelector")?,
+                };
+                let inputs = dual();
+                return Ok(Some(self.add(primitive, inputs)));
+            }
+            "feMerge" => return Ok(Some(self.add_merge(node))),
+            "feColorMatrix" => FilterPrimitive::ColorMatrix {
+                matrix: color_matrix(node)?,
+            },
+            "feMorphology" => FilterPrimitive::Morphology {
+                operator: match node.attribute("operator") {
+                    None | Some("erode") => MorphologyOperator::Erode,
+                    Some("dilate") => MorphologyOperator::Dilate,
+                    Some(value) => return Err(SvgError::invalid_attribute("operator", value)),
+                },
+                radius: number_pair(node, "radius", 0.0)?.0,
+            },
+            "feComponentTransfer" => {
+                let function = |name: &str| {
+                    node.children()
+                        .rfind(|child| child.has_tag_name(name))
+                        .map(transfer_function)
+                        .transpose()
+                };
+                FilterPrimitive::ComponentTransfer {
+                    red_function: function("feFuncR")?,
+                    green_function: function("feFuncG")?,
+                    blue_function: function("feFuncB")?,
+                    alpha_function: function("feFuncA")?,
+                }
+            }
+            "feTurbulence" => {
+                let primitive = FilterPrimitive::Turbulence {
+                    base_frequency: number_pair(node, "baseFrequency", 0.0)?.0,
+                    num_octaves: number(node, "numOctaves", 1.0)?.max(0.0) as u32,
+                    seed: number(node, "seed", 0.0)?.round() as u32,
+                    turbulence_type: match node.attribute("type") {
+                        None | Some("turbulence") => TurbulenceType::Turbulence,
+                        Some("fractalNoise") => TurbulenceType::FractalNoise,
+                        Some(value) => return Err(SvgError::invalid_attribute("type", value)),
+                    },
+                };
+                // Turbulence doesn't have any inputs.
+                return Ok(Some(self.graph.add(primitive, None)));
+            }
+            "feTile" => FilterPrimitive::Tile,
+            "feConvolveMatrix" => match convolution_kernel(node)? {
+                Some(kernel) => FilterPrimitive::ConvolveMatrix { kernel },
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+        Ok(Some(self.add(primitive, single)))
+    }
+
+    /// Add an `feMerge` element, which composites its inputs on top of each other.
+    fn add_merge(&mut self, node: Node<'a, '_>) -> FilterId {
+        let mut merged = None;
+        for merge_node in node.children().filter(|n| n.has_tag_name("feMergeNode")) {
+            let input = self.input(merge_node, "in");
+            merged = Some(match merged {
+                None => input,
+                Some(below) => FilterInput::Result(self.add(
+                    FilterPrimitive::Composite {
+                        operator: CompositeOperator::Over,
+                    },
+                    FilterInputs::dual(input, below),
+                )),
+            });
+        }
+        match merged {
+            Some(FilterInput::Result(id)) => id,
+            // An offset of zero simply passes through its input.
+            Some(input) => self.add(
+                FilterPrimitive::Offset { dx: 0.0, dy: 0.0 },
+                FilterInputs::single(input),
+            ),
+            None => self.graph.add(
+                FilterPrimitive::Flood {
+                    color: AlphaColor::TRANSPARENT,
+                },
+                None,
+            ),
+        }
+    }
+}
+
+fn number(node: Node<'_, '_>, attribute: &str, default: f32) -> Result<f32, SvgError> {
+    match node.attribute(attribute) {
+        None => Ok(default),
+        Some(value) => value
+            .trim()
+            .parse()
+            .ok()
+            .filter(|number: &f32| number.is_finite())
+            .ok_or_else(|| SvgError::invalid_attribute(attribute, value)),
+    }
+}
+
+/// Parse an attribute consisting of one or two numbers, where the second defaults to the first.
+fn number_pair(node: Node<'_, '_>, attribute: &str, default: f32) -> Result<(f32, f32), SvgError> {
+    let Some(value) = node.attribute(attribute) else {
+        return Ok((default, default));
+    };
+    match parse_number_list(value).as_deref() {
+        Some(&[x]) => Ok((x as f32, x as f32)),
+        Some(&[x, y]) => Ok((x as f32, y as f32)),
+        _ => Err(SvgError::invalid_attribute(attribute, value)),
+    }
+}
+
+/// Parse the `stdDeviation` attribute.
+///
+/// Since only uniform blurs are supported, different deviations for both axes are averaged.
+fn std_deviation(node: Node<'_, '_>, default: f32) -> Result<f32, SvgError> {
+    let (x, y) = number_pair(node, "stdDeviation", default)?;
+    if x < 0.0 || y < 0.0 {
+        let value = node.attribute("stdDeviation").unwrap_or_default();
+        return Err(SvgError::invalid_attribute("stdDeviation", value));
+    }
+    Ok((x + y) / 2.0)
+}
+
+fn edge_mode(node: Node<'_, '_>, default: EdgeMode) -> Result<EdgeMode, SvgError> {
+    match node.attribute("edgeMode") {
+        None => Ok(default),
+        Some("duplicate") => Ok(EdgeMode::Duplicate),
+        Some("wrap") => Ok(EdgeMode::Wrap),
+        Some("mirror") => Ok(EdgeMode::Mirror),
+        Some("none") => Ok(EdgeMode::None),
+        Some(value) => Err(SvgError::invalid_attribute("edgeMode", value)),
+    }
+}
+
+/// Look up a property, either in the `style` attribute or as a presentation attribute.
+fn property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').rev().find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    });
+    from_style.or_else(|| node.attribute(name).map(str::trim))
+}
+
+/// The `flood-color` and `flood-opacity` of an element.
+fn flood_color(node: Node<'_, '_>) -> Result<AlphaColor<Srgb>, SvgError> {
+    let color = match property(node, "flood-color") {
+        None => BLACK,
+        Some(value) => {
+            parse_color(value).ok_or_else(|| SvgError::invalid_attribute("flood-color", value))?
+        }
+    };
+    let opacity = match property(node, "flood-opacity") {
+        None => 1.0,
+        Some(value) => parse_opacity(value)
+            .ok_or_else(|| SvgError::invalid_attribute("flood-opacity", value))?,
+    };
+    Ok(color.multiply_alpha(opacity))
+}
+
+fn blend_mode(node: Node<'_, '_>) -> Result<Mix, SvgError> {
+    Ok(match node.attribute("mode") {
+        None | Some("normal") => Mix::Normal,
+        Some("multiply") => Mix::Multiply,
+        Some("screen") => Mix::Screen,
+        Some("overlay") => Mix::Overlay,
+        Some("darken") => Mix::Darken,
+        Some("lighten") => Mix::Lighten,
+        Some("color-dodge") => Mix::ColorDodge,
+        Some("color-burn") => Mix::ColorBurn,
+        Some("hard-light") => Mix::HardLight,
+        Some("soft-light") => Mix::SoftLight,
+        Some("difference") => Mix::Difference,
+        Some("exclusion") => Mix::Exclusion,
+        Some("hue") => Mix::Hue,
+        Some("saturation") => Mix::Saturation,
+        Some("color") => Mix::Color,
+        Some("luminosity") => Mix::Luminosity,
+        Some(value) => return Err(SvgError::invalid_attribute("mode", value)),
+    })
+}
+
+fn channel(node: Node<'_, '_>, attribute: &str) -> Result<ColorChannel, SvgError> {
+    match node.attribute(attribute) {
+        Some("R") => Ok(ColorChannel::Red),
+        Some("G") => Ok(ColorChannel::Green),
+        Some("B") => Ok(ColorChannel::Blue),
+        None | Some("A") => Ok(ColorChannel::Alpha),
+        Some(value) => Err(SvgError::invalid_attribute(attribute, value)),
+    }
+}
+
+fn color_matrix(node: Node<'_, '_>) -> Result<[f32; 20], SvgError> {
+    let values = node.attribute("values");
+    let invalid = || SvgError::invalid_attribute("values", values.unwrap_or_default());
+    let numbers = values
+        .map(|values| parse_number_list(values).ok_or_else(invalid))
+        .transpose()?;
+    let single = |default: f64| match numbers.as_deref() {
+        None => Ok(default as f32),
+        Some(&[value]) => Ok(value as f32),
+        Some(_) => Err(invalid()),
+    };
+    Ok(match node.attribute("type") {
+        None | Some("matrix") => match numbers {
+            None => crate::filter_effects::matrices::IDENTITY,
+            Some(numbers) => {
+                let numbers: Vec<f32> = numbers.into_iter().map(|n| n as f32).collect();
+                numbers.try_into().map_err(|_| invalid())?
+            }
+        },
+        Some("saturate") => {
+            let s = single(1.0)?;
+            #[rustfmt::skip]
+            let matrix = [
+                0.213 + 0.787 * s, 0.715 - 0.715 * s, 0.072 - 0.072 * s, 0.0, 0.0,
+                0.213 - 0.213 * s, 0.715 + 0.285 * s, 0.072 - 0.072 * s, 0.0, 0.0,
+                0.213 - 0.213 * s, 0.715 - 0.715 * s, 0.072 + 0.928 * s, 0.0, 0.0,
+                0.0, 0.0, 0.0, 1.0, 0.0,
+            ];
+            matrix
+        }
+        Some("hueRotate") => {
+            let (sin, cos) = single(0.0)?.to_radians().sin_cos();
+            #[rustfmt::skip]
+            let matrix = [
+                0.213 + cos * 0.787 - sin * 0.213,
+                0.715 - cos * 0.715 - sin * 0.715,
+                0.072 - cos * 0.072 + sin * 0.928,
+                0.0, 0.0,
+                0.213 - cos * 0.213 + sin * 0.143,
+                0.715 + cos * 0.285 + sin * 0.140,
+                0.072 - cos * 0.072 - sin * 0.283,
+                0.0, 0.0,
+                0.213 - cos * 0.213 - sin * 0.787,
+                0.715 - cos * 0.715 + sin * 0.715,
+                0.072 + cos * 0.928 + sin * 0.072,
+                0.0, 0.0,
+                0.0, 0.0, 0.0, 1.0, 0.0,
+            ];
+            matrix
+        }
+        #[rustfmt::skip]
+        Some("luminanceToAlpha") => [
+            0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0,
+            0.0, 0.0, 0.0, 0.0, 0.0,
+            0.2125, 0.7154, 0.0721, 0.0, 0.0,
+        ],
+        Some(value) => return Err(SvgError::invalid_attribute("type", value)),
+    })
+}
+
+/// Parse a `feFuncR`, `feFuncG`, `feFuncB` or `feFuncA` element.
+fn transfer_function(node: Node<'_, '_>) -> Result<TransferFunction, SvgError> {
+    let table = || {
+        let values = node.attribute("tableValues").unwrap_or_default();
+        parse_number_list(values)
+            .map(|values| values.into_iter().map(|v| v as f32).collect::<Vec<_>>())
+            .ok_or_else(|| SvgError::invalid_attribute("tableValues", values))
+    };
+    Ok(match node.attribute("type") {
+        None | Some("identity") => TransferFunction::Identity,
+        Some("table") => {
+            let values = table()?;
+            if values.is_empty() {
+                TransferFunction::Identity
+            } else {
+                TransferFunction::Table { values }
+            }
+        }
+        Some("discrete") => {
+            let values = table()?;
+            if values.is_empty() {
+                TransferFunction::Identity
+            } else {
+                TransferFunction::Discrete { values }
+            }
+        }
+        Some("linear") => TransferFunction::Linear {
+            slope: number(node, "slope", 1.0)?,
+            intercept: number(node, "intercept", 0.0)?,
+        },
+        Some("gamma") => TransferFunction::Gamma {
+            amplitude: number(node, "amplitude", 1.0)?,
+            exponent: number(node, "exponent", 1.0)?,
+            offset: number(node, "offset", 0.0)?,
+        },
+        Some(value) => return Err(SvgError::invalid_attribute("type", value)),
+    })
+}
+
+/// Parse the kernel of an `feConvolveMatrix` element.
+///
+/// Returns `Ok(None)` for non-square kernels, which are not supported.
+fn convolution_kernel(node: Node<'_, '_>) -> Result<Option<ConvolutionKernel>, SvgError> {
+    let (width, height) = number_pair(node, "order", 3.0)?;
+    if width != height || width < 1.0 || width.fract() != 0.0 {
+        return Ok(None);
+    }
+    let size = width as u32;
+    let len = usize::try_from(size)
+        .ok()
+        .and_then(|size| size.checked_mul(size));
+    let matrix = node.attribute("kernelMatrix").unwrap_or_default();
+    let values: Vec<f32> = parse_number_list(matrix)
+        .filter(|values| Some(values.len()) == len)
+        .ok_or_else(|| SvgError::invalid_attribute("kernelMatrix", matrix))?
+        .into_iter()
+        .map(|v| v as f32)
+        .collect();
+    let sum: f32 = values.iter().sum();
+    let divisor = number(node, "divisor", if sum == 0.0 { 1.0 } else { sum })?;
+    let preserve_alpha = match node.attribute("preserveAlpha") {
+        None | Some("false") => false,
+        Some("true") => true,
+        Some(value) => return Err(SvgError::invalid_attribute("preserveAlpha", value)),
+    };
+    Ok(Some(ConvolutionKernel {
+        size,
+        values,
+        divisor,
+        bias: number(node, "bias", 0.0)?,
+        preserve_alpha,
+    }))
+}
+
+/// Convert the lengths of a primitive from bounding box units, given the size of the box.
+fn scale_primitive(primitive: &FilterPrimitive, sx: f32, sy: f32) -> FilterPrimitive {
+    let s = (sx * sy).sqrt();
+    let mut primitive = primitive.clone();
+    match &mut primitive {
+        FilterPrimitive::GaussianBlur { std_deviation, .. } => *std_deviation *= s,
+        FilterPrimitive::Offset { dx, dy } => {
+            *dx *= sx;
+            *dy *= sy;
+        }
+        FilterPrimitive::DropShadow {
+            dx,
+            dy,
+            std_deviation,
+            ..
+        } => {
+            *dx *= sx;
+            *dy *= sy;
+            *std_deviation *= s;
+        }
+        FilterPrimitive::Morphology { radius, .. } => *radius *= s,
+        FilterPrimitive::DisplacementMap { scale, .. } => *scale *= s,
+        FilterPrimitive::Turbulence { base_frequency, .. } => *base_frequency /= s,
+        _ => {}
+    }
+    primitive
+}
+
+fn is_source(input: Option<&FilterInput>, source: FilterSource) -> bool {
+    matches!(input, Some(FilterInput::Source(s)) if *s == source)
+}
+
+/// Simplify a filter graph into a single primitive applied to the source graphic,
+/// which is what the renderers currently support.
+fn simplify(graph: &FilterGraph) -> Option<FilterPrimitive> {
+    let (primitive, inputs) = graph.get(graph.output)?;
+    let primary = inputs.map(|inputs| &inputs.primary);
+    match primitive {
+        FilterPrimitive::Flood { .. } => Some(primitive.clone()),
+        FilterPrimitive::GaussianBlur { .. }
+        | FilterPrimitive::Offset { .. }
+        | FilterPrimitive::DropShadow { .. }
+            if primary.is_none() || is_source(primary, FilterSource::SourceGraphic) =>
+        {
+            Some(primitive.clone())
+        }
+        // The source graphic on top of a shadow.
+        FilterPrimitive::Composite {
+            operator: CompositeOperator::Over,
+        }
+        | FilterPrimitive::Blend { mode: Mix::Normal }
+            if is_source(primary, FilterSource::SourceGraphic) =>
+        {
+            match inputs?.secondary.as_ref()? {
+                FilterInput::Result(shadow) => drop_shadow(graph, *shadow),
+                FilterInput::Source(_) => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Try to express the result of `id` as the shadow of a drop shadow filter, i.e. a
+/// colored, blurred and offset version of the alpha of the source graphic.
+fn drop_shadow(graph: &FilterGraph, id: FilterId) -> Option<FilterPrimitive> {
+    let mut color = BLACK;
+    let mut current = FilterInput::Result(id);
+    let mut std_deviation = None;
+    let mut edge_mode = EdgeMode::None;
+    let (mut dx, mut dy) = (0.0, 0.0);
+    // Whether only the alpha channel of the source matters.
+    let mut alpha_only = false;
+    let mut colored = false;
+
+    while let FilterInput::Result(id) = current {
+        let (primitive, inputs) = graph.get(id)?;
+        let primary = inputs.map_or(FilterInput::Source(FilterSource::SourceGraphic), |inputs| {
+            inputs.primary.clone()
+        });
+        match primitive {
+            FilterPrimitive::GaussianBlur {
+                std_deviation: sd,
+                edge_mode: mode,
+            } if std_deviation.is_none() => {
+                std_deviation = Some(*sd);
+                edge_mode = *mode;
+                current = primary;
+            }
+            FilterPrimitive::Offset { dx: x, dy: y } => {
+                dx += x;
+                dy += y;
+                current = primary;
+            }
+            // A flood color restricted to the shape of the shadow.
+            FilterPrimitive::Composite {
+                operator: CompositeOperator::In,
+            } if !colored => {
+                let FilterInput::Result(flood) = primary else {
+                    return None;
+                };
+                let (FilterPrimitive::Flood { color: flood }, _) = graph.get(flood)? else {
+                    return None;
+                };
+                color = *flood;
+                colored = true;
+                alpha_only = true;
+                current = inputs?.secondary.clone()?;
+            }
+            // A color matrix which replaces the color, keeping a multiple of the alpha.
+            FilterPrimitive::ColorMatrix { matrix } if !colored => {
+                let rgb_from_input = (0..3).any(|row| matrix[row * 5..row * 5 + 4] != [0.0; 4]);
+                if rgb_from_input || matrix[15..18] != [0.0; 3] || matrix[19] != 0.0 {
+                    return None;
+                }
+                color = AlphaColor::new([matrix[4], matrix[9], matrix[14], matrix[18]]);
+                colored = true;
+                alpha_only = true;
+                current = primary;
+            }
+            _ => return None,
+        }
+    }
+
+    match current {
+        FilterInput::Source(FilterSource::SourceAlpha) => {}
+        FilterInput::Source(FilterSource::SourceGraphic) if alpha_only => {}
+        _ => return None,
+    }
+    Some(FilterPrimitive::DropShadow {
+        dx,
+        dy,
+        std_deviation: std_deviation.unwrap_or(0.0),
+        color,
+        edge_mode,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrimitiveUnits, SvgFilter};
+    use crate::color::palette::css::{BLACK, RED};
+    use crate::filter_effects::{
+        EdgeMode, FilterId, FilterInput, FilterInputs, FilterPrimitive, FilterSource,
+    };
+    use crate::kurbo::Rect;
+    use roxmltree::Document;
+
+    fn parse(xml: &str) -> Option<SvgFilter> {
+        let doc = Document::parse(xml).unwrap();
+        SvgFilter::parse(doc.root_element()).unwrap()
+    }
+
+    fn single_primitive(filter: &SvgFilter, bbox: Option<Rect>) -> FilterPrimitive {
+        let filter = filter.resolve(bbox).unwrap();
+        assert_eq!(filter.graph.primitives.len(), 1);
+        filter.graph.primitives[0].clone()
+    }
+
+    #[test]
+    fn named_results() {
+        let filter = parse(
+            r#"<filter>
+                <feOffset in="SourceAlpha" dx="3" result="offset"/>
+                <feFlood flood-color="red"/>
+                <feComposite in="offset" in2="SourceGraphic" operator="xor"/>
+            </filter>"#,
+        )
+        .unwrap();
+        assert_eq!(filter.graph.output, FilterId(2));
+        assert_eq!(
+            filter.graph.inputs[0],
+            Some(FilterInputs::single(FilterInput::Source(
+                FilterSource::SourceAlpha
+            )))
+        );
+        assert_eq!(
+            filter.graph.inputs[2],
+            Some(FilterInputs::dual(
+                FilterInput::Result(FilterId(0)),
+                FilterInput::Source(FilterSource::SourceGraphic),
+            ))
+        );
+        // This graph can't be rendered yet.
+        assert!(filter.resolve(None).is_none());
+    }
+
+    #[test]
+    fn merge_drop_shadow() {
+        let filter = parse(
+            r#"<filter>
+                <feGaussianBlur in="SourceAlpha" stdDeviation="2"/>
+                <feOffset dx="4" dy="5" result="shadow"/>
+                <feMerge>
+                    <feMergeNode in="shadow"/>
+                    <feMergeNode in="SourceGraphic"/>
+                </feMerge>
+            </filter>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            single_primitive(&filter, None),
+            FilterPrimitive::DropShadow {
+                dx: 4.0,
+                dy: 5.0,
+                std_deviation: 2.0,
+                color: BLACK,
+                edge_mode: EdgeMode::None,
+            }
+        );
+    }
+
+    #[test]
+    fn colored_drop_shadow() {
+        let filter = parse(
+            r#"<filter>
+                <feOffset in="SourceGraphic" dx="1" result="offset"/>
+                <feFlood flood-color="red" flood-opacity="0.5"/>
+                <feComposite in2="offset" operator="in"/>
+                <feComposite in="SourceGraphic"/>
+            </filter>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            single_primitive(&filter, None),
+            FilterPrimitive::DropShadow {
+                dx: 1.0,
+                dy: 0.0,
+                std_deviation: 0.0,
+                color: RED.with_alpha(0.5),
+                edge_mode: EdgeMode::None,
+            }
+        );
+    }
+
+    #[test]
+    fn bounding_box_units() {
+        let filter = parse(
+            r#"<filter primitiveUnits="objectBoundingBox">
+                <feOffset dx="0.5" dy="0.25"/>
+            </filter>"#,
+        )
+        .unwrap();
+        assert_eq!(filter.primitive_units, PrimitiveUnits::ObjectBoundingBox);
+        assert!(filter.resolve(None).is_none());
+        assert_eq!(
+            single_primitive(&filter, Some(Rect::new(10.0, 10.0, 30.0, 50.0))),
+            FilterPrimitive::Offset { dx: 10.0, dy: 10.0 }
+        );
+    }
+
+    #[test]
+    fn unsupported_primitives() {
+        assert!(parse(r#"<filter><feImage href="a.png"/></filter>"#).is_none());
+        assert!(parse("<filter/>").is_none());
+    }
+
+    #[test]
+    fn invalid_numbers() {
+        let parse = |xml| SvgFilter::parse(Document::parse(xml).unwrap().root_element());
+        assert!(parse(r#"<filter><feOffset dx="NaN"/></filter>"#).is_err());
+        assert!(parse(r#"<filter><feOffset dy="inf"/></filter>"#).is_err());
+        assert!(parse(r#"<filter><feConvolveMatrix order="65536"/></filter>"#).is_err());
+    }
+}
@@ -8,7 +8,8 @@
 use vello_cpu::color::palette::css::YELLOW;
 use vello_cpu::kurbo::Affine;
 use vello_cpu::{
-    Level, Pixmap, RasterizerSettings, RenderContext, RenderMode, RenderSettings, Resources,
+    AntialiasingMode, Level, Pixmap, RasterizerSettings, RenderContext, RenderMode, RenderSettings,
+    Resources,
     color::palette::css::{BLUE, GREEN, RED},
     kurbo::{Circle, Rect, Shape},
 };
@@ -52,6 +53,15 @@ fn main() {
         // using 4+ threads might result in diminishing results, depending on
         // the workload.
         num_threads: 0,
+        // The method used to anti-alias the edges of paths. `Area` computes the exact
+        // coverage of each pixel, while the MSAA modes sample each pixel at multiple
+        // points, which avoids conflation artifacts (visible seams) where edges of a
+        // path coincide.
+        antialiasing_mode: AntialiasingMode::Area,
+        // Whether gradients and blurred rounded rectangles should be dithered. This
+        // hides the banding that large gradients with a low contrast can show, at the
+        // cost of some performance.
+        dithering: false,
     };
     let rasterizer_settings = RasterizerSettings {
         // Define whether the renderer should prioritize speed or quality
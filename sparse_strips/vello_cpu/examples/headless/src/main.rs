@@ -0,0 +1,315 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Renders our example scenes, SVG files or captured scenes to PNG with Vello CPU, without
+//! needing a GPU.
+
+#![allow(
+    clippy::cast_possible_truncation,
+    reason = "output sizes are far below the limits of u16"
+)]
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use clap::{Parser, ValueEnum};
+use fearless_simd::Level;
+use vello_common::kurbo::{Affine, Stroke};
+use vello_common::paint::ImageSource;
+use vello_common::pico_svg::{Item, PicoSvg};
+use vello_cpu::svg::Svg;
+use vello_cpu::{
+    Pixmap, RasterizerSettings, RenderContext, RenderMode, RenderSettings, Resources,
+    TextureBindings,
+};
+use vello_example_scenes::image::ImageScene;
+use vello_example_scenes::spritesheet::{SPRITESHEET_TEXTURE_ID, SpritesheetScene};
+use vello_example_scenes::{AnyScene, Capabilities, get_example_scenes};
+
+#[derive(Parser, Debug)]
+#[command(about, long_about = None)]
+struct Args {
+    /// The index of the example scene to render.
+    #[arg(long, default_value_t = 0, conflicts_with_all = ["svg", "capture"])]
+    scene: usize,
+    /// Render the given SVG file instead of an example scene.
+    #[arg(long, conflicts_with = "capture")]
+    svg: Option<PathBuf>,
+    /// Render a scene serialized with `RenderContext::start_svg_capture` instead of an
+    /// example scene.
+    #[arg(long)]
+    capture: Option<PathBuf>,
+    /// Print the available example scenes and exit.
+    #[arg(long)]
+    list: bool,
+    /// The path of the PNG file to write.
+    #[arg(short, long, default_value = "output.png")]
+    output: PathBuf,
+    /// The width of the output, defaulting to 1800 for example scenes and to the
+    /// scaled size of the document for SVG files and captured scenes.
+    #[arg(long)]
+    width: Option<u16>,
+    /// The height of the output, defaulting to 1200 for example scenes and to the
+    /// scaled size of the document for SVG files and captured scenes.
+    #[arg(long)]
+    height: Option<u16>,
+    /// The factor by which the content is scaled.
+    #[arg(long, default_value_t = 1.0)]
+    scale: f64,
+    /// Whether to use the u8 (speed) or the f32 (quality) pipeline.
+    #[arg(long, value_enum, default_value_t = Mode::Speed)]
+    render_mode: Mode,
+    /// The number of worker threads, 0 disables multi-threading.
+    ///
+    /// Multi-threaded rendering doesn't support filter effects yet, so scenes using them
+    /// are only listed without worker threads, and SVG files using them are rejected.
+    #[arg(long, default_value_t = 0)]
+    threads: u16,
+    /// The SIMD level used for rendering.
+    #[arg(long, value_enum, default_value_t = SimdLevel::Detect)]
+    level: SimdLevel,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Mode {
+    Speed,
+    Quality,
+}
+
+impl From<Mode> for RenderMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Speed => Self::OptimizeSpeed,
+            Mode::Quality => Self::OptimizeQuality,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SimdLevel {
+    /// The highest level supported by the host.
+    Detect,
+    /// No SIMD instructions.
+    Fallback,
+    Sse42,
+    Avx2,
+    Neon,
+    WasmSimd128,
+}
+
+impl SimdLevel {
+    fn to_level(self) -> Result<Level> {
+        let detected = Level::try_detect().unwrap_or(Level::baseline());
+        let level = match self {
+            Self::Detect => Some(detected),
+            Self::Fallback => Some(Level::fallback()),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::Sse42 => detected.as_sse4_2().map(Level::Sse4_2),
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Self::Avx2 => detected.as_avx2().map(Level::Avx2),
+            #[cfg(target_arch = "aarch64")]
+            Self::Neon => detected.as_neon().map(Level::Neon),
+            #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+            Self::WasmSimd128 => detected.as_wasm_simd128().map(Level::WasmSimd128),
+            _ => None,
+        };
+        level.with_context(|| format!("the SIMD level {self:?} is not available on this host"))
+    }
+}
+
+/// Wall-clock time spent in each stage of rendering.
+#[derive(Debug, Default)]
+struct Timings {
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    fn measure<T>(&mut self, stage: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.stages.push((stage, start.elapsed()));
+        result
+    }
+
+    fn report(&self) -> String {
+        let mut report = String::new();
+        let total: Duration = self.stages.iter().map(|(_, duration)| *duration).sum();
+        for (stage, duration) in self.stages.iter().chain([&("total", total)]) {
+            let millis = duration.as_secs_f64() * 1000.0;
+            writeln!(report, "{stage:>10}: {millis:>9.3} ms").unwrap();
+        }
+        report
+    }
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    if args.list {
+        for (idx, scene) in example_scenes(args.threads).iter().enumerate() {
+            println!("{idx}: {}", scene.name());
+        }
+        return Ok(());
+    }
+    if !(args.scale.is_finite() && args.scale > 0.0) {
+        bail!("the scale must be a positive number");
+    }
+
+    let mut timings = Timings::default();
+    let settings = RenderSettings {
+        level: args.level.to_level()?,
+        num_threads: args.threads,
+        ..Default::default()
+    };
+
+    let (mut ctx, mut resources) = match (&args.svg, &args.capture) {
+        (Some(path), _) => {
+            let svg = timings.measure("parse", || -> Result<PicoSvg> {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                PicoSvg::load(&content, args.scale)
+                    .with_context(|| format!("failed to parse {}", path.display()))
+            })?;
+            if args.threads > 0 && uses_filters(&svg.items) {
+                bail!("{} uses filter effects, which require --threads 0", path.display());
+            }
+            let width = args
+                .width
+                .unwrap_or((svg.size.width * args.scale).ceil() as u16);
+            let height = args
+                .height
+                .unwrap_or((svg.size.height * args.scale).ceil() as u16);
+            let mut ctx = new_context(width, height, settings)?;
+            timings.measure("encode", || {
+                render_svg(&mut ctx, &svg.items, Affine::IDENTITY);
+            });
+            (ctx, Resources::new())
+        }
+        (None, Some(path)) => {
+            let svg = timings.measure("parse", || -> Result<Svg> {
+                let content = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                Svg::parse(&content).with_context(|| format!("failed to parse {}", path.display()))
+            })?;
+            if args.threads > 0 && svg.uses_filters() {
+                bail!("{} uses filter effects, which require --threads 0", path.display());
+            }
+            let size = svg.size();
+            let width = args.width.unwrap_or((size.width * args.scale).ceil() as u16);
+            let height = args
+                .height
+                .unwrap_or((size.height * args.scale).ceil() as u16);
+            let mut ctx = new_context(width, height, settings)?;
+            timings.measure("encode", || {
+                ctx.set_transform(Affine::scale(args.scale));
+                svg.render(&mut ctx);
+            });
+            (ctx, Resources::new())
+        }
+        (None, None) => {
+            let mut scenes = example_scenes(args.threads);
+            let count = scenes.len();
+            let Some(scene) = scenes.get_mut(args.scene) else {
+                bail!(
+                    "there is no scene {}, the last one is {}",
+                    args.scene,
+                    count - 1
+                );
+            };
+            let width = args.width.unwrap_or(1800);
+            let height = args.height.unwrap_or(1200);
+            let mut ctx = new_context(width, height, settings)?;
+            timings.measure("encode", || {
+                scene.render(&mut ctx, Affine::scale(args.scale));
+            });
+            let resources = std::mem::take(scene.resources_mut());
+            (ctx, resources)
+        }
+    };
+
+    let spritesheet = SpritesheetScene::read_spritesheet();
+    let mut textures = TextureBindings::new();
+    textures.insert(SPRITESHEET_TEXTURE_ID, &spritesheet);
+
+    let mut pixmap = Pixmap::new(ctx.width(), ctx.height());
+    timings.measure("flush", || ctx.flush());
+    timings.measure("rasterize", || {
+        ctx.render_with(
+            &mut pixmap,
+            &mut resources,
+            RasterizerSettings {
+                render_mode: args.render_mode.into(),
+                textures: Some(&textures),
+                ..Default::default()
+            },
+        );
+    });
+    let png = timings.measure("png", || pixmap.into_png())?;
+    timings
+        .measure("write", || std::fs::write(&args.output, png))
+        .with_context(|| format!("failed to write {}", args.output.display()))?;
+
+    print!("{}", timings.report());
+    println!("Wrote result to {}", args.output.display());
+    Ok(())
+}
+
+fn example_scenes(threads: u16) -> Box<[AnyScene<RenderContext>]> {
+    let img_sources = vec![
+        ImageSource::Pixmap(Arc::new(ImageScene::read_flower_image())),
+        ImageSource::Pixmap(Arc::new(ImageScene::read_cowboy_image())),
+    ];
+    let capabilities = Capabilities {
+        external_textures: true,
+        filter_effects: threads == 0,
+    };
+    get_example_scenes(capabilities, None, img_sources)
+}
+
+fn new_context(width: u16, height: u16, settings: RenderSettings) -> Result<RenderContext> {
+    if width == 0 || height == 0 {
+        bail!("the output must not be empty, but its size is {width}x{height}");
+    }
+    Ok(RenderContext::new_with(width, height, settings))
+}
+
+/// Whether any of the items has a filter.
+fn uses_filters(items: &[Item]) -> bool {
+    items.iter().any(|item| match item {
+        Item::Group(group_item) => {
+            group_item.filter.is_some() || uses_filters(&group_item.children)
+        }
+        Item::Fill(_) | Item::Stroke(_) => false,
+    })
+}
+
+fn render_svg(ctx: &mut RenderContext, items: &[Item], transform: Affine) {
+    ctx.set_transform(transform);
+    for item in items {
+        match item {
+            Item::Fill(fill_item) => {
+                ctx.set_paint(fill_item.color);
+                ctx.fill_path(&fill_item.path);
+            }
+            Item::Stroke(stroke_item) => {
+                ctx.set_stroke(Stroke::new(stroke_item.width));
+                ctx.set_paint(stroke_item.color);
+                ctx.stroke_path(&stroke_item.path);
+            }
+            Item::Group(group_item) => {
+                let group_transform = transform * group_item.affine;
+                if let Some(filter) = &group_item.filter {
+                    ctx.set_transform(group_transform);
+                    ctx.push_filter_layer(filter.clone());
+                }
+                render_svg(ctx, &group_item.children, group_transform);
+                if group_item.filter.is_some() {
+                    ctx.pop_layer();
+                }
+                ctx.set_transform(transform);
+            }
+        }
+    }
+}
@@ -13,7 +13,8 @@ use std::rc::Rc;
 use std::{cell::RefCell, sync::Arc};
 use vello_common::kurbo::{Affine, Vec2};
 use vello_common::paint::ImageSource;
-use vello_cpu::RenderContext;
+use vello_cpu::{RasterizerSettings, RenderContext, TextureBindings};
+use vello_example_scenes::spritesheet::{SPRITESHEET_TEXTURE_ID, SpritesheetScene};
 use vello_example_scenes::{AnyScene, image::ImageScene};
 use wasm_bindgen::prelude::*;
 use web_sys::{Event, HtmlCanvasElement, KeyboardEvent, MouseEvent, WheelEvent};
@@ -29,6 +30,7 @@ struct AppState {
     height: u32,
     renderer: RenderContext,
     pixmap: vello_common::pixmap::Pixmap,
+    spritesheet: vello_common::pixmap::Pixmap,
     need_render: bool,
     canvas: HtmlCanvasElement,
 }
@@ -59,6 +61,7 @@ impl AppState {
             height,
             renderer,
             pixmap,
+            spritesheet: SpritesheetScene::read_spritesheet(),
             need_render: true,
             canvas,
         }
@@ -71,10 +74,17 @@ impl AppState {
         self.renderer.reset();
         self.scenes[self.current_scene].render(&mut self.renderer, self.transform);
 
+        let mut textures = TextureBindings::new();
+        textures.insert(SPRITESHEET_TEXTURE_ID, &self.spritesheet);
+
         // Render the current scene with transform
-        self.renderer.render(
+        self.renderer.render_with(
             &mut self.pixmap,
             self.scenes[self.current_scene].resources_mut(),
+            RasterizerSettings {
+                textures: Some(&textures),
+                ..Default::default()
+            },
         );
         let rgba_bytes = self.pixmap.data_as_u8_slice();
         let image_data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
@@ -233,7 +243,10 @@ pub async fn run_interactive(canvas_width: u16, canvas_height: u16) {
     let pixmap1 = ImageScene::read_flower_image();
     let pixmap2 = ImageScene::read_cowboy_image();
     let scenes = vello_example_scenes::get_example_scenes::<RenderContext>(
-        vello_example_scenes::Capabilities::default(),
+        vello_example_scenes::Capabilities {
+            external_textures: true,
+            filter_effects: true,
+        },
         vec![
             ImageSource::Pixmap(Arc::new(pixmap1)),
             ImageSource::Pixmap(Arc::new(pixmap2)),
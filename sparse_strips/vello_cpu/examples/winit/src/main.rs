@@ -79,7 +79,10 @@ fn main() {
             ImageSource::Pixmap(std::sync::Arc::new(pixmap1)),
             ImageSource::Pixmap(std::sync::Arc::new(pixmap2)),
         ];
-        let capabilities = Capabilities::default();
+        let capabilities = Capabilities {
+            filter_effects: true,
+            ..Default::default()
+        };
         let scenes = if svg_paths.is_empty() {
             get_example_scenes(capabilities, None, img_sources)
         } else {
@@ -95,7 +98,10 @@ fn main() {
         let pixmap2 = ImageScene::read_cowboy_image();
         (
             get_example_scenes(
-                Capabilities::default(),
+                Capabilities {
+                    filter_effects: true,
+                    ..Default::default()
+                },
                 vec![
                     ImageSource::Pixmap(std::sync::Arc::new(pixmap1)),
                     ImageSource::Pixmap(std::sync::Arc::new(pixmap2)),
@@ -0,0 +1,99 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! User-defined paints.
+//!
+//! A [`CustomPaint`] computes the colors of the pixels it covers itself, which makes it possible
+//! to draw procedural content like noise or checkerboards without rendering it into an image
+//! first. Custom paints are registered with [`Resources::register_custom_paint`](crate::Resources::register_custom_paint),
+//! and drawn by setting the returned [`CustomPaintId`] as the paint of a [`RenderContext`](crate::RenderContext).
+
+use alloc::sync::Arc;
+use core::fmt::Debug;
+use hashbrown::HashMap;
+use vello_common::kurbo::Affine;
+use vello_common::paint::CustomPaintId;
+
+/// A span of pixels that is painted by a [`CustomPaint`].
+///
+/// A span covers `width` columns of [`Tile::HEIGHT`](vello_common::tile::Tile::HEIGHT)
+/// pixels each. The pixels are stored in column-major order, so the pixel with index `i`
+/// is at the position `(x + i / Tile::HEIGHT, y + i % Tile::HEIGHT)`, and its components
+/// are at the indices `4 * i` to `4 * i + 3` of the buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomPaintSpan<'a> {
+    /// The x coordinate of the first column of the span, in device pixels.
+    pub x: u16,
+    /// The y coordinate of the first row of the span, in device pixels.
+    pub y: u16,
+    /// The number of columns of the span.
+    pub width: u16,
+    /// The transform from device coordinates to the coordinate system of the paint, which
+    /// includes the paint transform that was active when the paint was set.
+    ///
+    /// Note that the center of a pixel is at an offset of 0.5 from its position.
+    pub transform: Affine,
+    /// The coverage of each pixel of the span, from 0 to 255, or `None` if all pixels are
+    /// fully covered.
+    ///
+    /// The coverage is applied after painting, so custom paints don't need to take it into
+    /// account. It can be used to skip the work for pixels that aren't covered at all.
+    pub coverage: Option<&'a [u8]>,
+}
+
+/// A paint whose colors are computed by user code.
+///
+/// The methods are called from the fine rasterization stage with the spans of pixels that
+/// are covered by a shape, and need to write the premultiplied RGBA colors of all pixels
+/// of the span into the buffer. [`CustomPaint::paint_u8`] is used by the `u8` pipeline
+/// and [`CustomPaint::paint_f32`] by the `f32` pipeline.
+///
+/// When rendering with multiple threads, the methods may be called from several threads
+/// at the same time.
+///
+/// Note that custom paints are only compared by their handle (and transform) in
+/// [`SceneSnapshot::damage`](crate::SceneSnapshot::damage), so the regions showing a custom
+/// paint whose output has changed need to be added to the damage by the caller.
+pub trait CustomPaint: Debug + Send + Sync {
+    /// Paint a span of pixels, with components in the range from 0 to 255.
+    fn paint_u8(&self, span: &CustomPaintSpan<'_>, buf: &mut [u8]);
+
+    /// Paint a span of pixels, with components in the range from 0.0 to 1.0.
+    fn paint_f32(&self, span: &CustomPaintSpan<'_>, buf: &mut [f32]);
+
+    /// Return whether the paint may produce pixels that are not fully opaque.
+    ///
+    /// Opaque paints can be drawn more efficiently, as they don't need to be composited
+    /// with the content below them.
+    fn may_have_transparency(&self) -> bool {
+        true
+    }
+}
+
+/// Registry that maps [`CustomPaintId`]s to the registered custom paints.
+#[derive(Debug, Default)]
+#[doc(hidden)]
+pub struct CustomPaintRegistry {
+    paints: HashMap<u32, Arc<dyn CustomPaint>>,
+    next_id: u32,
+}
+
+impl CustomPaintRegistry {
+    pub(crate) fn register(&mut self, paint: Arc<dyn CustomPaint>) -> CustomPaintId {
+        let id = self.next_id;
+        self.next_id = self
+            .next_id
+            .checked_add(1)
+            .expect("custom paint registry exhausted IDs");
+        self.paints.insert(id, paint);
+        CustomPaintId::new(id)
+    }
+
+    pub(crate) fn destroy(&mut self, id: CustomPaintId) -> bool {
+        self.paints.remove(&id.as_u32()).is_some()
+    }
+
+    pub(crate) fn resolve(&self, id: CustomPaintId) -> Option<&dyn CustomPaint> {
+        self.paints.get(&id.as_u32()).map(|paint| &**paint)
+    }
+}
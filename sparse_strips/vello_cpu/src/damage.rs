@@ -0,0 +1,831 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Computing damage between recorded frames.
+//!
+//! A [`SceneSnapshot`] flattens the recorded command streams of a [`RenderContext`] into a
+//! sequence of entries, each consisting of the bounding box of the command on the canvas
+//! and a hash of everything that influences how it is drawn (strip geometry and coverage,
+//! paint, blend mode, mask, layer properties, ...). Comparing the entry sequences of two frames
+//! then tells us which areas of the canvas might look different.
+//!
+//! [`RenderContext`]: crate::RenderContext
+
+use crate::pattern::{self, PatternTile};
+use crate::record::{CommandRecorder, RecordedCmd, RecordedLayerKind, strip_bbox};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::mem::discriminant;
+use foldhash::fast::{FixedState, FoldHasher};
+use vello_common::color::{AlphaColor, ColorSpace, PremulColor};
+use vello_common::encode::{
+    EncodedBlurredRoundedRectangle, EncodedExternalTexture, EncodedKind, EncodedPaint,
+    GradientRange, RadialKind,
+};
+use vello_common::filter_effects::{
+    CompositeOperator, Filter, FilterInput, FilterInputs, FilterPrimitive, LightSource,
+    TransferFunction,
+};
+use vello_common::geometry::RectU16;
+use vello_common::kurbo::Affine;
+use vello_common::mask::Mask;
+use vello_common::paint::{ImageSource, Paint, Tint};
+use vello_common::pattern::PatternScene;
+use vello_common::peniko::{BlendMode, ImageSampler};
+use vello_common::strip::Strip;
+
+/// Hash seed, fixed so that hashes of different snapshots can be compared.
+const HASH_SEED: u64 = 0;
+
+/// A summary of the commands recorded into a [`RenderContext`], used to compute the
+/// regions that changed between two frames.
+///
+/// Create one with [`RenderContext::snapshot`] after recording (and flushing) a frame,
+/// and pass two of them to [`SceneSnapshot::damage`]. The resulting rectangles can be
+/// used as [`RasterizerSettings::damage`] to only re-render the parts of the target that
+/// actually changed.
+///
+/// Images stored in the scene are compared by identity, i.e. two images are only considered
+/// equal if they share the same allocation (or the same [`ImageId`](vello_common::paint::ImageId)).
+/// The same applies to the scenes of [patterns](vello_common::pattern::Pattern).
+/// Similarly, [custom paints](crate::CustomPaint) are compared by their handle, and
+/// [external textures](vello_common::TextureId) by their ID. All other state, including masks,
+/// is compared by value.
+///
+/// [`RenderContext`]: crate::RenderContext
+/// [`RenderContext::snapshot`]: crate::RenderContext::snapshot
+/// [`RasterizerSettings::damage`]: crate::RasterizerSettings::damage
+#[derive(Debug, Clone)]
+pub struct SceneSnapshot {
+    width: u16,
+    height: u16,
+    entries: Vec<Entry>,
+    /// Images that have been hashed by their address. Keeping them alive ensures that
+    /// a new image can't end up with the same address while this snapshot is still around.
+    #[expect(dead_code, reason = "only used to keep the images alive")]
+    images: Vec<ImageSource>,
+    /// The same as `images`, for the scenes of patterns.
+    #[expect(dead_code, reason = "only used to keep the scenes alive")]
+    pattern_scenes: Vec<Arc<PatternScene>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    /// The area of the canvas that is affected by the command.
+    bbox: RectU16,
+    /// The hash of the command.
+    ///
+    /// Note that for regular layers, this only covers the properties of the layer itself,
+    /// as the contents are represented by their own entries.
+    hash: u64,
+}
+
+impl SceneSnapshot {
+    /// Return the width of the snapshotted scene.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the height of the snapshotted scene.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Compute the regions of the scene that need to be redrawn to go from `previous` to `self`.
+    ///
+    /// None of the returned rectangles contains another one, but they are not necessarily
+    /// disjoint. If the scenes have different dimensions, the whole scene is returned.
+    /// If nothing changed, the result is empty.
+    pub fn damage(&self, previous: &Self) -> Vec<RectU16> {
+        if self.width != previous.width || self.height != previous.height {
+            return vec![RectU16::new(0, 0, self.width, self.height)];
+        }
+
+        let (old, new) = (previous.entries.as_slice(), self.entries.as_slice());
+        // Commands that were only appended, removed or changed somewhere in the middle should
+        // only cause damage for those commands, so we skip the common prefix and suffix.
+        let prefix = old
+            .iter()
+            .zip(new)
+            .take_while(|(a, b)| a.hash == b.hash)
+            .count();
+        let (old, new) = (&old[prefix..], &new[prefix..]);
+        let suffix = old
+            .iter()
+            .rev()
+            .zip(new.iter().rev())
+            .take_while(|(a, b)| a.hash == b.hash)
+            .count();
+        let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+
+        let mut damage = Vec::new();
+        let mut add = |bbox: RectU16| {
+            let bbox = bbox.intersect(RectU16::new(0, 0, self.width, self.height));
+            if bbox.is_empty() || damage.iter().any(|rect| covers(*rect, bbox)) {
+                return;
+            }
+            damage.retain(|rect| !covers(bbox, *rect));
+            damage.push(bbox);
+        };
+
+        if old.len() == new.len() {
+            // The structure of the scene stayed the same, so only the commands that
+            // actually differ need to be redrawn, at both their old and new location.
+            for (a, b) in old.iter().zip(new) {
+                if a.hash != b.hash {
+                    add(a.bbox);
+                    if b.bbox != a.bbox {
+                        add(b.bbox);
+                    }
+                }
+            }
+        } else {
+            for entry in old.iter().chain(new) {
+                add(entry.bbox);
+            }
+        }
+
+        damage
+    }
+}
+
+/// Whether `outer` contains all of `inner`.
+fn covers(outer: RectU16, inner: RectU16) -> bool {
+    outer.x0 <= inner.x0 && outer.y0 <= inner.y0 && outer.x1 >= inner.x1 && outer.y1 >= inner.y1
+}
+
+/// Create a snapshot of the commands in `recorder`.
+///
+/// `alpha_buffers` contains the alpha buffer of each thread, indexed by the thread index
+/// stored in the commands.
+pub(crate) fn snapshot(
+    recorder: &CommandRecorder,
+    strips: &[Strip],
+    alpha_buffers: &[&[u8]],
+    encoded_paints: &[EncodedPaint],
+    pattern_tiles: &[PatternTile],
+    width: u16,
+    height: u16,
+) -> SceneSnapshot {
+    let mut builder = SnapshotBuilder {
+        recorder,
+        strips,
+        alpha_buffers,
+        encoded_paints,
+        pattern_tiles,
+        width,
+        height,
+        images: Vec::new(),
+        pattern_scenes: Vec::new(),
+    };
+    let mut entries = Vec::new();
+    builder.visit(&recorder.root_cmds, &mut entries);
+
+    SceneSnapshot {
+        width,
+        height,
+        entries,
+        images: builder.images,
+        pattern_scenes: builder.pattern_scenes,
+    }
+}
+
+struct SnapshotBuilder<'a> {
+    recorder: &'a CommandRecorder,
+    strips: &'a [Strip],
+    alpha_buffers: &'a [&'a [u8]],
+    encoded_paints: &'a [EncodedPaint],
+    pattern_tiles: &'a [PatternTile],
+    width: u16,
+    height: u16,
+    images: Vec<ImageSource>,
+    pattern_scenes: Vec<Arc<PatternScene>>,
+}
+
+impl SnapshotBuilder<'_> {
+    fn visit(&mut self, cmds: &[RecordedCmd], entries: &mut Vec<Entry>) {
+        // Indices of the entries of currently open (regular) layers, whose bounding
+        // box can only be determined once all of their contents have been visited.
+        let mut open_layers = Vec::new();
+
+        for cmd in cmds {
+            let mut state = hasher();
+            let bbox = match cmd {
+                RecordedCmd::Fill {
+                    thread_idx,
+                    strip_range,
+                    paint,
+                    blend_mode,
+                    mask,
+                } => {
+                    let strips = &self.strips[strip_range.clone()];
+                    0_u8.hash(&mut state);
+                    self.hash_strips(strips, *thread_idx, &mut state);
+                    self.hash_paint(paint, &mut state);
+                    blend_mode.hash_bits(&mut state);
+                    hash_mask(mask.as_ref(), &mut state);
+
+                    strip_bbox(strips, self.width)
+                }
+                RecordedCmd::PushLayer { id } => {
+                    let props = &self.recorder.layers[id.get()].props;
+                    1_u8.hash(&mut state);
+                    props.blend_mode.hash_bits(&mut state);
+                    props.opacity.to_bits().hash(&mut state);
+                    hash_mask(props.mask.as_ref(), &mut state);
+                    if let Some(clip) = &props.clip_path {
+                        self.hash_strips(
+                            &self.strips[clip.strip_range.clone()],
+                            clip.thread_idx,
+                            &mut state,
+                        );
+                    }
+
+                    open_layers.push(entries.len());
+                    // Destructive blend modes affect the whole area of the layer, not only
+                    // where something was drawn.
+                    if props.blend_mode.is_destructive() {
+                        props
+                            .clip_path
+                            .as_ref()
+                            .map(|clip| clip.bbox)
+                            .unwrap_or(RectU16::new(0, 0, self.width, self.height))
+                    } else {
+                        RectU16::INVERTED
+                    }
+                }
+                RecordedCmd::PopLayer => {
+                    let start = open_layers.pop().unwrap();
+                    let mut content_bbox = RectU16::INVERTED;
+                    for entry in &entries[start..] {
+                        content_bbox.union(entry.bbox);
+                    }
+                    entries[start].bbox.union(content_bbox);
+                    2_u8.hash(&mut state);
+
+                    RectU16::INVERTED
+                }
+                RecordedCmd::FilterLayer { id } => {
+                    let layer = &self.recorder.layers[id.get()];
+                    let RecordedLayerKind::Filter {
+                        cmds,
+                        filter_data,
+                        placement,
+                    } = &layer.kind
+                    else {
+                        unreachable!("filter layer commands must reference filter layers");
+                    };
+                    3_u8.hash(&mut state);
+                    layer.props.blend_mode.hash_bits(&mut state);
+                    layer.props.opacity.to_bits().hash(&mut state);
+                    hash_mask(layer.props.mask.as_ref(), &mut state);
+                    filter_data.filter.hash_bits(&mut state);
+                    filter_data.transform.hash_bits(&mut state);
+                    placement.hash(&mut state);
+
+                    // Since filters can move pixels around arbitrarily, we can't
+                    // track damage inside of filter layers, so the contents simply
+                    // become part of the hash of the whole layer.
+                    let mut inner = Vec::new();
+                    self.visit(cmds, &mut inner);
+                    for entry in inner {
+                        entry.hash.hash(&mut state);
+                    }
+
+                    placement.dest_bbox
+                }
+            };
+
+            entries.push(Entry {
+                bbox,
+                hash: state.finish(),
+            });
+        }
+    }
+
+    fn hash_strips(&self, strips: &[Strip], thread_idx: u8, state: &mut FoldHasher<'_>) {
+        let alphas = self.alpha_buffers[usize::from(thread_idx)];
+
+        // The alpha indices themselves depend on everything that was drawn before, so
+        // we hash the actual alpha values instead.
+        for (idx, strip) in strips.iter().enumerate() {
+            strip.x.hash(state);
+            strip.y.hash(state);
+            strip.fill_gap().hash(state);
+
+            if let Some(next) = strips.get(idx + 1) {
+                let range = strip.alpha_idx() as usize..next.alpha_idx() as usize;
+                alphas[range].hash(state);
+            }
+        }
+    }
+
+    fn hash_paint(&mut self, paint: &Paint, state: &mut FoldHasher<'_>) {
+        let paint = match paint {
+            Paint::Solid(color) => {
+                color.as_premul_f32().hash_bits(state);
+                return;
+            }
+            Paint::Indexed(paint) => &self.encoded_paints[paint.index()],
+        };
+
+        match paint {
+            EncodedPaint::Gradient(gradient) => {
+                // The lookup tables are derived from the ranges, so there is no need to
+                // hash them (and they might not have been initialized yet anyway).
+                gradient.kind.hash_bits(state);
+                gradient.ranges.hash_bits(state);
+                gradient.transform.hash_bits(state);
+                discriminant(&gradient.extend).hash(state);
+            }
+            EncodedPaint::Image(image) => {
+                match &image.source {
+                    ImageSource::Pixmap(pixmap) => {
+                        (Arc::as_ptr(pixmap) as usize).hash(state);
+                        self.keep_alive(&image.source);
+                    }
+                    ImageSource::Raw(raw) => {
+                        (Arc::as_ptr(raw) as usize).hash(state);
+                        self.keep_alive(&image.source);
+                    }
+                    ImageSource::OpaqueId { id, .. } => {
+                        // The IDs of pattern tiles are only valid for a single scene, so
+                        // the tile itself needs to be hashed instead.
+                        match pattern::tile_index(*id).and_then(|idx| self.pattern_tiles.get(idx)) {
+                            Some(tile) => self.hash_pattern_tile(tile, state),
+                            None => id.hash(state),
+                        }
+                    }
+                }
+                image.sampler.hash_bits(state);
+                image.transform.hash_bits(state);
+                image.tint.hash_bits(state);
+            }
+            EncodedPaint::ExternalTexture(texture) => texture.hash_bits(state),
+            EncodedPaint::BlurredRoundedRect(rect) => rect.hash_bits(state),
+            EncodedPaint::Custom(paint) => {
+                // The contents of custom paints are opaque, so only their handle is hashed.
+                paint.id.hash(state);
+                paint.transform.hash_bits(state);
+            }
+            EncodedPaint::MeshGradient(mesh) => {
+                // The grid is derived from the triangles, so hashing those is enough.
+                for triangle in &mesh.triangles {
+                    for value in triangle.edges.iter().chain(&triangle.color).flatten() {
+                        value.to_bits().hash(state);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Keep an image that has been hashed by its address alive for as long as the snapshot.
+    fn hash_pattern_tile(&mut self, tile: &PatternTile, state: &mut FoldHasher<'_>) {
+        (Arc::as_ptr(&tile.scene) as usize).hash(state);
+        [tile.tile.x0, tile.tile.y0, tile.tile.x1, tile.tile.y1].hash_bits(state);
+        tile.width.hash(state);
+        tile.height.hash(state);
+        if !self
+            .pattern_scenes
+            .iter()
+            .any(|scene| Arc::ptr_eq(scene, &tile.scene))
+        {
+            self.pattern_scenes.push(tile.scene.clone());
+        }
+    }
+
+    fn keep_alive(&mut self, source: &ImageSource) {
+        let same_allocation = |other: &ImageSource| match (source, other) {
+            (ImageSource::Pixmap(a), ImageSource::Pixmap(b)) => Arc::ptr_eq(a, b),
+            (ImageSource::Raw(a), ImageSource::Raw(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        };
+        if !self.images.iter().any(same_allocation) {
+            self.images.push(source.clone());
+        }
+    }
+}
+
+fn hasher() -> FoldHasher<'static> {
+    FixedState::with_seed(HASH_SEED).build_hasher()
+}
+
+fn hash_mask(mask: Option<&Mask>, state: &mut FoldHasher<'_>) {
+    if let Some(mask) = mask {
+        mask.width().hash(state);
+        mask.height().hash(state);
+        mask.data().hash(state);
+    } else {
+        u16::MAX.hash(state);
+    }
+}
+
+/// Hashing of encoded state by the bit patterns of its fields.
+///
+/// Most of the encoded state doesn't implement `Hash` since it consists of floats. Two
+/// floats with the same bit pattern are always drawn the same way, so we hash those instead.
+/// Enums without fields are hashed by their discriminant.
+trait HashBits {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>);
+}
+
+impl HashBits for f32 {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.to_bits().hash(state);
+    }
+}
+
+impl HashBits for f64 {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.to_bits().hash(state);
+    }
+}
+
+impl<T: HashBits> HashBits for [T] {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.len().hash(state);
+        for value in self {
+            value.hash_bits(state);
+        }
+    }
+}
+
+impl<T: HashBits, const N: usize> HashBits for [T; N] {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self[..].hash_bits(state);
+    }
+}
+
+impl<T: HashBits> HashBits for Option<T> {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.is_some().hash(state);
+        if let Some(value) = self {
+            value.hash_bits(state);
+        }
+    }
+}
+
+impl HashBits for Affine {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.as_coeffs().hash_bits(state);
+    }
+}
+
+impl<CS: ColorSpace> HashBits for AlphaColor<CS> {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.components.hash_bits(state);
+    }
+}
+
+impl<CS: ColorSpace> HashBits for PremulColor<CS> {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.components.hash_bits(state);
+    }
+}
+
+impl HashBits for BlendMode {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(&self.mix).hash(state);
+        discriminant(&self.compose).hash(state);
+    }
+}
+
+impl HashBits for ImageSampler {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(&self.x_extend).hash(state);
+        discriminant(&self.y_extend).hash(state);
+        discriminant(&self.quality).hash(state);
+        self.alpha.hash_bits(state);
+    }
+}
+
+impl HashBits for Tint {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.color.hash_bits(state);
+        discriminant(&self.mode).hash(state);
+    }
+}
+
+impl HashBits for EncodedKind {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(self).hash(state);
+        match self {
+            Self::Linear(_) => {}
+            Self::Radial(kind) => {
+                discriminant(kind).hash(state);
+                match kind {
+                    RadialKind::Radial { bias, scale } => [*bias, *scale].hash_bits(state),
+                    RadialKind::Strip { scaled_r0_squared } => scaled_r0_squared.hash_bits(state),
+                    RadialKind::Focal {
+                        focal_data,
+                        fp0,
+                        fp1,
+                    } => {
+                        [focal_data.fr1, focal_data.f_focal_x, *fp0, *fp1].hash_bits(state);
+                        focal_data.f_is_swapped.hash(state);
+                    }
+                }
+            }
+            Self::Sweep(kind) => [kind.start_angle, kind.inv_angle_delta].hash_bits(state),
+        }
+    }
+}
+
+impl HashBits for GradientRange {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.x1.hash_bits(state);
+        self.bias.hash_bits(state);
+        self.scale.hash_bits(state);
+        discriminant(&self.interpolation_alpha_space).hash(state);
+    }
+}
+
+impl HashBits for EncodedExternalTexture {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.texture_id.hash(state);
+        self.source_region.hash(state);
+        self.sampler.hash_bits(state);
+        self.transform.hash_bits(state);
+        self.tint.hash_bits(state);
+    }
+}
+
+impl HashBits for EncodedBlurredRoundedRectangle {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        [
+            self.exponent,
+            self.recip_exponent,
+            self.scale,
+            self.std_dev_inv,
+            self.min_edge,
+            self.w,
+            self.h,
+            self.width,
+            self.height,
+            self.r1,
+        ]
+        .hash_bits(state);
+        self.invert.hash(state);
+        self.color.as_premul_f32().hash_bits(state);
+        self.transform.hash_bits(state);
+    }
+}
+
+impl HashBits for Filter {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        let graph = &self.graph;
+        graph.primitives.hash_bits(state);
+        graph.inputs.hash_bits(state);
+        graph.output.hash(state);
+    }
+}
+
+impl HashBits for FilterInputs {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        self.primary.hash_bits(state);
+        self.secondary.hash_bits(state);
+    }
+}
+
+impl HashBits for FilterInput {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(self).hash(state);
+        match self {
+            Self::Source(source) => discriminant(source).hash(state),
+            Self::Result(id) => id.hash(state),
+        }
+    }
+}
+
+impl HashBits for FilterPrimitive {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(self).hash(state);
+        match self {
+            Self::Flood { color } => color.hash_bits(state),
+            Self::GaussianBlur {
+                std_deviation,
+                edge_mode,
+            } => {
+                std_deviation.hash_bits(state);
+                discriminant(edge_mode).hash(state);
+            }
+            Self::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+                edge_mode,
+            } => {
+                [*dx, *dy, *std_deviation].hash_bits(state);
+                color.hash_bits(state);
+                discriminant(edge_mode).hash(state);
+            }
+            Self::ColorMatrix { matrix } => matrix.hash_bits(state),
+            Self::Offset { dx, dy } => [*dx, *dy].hash_bits(state),
+            Self::Composite { operator } => {
+                discriminant(operator).hash(state);
+                if let CompositeOperator::Arithmetic { k1, k2, k3, k4 } = operator {
+                    [*k1, *k2, *k3, *k4].hash_bits(state);
+                }
+            }
+            Self::Blend { mode } => discriminant(mode).hash(state),
+            Self::Morphology { operator, radius } => {
+                discriminant(operator).hash(state);
+                radius.hash_bits(state);
+            }
+            Self::ConvolveMatrix { kernel } => {
+                kernel.size.hash(state);
+                kernel.values.hash_bits(state);
+                [kernel.divisor, kernel.bias].hash_bits(state);
+                kernel.preserve_alpha.hash(state);
+            }
+            Self::Turbulence {
+                base_frequency,
+                num_octaves,
+                seed,
+                turbulence_type,
+            } => {
+                base_frequency.hash_bits(state);
+                num_octaves.hash(state);
+                seed.hash(state);
+                discriminant(turbulence_type).hash(state);
+            }
+            Self::DisplacementMap {
+                scale,
+                x_channel,
+                y_channel,
+            } => {
+                scale.hash_bits(state);
+                discriminant(x_channel).hash(state);
+                discriminant(y_channel).hash(state);
+            }
+            Self::ComponentTransfer {
+                red_function,
+                green_function,
+                blue_function,
+                alpha_function,
+            } => {
+                for function in [red_function, green_function, blue_function, alpha_function] {
+                    function.hash_bits(state);
+                }
+            }
+            Self::Image {
+                image_id,
+                transform,
+            } => {
+                image_id.hash(state);
+                transform.hash_bits(state);
+            }
+            Self::Tile => {}
+            Self::DiffuseLighting {
+                surface_scale,
+                diffuse_constant,
+                kernel_unit_length,
+                light_source,
+            } => {
+                [*surface_scale, *diffuse_constant, *kernel_unit_length].hash_bits(state);
+                light_source.hash_bits(state);
+            }
+            Self::SpecularLighting {
+                surface_scale,
+                specular_constant,
+                specular_exponent,
+                kernel_unit_length,
+                light_source,
+            } => {
+                [
+                    *surface_scale,
+                    *specular_constant,
+                    *specular_exponent,
+                    *kernel_unit_length,
+                ]
+                .hash_bits(state);
+                light_source.hash_bits(state);
+            }
+        }
+    }
+}
+
+impl HashBits for TransferFunction {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(self).hash(state);
+        match self {
+            Self::Identity => {}
+            Self::Table { values } | Self::Discrete { values } => values.hash_bits(state),
+            Self::Linear { slope, intercept } => [*slope, *intercept].hash_bits(state),
+            Self::Gamma {
+                amplitude,
+                exponent,
+                offset,
+            } => [*amplitude, *exponent, *offset].hash_bits(state),
+        }
+    }
+}
+
+impl HashBits for LightSource {
+    fn hash_bits(&self, state: &mut FoldHasher<'_>) {
+        discriminant(self).hash(state);
+        match self {
+            Self::Distant { azimuth, elevation } => [*azimuth, *elevation].hash_bits(state),
+            Self::Point { x, y, z } => [*x, *y, *z].hash_bits(state),
+            Self::Spot {
+                x,
+                y,
+                z,
+                points_at_x,
+                points_at_y,
+                points_at_z,
+                specular_exponent,
+                limiting_cone_angle,
+            } => {
+                [
+                    *x,
+                    *y,
+                    *z,
+                    *points_at_x,
+                    *points_at_y,
+                    *points_at_z,
+                    *specular_exponent,
+                ]
+                .hash_bits(state);
+                limiting_cone_angle.hash_bits(state);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::palette::css::{BLUE, GREEN, RED};
+    use crate::color::{AlphaColor, Srgb};
+    use crate::kurbo::{Circle, Rect, Shape};
+    use crate::{RectU16, RenderContext, RenderSettings, SceneSnapshot};
+
+    fn scene(
+        ctx: &mut RenderContext,
+        background: AlphaColor<Srgb>,
+        circle_x: f64,
+        circle_color: bool,
+    ) -> SceneSnapshot {
+        ctx.reset();
+        ctx.set_paint(background);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+        ctx.set_paint(if circle_color { GREEN } else { RED });
+        ctx.fill_path(&Circle::new((circle_x, 30.0), 10.0).to_path(0.1));
+        ctx.set_paint(BLUE);
+        ctx.push_opacity_layer(0.5);
+        ctx.fill_rect(&Rect::new(70.0, 70.0, 90.0, 90.0));
+        ctx.pop_layer();
+        ctx.flush();
+        ctx.snapshot()
+    }
+
+    fn damage(num_threads: u16) {
+        let settings = RenderSettings {
+            num_threads,
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new_with(100, 100, settings);
+
+        let base = scene(&mut ctx, BLUE, 30.0, false);
+        assert!(scene(&mut ctx, BLUE, 30.0, false).damage(&base).is_empty());
+        // Changing the color of the circle only damages the circle.
+        assert_eq!(
+            scene(&mut ctx, BLUE, 30.0, true).damage(&base),
+            [RectU16::new(20, 20, 44, 40)]
+        );
+        // Moving it damages both the old and the new location.
+        assert_eq!(
+            scene(&mut ctx, BLUE, 60.0, false).damage(&base),
+            [RectU16::new(20, 20, 44, 40), RectU16::new(48, 20, 72, 40)]
+        );
+        // Changing the background damages everything, which covers the damage of the circle.
+        assert_eq!(
+            scene(&mut ctx, GREEN, 30.0, true).damage(&base),
+            [RectU16::new(0, 0, 100, 100)]
+        );
+    }
+
+    #[test]
+    fn damage_between_snapshots() {
+        damage(0);
+    }
+
+    #[cfg(feature = "multithreading")]
+    #[test]
+    fn damage_between_snapshots_multithreaded() {
+        damage(2);
+    }
+
+    #[test]
+    fn resized_scene_is_fully_damaged() {
+        let mut ctx = RenderContext::new(100, 100);
+        let old = scene(&mut ctx, BLUE, 30.0, false);
+        let mut ctx = RenderContext::new(50, 100);
+        let new = scene(&mut ctx, BLUE, 30.0, false);
+
+        assert_eq!(new.damage(&old), [RectU16::new(0, 0, 50, 100)]);
+    }
+}
@@ -6,14 +6,21 @@ pub(crate) mod multi_threaded;
 pub(crate) mod single_threaded;
 
 use crate::RasterizerSettings;
+use crate::custom_paint::CustomPaintRegistry;
+use crate::damage::SceneSnapshot;
 use crate::kurbo::{Affine, BezPath, Rect, Stroke};
+use crate::pattern::PatternTile;
 use crate::peniko::{BlendMode, Fill};
 use crate::record::FilterData;
+use alloc::vec::Vec;
 use core::fmt::Debug;
+use vello_common::coarse::WideTile;
 use vello_common::encode::EncodedPaint;
+use vello_common::geometry::RectU16;
 use vello_common::mask::Mask;
 use vello_common::paint::{ImageResolver, Paint};
 use vello_common::pixmap::PixmapMut;
+use vello_common::tile::Tile;
 
 pub(crate) trait Dispatcher: Debug + Send {
     fn has_layers(&self) -> bool;
@@ -72,9 +79,119 @@ pub(crate) trait Dispatcher: Debug + Send {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     );
+    /// Create a snapshot of the recorded commands for computing damage between frames.
+    fn snapshot(
+        &self,
+        width: u16,
+        height: u16,
+        encoded_paints: &[EncodedPaint],
+        pattern_tiles: &[PatternTile],
+    ) -> SceneSnapshot;
+    /// Return the indices of the recorded fills that cover any pixel of `area`.
+    fn hit_test(&self, area: RectU16) -> Vec<usize>;
     fn is_multi_threaded(&self) -> bool;
 }
+
+/// Determine the viewports that need to be rasterized for the given damage rectangles.
+///
+/// Each damage rectangle is rounded out to whole wide tiles horizontally and whole strip
+/// rows vertically. The resulting viewports are disjoint, so that no pixel is rasterized twice
+/// (which would give wrong results when compositing with `SrcOver`).
+pub(crate) fn damage_viewports(damage: &[RectU16], width: u16, height: u16) -> Vec<RectU16> {
+    let cols = usize::from(width.div_ceil(WideTile::WIDTH));
+    let rows = usize::from(height.div_ceil(Tile::HEIGHT));
+    let mut dirty = alloc::vec![false; cols * rows];
+    let scene = RectU16::new(0, 0, width, height);
+    for rect in damage {
+        let rect = rect.intersect(scene);
+        if rect.is_empty() {
+            continue;
+        }
+        let cols_range =
+            usize::from(rect.x0 / WideTile::WIDTH)..usize::from(rect.x1.div_ceil(WideTile::WIDTH));
+        for row in usize::from(rect.y0 / Tile::HEIGHT)..usize::from(rect.y1.div_ceil(Tile::HEIGHT))
+        {
+            dirty[row * cols..][cols_range.clone()].fill(true);
+        }
+    }
+
+    // Merge runs of dirty wide tiles that span the same columns in consecutive rows.
+    let to_viewport = |cols: (usize, usize), rows: (usize, usize)| {
+        RectU16::new(
+            cols.0 as u16 * WideTile::WIDTH,
+            rows.0 as u16 * Tile::HEIGHT,
+            (cols.1 as u16).saturating_mul(WideTile::WIDTH).min(width),
+            (rows.1 as u16).saturating_mul(Tile::HEIGHT).min(height),
+        )
+    };
+    let mut viewports = Vec::new();
+    // The column ranges of the viewports that are still open, and the rows at which they start.
+    let mut open: Vec<((usize, usize), usize)> = Vec::new();
+    let mut runs = Vec::new();
+    for row in 0..=rows {
+        runs.clear();
+        if row < rows {
+            let row_dirty = &dirty[row * cols..][..cols];
+            let mut col = 0;
+            while col < cols {
+                if row_dirty[col] {
+                    let start = col;
+                    while col < cols && row_dirty[col] {
+                        col += 1;
+                    }
+                    runs.push((start, col));
+                }
+                col += 1;
+            }
+        }
+        open.retain(|&(open_cols, start_row)| {
+            let continued = runs.contains(&open_cols);
+            if !continued {
+                viewports.push(to_viewport(open_cols, (start_row, row)));
+            }
+            continued
+        });
+        for &run in &runs {
+            if !open.iter().any(|(open_cols, _)| *open_cols == run) {
+                open.push((run, row));
+            }
+        }
+    }
+    viewports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::damage_viewports;
+    use vello_common::geometry::RectU16;
+
+    #[test]
+    fn damage_is_rounded_out_and_merged() {
+        let damage = [
+            RectU16::new(10, 5, 20, 10),
+            // Overlaps the first rectangle once rounded out.
+            RectU16::new(100, 2, 300, 7),
+            // Partially outside of the scene.
+            RectU16::new(600, 0, 900, 1),
+        ];
+        assert_eq!(
+            damage_viewports(&damage, 700, 100),
+            [
+                RectU16::new(0, 0, 700, 4),
+                RectU16::new(0, 4, 512, 8),
+                RectU16::new(0, 8, 256, 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn damage_outside_of_scene() {
+        assert!(damage_viewports(&[RectU16::new(50, 50, 60, 60)], 40, 40).is_empty());
+        assert!(damage_viewports(&[], 40, 40).is_empty());
+    }
+}
@@ -4,12 +4,16 @@
 use crate::coarse::CommandBucketer;
 use crate::coarse::bucketer::LayerClip;
 use crate::coarse::depth::DepthBuffer;
-use crate::dispatch::Dispatcher;
+use crate::custom_paint::CustomPaintRegistry;
+use crate::damage::{self, SceneSnapshot};
 use crate::dispatch::multi_threaded::cost::{COST_THRESHOLD, estimate_render_task_cost};
 use crate::dispatch::multi_threaded::worker::Worker;
+use crate::dispatch::{Dispatcher, damage_viewports};
 use crate::filter::context::FilterContext;
 use crate::fine::{Fine, FineKernel, FineRenderParams, FineResources, rasterize_region};
+use crate::hit_test;
 use crate::kurbo::{Affine, BezPath, PathEl, Point, Rect, Stroke};
+use crate::pattern::PatternTile;
 use crate::peniko::{BlendMode, Fill};
 use crate::record::{CommandRecorder, FilterData, LayerProps, PoppedLayer};
 use crate::region::Regions;
@@ -33,7 +37,7 @@ use vello_common::geometry::RectU16;
 use vello_common::mask::Mask;
 use vello_common::paint::{ImageResolver, Paint};
 use vello_common::pixmap::PixmapMut;
-use vello_common::strip::Strip;
+use vello_common::strip::{AntialiasingMode, Strip};
 use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
 use vello_common::util::control_point_bbox_u16;
 
@@ -107,6 +111,8 @@ pub(crate) struct MultiThreadedDispatcher {
     /// The strip generator for the main thread, used for clip path rasterization.
     strip_generator: StripGenerator,
     level: Level,
+    /// Whether smooth paints are dithered during fine rasterization.
+    dithering: bool,
     flushed: bool,
     // So that we can reuse memory allocations across different runs.
     allocations: Allocations,
@@ -114,7 +120,14 @@ pub(crate) struct MultiThreadedDispatcher {
 }
 
 impl MultiThreadedDispatcher {
-    pub(crate) fn new(width: u16, height: u16, num_threads: u16, level: Level) -> Self {
+    pub(crate) fn new(
+        width: u16,
+        height: u16,
+        num_threads: u16,
+        level: Level,
+        antialiasing_mode: AntialiasingMode,
+        dithering: bool,
+    ) -> Self {
         let thread_pool = ThreadPoolBuilder::new()
             .num_threads(num_threads as usize)
             .build()
@@ -129,7 +142,7 @@ impl MultiThreadedDispatcher {
             // Create all workers once in `new`, so that later on we can just call`.get().unwrap()`.
             thread_pool.spawn_broadcast(move |_| {
                 let thread_id = thread_ids.fetch_add(1, Ordering::SeqCst);
-                let worker = Worker::new(width, height, thread_id, level);
+                let worker = Worker::new(width, height, thread_id, level, antialiasing_mode);
 
                 let _ = workers.get_or(|| RefCell::new(worker));
             });
@@ -139,6 +152,9 @@ impl MultiThreadedDispatcher {
         let batch_cost = 0.0;
         let flushed = false;
 
+        let mut strip_generator = StripGenerator::new(width, height, level);
+        strip_generator.set_antialiasing_mode(antialiasing_mode);
+
         let mut dispatcher = Self {
             bucketer: Mutex::new(CommandBucketer::from_wh(width, height)),
             thread_pool,
@@ -152,9 +168,10 @@ impl MultiThreadedDispatcher {
             recorder: CommandRecorder::new(),
             task_sender: None,
             recorded_command_receiver: None,
-            strip_generator: StripGenerator::new(width, height, level),
+            strip_generator,
             strip_storage: StripStorage::new(GenerationMode::Append),
             level,
+            dithering,
             alpha_storage,
             num_threads,
             layer_depth: 0,
@@ -171,12 +188,13 @@ impl MultiThreadedDispatcher {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
         use crate::fine::F32Kernel;
-        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver, custom_paints));
     }
 
     #[cfg(feature = "u8_pipeline")]
@@ -185,12 +203,13 @@ impl MultiThreadedDispatcher {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
         use crate::fine::U8Kernel;
-        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver, custom_paints));
     }
 
     fn init(&mut self) {
@@ -391,65 +410,80 @@ impl MultiThreadedDispatcher {
         mut target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
+        let viewports = match settings.damage {
+            Some(damage) => damage_viewports(damage, scene_width, scene_height),
+            None => vec![RectU16::new(0, 0, scene_width, scene_height)],
+        };
         let mut bucketer = self.bucketer.lock().unwrap();
         let filters = FilterContext::new(0);
-        bucketer.reset(RectU16::new(0, 0, scene_width, scene_height));
-        bucketer.bucket_commands(
-            &self.recorder.root_cmds,
-            &self.recorder.layers,
-            &self.strip_storage.strips,
-            encoded_paints,
-            &filters,
-        );
-
         let alpha_slots = self.alpha_storage.take();
         {
             let alpha_buffers = alpha_slots.iter().map(Vec::as_slice).collect::<Vec<_>>();
             let use_src_over = settings.composite_mode == CompositeMode::SrcOver;
-            let resources = FineResources {
-                alpha_buffers: &alpha_buffers,
-                encoded_paints,
-                filter_paints: &bucketer.filter_paints,
-                image_resolver,
-            };
-            let params = FineRenderParams {
-                scene_size: (scene_width, scene_height),
-                target_offset: settings.offset,
-            };
-
-            let mut regions = Regions::new(
-                &mut target,
-                params.scene_size,
-                params.target_offset,
-                bucketer.rows().len(),
-            );
-            let fines = ThreadLocal::new();
-            self.thread_pool.install(|| {
-                regions.update_par(|region| {
-                    let mut fine = fines
-                        .get_or(|| {
-                            RefCell::new((
-                                Fine::<S, F>::new(simd, bucketer.width()),
-                                DepthBuffer::new(bucketer.width()),
-                            ))
-                        })
-                        .borrow_mut();
-                    let (fine, depth) = &mut *fine;
-
-                    rasterize_region::<S, F>(
-                        fine,
-                        depth,
-                        region,
-                        &bucketer,
-                        resources,
-                        use_src_over,
-                    );
+
+            for viewport in viewports {
+                bucketer.reset(viewport);
+                bucketer.bucket_commands(
+                    &self.recorder.root_cmds,
+                    &self.recorder.layers,
+                    &self.strip_storage.strips,
+                    encoded_paints,
+                    &filters,
+                );
+
+                let resources = FineResources {
+                    alpha_buffers: &alpha_buffers,
+                    encoded_paints,
+                    filter_paints: &bucketer.filter_paints,
+                    image_resolver,
+                    custom_paints,
+                    textures: settings.textures,
+                    dithering: self.dithering,
+                };
+                let params = FineRenderParams {
+                    scene_size: (viewport.width(), viewport.height()),
+                    target_offset: (
+                        settings.offset.0.saturating_add(viewport.x0),
+                        settings.offset.1.saturating_add(viewport.y0),
+                    ),
+                };
+
+                let mut regions = Regions::new(
+                    &mut target,
+                    params.scene_size,
+                    params.target_offset,
+                    bucketer.rows().len(),
+                );
+                let bucketer = &*bucketer;
+                let fines = ThreadLocal::new();
+                self.thread_pool.install(|| {
+                    regions.update_par(|region| {
+                        let mut fine = fines
+                            .get_or(|| {
+                                RefCell::new((
+                                    Fine::<S, F>::new(simd, bucketer.width()),
+                                    DepthBuffer::new(bucketer.width()),
+                                ))
+                            })
+                            .borrow_mut();
+                        let (fine, depth) = &mut *fine;
+
+                        rasterize_region::<S, F>(
+                            fine,
+                            depth,
+                            region,
+                            bucketer,
+                            resources,
+                            use_src_over,
+                        );
+                    });
                 });
-            });
+            }
         }
 
         self.alpha_storage.init(alpha_slots);
@@ -643,9 +677,10 @@ impl Dispatcher for MultiThreadedDispatcher {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
         assert!(self.flushed, "attempted to rasterize before flushing");
 
@@ -659,6 +694,7 @@ impl Dispatcher for MultiThreadedDispatcher {
                 settings,
                 encoded_paints,
                 image_resolver,
+                custom_paints,
             );
         }
         // Only f32 pipeline enabled
@@ -671,6 +707,7 @@ impl Dispatcher for MultiThreadedDispatcher {
                 settings,
                 encoded_paints,
                 image_resolver,
+                custom_paints,
             );
         }
 
@@ -685,6 +722,7 @@ impl Dispatcher for MultiThreadedDispatcher {
                     settings,
                     encoded_paints,
                     image_resolver,
+                    custom_paints,
                 );
             }
             crate::RenderMode::OptimizeQuality => {
@@ -695,6 +733,7 @@ impl Dispatcher for MultiThreadedDispatcher {
                     settings,
                     encoded_paints,
                     image_resolver,
+                    custom_paints,
                 );
             }
         }
@@ -722,6 +761,43 @@ impl Dispatcher for MultiThreadedDispatcher {
         self.clip_context.pop_clip();
     }
 
+    fn snapshot(
+        &self,
+        width: u16,
+        height: u16,
+        encoded_paints: &[EncodedPaint],
+        pattern_tiles: &[PatternTile],
+    ) -> SceneSnapshot {
+        let alpha_slots = self.alpha_storage.take();
+        let alpha_buffers = alpha_slots.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let snapshot = damage::snapshot(
+            &self.recorder,
+            &self.strip_storage.strips,
+            &alpha_buffers,
+            encoded_paints,
+            pattern_tiles,
+            width,
+            height,
+        );
+        self.alpha_storage.init(alpha_slots);
+
+        snapshot
+    }
+
+    fn hit_test(&self, area: RectU16) -> Vec<usize> {
+        let alpha_slots = self.alpha_storage.take();
+        let alpha_buffers = alpha_slots.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        let hits = hit_test::hit_test(
+            &self.recorder,
+            &self.strip_storage.strips,
+            &alpha_buffers,
+            area,
+        );
+        self.alpha_storage.init(alpha_slots);
+
+        hits
+    }
+
     fn is_multi_threaded(&self) -> bool {
         true
     }
@@ -938,18 +1014,19 @@ impl<T: Default> MaybePresent<T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::Level;
     use crate::color::palette::css::BLUE;
     use crate::dispatch::Dispatcher;
     use crate::dispatch::multi_threaded::MultiThreadedDispatcher;
     use crate::kurbo::{Affine, Rect, Shape};
     use crate::peniko::{BlendMode, Fill};
+    use crate::{AntialiasingMode, Level};
     use vello_common::paint::{Paint, PremulColor};
 
     /// Ensure we don't cause a memory leak.
     #[test]
     fn allocations() {
-        let mut dispatcher = MultiThreadedDispatcher::new(100, 100, 4, Level::new());
+        let mut dispatcher =
+            MultiThreadedDispatcher::new(100, 100, 4, Level::new(), AntialiasingMode::Area, false);
         for _ in 0..20 {
             dispatcher.fill_path(
                 &Rect::new(0.0, 0.0, 50.0, 50.0).to_path(0.1),
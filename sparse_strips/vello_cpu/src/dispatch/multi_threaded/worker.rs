@@ -7,6 +7,7 @@ use crate::dispatch::multi_threaded::{
 };
 use std::vec::Vec;
 use vello_common::clip::PathDataRef;
+use vello_common::strip::AntialiasingMode;
 use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
 
 #[derive(Debug)]
@@ -17,8 +18,15 @@ pub(crate) struct Worker {
 }
 
 impl Worker {
-    pub(crate) fn new(width: u16, height: u16, thread_id: u8, level: Level) -> Self {
-        let strip_generator = StripGenerator::new(width, height, level);
+    pub(crate) fn new(
+        width: u16,
+        height: u16,
+        thread_id: u8,
+        level: Level,
+        antialiasing_mode: AntialiasingMode,
+    ) -> Self {
+        let mut strip_generator = StripGenerator::new(width, height, level);
+        strip_generator.set_antialiasing_mode(antialiasing_mode);
         let strip_storage = StripStorage::default();
 
         Self {
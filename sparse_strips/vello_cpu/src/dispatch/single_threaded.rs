@@ -5,16 +5,22 @@ use crate::clip::ClipState;
 use crate::coarse::CommandBucketer;
 use crate::coarse::bucketer::LayerClip;
 use crate::coarse::depth::DepthBuffer;
-use crate::dispatch::Dispatcher;
+use crate::custom_paint::CustomPaintRegistry;
+use crate::damage::{self, SceneSnapshot};
+use crate::dispatch::{Dispatcher, damage_viewports};
 use crate::filter::context::FilterContext;
 use crate::fine::{Fine, FineKernel, FineRenderParams, FineResources, rasterize_region};
+use crate::hit_test;
 use crate::kurbo::{Affine, BezPath, Rect, Stroke};
+use crate::pattern::PatternTile;
 use crate::peniko::{BlendMode, Fill};
 use crate::record::{
     CommandRecorder, FilterData, LayerProps, PoppedLayer, RecordedCmd, RecordedLayerKind,
 };
 use crate::region::Regions;
+use crate::texture::TextureBindings;
 use crate::{CompositeMode, RasterizerSettings};
+use alloc::vec;
 use alloc::vec::Vec;
 use core::cell::RefCell;
 use vello_common::encode::EncodedPaint;
@@ -23,6 +29,7 @@ use vello_common::geometry::RectU16;
 use vello_common::mask::Mask;
 use vello_common::paint::{ImageResolver, Paint};
 use vello_common::pixmap::{Pixmap, PixmapMut};
+use vello_common::strip::AntialiasingMode;
 use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
 use vello_common::util::control_point_bbox_u16;
 
@@ -43,6 +50,8 @@ pub(crate) struct SingleThreadedDispatcher {
     strip_generator_stack: Vec<StripGenerator>,
     /// SIMD level for fearless SIMD dispatch.
     level: Level,
+    /// Whether smooth paints are dithered during fine rasterization.
+    dithering: bool,
 }
 
 impl SingleThreadedDispatcher {
@@ -52,15 +61,26 @@ impl SingleThreadedDispatcher {
     /// * `width` - Width of the rendering surface in pixels.
     /// * `height` - Height of the rendering surface in pixels.
     /// * `level` - SIMD level to use for rasterization.
-    pub(crate) fn new(width: u16, height: u16, level: Level) -> Self {
+    /// * `antialiasing_mode` - The method used to compute the coverage of paths.
+    /// * `dithering` - Whether to dither gradients and blurred rounded rectangles.
+    pub(crate) fn new(
+        width: u16,
+        height: u16,
+        level: Level,
+        antialiasing_mode: AntialiasingMode,
+        dithering: bool,
+    ) -> Self {
+        let mut strip_generator = StripGenerator::new(width, height, level);
+        strip_generator.set_antialiasing_mode(antialiasing_mode);
         Self {
             bucketer: RefCell::new(CommandBucketer::from_wh(width, height)),
             clip_state: ClipState::new(),
             recorder: CommandRecorder::new(),
-            strip_generator: StripGenerator::new(width, height, level),
+            strip_generator,
             strip_storage: StripStorage::new(GenerationMode::Append),
             strip_generator_stack: Vec::new(),
             level,
+            dithering,
         }
     }
 
@@ -74,13 +94,14 @@ impl SingleThreadedDispatcher {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
         use crate::fine::F32Kernel;
         use vello_common::fearless_simd::dispatch;
-        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, F32Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver, custom_paints));
     }
 
     /// Rasterizes the scene using u8 precision (fast).
@@ -93,13 +114,14 @@ impl SingleThreadedDispatcher {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
         use crate::fine::U8Kernel;
         use vello_common::fearless_simd::dispatch;
-        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver));
+        dispatch!(self.level, simd => self.rasterize_with::<_, U8Kernel>(simd, target, scene_width, scene_height, settings, encoded_paints, image_resolver, custom_paints));
     }
 
     // Note: We purposefully don't add `vectorize` to each of these helpers,
@@ -107,31 +129,53 @@ impl SingleThreadedDispatcher {
     fn rasterize_with<S: Simd, F: FineKernel<S>>(
         &self,
         simd: S,
-        target: PixmapMut<'_>,
+        mut target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
-        let filters = self.rasterize_filter_layers::<S, F>(simd, encoded_paints, image_resolver);
-        let use_src_over = settings.composite_mode == CompositeMode::SrcOver;
-        let params = FineRenderParams {
-            scene_size: (scene_width, scene_height),
-            target_offset: settings.offset,
+        let viewports = match settings.damage {
+            Some(damage) => damage_viewports(damage, scene_width, scene_height),
+            None => vec![RectU16::new(0, 0, scene_width, scene_height)],
         };
-
-        self.bucket_and_rasterize::<S, F>(
+        if viewports.is_empty() {
+            return;
+        }
+        let filters = self.rasterize_filter_layers::<S, F>(
             simd,
-            &self.recorder.root_cmds,
-            RectU16::new(0, 0, scene_width, scene_height),
-            &filters,
-            target,
-            params,
-            use_src_over,
             encoded_paints,
             image_resolver,
+            custom_paints,
+            settings.textures,
         );
+        let use_src_over = settings.composite_mode == CompositeMode::SrcOver;
+
+        for viewport in viewports {
+            let params = FineRenderParams {
+                scene_size: (viewport.width(), viewport.height()),
+                target_offset: (
+                    settings.offset.0.saturating_add(viewport.x0),
+                    settings.offset.1.saturating_add(viewport.y0),
+                ),
+            };
+
+            self.bucket_and_rasterize::<S, F>(
+                simd,
+                &self.recorder.root_cmds,
+                viewport,
+                &filters,
+                target.reborrow(),
+                params,
+                use_src_over,
+                encoded_paints,
+                image_resolver,
+                custom_paints,
+                settings.textures,
+            );
+        }
     }
 
     fn bucket_and_rasterize<S: Simd, F: FineKernel<S>>(
@@ -145,6 +189,8 @@ impl SingleThreadedDispatcher {
         use_src_over: bool,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
+        textures: Option<&TextureBindings<'_>>,
     ) {
         let mut bucketer = self.bucketer.borrow_mut();
         bucketer.reset(viewport);
@@ -162,6 +208,9 @@ impl SingleThreadedDispatcher {
             encoded_paints,
             filter_paints: &bucketer.filter_paints,
             image_resolver,
+            custom_paints,
+            textures,
+            dithering: self.dithering,
         };
         let mut regions = Regions::new(
             &mut target,
@@ -219,6 +268,8 @@ impl SingleThreadedDispatcher {
         simd: S,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
+        textures: Option<&TextureBindings<'_>>,
     ) -> FilterContext {
         // TODO: Reuse across frames so that pixmaps can be reused.
         let mut filter_ctx = FilterContext::new(self.recorder.layers.len());
@@ -261,6 +312,8 @@ impl SingleThreadedDispatcher {
                 false,
                 encoded_paints,
                 image_resolver,
+                custom_paints,
+                textures,
             );
 
             F::filter_layer(
@@ -294,7 +347,8 @@ impl SingleThreadedDispatcher {
             .saturating_add(padding.y1);
         // TODO: Once `StripGenerator`s (in particular `Tiles`) can be resized,
         // we can use a pool of strip generators.
-        let filter_generator = StripGenerator::new(width, height, self.level);
+        let mut filter_generator = StripGenerator::new(width, height, self.level);
+        filter_generator.set_antialiasing_mode(self.strip_generator.antialiasing_mode());
         let parent_generator = core::mem::replace(&mut self.strip_generator, filter_generator);
         self.strip_generator_stack.push(parent_generator);
 
@@ -459,9 +513,10 @@ impl Dispatcher for SingleThreadedDispatcher {
         target: PixmapMut<'_>,
         scene_width: u16,
         scene_height: u16,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
         encoded_paints: &[EncodedPaint],
         image_resolver: &dyn ImageResolver,
+        custom_paints: &CustomPaintRegistry,
     ) {
         // If only the u8 pipeline is enabled, then use it.
         #[cfg(all(feature = "u8_pipeline", not(feature = "f32_pipeline")))]
@@ -473,6 +528,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                 settings,
                 encoded_paints,
                 image_resolver,
+                custom_paints,
             );
         }
 
@@ -486,6 +542,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                 settings,
                 encoded_paints,
                 image_resolver,
+                custom_paints,
             );
         }
 
@@ -501,6 +558,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                     settings,
                     encoded_paints,
                     image_resolver,
+                    custom_paints,
                 );
             }
             crate::RenderMode::OptimizeQuality => {
@@ -512,6 +570,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                     settings,
                     encoded_paints,
                     image_resolver,
+                    custom_paints,
                 );
             }
         }
@@ -527,6 +586,7 @@ impl Dispatcher for SingleThreadedDispatcher {
                 settings,
                 encoded_paints,
                 image_resolver,
+                custom_paints,
             );
         }
     }
@@ -551,6 +611,33 @@ impl Dispatcher for SingleThreadedDispatcher {
         self.clip_state.pop_clip();
     }
 
+    fn snapshot(
+        &self,
+        width: u16,
+        height: u16,
+        encoded_paints: &[EncodedPaint],
+        pattern_tiles: &[PatternTile],
+    ) -> SceneSnapshot {
+        damage::snapshot(
+            &self.recorder,
+            &self.strip_storage.strips,
+            &[self.strip_storage.alphas.as_slice()],
+            encoded_paints,
+            pattern_tiles,
+            width,
+            height,
+        )
+    }
+
+    fn hit_test(&self, area: RectU16) -> Vec<usize> {
+        hit_test::hit_test(
+            &self.recorder,
+            &self.strip_storage.strips,
+            &[self.strip_storage.alphas.as_slice()],
+            area,
+        )
+    }
+
     fn is_multi_threaded(&self) -> bool {
         false
     }
@@ -588,7 +675,8 @@ mod tests {
     /// rendering passes without accumulating stale data from previous frames.
     #[test]
     fn buffers_cleared_on_reset() {
-        let mut dispatcher = SingleThreadedDispatcher::new(100, 100, Level::new());
+        let mut dispatcher =
+            SingleThreadedDispatcher::new(100, 100, Level::new(), AntialiasingMode::Area, false);
 
         // Render a simple shape to populate internal buffers.
         dispatcher.fill_path(
@@ -0,0 +1,95 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of image pixels into the color space of the render target.
+
+use crate::fine::Painter;
+use alloc::vec::Vec;
+use vello_common::color::ColorSpaceTag;
+
+/// A painter that converts the premultiplied output of another painter from the given color
+/// space to sRGB.
+#[derive(Debug)]
+pub(crate) struct ColorSpacePainter<'a, P> {
+    inner: P,
+    scratch: &'a mut Vec<f32>,
+    color_space: ColorSpaceTag,
+}
+
+impl<'a, P: Painter> ColorSpacePainter<'a, P> {
+    /// Create a new painter converting from `color_space`.
+    pub(crate) fn new(inner: P, scratch: &'a mut Vec<f32>, color_space: ColorSpaceTag) -> Self {
+        Self {
+            inner,
+            scratch,
+            color_space,
+        }
+    }
+}
+
+/// Convert a premultiplied pixel to sRGB, clipping colors that are out of gamut.
+#[inline(always)]
+fn convert_pixel(pixel: &mut [f32], color_space: ColorSpaceTag) {
+    let alpha = pixel[3];
+    if alpha == 0.0 {
+        return;
+    }
+
+    let converted = color_space.convert(
+        ColorSpaceTag::Srgb,
+        [pixel[0] / alpha, pixel[1] / alpha, pixel[2] / alpha],
+    );
+    for (component, value) in pixel[..3].iter_mut().zip(converted) {
+        *component = value.clamp(0.0, 1.0) * alpha;
+    }
+}
+
+impl<P: Painter> Painter for ColorSpacePainter<'_, P> {
+    fn paint_u8(&mut self, buf: &mut [u8]) {
+        self.scratch.resize(buf.len(), 0.0);
+        self.inner.paint_f32(self.scratch);
+
+        for (pixel, converted) in buf
+            .chunks_exact_mut(4)
+            .zip(self.scratch.chunks_exact_mut(4))
+        {
+            convert_pixel(converted, self.color_space);
+            for (component, value) in pixel.iter_mut().zip(converted.iter()) {
+                *component = (value * 255.0 + 0.5) as u8;
+            }
+        }
+    }
+
+    fn paint_f32(&mut self, buf: &mut [f32]) {
+        self.inner.paint_f32(buf);
+
+        for pixel in buf.chunks_exact_mut(4) {
+            convert_pixel(pixel, self.color_space);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_premultiplied() {
+        // Pure Display P3 red at half opacity is out of the sRGB gamut.
+        let mut pixel = [0.5, 0.0, 0.0, 0.5];
+        convert_pixel(&mut pixel, ColorSpaceTag::DisplayP3);
+        assert_eq!(pixel, [0.5, 0.0, 0.0, 0.5]);
+
+        // A P3 gray stays gray.
+        let mut pixel = [0.25, 0.25, 0.25, 0.5];
+        convert_pixel(&mut pixel, ColorSpaceTag::DisplayP3);
+        for component in &pixel[..3] {
+            assert!((component - 0.25).abs() < 1e-3, "{pixel:?}");
+        }
+
+        // Linear sRGB mid gray is brighter in sRGB.
+        let mut pixel = [0.5, 0.5, 0.5, 1.0];
+        convert_pixel(&mut pixel, ColorSpaceTag::LinearSrgb);
+        assert!((pixel[0] - 0.735).abs() < 1e-3, "{pixel:?}");
+    }
+}
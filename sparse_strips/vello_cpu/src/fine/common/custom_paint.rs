@@ -0,0 +1,30 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Drawing custom paints.
+
+use crate::custom_paint::{CustomPaint, CustomPaintSpan};
+use crate::fine::Painter;
+
+/// A painter that forwards to a user-defined [`CustomPaint`].
+#[derive(Debug)]
+pub(crate) struct CustomPainter<'a> {
+    paint: &'a dyn CustomPaint,
+    span: CustomPaintSpan<'a>,
+}
+
+impl<'a> CustomPainter<'a> {
+    pub(crate) fn new(paint: &'a dyn CustomPaint, span: CustomPaintSpan<'a>) -> Self {
+        Self { paint, span }
+    }
+}
+
+impl Painter for CustomPainter<'_> {
+    fn paint_u8(&mut self, buf: &mut [u8]) {
+        self.paint.paint_u8(&self.span, buf);
+    }
+
+    fn paint_f32(&mut self, buf: &mut [f32]) {
+        self.paint.paint_f32(&self.span, buf);
+    }
+}
@@ -0,0 +1,125 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Ordered dithering of smooth paints.
+//!
+//! Gradients and blurred shapes with a low contrast only span a few of the 256 values a
+//! component can have in the final pixmap, which makes the steps between them visible as
+//! bands. Adding a small, position-dependent offset before quantizing breaks up those bands
+//! into a fine pattern that averages out to the exact color.
+
+use crate::fine::Painter;
+use alloc::vec::Vec;
+use vello_common::tile::Tile;
+
+/// The 8x8 Bayer matrix, indexed by `[y % 8][x % 8]`.
+const BAYER_8X8: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Return the dithering offset for the pixel at the given position, as a fraction of one
+/// 8-bit step in the range from -0.5 to 0.5.
+#[inline(always)]
+fn dither_offset(x: usize, y: usize) -> f32 {
+    let threshold = BAYER_8X8[y % 8][x % 8];
+    (f32::from(threshold) + 0.5) / 64.0 - 0.5
+}
+
+/// A painter that applies ordered dithering to the output of another painter.
+///
+/// The inner painter always paints with `f32` precision, so that the dithering can
+/// distribute the error that would otherwise be introduced by quantizing to `u8`.
+#[derive(Debug)]
+pub(crate) struct DitheredPainter<'a, P> {
+    inner: P,
+    scratch: &'a mut Vec<f32>,
+    start_x: u16,
+    start_y: u16,
+}
+
+impl<'a, P: Painter> DitheredPainter<'a, P> {
+    /// Create a new dithering painter for the span starting at the given pixel position.
+    pub(crate) fn new(inner: P, scratch: &'a mut Vec<f32>, start_x: u16, start_y: u16) -> Self {
+        Self {
+            inner,
+            scratch,
+            start_x,
+            start_y,
+        }
+    }
+}
+
+/// Iterate over the dithering offsets of the pixels of a span in column-major order, scaled
+/// to the range of `f32` components.
+fn offsets(start_x: u16, start_y: u16) -> impl Iterator<Item = f32> {
+    let height = usize::from(Tile::HEIGHT);
+    let (start_x, start_y) = (usize::from(start_x), usize::from(start_y));
+    (0..).map(move |idx| dither_offset(start_x + idx / height, start_y + idx % height) / 255.0)
+}
+
+/// Add the offset to the color components of a premultiplied pixel, keeping them valid.
+#[inline(always)]
+fn dither_pixel(pixel: &mut [f32], offset: f32) {
+    let alpha = pixel[3];
+    for component in &mut pixel[..3] {
+        *component = (*component + offset).clamp(0.0, alpha);
+    }
+}
+
+impl<P: Painter> Painter for DitheredPainter<'_, P> {
+    fn paint_u8(&mut self, buf: &mut [u8]) {
+        self.scratch.resize(buf.len(), 0.0);
+        self.inner.paint_f32(self.scratch);
+
+        for ((pixel, dithered), offset) in buf
+            .chunks_exact_mut(4)
+            .zip(self.scratch.chunks_exact_mut(4))
+            .zip(offsets(self.start_x, self.start_y))
+        {
+            dither_pixel(dithered, offset);
+            for (component, value) in pixel.iter_mut().zip(dithered.iter()) {
+                *component = (value * 255.0 + 0.5) as u8;
+            }
+        }
+    }
+
+    fn paint_f32(&mut self, buf: &mut [f32]) {
+        self.inner.paint_f32(buf);
+
+        for (pixel, offset) in buf
+            .chunks_exact_mut(4)
+            .zip(offsets(self.start_x, self.start_y))
+        {
+            dither_pixel(pixel, offset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bayer_matrix_is_permutation() {
+        let mut seen = [false; 64];
+        for threshold in BAYER_8X8.iter().flatten() {
+            assert!(!seen[usize::from(*threshold)]);
+            seen[usize::from(*threshold)] = true;
+        }
+    }
+
+    #[test]
+    fn offsets_average_to_zero() {
+        let sum: f32 = (0..8)
+            .flat_map(|y| (0..8).map(move |x| dither_offset(x, y)))
+            .sum();
+        assert!(sum.abs() < 1e-4);
+    }
+}
@@ -3,10 +3,15 @@
 
 use crate::fine::macros::{f32x16_painter, u8x16_painter};
 use crate::fine::{PosExt, Splat4thExt, u8_to_f32};
-use crate::kurbo::Point;
-use vello_common::encode::EncodedImage;
+use crate::kurbo::{Affine, Point, Vec2};
+use crate::peniko::{ImageQuality, ImageSampler};
+use vello_common::color::ColorSpaceTag;
+use vello_common::encode::{EncodedExternalTexture, EncodedImage};
 use vello_common::fearless_simd::{Bytes, Simd, SimdBase, SimdFloat, f32x4, f32x16, u8x16, u32x4};
-use vello_common::pixmap::Pixmap;
+use vello_common::geometry::RectU16;
+use vello_common::math::FloatExt;
+use vello_common::pixmap::{Pixmap, PixmapRef};
+use vello_common::raw_image::RawImage;
 use vello_common::simd::element_wise_splat;
 
 /// A painter for nearest-neighbor images with no skewing.
@@ -22,12 +27,12 @@ pub(crate) struct PlainNNImagePainter<'a, S: Simd> {
 impl<'a, S: Simd> PlainNNImagePainter<'a, S> {
     pub(crate) fn new(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> Self {
-        let data = ImagePainterData::new(simd, image, pixmap, start_x, start_y);
+        let data = ImagePainterData::new(simd, sampling, pixels, start_x, start_y);
 
         simd.vectorize(
             #[inline(always)]
@@ -40,7 +45,7 @@ impl<'a, S: Simd> PlainNNImagePainter<'a, S> {
                         data.x_advances.1,
                         data.y_advances.1,
                     ),
-                    image.sampler.y_extend,
+                    sampling.sampler.y_extend,
                     data.height,
                     data.height_inv,
                 );
@@ -54,7 +59,7 @@ impl<'a, S: Simd> PlainNNImagePainter<'a, S> {
 
                 Self {
                     data,
-                    advance: image.x_advance.x as f32,
+                    advance: sampling.x_advance.x as f32,
                     y_positions,
                     cur_x_pos,
                     simd,
@@ -72,7 +77,7 @@ impl<S: Simd> Iterator for PlainNNImagePainter<'_, S> {
         let x_pos = extend(
             self.simd,
             self.cur_x_pos,
-            self.data.image.sampler.x_extend,
+            self.data.sampling.sampler.x_extend,
             self.data.width,
             self.data.width_inv,
         );
@@ -97,12 +102,12 @@ pub(crate) struct NNImagePainter<'a, S: Simd> {
 impl<'a, S: Simd> NNImagePainter<'a, S> {
     pub(crate) fn new(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> Self {
-        let data = ImagePainterData::new(simd, image, pixmap, start_x, start_y);
+        let data = ImagePainterData::new(simd, sampling, pixels, start_x, start_y);
 
         Self { data, simd }
     }
@@ -121,7 +126,7 @@ impl<S: Simd> Iterator for NNImagePainter<'_, S> {
                 self.data.x_advances.0,
                 self.data.y_advances.0,
             ),
-            self.data.image.sampler.x_extend,
+            self.data.sampling.sampler.x_extend,
             self.data.width,
             self.data.width_inv,
         );
@@ -134,14 +139,14 @@ impl<S: Simd> Iterator for NNImagePainter<'_, S> {
                 self.data.x_advances.1,
                 self.data.y_advances.1,
             ),
-            self.data.image.sampler.y_extend,
+            self.data.sampling.sampler.y_extend,
             self.data.height,
             self.data.height_inv,
         );
 
         let samples = sample(self.simd, &self.data, x_positions, y_positions);
 
-        self.data.cur_pos += self.data.image.x_advance;
+        self.data.cur_pos += self.data.sampling.x_advance;
 
         Some(samples)
     }
@@ -167,12 +172,12 @@ pub(crate) struct FilteredImagePainter<'a, S: Simd, const QUALITY: u8> {
 impl<'a, S: Simd, const QUALITY: u8> FilteredImagePainter<'a, S, QUALITY> {
     pub(crate) fn new(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> Self {
-        let data = ImagePainterData::new(simd, image, pixmap, start_x, start_y);
+        let data = ImagePainterData::new(simd, sampling, pixels, start_x, start_y);
 
         Self { data, simd }
     }
@@ -221,7 +226,7 @@ impl<S: Simd, const QUALITY: u8> Iterator for FilteredImagePainter<'_, S, QUALIT
                 extend(
                     self.simd,
                     x_positions + $offsets[$idx],
-                    self.data.image.sampler.y_extend,
+                    self.data.sampling.sampler.x_extend,
                     self.data.width,
                     self.data.width_inv,
                 )
@@ -233,7 +238,7 @@ impl<S: Simd, const QUALITY: u8> Iterator for FilteredImagePainter<'_, S, QUALIT
                 extend(
                     self.simd,
                     y_positions + $offsets[$idx],
-                    self.data.image.sampler.y_extend,
+                    self.data.sampling.sampler.y_extend,
                     self.data.height,
                     self.data.height_inv,
                 )
@@ -330,7 +335,7 @@ impl<S: Simd, const QUALITY: u8> Iterator for FilteredImagePainter<'_, S, QUALIT
             ),
         }
 
-        self.data.cur_pos += self.data.image.x_advance;
+        self.data.cur_pos += self.data.sampling.x_advance;
 
         Some(interpolated_color)
     }
@@ -350,12 +355,108 @@ pub(crate) fn fract_floor<S: Simd>(val: f32x4<S>) -> f32x4<S> {
     val - val.floor()
 }
 
+/// How an image painter samples its pixels.
+///
+/// Images and external textures are sampled the same way, so the painters only take these
+/// parameters and the [pixels](ImagePixels) instead of the encoded paint.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageSampling {
+    /// The sampler, which determines the extend modes and the filtering.
+    pub sampler: ImageSampler,
+    /// The transform from the coordinate system of the render target to the one of the pixels.
+    pub transform: Affine,
+    /// The advance in the coordinate system of the pixels for one step in the x direction.
+    pub x_advance: Vec2,
+    /// The advance in the coordinate system of the pixels for one step in the y direction.
+    pub y_advance: Vec2,
+}
+
+impl ImageSampling {
+    /// Whether the transform has a skewing (or rotating) component.
+    pub(crate) fn has_skew(&self) -> bool {
+        !(self.x_advance.y as f32).is_nearly_zero() || !(self.y_advance.x as f32).is_nearly_zero()
+    }
+
+    /// Whether the pixels are sampled with nearest-neighbor filtering.
+    pub(crate) fn nearest_neighbor(&self) -> bool {
+        self.sampler.quality == ImageQuality::Low
+    }
+}
+
+impl From<&EncodedImage> for ImageSampling {
+    fn from(image: &EncodedImage) -> Self {
+        Self {
+            sampler: image.sampler,
+            transform: image.transform,
+            x_advance: image.x_advance,
+            y_advance: image.y_advance,
+        }
+    }
+}
+
+impl From<&EncodedExternalTexture> for ImageSampling {
+    fn from(texture: &EncodedExternalTexture) -> Self {
+        Self {
+            sampler: texture.sampler,
+            transform: texture.transform,
+            x_advance: texture.x_advance,
+            y_advance: texture.y_advance,
+        }
+    }
+}
+
+/// The pixels an image painter samples from.
+#[derive(Debug, Clone, Copy)]
+pub enum ImagePixels<'a> {
+    /// Premultiplied RGBA8 pixels, which can be read directly.
+    Pixmap(&'a Pixmap),
+    /// Pixels in a different format, which are converted to premultiplied RGBA8 when sampled.
+    Raw(&'a RawImage),
+    /// A region of an external texture, in premultiplied RGBA8.
+    Texture {
+        /// The pixels of the whole texture.
+        pixels: PixmapRef<'a>,
+        /// The region of the texture that is sampled, which must lie within its bounds.
+        region: RectU16,
+    },
+}
+
+impl ImagePixels<'_> {
+    /// Return the width of the image.
+    pub fn width(&self) -> u16 {
+        match self {
+            Self::Pixmap(pixmap) => pixmap.width(),
+            Self::Raw(raw) => raw.width(),
+            Self::Texture { region, .. } => region.width(),
+        }
+    }
+
+    /// Return the height of the image.
+    pub fn height(&self) -> u16 {
+        match self {
+            Self::Pixmap(pixmap) => pixmap.height(),
+            Self::Raw(raw) => raw.height(),
+            Self::Texture { region, .. } => region.height(),
+        }
+    }
+
+    /// Return the color space of the pixels.
+    pub fn color_space(&self) -> ColorSpaceTag {
+        match self {
+            Self::Pixmap(pixmap) => pixmap.color_space(),
+            // Raw images are always converted to sRGB.
+            Self::Raw(_) => ColorSpaceTag::Srgb,
+            Self::Texture { .. } => ColorSpaceTag::Srgb,
+        }
+    }
+}
+
 /// Common data used by different image painters
 #[derive(Debug)]
 pub(crate) struct ImagePainterData<'a, S: Simd> {
     pub(crate) cur_pos: Point,
-    pub(crate) image: &'a EncodedImage,
-    pub(crate) pixmap: &'a Pixmap,
+    pub(crate) sampling: ImageSampling,
+    pub(crate) pixels: ImagePixels<'a>,
     pub(crate) x_advances: (f32, f32),
     pub(crate) y_advances: (f32, f32),
     pub(crate) height: f32x4<S>,
@@ -368,33 +469,33 @@ pub(crate) struct ImagePainterData<'a, S: Simd> {
 impl<'a, S: Simd> ImagePainterData<'a, S> {
     pub(crate) fn new(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> Self {
         simd.vectorize(
             #[inline(always)]
             || {
-                let width = pixmap.width() as f32;
-                let height = pixmap.height() as f32;
-                let start_pos = image.transform * Point::new(start_x, start_y);
+                let width = pixels.width() as f32;
+                let height = pixels.height() as f32;
+                let start_pos = sampling.transform * Point::new(start_x, start_y);
 
                 let width_inv = f32x4::splat(simd, 1.0 / width);
                 let height_inv = f32x4::splat(simd, 1.0 / height);
                 let width = f32x4::splat(simd, width);
-                let width_u32 = u32x4::splat(simd, pixmap.width() as u32);
+                let width_u32 = u32x4::splat(simd, pixels.width() as u32);
                 let height = f32x4::splat(simd, height);
 
-                let x_advances = (image.x_advance.x as f32, image.x_advance.y as f32);
-                let y_advances = (image.y_advance.x as f32, image.y_advance.y as f32);
+                let x_advances = (sampling.x_advance.x as f32, sampling.x_advance.y as f32);
+                let y_advances = (sampling.y_advance.x as f32, sampling.y_advance.y as f32);
 
                 Self {
                     cur_pos: start_pos,
-                    pixmap,
+                    pixels,
                     x_advances,
                     y_advances,
-                    image,
+                    sampling,
                     width,
                     height,
                     width_u32,
@@ -413,18 +514,38 @@ pub(crate) fn sample<S: Simd>(
     x_positions: f32x4<S>,
     y_positions: f32x4<S>,
 ) -> u8x16<S> {
-    let idx = x_positions.to_int::<u32x4<S>>() + y_positions.to_int::<u32x4<S>>() * data.width_u32;
-
-    u32x4::from_slice(
-        simd,
-        &[
-            data.pixmap.sample_idx(idx[0]).to_u32(),
-            data.pixmap.sample_idx(idx[1]).to_u32(),
-            data.pixmap.sample_idx(idx[2]).to_u32(),
-            data.pixmap.sample_idx(idx[3]).to_u32(),
-        ],
-    )
-    .to_bytes()
+    let samples = match data.pixels {
+        ImagePixels::Pixmap(pixmap) => {
+            let idx = x_positions.to_int::<u32x4<S>>()
+                + y_positions.to_int::<u32x4<S>>() * data.width_u32;
+
+            [
+                pixmap.sample_idx(idx[0]).to_u32(),
+                pixmap.sample_idx(idx[1]).to_u32(),
+                pixmap.sample_idx(idx[2]).to_u32(),
+                pixmap.sample_idx(idx[3]).to_u32(),
+            ]
+        }
+        ImagePixels::Raw(raw) => {
+            // The positions have already been extended into the bounds of the image.
+            let x = x_positions.to_int::<u32x4<S>>();
+            let y = y_positions.to_int::<u32x4<S>>();
+
+            core::array::from_fn(|i| raw.sample(x[i] as u16, y[i] as u16).to_u32())
+        }
+        ImagePixels::Texture { pixels, region } => {
+            let x = x_positions.to_int::<u32x4<S>>();
+            let y = y_positions.to_int::<u32x4<S>>();
+
+            core::array::from_fn(|i| {
+                pixels
+                    .sample(region.x0 + x[i] as u16, region.y0 + y[i] as u16)
+                    .to_u32()
+            })
+        }
+    };
+
+    u32x4::from_slice(simd, &samples).to_bytes()
 }
 
 #[inline(always)]
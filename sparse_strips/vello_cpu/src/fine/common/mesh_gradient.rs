@@ -0,0 +1,55 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Drawing mesh gradients.
+
+use crate::fine::Painter;
+use vello_common::mesh_gradient::EncodedMeshGradient;
+use vello_common::tile::Tile;
+
+/// A painter for mesh gradients.
+///
+/// Finding the triangle that covers a pixel doesn't vectorize well, so unlike the other
+/// painters this one works on a single pixel at a time.
+#[derive(Debug)]
+pub(crate) struct MeshGradientPainter<'a> {
+    mesh: &'a EncodedMeshGradient,
+    start_x: f32,
+    start_y: f32,
+}
+
+impl<'a> MeshGradientPainter<'a> {
+    pub(crate) fn new(mesh: &'a EncodedMeshGradient, start_x: f64, start_y: f64) -> Self {
+        Self {
+            mesh,
+            start_x: start_x as f32,
+            start_y: start_y as f32,
+        }
+    }
+
+    /// Iterate over the premultiplied colors of the pixels in column-major order.
+    fn colors(&self) -> impl Iterator<Item = [f32; 4]> + '_ {
+        let height = usize::from(Tile::HEIGHT);
+        (0..).map(move |idx| {
+            let x = self.start_x + (idx / height) as f32;
+            let y = self.start_y + (idx % height) as f32;
+            self.mesh.sample(x, y)
+        })
+    }
+}
+
+impl Painter for MeshGradientPainter<'_> {
+    fn paint_u8(&mut self, buf: &mut [u8]) {
+        for (pixel, color) in buf.chunks_exact_mut(4).zip(self.colors()) {
+            for (component, value) in pixel.iter_mut().zip(color) {
+                *component = (value * 255.0 + 0.5) as u8;
+            }
+        }
+    }
+
+    fn paint_f32(&mut self, buf: &mut [f32]) {
+        for (pixel, color) in buf.chunks_exact_mut(4).zip(self.colors()) {
+            pixel.copy_from_slice(&color);
+        }
+    }
+}
@@ -1,6 +1,10 @@
 // Copyright 2025 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+pub(crate) mod color_space;
+pub(crate) mod custom_paint;
+pub(crate) mod dither;
 pub(crate) mod gradient;
 pub(crate) mod image;
+pub(crate) mod mesh_gradient;
 pub(crate) mod rounded_blurred_rect;
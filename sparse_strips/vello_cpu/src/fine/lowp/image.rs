@@ -2,11 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::fine::PosExt;
-use crate::fine::common::image::{ImagePainterData, extend, fract_floor, sample};
+use crate::fine::common::image::{
+    ImagePainterData, ImagePixels, ImageSampling, extend, fract_floor, sample,
+};
 use crate::fine::macros::u8x16_painter;
-use vello_common::encode::EncodedImage;
 use vello_common::fearless_simd::{Simd, SimdBase, SimdFloat, f32x4, u8x16, u16x16};
-use vello_common::pixmap::Pixmap;
 use vello_common::simd::element_wise_splat;
 use vello_common::util::{Div255Ext, f32_to_u8};
 
@@ -20,12 +20,12 @@ pub(crate) struct BilinearImagePainter<'a, S: Simd> {
 impl<'a, S: Simd> BilinearImagePainter<'a, S> {
     pub(crate) fn new(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> Self {
-        let data = ImagePainterData::new(simd, image, pixmap, start_x, start_y);
+        let data = ImagePainterData::new(simd, sampling, pixels, start_x, start_y);
 
         Self { data, simd }
     }
@@ -54,7 +54,7 @@ impl<S: Simd> Iterator for BilinearImagePainter<'_, S> {
             extend(
                 self.simd,
                 x_pos,
-                self.data.image.sampler.x_extend,
+                self.data.sampling.sampler.x_extend,
                 self.data.width,
                 self.data.width_inv,
             )
@@ -64,7 +64,7 @@ impl<S: Simd> Iterator for BilinearImagePainter<'_, S> {
             extend(
                 self.simd,
                 y_pos,
-                self.data.image.sampler.y_extend,
+                self.data.sampling.sampler.y_extend,
                 self.data.height,
                 self.data.height_inv,
             )
@@ -106,7 +106,7 @@ impl<S: Simd> Iterator for BilinearImagePainter<'_, S> {
         let ip2 = (p01 * fx_inv + p11 * fx).div_255();
         let res = self.simd.narrow_u16x16((ip1 * fy_inv + ip2 * fy).div_255());
 
-        self.data.cur_pos += self.data.image.x_advance;
+        self.data.cur_pos += self.data.sampling.x_advance;
 
         Some(res)
     }
@@ -139,12 +139,12 @@ pub(crate) struct PlainBilinearImagePainter<'a, S: Simd> {
 impl<'a, S: Simd> PlainBilinearImagePainter<'a, S> {
     pub(crate) fn new(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> Self {
-        let data = ImagePainterData::new(simd, image, pixmap, start_x, start_y);
+        let data = ImagePainterData::new(simd, sampling, pixels, start_x, start_y);
 
         simd.vectorize(
             #[inline(always)]
@@ -161,14 +161,14 @@ impl<'a, S: Simd> PlainBilinearImagePainter<'a, S> {
                 let y_pos1 = extend(
                     simd,
                     y_positions - 0.5,
-                    image.sampler.y_extend,
+                    sampling.sampler.y_extend,
                     data.height,
                     data.height_inv,
                 );
                 let y_pos2 = extend(
                     simd,
                     y_positions + 0.5,
-                    image.sampler.y_extend,
+                    sampling.sampler.y_extend,
                     data.height,
                     data.height_inv,
                 );
@@ -195,7 +195,7 @@ impl<'a, S: Simd> PlainBilinearImagePainter<'a, S> {
                     fy,
                     fy_inv,
                     cur_x_pos,
-                    advance: image.x_advance.x as f32,
+                    advance: sampling.x_advance.x as f32,
                     simd,
                 }
             },
@@ -215,14 +215,14 @@ impl<S: Simd> Iterator for PlainBilinearImagePainter<'_, S> {
         let x_pos1 = extend(
             self.simd,
             x_minus_half,
-            self.data.image.sampler.x_extend,
+            self.data.sampling.sampler.x_extend,
             self.data.width,
             self.data.width_inv,
         );
         let x_pos2 = extend(
             self.simd,
             x_plus_half,
-            self.data.image.sampler.x_extend,
+            self.data.sampling.sampler.x_extend,
             self.data.width,
             self.data.width_inv,
         );
@@ -16,14 +16,17 @@ mod image;
 use crate::filter::context::ScratchBuffer;
 use crate::filter::filter_lowp;
 use crate::fine::lowp::image::{BilinearImagePainter, PlainBilinearImagePainter};
-use crate::fine::{COLOR_COMPONENTS, FineKernel, Painter, Splat4thExt, TILE_HEIGHT_COMPONENTS};
+use crate::fine::{
+    COLOR_COMPONENTS, FineKernel, ImagePixels, ImageSampling, Painter, Splat4thExt,
+    TILE_HEIGHT_COMPONENTS,
+};
 use crate::peniko::BlendMode;
 use crate::region::Region;
 use crate::util::NormalizedMulExt;
 use crate::util::scalar::div_255;
 use bytemuck::{cast_slice, cast_slice_mut};
 use core::iter;
-use vello_common::encode::{EncodedGradient, EncodedImage};
+use vello_common::encode::EncodedGradient;
 use vello_common::fearless_simd::*;
 use vello_common::filter_effects::Filter;
 use vello_common::kurbo::Affine;
@@ -96,14 +99,14 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
     /// Returns a painter that samples the image with bilinear interpolation.
     fn medium_quality_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || BilinearImagePainter::new(simd, image, pixmap, start_x, start_y),
+            || BilinearImagePainter::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
@@ -113,14 +116,14 @@ impl<S: Simd> FineKernel<S> for U8Kernel {
     /// This is an optimized version for images without skew transformation.
     fn plain_medium_quality_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || PlainBilinearImagePainter::new(simd, image, pixmap, start_x, start_y),
+            || PlainBilinearImagePainter::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
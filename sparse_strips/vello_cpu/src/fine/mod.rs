@@ -13,31 +13,40 @@ mod lowp;
 
 use crate::coarse::depth::DepthBuffer;
 use crate::coarse::{CommandBucketer, LayerFillAttrs, RenderCmd, RowState};
+use crate::custom_paint::CustomPaintSpan;
 use crate::filter::context::ScratchBuffer;
+use crate::fine::common::color_space::ColorSpacePainter;
+use crate::fine::common::custom_paint::CustomPainter;
+use crate::fine::common::dither::DitheredPainter;
 use crate::fine::common::gradient::GradientPainter;
 pub(crate) use crate::fine::common::gradient::calculate_t_vals;
 pub(crate) use crate::fine::common::gradient::linear::SimdLinearKind;
 pub(crate) use crate::fine::common::gradient::radial::SimdRadialKind;
 pub(crate) use crate::fine::common::gradient::sweep::SimdSweepKind;
 use crate::fine::common::image::{FilteredImagePainter, NNImagePainter, PlainNNImagePainter};
+use crate::fine::common::mesh_gradient::MeshGradientPainter;
 use crate::fine::common::rounded_blurred_rect::BlurredRoundedRectFiller;
+use crate::peniko::color::ColorSpaceTag;
 use crate::peniko::{BlendMode, ImageQuality};
 use crate::region::Region;
-use crate::util::{EncodedImageExt, VecPool};
+use crate::texture::TextureBindings;
+use crate::util::VecPool;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::iter;
 use vello_common::encode::{
-    EncodedBlurredRoundedRectangle, EncodedGradient, EncodedImage, EncodedKind, EncodedPaint,
+    EncodedBlurredRoundedRectangle, EncodedGradient, EncodedKind, EncodedPaint,
 };
 use vello_common::fearless_simd::{
     Bytes, Simd, SimdBase, SimdFloat, SimdInt, SimdInto, f32x4, f32x8, f32x16, u8x16, u8x32, u32x4,
     u32x8,
 };
 use vello_common::filter_effects::Filter;
+use vello_common::geometry::RectU16;
 use vello_common::kurbo::Affine;
 use vello_common::mask::Mask;
+use vello_common::mesh_gradient::EncodedMeshGradient;
 use vello_common::paint::{ImageResolver, ImageSource, Paint, PremulColor, Tint};
 use vello_common::pixmap::Pixmap;
 use vello_common::simd::Splat4thExt;
@@ -47,7 +56,9 @@ use vello_common::util::f32_to_u8;
 #[doc(hidden)]
 pub use crate::coarse::PaintFillAttrs;
 #[doc(hidden)]
+pub use crate::custom_paint::CustomPaintRegistry;
 pub use crate::util::Span;
+pub use common::image::{ImagePixels, ImageSampling};
 pub use highp::F32Kernel;
 pub use lowp::U8Kernel;
 
@@ -294,14 +305,14 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     /// transform. This is the fastest image rendering path.
     fn plain_nn_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || PlainNNImagePainter::new(simd, image, pixmap, start_x, start_y),
+            || PlainNNImagePainter::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
@@ -311,14 +322,14 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     /// including skewing and rotation.
     fn nn_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || NNImagePainter::new(simd, image, pixmap, start_x, start_y),
+            || NNImagePainter::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
@@ -327,14 +338,14 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     /// Uses bilinear filtering for smoother appearance than nearest-neighbor.
     fn medium_quality_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || FilteredImagePainter::<S, 1>::new(simd, image, pixmap, start_x, start_y),
+            || FilteredImagePainter::<S, 1>::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
@@ -343,14 +354,14 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     /// Optimized painter for images with bilinear filtering and no skewing component.
     fn plain_medium_quality_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || FilteredImagePainter::<S, 1>::new(simd, image, pixmap, start_x, start_y),
+            || FilteredImagePainter::<S, 1>::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
@@ -359,14 +370,14 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
     /// Uses high-quality filtering for the best visual appearance.
     fn high_quality_image_painter<'a>(
         simd: S,
-        image: &'a EncodedImage,
-        pixmap: &'a Pixmap,
+        sampling: ImageSampling,
+        pixels: ImagePixels<'a>,
         start_x: f64,
         start_y: f64,
     ) -> impl Painter + 'a {
         simd.vectorize(
             #[inline(always)]
-            || FilteredImagePainter::<S, 2>::new(simd, image, pixmap, start_x, start_y),
+            || FilteredImagePainter::<S, 2>::new(simd, sampling, pixels, start_x, start_y),
         )
     }
 
@@ -385,6 +396,15 @@ pub trait FineKernel<S: Simd>: Send + Sync + 'static {
             || BlurredRoundedRectFiller::new(simd, rect, start_x, start_y),
         )
     }
+
+    /// Create a painter for rendering mesh gradients.
+    fn mesh_gradient_painter<'a>(
+        mesh: &'a EncodedMeshGradient,
+        start_x: f64,
+        start_y: f64,
+    ) -> impl Painter + 'a {
+        MeshGradientPainter::new(mesh, start_x, start_y)
+    }
     /// Apply a mask to the destination buffer.
     ///
     /// Multiplies each pixel in the destination by the corresponding mask value,
@@ -509,6 +529,9 @@ pub struct Fine<S: Simd, T: FineKernel<S>> {
     paint_buf: Vec<T::Numeric>,
     /// Buffer for storing gradient interpolation parameters (t values).
     f32_buf: Vec<f32>,
+    /// Buffer for storing unquantized colors of paints that are dithered or converted
+    /// between color spaces.
+    unquantized_buf: Vec<f32>,
     /// The current strip row y-coordinate in scene/filter coordinates.
     row_y: u16,
     /// The origin of the current target we are rendering into.
@@ -529,6 +552,7 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
             buffer_pool: VecPool::new(false),
             paint_buf: Vec::new(),
             f32_buf: Vec::new(),
+            unquantized_buf: Vec::new(),
             row_y: 0,
             origin: (0, 0),
         }
@@ -930,10 +954,21 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
 
         match encoded_paint {
             EncodedPaint::BlurredRoundedRect(rect) => {
-                fill_complex_paint!(
-                    true,
-                    T::blurred_rounded_rectangle_painter(simd, rect, sampler_x, sampler_y)
-                );
+                let painter =
+                    T::blurred_rounded_rectangle_painter(simd, rect, sampler_x, sampler_y);
+                if resources.dithering {
+                    fill_complex_paint!(
+                        true,
+                        DitheredPainter::new(
+                            painter,
+                            &mut self.unquantized_buf,
+                            sample_x,
+                            sample_y
+                        )
+                    );
+                } else {
+                    fill_complex_paint!(true, painter);
+                }
             }
             EncodedPaint::Gradient(gradient) => {
                 // Note that we are calculating the t values first, store them in a separate
@@ -943,7 +978,7 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                 // position calculation need to be inlined for good performance.
                 let t_vals = &mut self.f32_buf[..width * Tile::HEIGHT as usize];
 
-                match &gradient.kind {
+                let has_undefined = match &gradient.kind {
                     EncodedKind::Linear(kind) => {
                         calculate_t_vals(
                             simd,
@@ -953,10 +988,7 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                             sampler_x,
                             sampler_y,
                         );
-                        fill_complex_paint!(
-                            gradient.may_have_transparency,
-                            T::gradient_painter(simd, gradient, t_vals)
-                        );
+                        false
                     }
                     EncodedKind::Sweep(kind) => {
                         calculate_t_vals(
@@ -967,10 +999,7 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                             sampler_x,
                             sampler_y,
                         );
-                        fill_complex_paint!(
-                            gradient.may_have_transparency,
-                            T::gradient_painter(simd, gradient, t_vals)
-                        );
+                        false
                     }
                     EncodedKind::Radial(kind) => {
                         calculate_t_vals(
@@ -981,90 +1010,187 @@ impl<S: Simd, T: FineKernel<S>> Fine<S, T> {
                             sampler_x,
                             sampler_y,
                         );
-
-                        if kind.has_undefined() {
-                            fill_complex_paint!(
-                                gradient.may_have_transparency,
-                                T::gradient_painter_with_undefined(simd, gradient, t_vals)
-                            );
-                        } else {
-                            fill_complex_paint!(
-                                gradient.may_have_transparency,
-                                T::gradient_painter(simd, gradient, t_vals)
-                            );
-                        }
+                        kind.has_undefined()
                     }
+                };
+                let t_vals = &*t_vals;
+
+                if resources.dithering {
+                    // Dithering needs the unquantized colors, so we always use the `f32`
+                    // lookup table, even in the `u8` pipeline.
+                    fill_complex_paint!(
+                        gradient.may_have_transparency,
+                        DitheredPainter::new(
+                            GradientPainter::new(simd, gradient, t_vals),
+                            &mut self.unquantized_buf,
+                            sample_x,
+                            sample_y
+                        )
+                    );
+                } else if has_undefined {
+                    fill_complex_paint!(
+                        gradient.may_have_transparency,
+                        T::gradient_painter_with_undefined(simd, gradient, t_vals)
+                    );
+                } else {
+                    fill_complex_paint!(
+                        gradient.may_have_transparency,
+                        T::gradient_painter(simd, gradient, t_vals)
+                    );
                 }
             }
-            EncodedPaint::Image(image) => {
-                let pixmap = match &image.source {
-                    ImageSource::Pixmap(pixmap) => pixmap.clone(),
-                    ImageSource::OpaqueId { id, .. } => resources
-                        .image_resolver
-                        .resolve(*id)
-                        .unwrap_or_else(|| panic!("Image {:?} not found in registry", id)),
+            EncodedPaint::Image(_) | EncodedPaint::ExternalTexture(_) => {
+                let resolved;
+                let mip_level;
+                let (sampling, pixels, may_have_transparency, tint) = match encoded_paint {
+                    EncodedPaint::Image(image) => {
+                        let pixels = match &image.source {
+                            ImageSource::Pixmap(pixmap) => ImagePixels::Pixmap(pixmap),
+                            ImageSource::OpaqueId { id, .. } => {
+                                resolved =
+                                    resources.image_resolver.resolve(*id).unwrap_or_else(|| {
+                                        panic!("Image {:?} not found in registry", id)
+                                    });
+                                ImagePixels::Pixmap(&resolved)
+                            }
+                            ImageSource::Raw(raw) => ImagePixels::Raw(raw),
+                        };
+                        // When the image is drawn minified, sample from a level of its mip
+                        // pyramid instead (if the resolver provides one), so that all pixels
+                        // contribute.
+                        mip_level = match &image.source {
+                            ImageSource::OpaqueId { id, .. } => match image.mip_level() {
+                                0 => None,
+                                level => resources.image_resolver.resolve_mip_level(*id, level),
+                            },
+                            ImageSource::Pixmap(_) | ImageSource::Raw(_) => None,
+                        };
+                        let (sampling, pixels) = match &mip_level {
+                            Some(level) => {
+                                let level_image = image.with_level_size(
+                                    (pixels.width(), pixels.height()),
+                                    (level.width(), level.height()),
+                                );
+                                (
+                                    ImageSampling::from(&level_image),
+                                    ImagePixels::Pixmap(level),
+                                )
+                            }
+                            None => (ImageSampling::from(image), pixels),
+                        };
+                        (
+                            sampling,
+                            pixels,
+                            image.may_have_transparency,
+                            image.tint.as_ref(),
+                        )
+                    }
+                    EncodedPaint::ExternalTexture(texture) => {
+                        // Textures which aren't bound are not drawn (the warning for them is
+                        // logged once per render, see `texture::warn_unbound`).
+                        let Some(pixels) = resources
+                            .textures
+                            .and_then(|textures| textures.get(texture.texture_id))
+                        else {
+                            return;
+                        };
+                        let region = texture.source_region.intersect(RectU16::new(
+                            0,
+                            0,
+                            pixels.width(),
+                            pixels.height(),
+                        ));
+                        // Nothing can be sampled from an empty region.
+                        if region.is_empty() {
+                            return;
+                        }
+                        (
+                            ImageSampling::from(texture),
+                            ImagePixels::Texture { pixels, region },
+                            texture.may_have_transparency,
+                            texture.tint.as_ref(),
+                        )
+                    }
+                    _ => unreachable!(),
                 };
-                let tint = image.tint.as_ref();
-
-                match (image.has_skew(), image.nearest_neighbor()) {
-                    (false, false) => {
-                        // Axis-aligned with filtering - use optimized plain painters
-                        if image.sampler.quality == ImageQuality::Medium {
-                            fill_complex_paint!(
-                                image.may_have_transparency,
-                                T::plain_medium_quality_image_painter(
-                                    simd, image, &pixmap, sampler_x, sampler_y
-                                ),
-                                tint
-                            );
+                // Images in a different color space are converted after they have been
+                // sampled, so that the conversion doesn't require a copy of the image.
+                let color_space = pixels.color_space();
+
+                macro_rules! fill_image {
+                    ($painter:expr) => {
+                        if color_space == ColorSpaceTag::Srgb {
+                            fill_complex_paint!(may_have_transparency, $painter, tint);
                         } else {
                             fill_complex_paint!(
-                                image.may_have_transparency,
-                                T::high_quality_image_painter(
-                                    simd, image, &pixmap, sampler_x, sampler_y
+                                may_have_transparency,
+                                ColorSpacePainter::new(
+                                    $painter,
+                                    &mut self.unquantized_buf,
+                                    color_space
                                 ),
                                 tint
                             );
                         }
+                    };
+                }
+
+                match (sampling.has_skew(), sampling.nearest_neighbor()) {
+                    (false, false) => {
+                        // Axis-aligned with filtering - use optimized plain painters
+                        if sampling.sampler.quality == ImageQuality::Medium {
+                            fill_image!(T::plain_medium_quality_image_painter(
+                                simd, sampling, pixels, sampler_x, sampler_y
+                            ));
+                        } else {
+                            fill_image!(T::high_quality_image_painter(
+                                simd, sampling, pixels, sampler_x, sampler_y
+                            ));
+                        }
                     }
                     (true, false) => {
                         // Skewed with filtering - use generic filtered painters
-                        if image.sampler.quality == ImageQuality::Medium {
-                            fill_complex_paint!(
-                                image.may_have_transparency,
-                                T::medium_quality_image_painter(
-                                    simd, image, &pixmap, sampler_x, sampler_y
-                                ),
-                                tint
-                            );
+                        if sampling.sampler.quality == ImageQuality::Medium {
+                            fill_image!(T::medium_quality_image_painter(
+                                simd, sampling, pixels, sampler_x, sampler_y
+                            ));
                         } else {
-                            fill_complex_paint!(
-                                image.may_have_transparency,
-                                T::high_quality_image_painter(
-                                    simd, image, &pixmap, sampler_x, sampler_y
-                                ),
-                                tint
-                            );
+                            fill_image!(T::high_quality_image_painter(
+                                simd, sampling, pixels, sampler_x, sampler_y
+                            ));
                         }
                     }
                     (false, true) => {
-                        fill_complex_paint!(
-                            image.may_have_transparency,
-                            T::plain_nn_image_painter(simd, image, &pixmap, sampler_x, sampler_y),
-                            tint
-                        );
+                        fill_image!(T::plain_nn_image_painter(
+                            simd, sampling, pixels, sampler_x, sampler_y
+                        ));
                     }
                     (true, true) => {
-                        fill_complex_paint!(
-                            image.may_have_transparency,
-                            T::nn_image_painter(simd, image, &pixmap, sampler_x, sampler_y),
-                            tint
-                        );
+                        fill_image!(T::nn_image_painter(
+                            simd, sampling, pixels, sampler_x, sampler_y
+                        ));
                     }
                 }
             }
-            EncodedPaint::ExternalTexture(_) => {
-                unimplemented!("External textures are not supported by `vello_cpu`")
+            EncodedPaint::Custom(custom) => {
+                // Custom paints that have been destroyed in the meantime are not drawn.
+                let Some(paint) = resources.custom_paints.resolve(custom.id) else {
+                    return;
+                };
+                let span = CustomPaintSpan {
+                    x: sample_x,
+                    y: sample_y,
+                    width: width as u16,
+                    transform: custom.transform,
+                    coverage: alphas,
+                };
+                fill_complex_paint!(
+                    paint.may_have_transparency(),
+                    CustomPainter::new(paint, span)
+                );
+            }
+            EncodedPaint::MeshGradient(mesh) => {
+                fill_complex_paint!(true, T::mesh_gradient_painter(mesh, sampler_x, sampler_y));
             }
         }
     }
@@ -1077,6 +1203,9 @@ pub struct FineResources<'a> {
     pub encoded_paints: &'a [EncodedPaint],
     pub filter_paints: &'a [EncodedPaint],
     pub image_resolver: &'a dyn ImageResolver,
+    pub custom_paints: &'a CustomPaintRegistry,
+    pub textures: Option<&'a TextureBindings<'a>>,
+    pub dithering: bool,
 }
 
 impl Debug for FineResources<'_> {
@@ -1085,6 +1214,7 @@ impl Debug for FineResources<'_> {
             .field("alpha_buffers", &self.alpha_buffers.len())
             .field("encoded_paints", &self.encoded_paints.len())
             .field("filter_paints", &self.filter_paints.len())
+            .field("dithering", &self.dithering)
             .finish_non_exhaustive()
     }
 }
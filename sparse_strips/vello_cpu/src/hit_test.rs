@@ -0,0 +1,315 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Querying which recorded fills cover a region of the canvas.
+//!
+//! Since the strips of each fill command store the exact coverage of the drawn path (after
+//! applying the fill rule, stroking, transforms and clip paths), hit testing simply amounts
+//! to checking whether any of the strips has a non-zero coverage in the queried area. Clip
+//! layers are respected by intersecting with the coverage of their clip paths. Paints,
+//! masks and opacity are ignored, i.e. a shape with a fully transparent paint can still
+//! be hit.
+
+use crate::record::{CommandRecorder, RecordedCmd, RecordedLayerKind};
+use alloc::vec;
+use alloc::vec::Vec;
+use vello_common::geometry::RectU16;
+use vello_common::strip::Strip;
+use vello_common::tile::Tile;
+
+/// Return the indices of all fill commands (counted in recording order) that cover
+/// at least one pixel of `area`.
+///
+/// `alpha_buffers` contains the alpha buffer of each thread, indexed by the thread index
+/// stored in the commands.
+pub(crate) fn hit_test(
+    recorder: &CommandRecorder,
+    strips: &[Strip],
+    alpha_buffers: &[&[u8]],
+    area: RectU16,
+) -> Vec<usize> {
+    let mut tester = HitTester {
+        recorder,
+        strips,
+        alpha_buffers,
+        fill_idx: 0,
+        hits: Vec::new(),
+    };
+
+    if !area.is_empty() {
+        let clip = Coverage::full(area);
+        tester.visit(&recorder.root_cmds, &clip);
+    }
+
+    tester.hits
+}
+
+struct HitTester<'a> {
+    recorder: &'a CommandRecorder,
+    strips: &'a [Strip],
+    alpha_buffers: &'a [&'a [u8]],
+    /// The index of the next fill command.
+    fill_idx: usize,
+    hits: Vec<usize>,
+}
+
+impl HitTester<'_> {
+    fn visit(&mut self, cmds: &[RecordedCmd], clip: &Coverage) {
+        // The clip coverage of each currently open layer.
+        let mut clips: Vec<Coverage> = Vec::new();
+
+        for cmd in cmds {
+            let current = clips.last().unwrap_or(clip);
+
+            match cmd {
+                RecordedCmd::Fill {
+                    thread_idx,
+                    strip_range,
+                    ..
+                } => {
+                    let strips = &self.strips[strip_range.clone()];
+                    if self
+                        .find_covered(strips, *thread_idx, current.area, |x, y| current.get(x, y))
+                    {
+                        self.hits.push(self.fill_idx);
+                    }
+                    self.fill_idx += 1;
+                }
+                RecordedCmd::PushLayer { id } => {
+                    let layer_clip = match &self.recorder.layers[id.get()].props.clip_path {
+                        Some(layer_clip) => self.clip(
+                            current,
+                            &self.strips[layer_clip.strip_range.clone()],
+                            layer_clip.thread_idx,
+                        ),
+                        None => current.clone(),
+                    };
+                    clips.push(layer_clip);
+                }
+                RecordedCmd::PopLayer => {
+                    clips.pop();
+                }
+                RecordedCmd::FilterLayer { id } => {
+                    let layer = &self.recorder.layers[id.get()];
+                    let RecordedLayerKind::Filter {
+                        cmds, filter_data, ..
+                    } = &layer.kind
+                    else {
+                        unreachable!("filter layer commands must reference filter layers");
+                    };
+
+                    // The contents of filter layers are shifted by the source padding, so we
+                    // need to do the same to the queried area. Note that we ignore the filter
+                    // itself, i.e. contents are hit at the location they were drawn at.
+                    let (shift_x, shift_y) = filter_data.source_shift();
+                    let mut shifted = current.clone();
+                    shifted.area = RectU16::new(
+                        current.area.x0.saturating_add(shift_x),
+                        current.area.y0.saturating_add(shift_y),
+                        current.area.x1.saturating_add(shift_x),
+                        current.area.y1.saturating_add(shift_y),
+                    );
+                    if let Some(layer_clip) = &layer.props.clip_path {
+                        shifted = self.clip(
+                            &shifted,
+                            &self.strips[layer_clip.strip_range.clone()],
+                            layer_clip.thread_idx,
+                        );
+                    }
+
+                    self.visit(cmds, &shifted);
+                }
+            }
+        }
+    }
+
+    /// Restrict `coverage` to the pixels covered by the given clip path strips.
+    fn clip(&self, coverage: &Coverage, strips: &[Strip], thread_idx: u8) -> Coverage {
+        let mut clipped = Coverage::empty(coverage.area);
+        self.find_covered(strips, thread_idx, coverage.area, |x, y| {
+            if coverage.get(x, y) {
+                clipped.set(x, y);
+            }
+            false
+        });
+        clipped
+    }
+
+    /// Call `f` for the pixels of `area` that are covered by the given strips, until
+    /// it returns `true`.
+    ///
+    /// Returns whether `f` returned `true` for any pixel.
+    fn find_covered(
+        &self,
+        strips: &[Strip],
+        thread_idx: u8,
+        area: RectU16,
+        mut f: impl FnMut(u16, u16) -> bool,
+    ) -> bool {
+        let alphas = self.alpha_buffers[usize::from(thread_idx)];
+
+        for pair in strips.windows(2) {
+            let strip = pair[0];
+            let next_strip = pair[1];
+            if strip.is_sentinel() {
+                continue;
+            }
+
+            let row_y = strip.strip_y() * Tile::HEIGHT;
+            if row_y >= area.y1 || row_y + Tile::HEIGHT <= area.y0 {
+                continue;
+            }
+            let rows = area.y0.saturating_sub(row_y)..Tile::HEIGHT.min(area.y1 - row_y);
+
+            // Only look at the columns of the strip which lie within the area.
+            let strip_width = strip.width_to(&next_strip);
+            let cols =
+                area.x0.saturating_sub(strip.x)..strip_width.min(area.x1.saturating_sub(strip.x));
+            let alpha_idx = strip.alpha_idx() as usize;
+            for col in cols {
+                for row in rows.clone() {
+                    let idx = alpha_idx + usize::from(col * Tile::HEIGHT + row);
+                    if alphas[idx] != 0 && f(strip.x + col, row_y + row) {
+                        return true;
+                    }
+                }
+            }
+
+            // Sentinel strips are always located outside of the viewport, so we can just
+            // fill until the end of the area.
+            if next_strip.fill_gap() && strip.strip_y() == next_strip.strip_y() {
+                let x0 = strip.x.saturating_add(strip_width);
+                for x in x0.max(area.x0)..next_strip.x.min(area.x1) {
+                    for row in rows.clone() {
+                        if f(x, row_y + row) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+
+        false
+    }
+}
+
+/// A binary coverage mask for a rectangular area.
+#[derive(Debug, Clone)]
+struct Coverage {
+    area: RectU16,
+    covered: Vec<bool>,
+}
+
+impl Coverage {
+    fn empty(area: RectU16) -> Self {
+        Self {
+            area,
+            covered: vec![false; usize::from(area.width()) * usize::from(area.height())],
+        }
+    }
+
+    fn full(area: RectU16) -> Self {
+        Self {
+            area,
+            covered: vec![true; usize::from(area.width()) * usize::from(area.height())],
+        }
+    }
+
+    /// The index of a pixel, which must lie within the area.
+    fn index(&self, x: u16, y: u16) -> usize {
+        debug_assert!(self.area.contains(x, y), "pixel outside of the area");
+        usize::from(y - self.area.y0) * usize::from(self.area.width())
+            + usize::from(x - self.area.x0)
+    }
+
+    fn get(&self, x: u16, y: u16) -> bool {
+        self.covered[self.index(x, y)]
+    }
+
+    fn set(&mut self, x: u16, y: u16) {
+        let idx = self.index(x, y);
+        self.covered[idx] = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::kurbo::{BezPath, Circle, Point, Rect, Shape, Stroke};
+    use crate::peniko::Fill;
+    use crate::{RenderContext, RenderSettings};
+
+    fn scene(num_threads: u16) -> RenderContext {
+        let settings = RenderSettings {
+            num_threads,
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new_with(100, 100, settings);
+
+        ctx.set_hit_id(0);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+
+        // A self-intersecting star, whose center is only filled with the non-zero fill rule.
+        let mut star = BezPath::new();
+        star.move_to((50.0, 0.0));
+        star.line_to((79.0, 90.0));
+        star.line_to((2.0, 35.0));
+        star.line_to((98.0, 35.0));
+        star.line_to((21.0, 90.0));
+        star.close_path();
+        ctx.set_hit_id(1);
+        ctx.set_fill_rule(Fill::EvenOdd);
+        ctx.fill_path(&star);
+
+        ctx.set_hit_id(2);
+        ctx.set_stroke(Stroke::new(4.0));
+        ctx.stroke_path(&Circle::new((80.0, 80.0), 10.0).to_path(0.1));
+
+        // Only the part inside of the clip can be hit.
+        ctx.push_clip_layer(&Rect::new(0.0, 0.0, 10.0, 100.0).to_path(0.1));
+        ctx.set_hit_id(3);
+        ctx.fill_rect(&Rect::new(0.0, 90.0, 50.0, 100.0));
+        ctx.pop_layer();
+
+        // Not tagged, so it is never reported.
+        ctx.reset_hit_id();
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 5.0, 5.0));
+
+        ctx.flush();
+        ctx
+    }
+
+    fn hit_test(num_threads: u16) {
+        let ctx = scene(num_threads);
+
+        assert_eq!(ctx.hit_test(Point::new(50.0, 20.0)), [1, 0]);
+        // The center of the star is empty due to the even-odd fill rule.
+        assert_eq!(ctx.hit_test(Point::new(50.0, 50.0)), [0]);
+        // The stroke is hit, but not the interior of the circle.
+        assert_eq!(ctx.hit_test(Point::new(80.0, 71.0)), [2, 0]);
+        assert_eq!(ctx.hit_test(Point::new(80.0, 80.0)), [0]);
+        assert_eq!(ctx.hit_test(Point::new(5.0, 95.0)), [3, 0]);
+        assert_eq!(ctx.hit_test(Point::new(30.0, 95.0)), [0]);
+        assert_eq!(ctx.hit_test(Point::new(2.0, 2.0)), [0]);
+        assert!(ctx.hit_test(Point::new(150.0, 50.0)).is_empty());
+
+        assert_eq!(
+            ctx.hit_test_rect(&Rect::new(60.0, 60.0, 100.0, 100.0)),
+            [2, 1, 0]
+        );
+        assert_eq!(
+            ctx.hit_test_rect(&Rect::new(0.0, 90.0, 20.0, 100.0)),
+            [3, 0]
+        );
+    }
+
+    #[test]
+    fn hit_test_single_threaded() {
+        hit_test(0);
+    }
+
+    #[cfg(feature = "multithreading")]
+    #[test]
+    fn hit_test_multithreaded() {
+        hit_test(2);
+    }
+}
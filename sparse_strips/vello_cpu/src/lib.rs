@@ -68,8 +68,13 @@
 //! - `libm`: Use floating point implementations from [libm][].
 //! - `png`(enabled by default): Allow loading [`Pixmap`]s from PNG images.
 //!   Also required for rendering glyphs with an embedded PNG. Implies `std`.
+//! - `jpeg`: Allow loading [`Pixmap`]s from JPEG images. Implies `std`.
+//! - `webp`: Allow loading [`Pixmap`]s from WebP images. Implies `std`.
 //! - `multithreading`: Enable multi-threaded rendering. Implies `std`.
 //! - `text` (enabled by default): Enables glyph rendering ([`glyph_run`][RenderContext::glyph_run]).
+//! - `svg`: Enables rendering of SVG documents (see the `svg` module). Implies `std`.
+//! - `svg_export`: Enables exporting drawing commands as SVG documents
+//!   (see [`start_svg_capture`][RenderContext::start_svg_capture]). Implies `std` and `png`.
 //! - `u8_pipeline` (enabled by default): Enable the u8 pipeline, for speed focused rendering using u8 math.
 //!   The `u8` pipeline will be used for [`OptimizeSpeed`][RenderMode::OptimizeSpeed], if both pipelines are enabled.
 //!   If you're using Vello CPU for application rendering, you should prefer this pipeline.
@@ -148,12 +153,17 @@ mod render;
 
 mod clip;
 mod coarse;
+mod custom_paint;
+mod damage;
 mod dispatch;
 mod filter;
+mod hit_test;
+mod pattern;
 #[cfg(feature = "text")]
 mod text;
 #[cfg(all(feature = "text", feature = "std", debug_assertions))]
 mod text_debug;
+mod texture;
 mod util;
 
 #[doc(hidden)]
@@ -161,20 +171,30 @@ pub mod fine;
 pub(crate) mod record;
 #[doc(hidden)]
 pub mod region;
+#[cfg(feature = "svg")]
+pub mod svg;
+#[cfg(feature = "svg_export")]
+mod svg_export;
 
+pub use custom_paint::{CustomPaint, CustomPaintSpan};
+pub use damage::SceneSnapshot;
 pub use render::{
     CompositeMode, PixelFormat, RasterizerSettings, RenderContext, RenderSettings, Resources,
 };
+pub use texture::TextureBindings;
 // Note: The first one is not something that should be
 // exposed, but is currently needed by vello_sparse_tests.
 #[cfg(feature = "text")]
 pub use glifo::Glyph;
 #[cfg(feature = "text")]
 pub use text::{CpuGlyphRunBackend, GlyphRunBuilder};
+pub use vello_common::TextureId;
 pub use vello_common::fearless_simd::Level;
+pub use vello_common::geometry::RectU16;
 pub use vello_common::mask::Mask;
-pub use vello_common::paint::{Image, ImageSource, Paint, PaintType};
-pub use vello_common::pixmap::{Pixmap, PixmapMut};
+pub use vello_common::paint::{ExternalTexture, Image, ImageSource, Paint, PaintType};
+pub use vello_common::pixmap::{Pixmap, PixmapMut, PixmapRef};
+pub use vello_common::strip::AntialiasingMode;
 pub use vello_common::{color, kurbo, peniko};
 
 /// The selected rendering mode.
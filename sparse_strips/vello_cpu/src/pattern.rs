@@ -0,0 +1,403 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rasterization of pattern tiles.
+//!
+//! A pattern is drawn by rendering a single tile of it into a pixmap, at the resolution
+//! it is displayed at, and then repeating that pixmap like an image. As the content of a tile
+//! can refer to images and custom paints registered with the [`Resources`], the render context
+//! only records the tiles it needs together with the image paints that sample them, and the
+//! tiles are rendered right before the scene is rasterized. The rendered tiles are cached in
+//! the resources, so that drawing the same pattern again at the same scale (for example in the
+//! next frame) doesn't render it again.
+
+use crate::kurbo::{Affine, Rect, Vec2};
+use crate::peniko::{Extend, ImageSampler};
+use crate::render::PATTERN_IMAGE_ID_BASE;
+use crate::{RasterizerSettings, RenderContext, RenderMode, RenderSettings, Resources};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use vello_common::paint::{Image, ImageId, ImageSource};
+use vello_common::pattern::{Pattern, PatternCommand, PatternScene};
+use vello_common::pixmap::Pixmap;
+use vello_common::strip::AntialiasingMode;
+use vello_common::util::extract_scales;
+
+#[cfg(not(feature = "std"))]
+use vello_common::kurbo::common::FloatFuncs as _;
+
+/// The largest width or height (in pixels) of a rendered pattern tile.
+const MAX_TILE_SIZE: f64 = 2048.0;
+/// The maximum size (in bytes) of the rendered tiles that are kept in the cache.
+const MAX_CACHED_BYTES: usize = 64 * 1024 * 1024;
+
+/// A pattern tile that needs to be rendered before the scene is rasterized.
+#[derive(Debug)]
+pub(crate) struct PatternTile {
+    pub(crate) scene: Arc<PatternScene>,
+    /// The area of the pattern's scene covered by the tile.
+    pub(crate) tile: Rect,
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+}
+
+impl PatternTile {
+    /// Create the tile for drawing `pattern` with the given transform from the paint to the
+    /// device coordinate system, which is the tile with index `idx` of the scene.
+    ///
+    /// Returns the tile together with the image paint that samples it, and the transform from
+    /// the coordinate system of that image to the coordinate system of the paint. The image
+    /// refers to the tile by an ID that is only registered while the scene is rasterized,
+    /// see [`register_tiles`].
+    ///
+    /// Returns `None` if the tile of the pattern is empty.
+    pub(crate) fn new(
+        pattern: &Pattern,
+        paint_transform: Affine,
+        idx: usize,
+    ) -> Option<(Self, Image, Affine)> {
+        let tile = pattern.tile;
+        if !(tile.width() > 0.0 && tile.height() > 0.0) {
+            return None;
+        }
+
+        // Render the tile at roughly the resolution it will be displayed at.
+        let (scale_x, scale_y) = extract_scales(&(paint_transform * pattern.transform));
+        let pixmap_size = |length: f64, scale: f32| {
+            (length * f64::from(scale)).ceil().clamp(1.0, MAX_TILE_SIZE) as u16
+        };
+        let width = pixmap_size(tile.width(), scale_x);
+        let height = pixmap_size(tile.height(), scale_y);
+        let pattern_tile = Self {
+            scene: pattern.scene.clone(),
+            tile,
+            width,
+            height,
+        };
+
+        let image = Image {
+            image: ImageSource::opaque_id(tile_image_id(idx)),
+            sampler: ImageSampler {
+                x_extend: Extend::Repeat,
+                y_extend: Extend::Repeat,
+                ..Default::default()
+            },
+        };
+        let image_transform = pattern.transform * pattern_tile.transform().inverse();
+
+        Some((pattern_tile, image, image_transform))
+    }
+
+    /// The transform from the coordinate system of the pattern's scene to the pixels of
+    /// the tile.
+    fn transform(&self) -> Affine {
+        let scale = Vec2::new(
+            f64::from(self.width) / self.tile.width(),
+            f64::from(self.height) / self.tile.height(),
+        );
+        Affine::scale_non_uniform(scale.x, scale.y)
+            * Affine::translate(-self.tile.origin().to_vec2())
+    }
+
+    /// Render the commands of the pattern's scene into a new pixmap.
+    fn render(
+        &self,
+        resources: &mut Resources,
+        settings: RenderSettings,
+        render_mode: RenderMode,
+    ) -> Pixmap {
+        let transform = self.transform();
+        // Tiles are rendered while the caller is waiting for the scene to be rasterized, so
+        // there is nothing to gain from handing them to worker threads.
+        let settings = RenderSettings {
+            num_threads: 0,
+            ..settings
+        };
+        let mut ctx = RenderContext::new_with(self.width, self.height, settings);
+        for command in self.scene.commands() {
+            match command {
+                PatternCommand::Fill { path, state } | PatternCommand::Stroke { path, state } => {
+                    let mut state = state.clone();
+                    state.transform = transform * state.transform;
+                    ctx.restore_state(state);
+                    if matches!(command, PatternCommand::Fill { .. }) {
+                        ctx.fill_path(path);
+                    } else {
+                        ctx.stroke_path(path);
+                    }
+                }
+                PatternCommand::PushLayer {
+                    clip_path,
+                    fill_rule,
+                    transform: layer_transform,
+                    blend_mode,
+                    opacity,
+                    filter,
+                } => {
+                    ctx.set_transform(transform * *layer_transform);
+                    ctx.set_fill_rule(*fill_rule);
+                    ctx.push_layer(
+                        clip_path.as_ref(),
+                        Some(*blend_mode),
+                        Some(*opacity),
+                        None,
+                        filter.clone(),
+                    );
+                }
+                PatternCommand::PopLayer => ctx.pop_layer(),
+            }
+        }
+        ctx.flush();
+
+        let mut pixmap = Pixmap::new(self.width, self.height);
+        ctx.rasterize(
+            (&mut pixmap).into(),
+            resources,
+            RasterizerSettings {
+                render_mode,
+                ..Default::default()
+            },
+        );
+        pixmap
+    }
+}
+
+/// The settings that affect the content of a rendered tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TileSettings {
+    antialiasing_mode: AntialiasingMode,
+    dithering: bool,
+    render_mode: RenderMode,
+}
+
+/// A rendered tile, together with the pattern it belongs to.
+#[derive(Debug)]
+struct CachedTile {
+    // Keeping the scene alive ensures that its address can't be reused by another scene.
+    scene: Arc<PatternScene>,
+    tile: [u64; 4],
+    width: u16,
+    height: u16,
+    settings: TileSettings,
+    pixmap: Arc<Pixmap>,
+}
+
+impl CachedTile {
+    fn size(&self) -> usize {
+        usize::from(self.width) * usize::from(self.height) * 4
+    }
+}
+
+/// A cache of rendered pattern tiles, evicting the least recently used ones.
+#[derive(Debug, Default)]
+pub(crate) struct PatternCache {
+    tiles: Vec<CachedTile>,
+}
+
+impl PatternCache {
+    /// Return the number of cached tiles.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    fn get(&mut self, tile: &PatternTile, settings: TileSettings) -> Option<Arc<Pixmap>> {
+        let tile_key = tile_key(tile.tile);
+        let idx = self.tiles.iter().position(|cached| {
+            Arc::ptr_eq(&cached.scene, &tile.scene)
+                && cached.tile == tile_key
+                && cached.width == tile.width
+                && cached.height == tile.height
+                && cached.settings == settings
+        })?;
+        let cached = self.tiles.remove(idx);
+        let pixmap = cached.pixmap.clone();
+        self.tiles.push(cached);
+        Some(pixmap)
+    }
+
+    fn insert(&mut self, tile: &PatternTile, settings: TileSettings, pixmap: Arc<Pixmap>) {
+        self.tiles.push(CachedTile {
+            scene: tile.scene.clone(),
+            tile: tile_key(tile.tile),
+            width: tile.width,
+            height: tile.height,
+            settings,
+            pixmap,
+        });
+
+        let mut size: usize = self.tiles.iter().map(CachedTile::size).sum();
+        while size > MAX_CACHED_BYTES && self.tiles.len() > 1 {
+            size -= self.tiles.remove(0).size();
+        }
+    }
+}
+
+/// Render the pattern tiles of a scene (or take them from the cache), and register them
+/// under the image IDs that the image paints sampling them refer to.
+pub(crate) fn register_tiles(
+    tiles: &[PatternTile],
+    resources: &mut Resources,
+    settings: RenderSettings,
+    render_mode: RenderMode,
+) {
+    let tile_settings = TileSettings {
+        antialiasing_mode: settings.antialiasing_mode,
+        dithering: settings.dithering,
+        render_mode,
+    };
+    let mut pixmaps = Vec::with_capacity(tiles.len());
+    for tile in tiles {
+        let pixmap = match resources.pattern_cache.get(tile, tile_settings) {
+            Some(pixmap) => pixmap,
+            None => {
+                let pixmap = Arc::new(tile.render(resources, settings, render_mode));
+                resources
+                    .pattern_cache
+                    .insert(tile, tile_settings, pixmap.clone());
+                pixmap
+            }
+        };
+        pixmaps.push(pixmap);
+    }
+
+    // Rendering a tile that contains patterns itself registers (and removes) the tiles of its
+    // content under the same IDs, so the tiles can only be registered once all of them have
+    // been rendered.
+    for (idx, pixmap) in pixmaps.into_iter().enumerate() {
+        resources
+            .image_registry
+            .register_pattern_tile(tile_image_id(idx), pixmap);
+    }
+}
+
+/// Remove the tiles registered by [`register_tiles`] from the image registry.
+pub(crate) fn unregister_tiles(tiles: &[PatternTile], resources: &mut Resources) {
+    for idx in 0..tiles.len() {
+        resources.image_registry.destroy(tile_image_id(idx));
+    }
+}
+
+/// Return the index of the tile that an image ID refers to, if it refers to a pattern tile.
+pub(crate) fn tile_index(id: ImageId) -> Option<usize> {
+    let idx = id.as_u32().checked_sub(PATTERN_IMAGE_ID_BASE)?;
+    usize::try_from(idx).ok()
+}
+
+fn tile_image_id(idx: usize) -> ImageId {
+    let id = u32::try_from(idx)
+        .ok()
+        .and_then(|idx| PATTERN_IMAGE_ID_BASE.checked_add(idx))
+        .expect("too many pattern tiles");
+    ImageId::new(id)
+}
+
+fn tile_key(tile: Rect) -> [u64; 4] {
+    [tile.x0, tile.y0, tile.x1, tile.y1].map(f64::to_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::color::palette::css::RED;
+    use crate::kurbo::{Affine, Rect, Shape};
+    use crate::peniko::{BlendMode, Fill, ImageSampler};
+    use crate::{Pixmap, RenderContext, RenderSettings, Resources};
+    use alloc::sync::Arc;
+    use vello_common::filter_effects::{Filter, FilterPrimitive};
+    use vello_common::paint::{Image, ImageSource};
+    use vello_common::pattern::{Pattern, PatternCommand, PatternScene};
+    use vello_common::render_state::RenderState;
+
+    fn render(pattern: &Pattern, scale: f64, settings: RenderSettings, resources: &mut Resources) {
+        let mut ctx = RenderContext::new_with(16, 16, settings);
+        ctx.set_paint(pattern.clone());
+        ctx.set_paint_transform(Affine::scale(scale));
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+        ctx.flush();
+        ctx.render(&mut Pixmap::new(16, 16), resources);
+    }
+
+    #[test]
+    fn tiles_are_cached_per_scale() {
+        let mut scene = PatternScene::new();
+        scene.fill_path(
+            Rect::new(0.0, 0.0, 1.0, 1.0).to_path(0.1),
+            &RenderState::default(),
+        );
+        let pattern = Pattern::new(scene, Rect::new(0.0, 0.0, 2.0, 2.0));
+        let settings = RenderSettings::default();
+
+        let mut resources = Resources::new();
+        render(&pattern, 4.0, settings, &mut resources);
+        render(&pattern, 4.0, settings, &mut resources);
+        assert_eq!(resources.pattern_cache.len(), 1);
+
+        // At a different scale, the tile is rendered again.
+        render(&pattern, 2.0, settings, &mut resources);
+        assert_eq!(resources.pattern_cache.len(), 2);
+    }
+
+    #[test]
+    fn registered_image_in_pattern() {
+        let mut resources = Resources::new();
+        let mut image = Pixmap::new(1, 1);
+        image.set_pixel(0, 0, RED.premultiply().to_rgba8());
+        let id = resources.register_image(Arc::new(image));
+
+        let mut scene = PatternScene::new();
+        let state = RenderState {
+            paint: Image {
+                image: ImageSource::opaque_id(id),
+                sampler: ImageSampler::default(),
+            }
+            .into(),
+            ..Default::default()
+        };
+        scene.fill_path(Rect::new(0.0, 0.0, 1.0, 1.0).to_path(0.1), &state);
+        let pattern = Pattern::new(scene, Rect::new(0.0, 0.0, 1.0, 1.0));
+
+        let mut ctx = RenderContext::new(4, 4);
+        ctx.set_paint(pattern);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 4.0, 4.0));
+        ctx.flush();
+        let mut target = Pixmap::new(4, 4);
+        ctx.render(&mut target, &mut resources);
+        assert_eq!(target.sample(2, 2), RED.premultiply().to_rgba8());
+    }
+
+    #[test]
+    #[cfg(feature = "multithreading")]
+    fn filter_layer_in_pattern_multithreaded() {
+        let mut scene = PatternScene::new();
+        scene.push(PatternCommand::PushLayer {
+            clip_path: None,
+            fill_rule: Fill::NonZero,
+            transform: Affine::IDENTITY,
+            blend_mode: BlendMode::default(),
+            opacity: 1.0,
+            filter: Some(Filter::from_primitive(FilterPrimitive::Offset {
+                dx: 0.0,
+                dy: 0.0,
+            })),
+        });
+        let state = RenderState {
+            paint: RED.into(),
+            ..Default::default()
+        };
+        scene.fill_path(Rect::new(0.0, 0.0, 4.0, 4.0).to_path(0.1), &state);
+        scene.push(PatternCommand::PopLayer);
+        let pattern = Pattern::new(scene, Rect::new(0.0, 0.0, 4.0, 4.0));
+
+        let settings = RenderSettings {
+            num_threads: 2,
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new_with(16, 16, settings);
+        ctx.set_paint(pattern);
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+        ctx.flush();
+        let mut target = Pixmap::new(16, 16);
+        ctx.render(&mut target, &mut Resources::new());
+        assert_eq!(target.sample(10, 10), RED.premultiply().to_rgba8());
+    }
+}
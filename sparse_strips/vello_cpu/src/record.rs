@@ -133,7 +133,7 @@ impl RecordedLayer {
 }
 
 /// Metadata about a filter layer and how it should be composited back into the parent layer.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Hash)]
 pub(crate) struct FilterLayerPlacement {
     /// The conceptual bounding box of the pixmap that needs to be allocated to render
     /// a layer correctly, including the area affected by the filter.
@@ -432,7 +432,7 @@ pub(crate) enum PoppedLayer {
     Filter,
 }
 
-fn strip_bbox(strips: &[Strip], viewport_width: u16) -> RectU16 {
+pub(crate) fn strip_bbox(strips: &[Strip], viewport_width: u16) -> RectU16 {
     let mut bbox = RectU16::INVERTED;
 
     // Need at least one strip (and the sentinel one).
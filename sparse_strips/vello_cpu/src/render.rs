@@ -4,6 +4,7 @@
 //! Basic render operations.
 
 use crate::RenderMode;
+use crate::damage::SceneSnapshot;
 use crate::dispatch::Dispatcher;
 #[cfg(feature = "multithreading")]
 use crate::dispatch::multi_threaded::MultiThreadedDispatcher;
@@ -12,25 +13,37 @@ use crate::text::{GlyphAtlasResources, GlyphRunBuilder};
 #[cfg(feature = "text")]
 use glifo::GlyphPrepCache;
 
+use crate::custom_paint::{CustomPaint, CustomPaintRegistry};
 use crate::dispatch::single_threaded::SingleThreadedDispatcher;
 use crate::kurbo::{PathEl, Point};
+use crate::pattern::{self, PatternCache, PatternTile};
 use crate::record::FilterData;
+#[cfg(feature = "svg_export")]
+use crate::svg_export::{Shape, SvgCapture};
+use crate::texture::{self, TextureBindings};
 use alloc::boxed::Box;
+#[cfg(feature = "svg_export")]
+use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 use hashbrown::HashMap;
 use vello_common::blurred_rounded_rect::BlurredRoundedRectangle;
-use vello_common::encode::{EncodeExt, EncodedPaint};
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::encode::{EncodeExt, EncodedCustomPaint, EncodedPaint};
 use vello_common::fearless_simd::Level;
 use vello_common::filter_effects::Filter;
+use vello_common::geometry::RectU16;
 use vello_common::kurbo::{Affine, BezPath, Rect, Stroke};
 use vello_common::mask::Mask;
-use vello_common::paint::{ImageId, ImageResolver, Paint, PaintType, Tint};
+use vello_common::paint::{
+    CustomPaintId, ImageId, ImageResolver, ImageSource, IndexedPaint, Paint, PaintType, Tint,
+};
 use vello_common::peniko::color::palette::css::BLACK;
 use vello_common::peniko::{BlendMode, Fill};
 use vello_common::pixmap::{Pixmap, PixmapMut};
 use vello_common::render_state::RenderState;
+use vello_common::strip::AntialiasingMode;
 use vello_common::util::is_axis_aligned;
 
 #[cfg(feature = "text")]
@@ -52,13 +65,19 @@ pub(crate) const DEFAULT_GLYPH_ATLAS_SIZE: u16 = 4096;
 // glyph and apply special handling based on that.
 //
 // All IDs < than this value are reserved for normal images, all IDs >= this value are
-// reserved for atlas pages.
+// reserved for atlas pages and pattern tiles.
 pub(crate) const ATLAS_IMAGE_ID_BASE: u32 = u32::MAX / 2;
+// The IDs >= this value are reserved for the pattern tiles of the scene that is currently being
+// rasterized, see the `pattern` module.
+pub(crate) const PATTERN_IMAGE_ID_BASE: u32 = ATLAS_IMAGE_ID_BASE + u32::MAX / 4;
 
 /// Persistent resources required by Vello CPU for rendering.
 #[derive(Debug, Default)]
 pub struct Resources {
     pub(crate) image_registry: ImageRegistry,
+    pub(crate) custom_paints: CustomPaintRegistry,
+    /// The rendered tiles of patterns, which are kept across frames.
+    pub(crate) pattern_cache: PatternCache,
     #[cfg(feature = "text")]
     pub(crate) glyph_prep_cache: GlyphPrepCache,
     // Will be initialized lazily on first use.
@@ -110,7 +129,7 @@ pub enum PixelFormat {
 
 /// Settings used when rasterizing a scene into a pixmap.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub struct RasterizerSettings {
+pub struct RasterizerSettings<'a> {
     /// Whether to prioritize speed or quality when rendering.
     ///
     /// For most cases (especially for real-time rendering), it is highly recommended to set
@@ -129,15 +148,31 @@ pub struct RasterizerSettings {
     ///
     /// See [`RenderContext::render_with`] for more information.
     pub offset: (u16, u16),
+    /// The parts of the scene that have changed since the target was last rendered into.
+    ///
+    /// If set, only the wide tiles intersecting one of the rectangles are rasterized,
+    /// and all other pixels of the target are left untouched. The rectangles are in the
+    /// coordinate system of the scene, i.e. [`RasterizerSettings::offset`] is applied
+    /// to them as well.
+    ///
+    /// `None` (the default) means that the whole scene is rasterized.
+    pub damage: Option<&'a [RectU16]>,
+    /// The pixels of the external textures drawn by the scene.
+    ///
+    /// [`ExternalTexture`](vello_common::paint::ExternalTexture) paints which refer to a
+    /// texture that isn't bound are not drawn, and a warning is logged.
+    pub textures: Option<&'a TextureBindings<'a>>,
 }
 
-impl Default for RasterizerSettings {
+impl Default for RasterizerSettings<'_> {
     fn default() -> Self {
         Self {
             render_mode: RenderMode::OptimizeSpeed,
             composite_mode: CompositeMode::Replace,
             pixel_format: PixelFormat::Rgba8,
             offset: (0, 0),
+            damage: None,
+            textures: None,
         }
     }
 }
@@ -159,18 +194,23 @@ pub struct RenderContext {
     root_transforms: Vec<Affine>,
     /// The current mask in place.
     pub(crate) mask: Option<Mask>,
+    /// The ID that is reported for drawn items when hit testing.
+    hit_id: Option<u32>,
+    /// The hit test ID of each recorded fill, in recording order.
+    hit_ids: Vec<Option<u32>>,
     /// Temporary path buffer to avoid repeated allocations.
     pub(crate) temp_path: BezPath,
     /// Optional threshold for aliasing.
     pub(crate) aliasing_threshold: Option<u8>,
     pub(crate) encoded_paints: Vec<EncodedPaint>,
     pub(crate) filter: Option<Filter>,
-    #[cfg_attr(
-        not(feature = "text"),
-        allow(dead_code, reason = "used when the `text` feature is enabled")
-    )]
     pub(crate) render_settings: RenderSettings,
+    /// The pattern tiles to render before rasterizing the scene.
+    pattern_tiles: Vec<PatternTile>,
     dispatcher: Box<dyn Dispatcher>,
+    /// The SVG elements of the drawing commands, if they are currently captured.
+    #[cfg(feature = "svg_export")]
+    svg_capture: Option<Box<SvgCapture>>,
 }
 
 /// Settings to apply to the render context.
@@ -181,6 +221,17 @@ pub struct RenderSettings {
     /// The number of worker threads that should be used for rendering. Only has an effect
     /// if the `multithreading` feature is active.
     pub num_threads: u16,
+    /// The method used to compute the anti-aliased coverage of paths.
+    ///
+    /// The MSAA modes avoid conflation artifacts where edges of a path coincide, at the
+    /// cost of some performance.
+    pub antialiasing_mode: AntialiasingMode,
+    /// Whether to apply ordered dithering to gradients and blurred rounded rectangles.
+    ///
+    /// Smooth gradients with a low contrast can show visible bands, because only a few
+    /// distinct 8-bit values are available to represent them. Dithering breaks up those bands
+    /// into a fine noise pattern, at the cost of some performance when drawing these paints.
+    pub dithering: bool,
 }
 
 impl Default for RenderSettings {
@@ -195,6 +246,8 @@ impl Default for RenderSettings {
                 .min(8),
             #[cfg(not(feature = "multithreading"))]
             num_threads: 0,
+            antialiasing_mode: AntialiasingMode::default(),
+            dithering: false,
         }
     }
 }
@@ -209,19 +262,34 @@ impl RenderContext {
     pub fn new_with(width: u16, height: u16, settings: RenderSettings) -> Self {
         #[cfg(feature = "multithreading")]
         let dispatcher: Box<dyn Dispatcher> = if settings.num_threads == 0 {
-            Box::new(SingleThreadedDispatcher::new(width, height, settings.level))
+            Box::new(SingleThreadedDispatcher::new(
+                width,
+                height,
+                settings.level,
+                settings.antialiasing_mode,
+                settings.dithering,
+            ))
         } else {
             Box::new(MultiThreadedDispatcher::new(
                 width,
                 height,
                 settings.num_threads,
                 settings.level,
+                settings.antialiasing_mode,
+                settings.dithering,
             ))
         };
 
         #[cfg(not(feature = "multithreading"))]
-        let dispatcher: Box<dyn Dispatcher> =
-            { Box::new(SingleThreadedDispatcher::new(width, height, settings.level)) };
+        let dispatcher: Box<dyn Dispatcher> = {
+            Box::new(SingleThreadedDispatcher::new(
+                width,
+                height,
+                settings.level,
+                settings.antialiasing_mode,
+                settings.dithering,
+            ))
+        };
 
         let encoded_paints = vec![];
         let temp_path = BezPath::new();
@@ -236,9 +304,14 @@ impl RenderContext {
             aliasing_threshold,
             render_settings: settings,
             mask: None,
+            hit_id: None,
+            hit_ids: Vec::new(),
             temp_path,
             encoded_paints,
             filter: None,
+            pattern_tiles: Vec::new(),
+            #[cfg(feature = "svg_export")]
+            svg_capture: None,
         }
     }
 
@@ -254,6 +327,37 @@ impl RenderContext {
                 let transform = self.effective_paint_transform();
                 i.encode_into(&mut self.encoded_paints, transform, self.state.tint)
             }
+            PaintType::MeshGradient(m) => {
+                let transform = self.effective_paint_transform();
+                m.encode_into(&mut self.encoded_paints, transform, None)
+            }
+            PaintType::Custom(id) => {
+                let idx = self.encoded_paints.len();
+                let transform = self.effective_paint_transform().inverse();
+                self.encoded_paints
+                    .push(EncodedPaint::Custom(EncodedCustomPaint { id, transform }));
+                Paint::Indexed(IndexedPaint::new(idx))
+            }
+            PaintType::Pattern(p) => {
+                let transform = self.effective_paint_transform();
+                match PatternTile::new(&p, transform, self.pattern_tiles.len()) {
+                    Some((tile, image, image_transform)) => {
+                        self.pattern_tiles.push(tile);
+                        image.encode_into(
+                            &mut self.encoded_paints,
+                            transform * image_transform,
+                            None,
+                        )
+                    }
+                    None => AlphaColor::<Srgb>::TRANSPARENT.into(),
+                }
+            }
+            PaintType::ExternalTexture(t) => {
+                let transform = self.effective_paint_transform();
+                t.encode_into(&mut self.encoded_paints, transform, self.state.tint)
+            }
+            // Paints that aren't supported yet are drawn transparent.
+            _ => AlphaColor::<Srgb>::TRANSPARENT.into(),
         }
     }
 
@@ -290,8 +394,13 @@ impl RenderContext {
     /// Fill a path.
     pub fn fill_path(&mut self, path: &BezPath) {
         self.with_optional_filter(|ctx| {
+            #[cfg(feature = "svg_export")]
+            ctx.capture_svg(|capture, ctx| {
+                capture.draw(Shape::Fill(path), &ctx.state, ctx.mask.as_ref());
+            });
             let paint = ctx.encode_current_paint();
             let transform = ctx.effective_path_transform();
+            ctx.hit_ids.push(ctx.hit_id);
             ctx.dispatcher.fill_path(
                 path,
                 ctx.state.fill_rule,
@@ -307,8 +416,13 @@ impl RenderContext {
     /// Stroke a path.
     pub fn stroke_path(&mut self, path: &BezPath) {
         self.with_optional_filter(|ctx| {
+            #[cfg(feature = "svg_export")]
+            ctx.capture_svg(|capture, ctx| {
+                capture.draw(Shape::Stroke(path), &ctx.state, ctx.mask.as_ref());
+            });
             let paint = ctx.encode_current_paint();
             let transform = ctx.effective_path_transform();
+            ctx.hit_ids.push(ctx.hit_id);
             ctx.dispatcher.stroke_path(
                 path,
                 &ctx.state.stroke,
@@ -324,15 +438,24 @@ impl RenderContext {
     /// Fill a rectangle.
     pub fn fill_rect(&mut self, rect: &Rect) {
         self.with_optional_filter(|ctx| {
+            #[cfg(feature = "svg_export")]
+            ctx.capture_svg(|capture, ctx| {
+                let path = vello_common::kurbo::Shape::to_path(rect, 0.1);
+                capture.draw(Shape::Fill(&path), &ctx.state, ctx.mask.as_ref());
+            });
             let paint = ctx.encode_current_paint();
             let transform = ctx.effective_path_transform();
 
             // Fast path: Use optimized rect filling if we have no skew in the path transform
-            // and anti-aliasing is enabled.
+            // and analytic anti-aliasing is enabled.
             // TODO: Maybe also support no anti-aliasing in the fast path
-            if is_axis_aligned(&transform) && ctx.aliasing_threshold.is_none() {
+            if is_axis_aligned(&transform)
+                && ctx.aliasing_threshold.is_none()
+                && ctx.render_settings.antialiasing_mode == AntialiasingMode::Area
+            {
                 // Transform the rect to screen coordinates.
                 let transformed_rect = transform.transform_rect_bbox(*rect);
+                ctx.hit_ids.push(ctx.hit_id);
                 ctx.dispatcher.fill_rect_fast(
                     &transformed_rect,
                     paint,
@@ -342,6 +465,7 @@ impl RenderContext {
             } else {
                 // Fall back to path-based rendering for rotated/skewed transforms.
                 ctx.rect_to_temp_path(rect);
+                ctx.hit_ids.push(ctx.hit_id);
                 ctx.dispatcher.fill_path(
                     &ctx.temp_path,
                     ctx.state.fill_rule,
@@ -359,8 +483,13 @@ impl RenderContext {
     pub fn stroke_rect(&mut self, rect: &Rect) {
         self.with_optional_filter(|ctx| {
             ctx.rect_to_temp_path(rect);
+            #[cfg(feature = "svg_export")]
+            ctx.capture_svg(|capture, ctx| {
+                capture.draw(Shape::Stroke(&ctx.temp_path), &ctx.state, ctx.mask.as_ref());
+            });
             let paint = ctx.encode_current_paint();
             let transform = ctx.effective_path_transform();
+            ctx.hit_ids.push(ctx.hit_id);
             ctx.dispatcher.stroke_path(
                 &ctx.temp_path,
                 &ctx.state.stroke,
@@ -402,6 +531,16 @@ impl RenderContext {
         invert: bool,
     ) {
         let rect = rect.abs();
+        #[cfg(feature = "svg_export")]
+        self.capture_svg(|capture, ctx| {
+            let shape = Shape::BlurredRoundedRect {
+                rect,
+                radius,
+                std_dev,
+                invert,
+            };
+            capture.draw(shape, &ctx.state, ctx.mask.as_ref());
+        });
         let color = match self.state.paint {
             PaintType::Solid(s) => s,
             // Fallback to black when attempting to blur a rectangle with an image/gradient paint
@@ -428,6 +567,7 @@ impl RenderContext {
         self.rect_to_temp_path(&inflated_rect);
 
         let paint = blurred_rect.encode_into(&mut self.encoded_paints, paint_transform, None);
+        self.hit_ids.push(self.hit_id);
         self.dispatcher.fill_path(
             &self.temp_path,
             Fill::NonZero,
@@ -481,6 +621,18 @@ impl RenderContext {
 
         let blend_mode = blend_mode.unwrap_or_default();
         let opacity = opacity.unwrap_or(1.0);
+        #[cfg(feature = "svg_export")]
+        self.capture_svg(|capture, ctx| {
+            capture.push_layer(
+                clip_path,
+                ctx.state.fill_rule,
+                ctx.state.transform,
+                blend_mode,
+                opacity,
+                mask.as_ref(),
+                filter.as_ref(),
+            );
+        });
         let layer_transform = self.effective_path_transform();
         let filter_plan = filter.map(|filter| FilterData::new(filter, layer_transform));
 
@@ -566,6 +718,8 @@ impl RenderContext {
 
     /// Pop the last-pushed layer.
     pub fn pop_layer(&mut self) {
+        #[cfg(feature = "svg_export")]
+        self.capture_svg(|capture, _| capture.pop_layer());
         self.dispatcher.pop_layer();
         self.pop_root_transform();
     }
@@ -659,6 +813,18 @@ impl RenderContext {
         self.mask = None;
     }
 
+    /// Set the ID that is reported for subsequently drawn items when hit testing.
+    ///
+    /// See [`RenderContext::hit_test`] for more information.
+    pub fn set_hit_id(&mut self, id: u32) {
+        self.hit_id = Some(id);
+    }
+
+    /// Reset the hit test ID, so that subsequently drawn items can't be hit.
+    pub fn reset_hit_id(&mut self) {
+        self.hit_id = None;
+    }
+
     /// Get the current fill rule.
     pub fn fill_rule(&self) -> &Fill {
         &self.state.fill_rule
@@ -696,10 +862,17 @@ impl RenderContext {
     pub fn reset(&mut self) {
         self.dispatcher.reset();
         self.encoded_paints.clear();
+        self.pattern_tiles.clear();
         self.mask = None;
+        self.hit_id = None;
+        self.hit_ids.clear();
         self.root_transforms.clear();
         self.root_transforms.push(Affine::IDENTITY);
         self.state.reset();
+        #[cfg(feature = "svg_export")]
+        if self.svg_capture.is_some() {
+            self.start_svg_capture();
+        }
     }
 
     /// Push a new clip path to the clip stack.
@@ -708,6 +881,10 @@ impl RenderContext {
     /// example for how this method differs from `push_clip_layer`.
     pub fn push_clip_path(&mut self, path: &BezPath) {
         let transform = self.clip_path_transform();
+        #[cfg(feature = "svg_export")]
+        self.capture_svg(|capture, ctx| {
+            capture.push_clip_path(path, ctx.state.fill_rule, transform);
+        });
         self.dispatcher.push_clip_path(
             path,
             self.state.fill_rule,
@@ -721,9 +898,62 @@ impl RenderContext {
     /// Note that unlike `push_clip_layer`, it is permissible to have pending
     /// pushed clip paths before finishing the rendering operation.
     pub fn pop_clip_path(&mut self) {
+        #[cfg(feature = "svg_export")]
+        self.capture_svg(|capture, _| capture.pop_clip_path());
         self.dispatcher.pop_clip_path();
     }
 
+    /// Start capturing drawing commands as an SVG document.
+    ///
+    /// All subsequent drawing commands are still rendered as usual, but are additionally
+    /// converted into SVG elements, until [`RenderContext::finish_svg_capture`] is called.
+    /// If a capture is already active, it is discarded and a new one is started. Calling
+    /// [`RenderContext::reset`] also restarts the capture.
+    ///
+    /// Paths, strokes, gradients, images (embedded as PNG data URIs), clip paths, blend
+    /// modes, opacity, masks and the filters that Vello CPU can render are converted into
+    /// their SVG equivalents. Paints without an SVG equivalent, such as sweep gradients
+    /// and tinted images, are rasterized and embedded as images instead. Glyphs are
+    /// always exported as outlines, and images that were registered with
+    /// [`Resources::register_image`] are omitted.
+    #[cfg(feature = "svg_export")]
+    pub fn start_svg_capture(&mut self) {
+        self.svg_capture = Some(Box::new(SvgCapture::new(
+            self.width,
+            self.height,
+            self.render_settings,
+        )));
+    }
+
+    /// Stop capturing drawing commands and return the SVG document, or `None` if no
+    /// capture was active.
+    ///
+    /// Layers and clip paths that are still pushed are closed implicitly.
+    #[cfg(feature = "svg_export")]
+    pub fn finish_svg_capture(&mut self) -> Option<String> {
+        self.svg_capture.take().map(|capture| capture.finish())
+    }
+
+    /// Whether drawing commands are currently captured as SVG.
+    #[cfg(all(feature = "text", feature = "svg_export"))]
+    pub(crate) fn is_capturing_svg(&self) -> bool {
+        self.svg_capture.is_some()
+    }
+
+    /// Whether drawing commands are currently captured as SVG.
+    #[cfg(all(feature = "text", not(feature = "svg_export")))]
+    pub(crate) fn is_capturing_svg(&self) -> bool {
+        false
+    }
+
+    #[cfg(feature = "svg_export")]
+    fn capture_svg(&mut self, f: impl FnOnce(&mut SvgCapture, &Self)) {
+        if let Some(mut capture) = self.svg_capture.take() {
+            f(&mut capture, self);
+            self.svg_capture = Some(capture);
+        }
+    }
+
     /// Flush any pending operations.
     ///
     /// This is a no-op when using the single-threaded render mode, and can be ignored.
@@ -773,11 +1003,18 @@ impl RenderContext {
     /// 3. In case the width/height of the pixmap is _smaller_ than the offset + width/height of the
     ///    scene, then anything that exceeds the pixmap boundaries is simply cut off. This can be useful
     ///    if for some reason you only want to rasterize a small cut-out of the original scene.
+    ///
+    /// 4. In case [`RasterizerSettings::damage`] is set, only the parts of the scene covered by the
+    ///    damage rectangles (rounded out to whole wide tiles) are rasterized. This is useful for
+    ///    interactive applications where only a small part of the scene changes between frames,
+    ///    e.g. a blinking cursor. The target then needs to contain the result of rendering the
+    ///    previous frame, since all other pixels are left untouched (even when using
+    ///    [`CompositeMode::Replace`]).
     pub fn render_with<'a>(
         &self,
         target: impl Into<PixmapMut<'a>>,
         resources: &mut Resources,
-        settings: RasterizerSettings,
+        settings: RasterizerSettings<'_>,
     ) {
         // TODO: Maybe we should move those checks into the dispatcher.
         assert!(
@@ -786,14 +1023,45 @@ impl RenderContext {
         );
 
         resources.before_render(settings.render_mode);
-        let mut target = target.into();
+        self.rasterize(target.into(), resources, settings);
+        // TODO: We need to figure something out here API-wise. At the moment, the user can
+        // theoretically rasterize the same `RenderContext` multiple times without resetting in-between.
+        // However, if glyph caching is enabled, this method call could now evict that were previously
+        // assumed to exist in `RenderContext`, meaning that if the user rasterizes the same `RenderContext`
+        // again without resetting it, some of the cached glyphs might be stale and not exist anymore.
+        resources.after_render();
+    }
+
+    /// Rasterize the scene into the target, without maintaining the glyph cache.
+    ///
+    /// This is also used for rendering pattern tiles, which happens while the glyph cache
+    /// is prepared for rasterizing the outer scene.
+    pub(crate) fn rasterize(
+        &self,
+        mut target: PixmapMut<'_>,
+        resources: &mut Resources,
+        settings: RasterizerSettings<'_>,
+    ) {
+        pattern::register_tiles(
+            &self.pattern_tiles,
+            resources,
+            self.render_settings,
+            settings.render_mode,
+        );
+        resources
+            .image_registry
+            .prepare_mip_levels(&self.encoded_paints);
+        texture::warn_unbound(&self.encoded_paints, settings.textures);
         let target_fully_covered = settings.offset == (0, 0)
             && self.width >= target.width()
             && self.height >= target.height();
         // If the scene covers the whole pixmap than packing will take care
         // of clearing everything anyway, so no reason to clear it explicitly
         // here.
-        if settings.composite_mode == CompositeMode::Replace && !target_fully_covered {
+        if settings.composite_mode == CompositeMode::Replace
+            && !target_fully_covered
+            && settings.damage.is_none()
+        {
             target.data_mut().fill(0);
         }
 
@@ -804,13 +1072,100 @@ impl RenderContext {
             settings,
             &self.encoded_paints,
             &resources.image_registry,
+            &resources.custom_paints,
         );
-        // TODO: We need to figure something out here API-wise. At the moment, the user can
-        // theoretically rasterize the same `RenderContext` multiple times without resetting in-between.
-        // However, if glyph caching is enabled, this method call could now evict that were previously
-        // assumed to exist in `RenderContext`, meaning that if the user rasterizes the same `RenderContext`
-        // again without resetting it, some of the cached glyphs might be stale and not exist anymore.
-        resources.after_render();
+        pattern::unregister_tiles(&self.pattern_tiles, resources);
+    }
+
+    /// Create a snapshot of the commands recorded so far.
+    ///
+    /// Comparing the snapshots of two consecutive frames with [`SceneSnapshot::damage`] yields
+    /// the regions that need to be redrawn, which can be passed as [`RasterizerSettings::damage`].
+    /// Just like for rendering, all layers must have been popped, and in multi-threaded mode,
+    /// [`RenderContext::flush`] must have been called before.
+    pub fn snapshot(&self) -> SceneSnapshot {
+        assert!(
+            !self.dispatcher.has_layers(),
+            "some layers haven't been popped yet"
+        );
+
+        self.dispatcher.snapshot(
+            self.width,
+            self.height,
+            &self.encoded_paints,
+            &self.pattern_tiles,
+        )
+    }
+
+    /// Return the hit test IDs of all items covering the pixel at the given point,
+    /// ordered from topmost to bottommost.
+    ///
+    /// The point is specified in device coordinates, i.e. it is not affected by the
+    /// current transform. Hit testing uses the exact coverage of the drawn items, so it takes
+    /// the fill rule, the stroke geometry, transforms as well as clip paths and clip layers into
+    /// account. Paints, masks and opacity are ignored. Items drawn without an ID (see
+    /// [`RenderContext::set_hit_id`]) are never reported, and each ID is reported at most once.
+    /// Just like for rendering, all layers must have been popped, and in multi-threaded mode,
+    /// [`RenderContext::flush`] must have been called before.
+    pub fn hit_test(&self, point: Point) -> Vec<u32> {
+        if !(0.0..f64::from(self.width)).contains(&point.x)
+            || !(0.0..f64::from(self.height)).contains(&point.y)
+        {
+            return Vec::new();
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the point has been checked to lie within the scene"
+        )]
+        let (x, y) = (point.x as u16, point.y as u16);
+        self.hit_test_area(RectU16::new(x, y, x + 1, y + 1))
+    }
+
+    /// Return the hit test IDs of all items covering at least one pixel of the given rectangle,
+    /// ordered from topmost to bottommost.
+    ///
+    /// See [`RenderContext::hit_test`] for more information.
+    pub fn hit_test_rect(&self, rect: &Rect) -> Vec<u32> {
+        let rect = rect.abs().expand().intersect(Rect::new(
+            0.0,
+            0.0,
+            f64::from(self.width),
+            f64::from(self.height),
+        ));
+        if rect.is_zero_area() {
+            return Vec::new();
+        }
+
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the rectangle has been clamped to the scene"
+        )]
+        let area = RectU16::new(
+            rect.x0 as u16,
+            rect.y0 as u16,
+            rect.x1 as u16,
+            rect.y1 as u16,
+        );
+        self.hit_test_area(area)
+    }
+
+    fn hit_test_area(&self, area: RectU16) -> Vec<u32> {
+        assert!(
+            !self.dispatcher.has_layers(),
+            "some layers haven't been popped yet"
+        );
+
+        let mut ids = Vec::new();
+        for fill_idx in self.dispatcher.hit_test(area).into_iter().rev() {
+            if let Some(id) = self.hit_ids[fill_idx]
+                && !ids.contains(&id)
+            {
+                ids.push(id);
+            }
+        }
+
+        ids
     }
 
     /// Return the width of the scene.
@@ -886,15 +1241,67 @@ impl Resources {
     }
 }
 
+/// Custom paint registry implementation.
+impl Resources {
+    /// Register a custom paint and return its [`CustomPaintId`].
+    ///
+    /// The custom paint is drawn by passing the ID to [`RenderContext::set_paint`].
+    pub fn register_custom_paint(&mut self, paint: Arc<dyn CustomPaint>) -> CustomPaintId {
+        self.custom_paints.register(paint)
+    }
+
+    /// Remove a custom paint from the registry.
+    pub fn destroy_custom_paint(&mut self, id: CustomPaintId) -> bool {
+        self.custom_paints.destroy(id)
+    }
+}
+
 /// Registry that maps opaque [`ImageId`]s to [`Pixmap`] data.
 ///
 /// Used by [`RenderContext`] to resolve `ImageSource::OpaqueId` at rasterization time.
 #[derive(Debug, Default)]
 pub(crate) struct ImageRegistry {
-    images: HashMap<u32, Arc<Pixmap>>,
+    images: HashMap<u32, RegisteredImage>,
     next_id: u32,
 }
 
+/// An image in the registry, together with the levels of its mip pyramid that have been
+/// built so far.
+#[derive(Debug)]
+struct RegisteredImage {
+    pixmap: Arc<Pixmap>,
+    /// The mip levels, starting with level 1. They are only built once an image is drawn
+    /// minified, and only up to the level that is actually needed.
+    mip_levels: Vec<Arc<Pixmap>>,
+}
+
+impl RegisteredImage {
+    fn new(pixmap: Arc<Pixmap>) -> Self {
+        Self {
+            pixmap,
+            mip_levels: Vec::new(),
+        }
+    }
+
+    fn build_mip_levels(&mut self, level: u8) {
+        while self.mip_levels.len() < usize::from(level) {
+            let last = self.mip_levels.last().unwrap_or(&self.pixmap);
+            if last.width() <= 1 && last.height() <= 1 {
+                break;
+            }
+            let next = Arc::new(last.downsample());
+            self.mip_levels.push(next);
+        }
+    }
+
+    fn mip_level(&self, level: u8) -> Option<&Arc<Pixmap>> {
+        match usize::from(level).min(self.mip_levels.len()) {
+            0 => None,
+            level => Some(&self.mip_levels[level - 1]),
+        }
+    }
+}
+
 impl ImageRegistry {
     fn register(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
         let id = self.next_id;
@@ -904,7 +1311,7 @@ impl ImageRegistry {
         );
 
         self.next_id += 1;
-        self.images.insert(id, pixmap);
+        self.images.insert(id, RegisteredImage::new(pixmap));
         ImageId::new(id)
     }
 
@@ -912,10 +1319,15 @@ impl ImageRegistry {
     pub(crate) fn register_atlas_page(&mut self, page_index: u32, pixmap: Arc<Pixmap>) {
         self.images.insert(
             ImageId::new(ATLAS_IMAGE_ID_BASE + page_index).as_u32(),
-            pixmap,
+            RegisteredImage::new(pixmap),
         );
     }
 
+    pub(crate) fn register_pattern_tile(&mut self, id: ImageId, pixmap: Arc<Pixmap>) {
+        self.images
+            .insert(id.as_u32(), RegisteredImage::new(pixmap));
+    }
+
     pub(crate) fn destroy(&mut self, id: ImageId) -> bool {
         self.images.remove(&id.as_u32()).is_some()
     }
@@ -926,7 +1338,24 @@ impl ImageRegistry {
     }
 
     fn resolve(&self, id: ImageId) -> Option<Arc<Pixmap>> {
-        self.images.get(&id.as_u32()).cloned()
+        self.images
+            .get(&id.as_u32())
+            .map(|image| image.pixmap.clone())
+    }
+
+    /// Build the mip levels needed for drawing the given paints.
+    ///
+    /// Glyph atlas pages are never drawn minified, so they are skipped.
+    fn prepare_mip_levels(&mut self, encoded_paints: &[EncodedPaint]) {
+        for paint in encoded_paints {
+            if let EncodedPaint::Image(image) = paint
+                && let ImageSource::OpaqueId { id, .. } = image.source
+                && id.as_u32() < ATLAS_IMAGE_ID_BASE
+                && let Some(registered) = self.images.get_mut(&id.as_u32())
+            {
+                registered.build_mip_levels(image.mip_level());
+            }
+        }
     }
 
     fn clear(&mut self) {
@@ -937,7 +1366,11 @@ impl ImageRegistry {
 
 impl ImageResolver for ImageRegistry {
     fn resolve(&self, id: ImageId) -> Option<Arc<Pixmap>> {
-        self.images.get(&id.as_u32()).cloned()
+        Self::resolve(self, id)
+    }
+
+    fn resolve_mip_level(&self, id: ImageId, level: u8) -> Option<Arc<Pixmap>> {
+        self.images.get(&id.as_u32())?.mip_level(level).cloned()
     }
 }
 
@@ -1172,15 +1605,71 @@ mod tests {
         );
     }
 
+    fn render_damaged(num_threads: u16) {
+        use crate::{RectU16, RenderSettings};
+
+        let settings = RenderSettings {
+            num_threads,
+            ..Default::default()
+        };
+        let mut ctx = RenderContext::new_with(600, 20, settings);
+        ctx.set_paint(RED.with_alpha(0.5));
+        ctx.fill_rect(&Rect::new(0.0, 0.0, 600.0, 20.0));
+        ctx.flush();
+        let mut resources = Resources::new();
+        let mut pixmap = solid_pixmap(600, 20, blue_pixel());
+
+        // The two rectangles overlap once they are rounded out to whole wide tiles and strip rows.
+        let damage = [
+            RectU16::new(300, 10, 310, 11),
+            RectU16::new(400, 9, 401, 12),
+        ];
+        ctx.render_with(
+            &mut pixmap,
+            &mut resources,
+            RasterizerSettings {
+                composite_mode: CompositeMode::SrcOver,
+                damage: Some(&damage),
+                ..Default::default()
+            },
+        );
+
+        let composited = PremulRgba8 {
+            r: 128,
+            g: 0,
+            b: 127,
+            a: 255,
+        };
+        for (x, y) in [(256, 8), (300, 10), (511, 11)] {
+            assert_eq!(pixmap.sample(x, y), composited, "pixel at ({x}, {y})");
+        }
+        for (x, y) in [(255, 8), (300, 7), (512, 10), (300, 12), (0, 0)] {
+            assert_eq!(pixmap.sample(x, y), blue_pixel(), "pixel at ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn render_with_damage() {
+        render_damaged(0);
+    }
+
+    #[cfg(feature = "multithreading")]
+    #[test]
+    fn render_with_damage_multithreaded() {
+        render_damaged(2);
+    }
+
     #[cfg(feature = "multithreading")]
     #[test]
     fn multithreaded_crash_after_reset() {
-        use crate::{Level, RasterizerSettings, RenderMode, RenderSettings};
+        use crate::{AntialiasingMode, Level, RasterizerSettings, RenderMode, RenderSettings};
 
         let mut pixmap = Pixmap::new(200, 200);
         let settings = RenderSettings {
             level: Level::try_detect().unwrap_or(Level::baseline()),
             num_threads: 1,
+            antialiasing_mode: AntialiasingMode::Area,
+            dithering: false,
         };
         let rasterizer_settings = RasterizerSettings {
             render_mode: RenderMode::OptimizeQuality,
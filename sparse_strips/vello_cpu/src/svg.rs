@@ -0,0 +1,1969 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of SVG documents.
+//!
+//! An [`Svg`] is parsed once from the XML source and can then be drawn into any
+//! [`RenderContext`]. The following subset of SVG is supported:
+//!
+//! - The basic shapes (`rect`, `circle`, `ellipse`, `line`, `polyline` and `polygon`)
+//!   as well as `path`.
+//! - Grouping using `g`, and reuse of elements using `use`, `defs` and `symbol`.
+//! - Solid colors, linear and radial gradients and patterns as fill and stroke paints.
+//! - PNG images embedded as `data:` URLs, if the `png` feature is enabled. This includes
+//!   the images in documents exported by [`RenderContext::start_svg_capture`].
+//! - Stroke styling, including dashing, line joins, line caps and the miter limit.
+//! - `transform`, `opacity`, `clip-path`, `mask` and `filter`.
+//! - Presentation attributes as well as inline `style` attributes.
+//!
+//! Unsupported elements (such as text or external images) are ignored, whereas
+//! malformed attributes of supported elements result in an [`SvgError`].
+//! Elements with a filter that cannot be rendered yet (see [`SvgFilter::resolve`])
+//! are drawn without it.
+//!
+//! Patterns are drawn as [`Pattern`] paints, so that their content is rasterized at the
+//! resolution it is displayed at. As a pattern can't hold masks, masked elements inside a
+//! pattern are drawn without their mask.
+//!
+//! Note that a `clipPath` with several children is approximated by the union of their
+//! outlines using the non-zero fill rule.
+
+use crate::kurbo::{
+    Affine, BezPath, Cap, Ellipse, Join, Point, Rect, Shape as _, Size, Stroke, Vec2,
+};
+use crate::peniko::{
+    BlendMode, ColorStop, Extend, Fill, Gradient, ImageSampler, LinearGradientPosition,
+    RadialGradientPosition,
+};
+use crate::{Image, ImageSource, Mask, PaintType, Pixmap, RenderContext, Resources};
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use roxmltree::{Document, Node as XmlNode};
+use vello_common::color::palette::css::BLACK;
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::filter_effects::Filter;
+use vello_common::pattern::{Pattern, PatternCommand, PatternScene};
+use vello_common::pico_svg::{
+    Length, parse_color, parse_length, parse_number_list, parse_opacity, parse_transform,
+    parse_view_box,
+};
+use vello_common::render_state::RenderState;
+use vello_common::svg_filter::SvgFilter;
+
+pub use vello_common::pico_svg::SvgError;
+
+/// The tolerance used when converting shapes like circles to paths.
+const TOLERANCE: f64 = 0.1;
+
+const XLINK_NS: &str = "http://www.w3.org/1999/xlink";
+
+/// A parsed SVG document.
+#[derive(Debug)]
+pub struct Svg {
+    size: Size,
+    view_transform: Affine,
+    children: Vec<Node>,
+}
+
+impl Svg {
+    /// Parse an SVG document from a string.
+    pub fn parse(xml: &str) -> Result<Self, SvgError> {
+        let doc = Document::parse(xml)?;
+        let root = doc.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(SvgError::NotAnSvg);
+        }
+
+        let width = root_length(root, "width")?;
+        let height = root_length(root, "height")?;
+        let view_box = root
+            .attribute("viewBox")
+            .map(|vb| parse_view_box(vb).ok_or_else(|| SvgError::invalid_attribute("viewBox", vb)))
+            .transpose()?;
+        let aspect_ratio = AspectRatio::parse(root)?;
+
+        let size = match (width, height, view_box) {
+            (Some(width), Some(height), _) => Size::new(width, height),
+            (Some(width), None, Some(vb)) if vb.width() > 0.0 => {
+                Size::new(width, width * vb.height() / vb.width())
+            }
+            (None, Some(height), Some(vb)) if vb.height() > 0.0 => {
+                Size::new(height * vb.width() / vb.height(), height)
+            }
+            (None, None, Some(vb)) => vb.size(),
+            (width, height, _) => Size::new(width.unwrap_or(300.0), height.unwrap_or(150.0)),
+        };
+
+        let (view_transform, viewport) = match view_box {
+            Some(vb) => (aspect_ratio.view_box_transform(vb, size), vb.size()),
+            None => (Affine::IDENTITY, size),
+        };
+
+        let mut parser = Parser::new(&doc, viewport);
+        let style = parser.resolve_style(root, &Style::default())?;
+        let mut children = Vec::new();
+        // A view box with a width or height of zero disables rendering.
+        if viewport.width > 0.0 && viewport.height > 0.0 {
+            for child in root.children() {
+                parser.parse_node(child, &style, &mut children)?;
+            }
+        }
+
+        Ok(Self {
+            size,
+            view_transform,
+            children,
+        })
+    }
+
+    /// The size of the document, in pixels.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Whether any element of the document has a filter.
+    ///
+    /// Filter effects are not supported by multi-threaded rendering yet, so documents
+    /// using them must be rendered by a single-threaded [`RenderContext`].
+    pub fn uses_filters(&self) -> bool {
+        uses_filters(&self.children)
+    }
+
+    /// Draw the document into `ctx`.
+    ///
+    /// The current transform of `ctx` is applied to the whole document. Apart from that,
+    /// the render state of `ctx` is left unchanged.
+    pub fn render(&self, ctx: &mut RenderContext) {
+        let state = ctx.save_current_state();
+        let transform = *ctx.transform() * self.view_transform;
+        render_nodes(ctx, &self.children, transform);
+        ctx.restore_state(state);
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Group(Group),
+    Shape(Shape),
+    Image(SvgImage),
+}
+
+impl Node {
+    fn bounding_box(&self) -> Option<Rect> {
+        match self {
+            Self::Group(group) => {
+                bounding_box(&group.children).map(|bbox| group.transform.transform_rect_bbox(bbox))
+            }
+            Self::Shape(shape) => shape.bounding_box(),
+            Self::Image(image) => Some(image.bounds()),
+        }
+    }
+}
+
+/// Whether any of `nodes`, including the contents of their masks and patterns, has a filter.
+fn uses_filters(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| match node {
+        Node::Group(group) => {
+            group.filter.is_some()
+                || group
+                    .mask
+                    .as_ref()
+                    .is_some_and(|mask| uses_filters(&mask.children))
+                || uses_filters(&group.children)
+        }
+        Node::Shape(shape) => shape
+            .fill
+            .iter()
+            .map(|(paint, _)| paint)
+            .chain(shape.stroke.iter().map(|(paint, _)| paint))
+            .any(|paint| match paint {
+                SvgPaint::Pattern { pattern, .. } => uses_filters(&pattern.children),
+                SvgPaint::Color(_) | SvgPaint::Gradient { .. } => false,
+            }),
+        Node::Image(_) => false,
+    })
+}
+
+/// The union of the bounding boxes of `nodes`.
+fn bounding_box(nodes: &[Node]) -> Option<Rect> {
+    nodes
+        .iter()
+        .filter_map(Node::bounding_box)
+        .reduce(|a, b| a.union(b))
+}
+
+#[derive(Debug)]
+struct Group {
+    transform: Affine,
+    opacity: f32,
+    clip_path: Option<Arc<ClipPath>>,
+    mask: Option<Arc<SvgMask>>,
+    filter: Option<Arc<SvgFilter>>,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+struct Shape {
+    path: BezPath,
+    fill: Option<(SvgPaint, Fill)>,
+    stroke: Option<(SvgPaint, Stroke)>,
+    /// The fill rule to use if the shape is part of a clip path.
+    clip_rule: Fill,
+}
+
+impl Shape {
+    fn bounding_box(&self) -> Option<Rect> {
+        (!self.path.elements().is_empty()).then(|| self.path.bounding_box())
+    }
+}
+
+#[derive(Debug)]
+struct SvgImage {
+    pixmap: Arc<Pixmap>,
+    /// The transform from the pixels of the image to user space.
+    transform: Affine,
+    /// The viewport of the image, which it is clipped to.
+    viewport: Rect,
+}
+
+impl SvgImage {
+    /// The area covered by the image.
+    fn bounds(&self) -> Rect {
+        let size = Size::new(
+            f64::from(self.pixmap.width()),
+            f64::from(self.pixmap.height()),
+        );
+        self.transform
+            .transform_rect_bbox(size.to_rect())
+            .intersect(self.viewport)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum SvgPaint {
+    Color(AlphaColor<Srgb>),
+    Gradient {
+        gradient: Arc<SvgGradient>,
+        opacity: f32,
+    },
+    Pattern {
+        pattern: Arc<SvgPattern>,
+        opacity: f32,
+    },
+}
+
+impl SvgPaint {
+    fn multiply_alpha(self, alpha: f32) -> Self {
+        match self {
+            Self::Color(color) => Self::Color(color.multiply_alpha(alpha)),
+            Self::Gradient { gradient, opacity } => Self::Gradient {
+                gradient,
+                opacity: opacity * alpha,
+            },
+            Self::Pattern { pattern, opacity } => Self::Pattern {
+                pattern,
+                opacity: opacity * alpha,
+            },
+        }
+    }
+}
+
+/// The coordinate system of the attributes or contents of an element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Units {
+    UserSpaceOnUse,
+    ObjectBoundingBox,
+}
+
+impl Units {
+    fn parse(node: XmlNode<'_, '_>, attribute: &str, default: Self) -> Result<Self, SvgError> {
+        match node.attribute(attribute) {
+            None => Ok(default),
+            Some("userSpaceOnUse") => Ok(Self::UserSpaceOnUse),
+            Some("objectBoundingBox") => Ok(Self::ObjectBoundingBox),
+            Some(value) => Err(SvgError::invalid_attribute(attribute, value)),
+        }
+    }
+
+    /// The transform from this coordinate system to user space.
+    ///
+    /// Returns `None` if the bounding box is needed, but missing or empty.
+    fn transform(self, bbox: Option<Rect>) -> Option<Affine> {
+        match self {
+            Self::UserSpaceOnUse => Some(Affine::IDENTITY),
+            Self::ObjectBoundingBox => {
+                let bbox = bbox.filter(|bbox| bbox.width() > 0.0 && bbox.height() > 0.0)?;
+                Some(Affine::new([
+                    bbox.width(),
+                    0.0,
+                    0.0,
+                    bbox.height(),
+                    bbox.x0,
+                    bbox.y0,
+                ]))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SvgGradient {
+    gradient: Gradient,
+    units: Units,
+    transform: Affine,
+}
+
+#[derive(Debug)]
+struct SvgPattern {
+    /// The pattern tile, in `units`.
+    tile: Rect,
+    units: Units,
+    content_units: Units,
+    view_box: Option<(Rect, AspectRatio)>,
+    transform: Affine,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+struct ClipPath {
+    units: Units,
+    transform: Affine,
+    path: BezPath,
+    fill_rule: Fill,
+}
+
+#[derive(Debug)]
+struct SvgMask {
+    /// The masked area, in `units`.
+    rect: Rect,
+    units: Units,
+    content_units: Units,
+    luminance: bool,
+    children: Vec<Node>,
+}
+
+/// The value of a `preserveAspectRatio` attribute.
+#[derive(Clone, Copy, Debug)]
+struct AspectRatio {
+    /// The alignment along both axes, as a fraction of the free space,
+    /// or `None` if the view box should be stretched to fill the viewport.
+    align: Option<(f64, f64)>,
+    slice: bool,
+}
+
+impl AspectRatio {
+    fn parse(node: XmlNode<'_, '_>) -> Result<Self, SvgError> {
+        let Some(value) = node.attribute("preserveAspectRatio") else {
+            return Ok(Self {
+                align: Some((0.5, 0.5)),
+                slice: false,
+            });
+        };
+        let invalid = || SvgError::invalid_attribute("preserveAspectRatio", value);
+        let mut parts = value.split_whitespace();
+        let align = parts.next().ok_or_else(invalid)?;
+        let align = if align == "none" {
+            None
+        } else {
+            let fraction = |s: &str| match s {
+                "Min" => Some(0.0),
+                "Mid" => Some(0.5),
+                "Max" => Some(1.0),
+                _ => None,
+            };
+            let (x, y) = align
+                .strip_prefix('x')
+                .and_then(|align| align.split_once('Y'))
+                .ok_or_else(invalid)?;
+            Some((
+                fraction(x).ok_or_else(invalid)?,
+                fraction(y).ok_or_else(invalid)?,
+            ))
+        };
+        let slice = match parts.next() {
+            None | Some("meet") => false,
+            Some("slice") => true,
+            Some(_) => return Err(invalid()),
+        };
+        Ok(Self { align, slice })
+    }
+
+    /// The transform which maps `view_box` into a viewport of the given size at the origin.
+    fn view_box_transform(self, view_box: Rect, size: Size) -> Affine {
+        if view_box.width() <= 0.0 || view_box.height() <= 0.0 {
+            return Affine::IDENTITY;
+        }
+        let sx = size.width / view_box.width();
+        let sy = size.height / view_box.height();
+        let to_origin = Affine::translate(-view_box.origin().to_vec2());
+        match self.align {
+            None => Affine::scale_non_uniform(sx, sy) * to_origin,
+            Some((ax, ay)) => {
+                let scale = if self.slice { sx.max(sy) } else { sx.min(sy) };
+                let offset = Vec2::new(
+                    (size.width - view_box.width() * scale) * ax,
+                    (size.height - view_box.height() * scale) * ay,
+                );
+                Affine::translate(offset) * Affine::scale(scale) * to_origin
+            }
+        }
+    }
+}
+
+/// The inherited properties of an element.
+#[derive(Clone, Debug)]
+struct Style {
+    color: AlphaColor<Srgb>,
+    fill: Option<SvgPaint>,
+    fill_opacity: f32,
+    fill_rule: Fill,
+    stroke: Option<SvgPaint>,
+    stroke_opacity: f32,
+    stroke_style: Stroke,
+    clip_rule: Fill,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            color: BLACK,
+            fill: Some(SvgPaint::Color(BLACK)),
+            fill_opacity: 1.0,
+            fill_rule: Fill::NonZero,
+            stroke: None,
+            stroke_opacity: 1.0,
+            stroke_style: Stroke::new(1.0).with_miter_limit(4.0),
+            clip_rule: Fill::NonZero,
+        }
+    }
+}
+
+/// Look up a property of an element, either in its `style` attribute or as a
+/// presentation attribute.
+///
+/// Returns `None` if the property is not specified or is `inherit`.
+fn property<'a>(node: XmlNode<'a, '_>, name: &str) -> Option<&'a str> {
+    let from_style = node.attribute("style").and_then(|style| {
+        style.split(';').rev().find_map(|declaration| {
+            let (key, value) = declaration.split_once(':')?;
+            (key.trim() == name).then(|| value.trim())
+        })
+    });
+    from_style
+        .or_else(|| node.attribute(name).map(str::trim))
+        .filter(|value| *value != "inherit")
+}
+
+/// The fragment identifier of the element referenced by `node`.
+fn href<'a>(node: XmlNode<'a, '_>) -> Option<&'a str> {
+    node.attribute((XLINK_NS, "href"))
+        .or_else(|| node.attribute("href"))
+        .and_then(|href| href.trim().strip_prefix('#'))
+}
+
+/// Parse the `width` or `height` attribute of the root element.
+fn root_length(root: XmlNode<'_, '_>, attribute: &'static str) -> Result<Option<f64>, SvgError> {
+    match root.attribute(attribute) {
+        None => Ok(None),
+        Some(value) => match parse_length(value) {
+            Some(Length::Absolute(length)) => Ok(Some(length)),
+            // There is no viewport to resolve percentages against.
+            Some(Length::Percent(_)) => Ok(None),
+            None => Err(SvgError::invalid_attribute(attribute, value)),
+        },
+    }
+}
+
+fn parse_fill_rule(attribute: &str, value: &str) -> Result<Fill, SvgError> {
+    match value {
+        "nonzero" => Ok(Fill::NonZero),
+        "evenodd" => Ok(Fill::EvenOdd),
+        _ => Err(SvgError::invalid_attribute(attribute, value)),
+    }
+}
+
+fn parse_number(attribute: &str, value: &str) -> Result<f64, SvgError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| SvgError::invalid_attribute(attribute, value))
+}
+
+/// The axis which percentages of a length are relative to.
+#[derive(Clone, Copy)]
+enum Axis {
+    X,
+    Y,
+    Diagonal,
+}
+
+struct Parser<'a, 'input> {
+    ids: HashMap<&'a str, XmlNode<'a, 'input>>,
+    viewport: Size,
+    /// The ids of the referenced elements which are currently being parsed.
+    active: Vec<&'a str>,
+    paint_servers: HashMap<&'a str, Option<SvgPaint>>,
+    clip_paths: HashMap<&'a str, Arc<ClipPath>>,
+    masks: HashMap<&'a str, Arc<SvgMask>>,
+    filters: HashMap<&'a str, Option<Arc<SvgFilter>>>,
+    /// The decoded images, by the value of their `href` attribute.
+    images: HashMap<&'a str, Option<Arc<Pixmap>>>,
+}
+
+impl<'a, 'input> Parser<'a, 'input> {
+    fn new(doc: &'a Document<'input>, viewport: Size) -> Self {
+        let mut ids = HashMap::new();
+        for node in doc.descendants() {
+            if let Some(id) = node.attribute("id") {
+                ids.entry(id).or_insert(node);
+            }
+        }
+        Self {
+            ids,
+            viewport,
+            active: Vec::new(),
+            paint_servers: HashMap::new(),
+            clip_paths: HashMap::new(),
+            masks: HashMap::new(),
+            filters: HashMap::new(),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Run `f` on the element with the given id, checking for reference cycles.
+    ///
+    /// Returns `Ok(None)` if there is no such element.
+    fn with_reference<T>(
+        &mut self,
+        id: &str,
+        f: impl FnOnce(&mut Self, XmlNode<'a, 'input>) -> Result<T, SvgError>,
+    ) -> Result<Option<T>, SvgError> {
+        let Some((&id, &node)) = self.ids.get_key_value(id) else {
+            return Ok(None);
+        };
+        if self.active.contains(&id) {
+            return Err(SvgError::CyclicReference(id.to_string()));
+        }
+        self.active.push(id);
+        let result = f(self, node);
+        self.active.pop();
+        result.map(Some)
+    }
+
+    fn reference_length(&self, axis: Axis) -> f64 {
+        let Size { width, height } = self.viewport;
+        match axis {
+            Axis::X => width,
+            Axis::Y => height,
+            Axis::Diagonal => ((width * width + height * height) / 2.0).sqrt(),
+        }
+    }
+
+    fn resolve_length(&self, attribute: &str, value: &str, axis: Axis) -> Result<f64, SvgError> {
+        parse_length(value)
+            .map(|length| length.resolve(self.reference_length(axis)))
+            .ok_or_else(|| SvgError::invalid_attribute(attribute, value))
+    }
+
+    /// Parse a length attribute, resolving percentages against the viewport.
+    fn length(
+        &self,
+        node: XmlNode<'_, '_>,
+        attribute: &str,
+        axis: Axis,
+        default: f64,
+    ) -> Result<f64, SvgError> {
+        node.attribute(attribute).map_or(Ok(default), |value| {
+            self.resolve_length(attribute, value, axis)
+        })
+    }
+
+    /// Parse a length attribute of an element whose attributes can be given in `units`.
+    fn length_in_units(
+        &self,
+        node: XmlNode<'_, '_>,
+        attribute: &str,
+        units: Units,
+        axis: Axis,
+        default: Length,
+    ) -> Result<f64, SvgError> {
+        let length = match node.attribute(attribute) {
+            None => default,
+            Some(value) => {
+                parse_length(value).ok_or_else(|| SvgError::invalid_attribute(attribute, value))?
+            }
+        };
+        Ok(match units {
+            Units::UserSpaceOnUse => length.resolve(self.reference_length(axis)),
+            Units::ObjectBoundingBox => length.resolve(1.0),
+        })
+    }
+
+    fn transform(&self, node: XmlNode<'_, '_>, attribute: &str) -> Result<Affine, SvgError> {
+        node.attribute(attribute)
+            .map_or(Ok(Affine::IDENTITY), |value| {
+                parse_transform(value).ok_or_else(|| SvgError::invalid_attribute(attribute, value))
+            })
+    }
+
+    /// Compute the inherited properties of `node`.
+    fn resolve_style(
+        &mut self,
+        node: XmlNode<'a, 'input>,
+        parent: &Style,
+    ) -> Result<Style, SvgError> {
+        let mut style = parent.clone();
+        if let Some(value) = property(node, "color") {
+            style.color =
+                parse_color(value).ok_or_else(|| SvgError::invalid_attribute("color", value))?;
+        }
+        if let Some(value) = property(node, "fill") {
+            style.fill = self.paint("fill", value, style.color)?;
+        }
+        if let Some(value) = property(node, "fill-opacity") {
+            style.fill_opacity = parse_opacity(value)
+                .ok_or_else(|| SvgError::invalid_attribute("fill-opacity", value))?;
+        }
+        if let Some(value) = property(node, "fill-rule") {
+            style.fill_rule = parse_fill_rule("fill-rule", value)?;
+        }
+        if let Some(value) = property(node, "clip-rule") {
+            style.clip_rule = parse_fill_rule("clip-rule", value)?;
+        }
+        if let Some(value) = property(node, "stroke") {
+            style.stroke = self.paint("stroke", value, style.color)?;
+        }
+        if let Some(value) = property(node, "stroke-opacity") {
+            style.stroke_opacity = parse_opacity(value)
+                .ok_or_else(|| SvgError::invalid_attribute("stroke-opacity", value))?;
+        }
+
+        let stroke = &mut style.stroke_style;
+        if let Some(value) = property(node, "stroke-width") {
+            let width = self.resolve_length("stroke-width", value, Axis::Diagonal)?;
+            if width < 0.0 {
+                return Err(SvgError::invalid_attribute("stroke-width", value));
+            }
+            stroke.width = width;
+        }
+        if let Some(value) = property(node, "stroke-linecap") {
+            let cap = match value {
+                "butt" => Cap::Butt,
+                "round" => Cap::Round,
+                "square" => Cap::Square,
+                _ => return Err(SvgError::invalid_attribute("stroke-linecap", value)),
+            };
+            stroke.start_cap = cap;
+            stroke.end_cap = cap;
+        }
+        if let Some(value) = property(node, "stroke-linejoin") {
+            stroke.join = match value {
+                "miter" | "miter-clip" | "arcs" => Join::Miter,
+                "round" => Join::Round,
+                "bevel" => Join::Bevel,
+                _ => return Err(SvgError::invalid_attribute("stroke-linejoin", value)),
+            };
+        }
+        if let Some(value) = property(node, "stroke-miterlimit") {
+            let limit = parse_number("stroke-miterlimit", value)?;
+            if limit < 1.0 {
+                return Err(SvgError::invalid_attribute("stroke-miterlimit", value));
+            }
+            stroke.miter_limit = limit;
+        }
+        if let Some(value) = property(node, "stroke-dashoffset") {
+            stroke.dash_offset = self.resolve_length("stroke-dashoffset", value, Axis::Diagonal)?;
+        }
+        if let Some(value) = property(node, "stroke-dasharray") {
+            stroke.dash_pattern.clear();
+            if value != "none" {
+                let mut dashes = value
+                    .split(|c: char| c == ',' || c.is_ascii_whitespace())
+                    .filter(|dash| !dash.is_empty())
+                    .map(|dash| self.resolve_length("stroke-dasharray", dash, Axis::Diagonal))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if dashes.iter().any(|dash| *dash < 0.0) {
+                    return Err(SvgError::invalid_attribute("stroke-dasharray", value));
+                }
+                // A dash array whose sum is zero is rendered as a solid line.
+                if dashes.iter().sum::<f64>() > 0.0 {
+                    if dashes.len() % 2 == 1 {
+                        dashes.extend_from_within(..);
+                    }
+                    stroke.dash_pattern.extend(dashes);
+                }
+            }
+        }
+        Ok(style)
+    }
+
+    /// Parse the value of a `fill` or `stroke` property.
+    fn paint(
+        &mut self,
+        attribute: &str,
+        value: &str,
+        current_color: AlphaColor<Srgb>,
+    ) -> Result<Option<SvgPaint>, SvgError> {
+        let invalid = || SvgError::invalid_attribute(attribute, value);
+        match value {
+            "none" => Ok(None),
+            "currentColor" => Ok(Some(SvgPaint::Color(current_color))),
+            _ => {
+                let Some(reference) = value.strip_prefix("url(") else {
+                    return parse_color(value)
+                        .map(|color| Some(SvgPaint::Color(color)))
+                        .ok_or_else(invalid);
+                };
+                let (url, fallback) = reference.split_once(')').ok_or_else(invalid)?;
+                let id = url
+                    .trim()
+                    .trim_matches(|c| c == '"' || c == '\'')
+                    .strip_prefix('#')
+                    .ok_or_else(invalid)?;
+                if let Some(paint) = self.paint_server(id)? {
+                    return Ok(paint);
+                }
+                // The paint server doesn't exist, so we use the fallback if there is one.
+                match fallback.trim() {
+                    "" => Ok(None),
+                    fallback => self.paint(attribute, fallback, current_color),
+                }
+            }
+        }
+    }
+
+    /// Resolve the paint server with the given id.
+    ///
+    /// Returns `Ok(None)` if there is no paint server with that id.
+    fn paint_server(&mut self, id: &str) -> Result<Option<Option<SvgPaint>>, SvgError> {
+        if let Some(paint) = self.paint_servers.get(id) {
+            return Ok(Some(paint.clone()));
+        }
+        let paint = self.with_reference(id, |parser, node| match node.tag_name().name() {
+            "linearGradient" | "radialGradient" => parser.parse_gradient(node).map(Some),
+            "pattern" => parser.parse_pattern(node).map(Some),
+            _ => Ok(None),
+        })?;
+        let Some(Some(paint)) = paint else {
+            return Ok(None);
+        };
+        if let Some((&id, _)) = self.ids.get_key_value(id) {
+            self.paint_servers.insert(id, paint.clone());
+        }
+        Ok(Some(paint))
+    }
+
+    /// The chain of elements that `node` inherits attributes from via `href`,
+    /// starting with `node` itself.
+    fn template_chain(&self, node: XmlNode<'a, 'input>) -> Vec<XmlNode<'a, 'input>> {
+        let mut chain = alloc::vec![node];
+        while let Some(next) = href(*chain.last().unwrap()).and_then(|id| self.ids.get(id)) {
+            if chain.contains(next)
+                || next.tag_name() != node.tag_name() && !is_gradient(*next, node)
+            {
+                break;
+            }
+            chain.push(*next);
+        }
+        chain
+    }
+
+    fn parse_gradient(&mut self, node: XmlNode<'a, 'input>) -> Result<Option<SvgPaint>, SvgError> {
+        let chain = self.template_chain(node);
+        // The first element of the chain which specifies the attribute.
+        let attr = |name: &str| chain.iter().copied().find(|node| node.has_attribute(name));
+        let attr_node = |name: &str| attr(name).unwrap_or(node);
+
+        let units = Units::parse(
+            attr_node("gradientUnits"),
+            "gradientUnits",
+            Units::ObjectBoundingBox,
+        )?;
+        let transform = self.transform(attr_node("gradientTransform"), "gradientTransform")?;
+        let extend = match attr_node("spreadMethod").attribute("spreadMethod") {
+            None | Some("pad") => Extend::Pad,
+            Some("reflect") => Extend::Reflect,
+            Some("repeat") => Extend::Repeat,
+            Some(value) => return Err(SvgError::invalid_attribute("spreadMethod", value)),
+        };
+
+        let stops_node = chain
+            .iter()
+            .copied()
+            .find(|node| node.children().any(|child| child.has_tag_name("stop")));
+        let mut stops: Vec<ColorStop> = Vec::new();
+        for stop in stops_node
+            .into_iter()
+            .flat_map(|node| node.children())
+            .filter(|child| child.has_tag_name("stop"))
+        {
+            stops.push(self.parse_stop(stop, stops.last().map_or(0.0, |last| last.offset))?);
+        }
+        match stops.as_slice() {
+            [] => return Ok(None),
+            [stop] => return Ok(Some(SvgPaint::Color(stop.color.to_alpha_color()))),
+            _ => {}
+        }
+
+        let length = |name: &str, axis: Axis, default: Length| {
+            self.length_in_units(attr_node(name), name, units, axis, default)
+        };
+        let kind = if node.has_tag_name("linearGradient") {
+            LinearGradientPosition {
+                start: Point::new(
+                    length("x1", Axis::X, Length::Percent(0.0))?,
+                    length("y1", Axis::Y, Length::Percent(0.0))?,
+                ),
+                end: Point::new(
+                    length("x2", Axis::X, Length::Percent(1.0))?,
+                    length("y2", Axis::Y, Length::Percent(0.0))?,
+                ),
+            }
+            .into()
+        } else {
+            let center = Point::new(
+                length("cx", Axis::X, Length::Percent(0.5))?,
+                length("cy", Axis::Y, Length::Percent(0.5))?,
+            );
+            let radius = length("r", Axis::Diagonal, Length::Percent(0.5))?;
+            if radius <= 0.0 {
+                // The whole area is painted with the color of the last stop.
+                let last = stops.last().unwrap();
+                return Ok(Some(SvgPaint::Color(last.color.to_alpha_color())));
+            }
+            let focus = Point::new(
+                length("fx", Axis::X, Length::Absolute(center.x))?,
+                length("fy", Axis::Y, Length::Absolute(center.y))?,
+            );
+            let focal_radius = length("fr", Axis::Diagonal, Length::Percent(0.0))?;
+            RadialGradientPosition {
+                start_center: focus,
+                start_radius: focal_radius as f32,
+                end_center: center,
+                end_radius: radius as f32,
+            }
+            .into()
+        };
+
+        let gradient = Gradient {
+            kind,
+            extend,
+            stops: stops.as_slice().into(),
+            ..Default::default()
+        };
+        Ok(Some(SvgPaint::Gradient {
+            gradient: Arc::new(SvgGradient {
+                gradient,
+                units,
+                transform,
+            }),
+            opacity: 1.0,
+        }))
+    }
+
+    fn parse_stop(&self, stop: XmlNode<'_, '_>, min_offset: f32) -> Result<ColorStop, SvgError> {
+        let offset = match stop.attribute("offset") {
+            None => 0.0,
+            Some(value) => {
+                parse_opacity(value).ok_or_else(|| SvgError::invalid_attribute("offset", value))?
+            }
+        };
+        let color = match property(stop, "stop-color") {
+            None => BLACK,
+            Some("currentColor") => match property(stop, "color") {
+                None => BLACK,
+                Some(value) => {
+                    parse_color(value).ok_or_else(|| SvgError::invalid_attribute("color", value))?
+                }
+            },
+            Some(value) => parse_color(value)
+                .ok_or_else(|| SvgError::invalid_attribute("stop-color", value))?,
+        };
+        let opacity = match property(stop, "stop-opacity") {
+            None => 1.0,
+            Some(value) => parse_opacity(value)
+                .ok_or_else(|| SvgError::invalid_attribute("stop-opacity", value))?,
+        };
+        Ok(ColorStop::from((
+            offset.max(min_offset),
+            color.multiply_alpha(opacity),
+        )))
+    }
+
+    fn parse_pattern(&mut self, node: XmlNode<'a, 'input>) -> Result<Option<SvgPaint>, SvgError> {
+        let chain = self.template_chain(node);
+        let attr_node = |name: &str| {
+            chain
+                .iter()
+                .copied()
+                .find(|node| node.has_attribute(name))
+                .unwrap_or(node)
+        };
+
+        let units = Units::parse(
+            attr_node("patternUnits"),
+            "patternUnits",
+            Units::ObjectBoundingBox,
+        )?;
+        let content_units = Units::parse(
+            attr_node("patternContentUnits"),
+            "patternContentUnits",
+            Units::UserSpaceOnUse,
+        )?;
+        let transform = self.transform(attr_node("patternTransform"), "patternTransform")?;
+        let length = |name: &str, axis: Axis| {
+            self.length_in_units(attr_node(name), name, units, axis, Length::Absolute(0.0))
+        };
+        let x = length("x", Axis::X)?;
+        let y = length("y", Axis::Y)?;
+        let width = length("width", Axis::X)?;
+        let height = length("height", Axis::Y)?;
+        if width <= 0.0 || height <= 0.0 {
+            return Ok(None);
+        }
+
+        let view_box_node = attr_node("viewBox");
+        let view_box = view_box_node
+            .attribute("viewBox")
+            .map(|vb| {
+                let view_box =
+                    parse_view_box(vb).ok_or_else(|| SvgError::invalid_attribute("viewBox", vb))?;
+                Ok::<_, SvgError>((view_box, AspectRatio::parse(view_box_node)?))
+            })
+            .transpose()?;
+
+        let content_node = chain
+            .iter()
+            .copied()
+            .find(|node| node.children().any(|child| child.is_element()))
+            .unwrap_or(node);
+        let style = self.ancestor_style(content_node)?;
+        let mut children = Vec::new();
+        for child in content_node.children() {
+            self.parse_node(child, &style, &mut children)?;
+        }
+
+        Ok(Some(SvgPaint::Pattern {
+            pattern: Arc::new(SvgPattern {
+                tile: Rect::new(x, y, x + width, y + height),
+                units,
+                content_units,
+                view_box,
+                transform,
+                children,
+            }),
+            opacity: 1.0,
+        }))
+    }
+
+    /// Compute the inherited properties of `node`, based on its ancestors in the document.
+    fn ancestor_style(&mut self, node: XmlNode<'a, 'input>) -> Result<Style, SvgError> {
+        let ancestors = node
+            .ancestors()
+            .filter(XmlNode::is_element)
+            .collect::<Vec<_>>();
+        let mut style = Style::default();
+        for ancestor in ancestors.into_iter().rev() {
+            style = self.resolve_style(ancestor, &style)?;
+        }
+        Ok(style)
+    }
+
+    fn clip_path(&mut self, id: &str) -> Result<Option<Arc<ClipPath>>, SvgError> {
+        if let Some(clip_path) = self.clip_paths.get(id) {
+            return Ok(Some(clip_path.clone()));
+        }
+        let clip_path = self.with_reference(id, |parser, node| {
+            if !node.has_tag_name("clipPath") {
+                return Ok(None);
+            }
+            let units = Units::parse(node, "clipPathUnits", Units::UserSpaceOnUse)?;
+            let transform = parser.transform(node, "transform")?;
+            let style = parser.ancestor_style(node)?;
+            let mut children = Vec::new();
+            for child in node.children() {
+                parser.parse_node(child, &style, &mut children)?;
+            }
+            let mut outlines = Vec::new();
+            collect_outlines(&children, Affine::IDENTITY, &mut outlines);
+            let fill_rule = match outlines.as_slice() {
+                [(_, fill_rule)] => *fill_rule,
+                _ => Fill::NonZero,
+            };
+            let mut path = BezPath::new();
+            for (outline, _) in outlines {
+                path.extend(outline);
+            }
+            Ok(Some(Arc::new(ClipPath {
+                units,
+                transform,
+                path,
+                fill_rule,
+            })))
+        })?;
+        let Some(Some(clip_path)) = clip_path else {
+            return Ok(None);
+        };
+        if let Some((&id, _)) = self.ids.get_key_value(id) {
+            self.clip_paths.insert(id, clip_path.clone());
+        }
+        Ok(Some(clip_path))
+    }
+
+    fn mask(&mut self, id: &str) -> Result<Option<Arc<SvgMask>>, SvgError> {
+        if let Some(mask) = self.masks.get(id) {
+            return Ok(Some(mask.clone()));
+        }
+        let mask = self.with_reference(id, |parser, node| {
+            if !node.has_tag_name("mask") {
+                return Ok(None);
+            }
+            let units = Units::parse(node, "maskUnits", Units::ObjectBoundingBox)?;
+            let content_units = Units::parse(node, "maskContentUnits", Units::UserSpaceOnUse)?;
+            let length = |name: &str, axis: Axis, default: f64| {
+                parser.length_in_units(node, name, units, axis, Length::Percent(default))
+            };
+            let x = length("x", Axis::X, -0.1)?;
+            let y = length("y", Axis::Y, -0.1)?;
+            let width = length("width", Axis::X, 1.2)?;
+            let height = length("height", Axis::Y, 1.2)?;
+            let luminance = match property(node, "mask-type") {
+                None | Some("luminance") => true,
+                Some("alpha") => false,
+                Some(value) => return Err(SvgError::invalid_attribute("mask-type", value)),
+            };
+            let style = parser.ancestor_style(node)?;
+            let mut children = Vec::new();
+            for child in node.children() {
+                parser.parse_node(child, &style, &mut children)?;
+            }
+            Ok(Some(Arc::new(SvgMask {
+                rect: Rect::new(x, y, x + width, y + height),
+                units,
+                content_units,
+                luminance,
+                children,
+            })))
+        })?;
+        let Some(Some(mask)) = mask else {
+            return Ok(None);
+        };
+        if let Some((&id, _)) = self.ids.get_key_value(id) {
+            self.masks.insert(id, mask.clone());
+        }
+        Ok(Some(mask))
+    }
+
+    /// Resolve the filter with the given id.
+    ///
+    /// Returns `Ok(None)` if there is no filter with that id, and `Ok(Some(None))`
+    /// if the filter uses unsupported primitives.
+    fn filter(&mut self, id: &str) -> Result<Option<Option<Arc<SvgFilter>>>, SvgError> {
+        if let Some(filter) = self.filters.get(id) {
+            return Ok(Some(filter.clone()));
+        }
+        let Some(node) = self.ids.get(id).filter(|node| node.has_tag_name("filter")) else {
+            return Ok(None);
+        };
+        let filter = SvgFilter::parse(*node)?.map(Arc::new);
+        if let Some((&id, _)) = self.ids.get_key_value(id) {
+            self.filters.insert(id, filter.clone());
+        }
+        Ok(Some(filter))
+    }
+
+    /// Parse a `url(#id)` reference, as used by `clip-path`, `mask` and `filter`.
+    fn url_reference<'v>(attribute: &str, value: &'v str) -> Result<Option<&'v str>, SvgError> {
+        if value == "none" {
+            return Ok(None);
+        }
+        value
+            .strip_prefix("url(")
+            .and_then(|url| url.strip_suffix(')'))
+            .map(|url| url.trim().trim_matches(|c| c == '"' || c == '\''))
+            .and_then(|url| url.strip_prefix('#'))
+            .map(Some)
+            .ok_or_else(|| SvgError::invalid_attribute(attribute, value))
+    }
+
+    /// Parse an element and its descendants, and append the result to `nodes`.
+    fn parse_node(
+        &mut self,
+        node: XmlNode<'a, 'input>,
+        parent_style: &Style,
+        nodes: &mut Vec<Node>,
+    ) -> Result<(), SvgError> {
+        if !node.is_element() || property(node, "display") == Some("none") {
+            return Ok(());
+        }
+        let name = node.tag_name().name();
+        let is_supported = matches!(
+            name,
+            "g" | "a"
+                | "switch"
+                | "use"
+                | "path"
+                | "rect"
+                | "circle"
+                | "ellipse"
+                | "line"
+                | "polyline"
+                | "polygon"
+                | "image"
+        );
+        if !is_supported {
+            // This includes elements like `defs` and `linearGradient`, which are only
+            // rendered when they are referenced.
+            return Ok(());
+        }
+
+        let style = self.resolve_style(node, parent_style)?;
+        let mut transform = self.transform(node, "transform")?;
+        let mut children = Vec::new();
+        match name {
+            "g" | "a" | "switch" => {
+                for child in node.children() {
+                    self.parse_node(child, &style, &mut children)?;
+                }
+            }
+            "use" => {
+                let Some(id) = href(node) else {
+                    return Ok(());
+                };
+                let x = self.length(node, "x", Axis::X, 0.0)?;
+                let y = self.length(node, "y", Axis::Y, 0.0)?;
+                transform *= Affine::translate((x, y));
+                self.with_reference(id, |parser, referenced| {
+                    if referenced.has_tag_name("symbol") {
+                        let style = parser.resolve_style(referenced, &style)?;
+                        for child in referenced.children() {
+                            parser.parse_node(child, &style, &mut children)?;
+                        }
+                        Ok(())
+                    } else {
+                        parser.parse_node(referenced, &style, &mut children)
+                    }
+                })?;
+            }
+            "image" => {
+                if let Some(image) = self.image(node)? {
+                    children.push(Node::Image(image));
+                }
+            }
+            _ => {
+                if let Some(path) = self.shape_path(node)? {
+                    children.push(Node::Shape(Shape {
+                        path,
+                        fill: style.fill.clone().map(|paint| {
+                            (paint.multiply_alpha(style.fill_opacity), style.fill_rule)
+                        }),
+                        stroke: style
+                            .stroke
+                            .clone()
+                            .filter(|_| style.stroke_style.width > 0.0)
+                            .map(|paint| {
+                                (
+                                    paint.multiply_alpha(style.stroke_opacity),
+                                    style.stroke_style.clone(),
+                                )
+                            }),
+                        clip_rule: style.clip_rule,
+                    }));
+                }
+            }
+        }
+
+        let opacity = match property(node, "opacity") {
+            None => 1.0,
+            Some(value) => {
+                parse_opacity(value).ok_or_else(|| SvgError::invalid_attribute("opacity", value))?
+            }
+        };
+        let clip_path = match property(node, "clip-path") {
+            None => None,
+            Some(value) => match Self::url_reference("clip-path", value)? {
+                None => None,
+                // A reference to a non-existent clip path means the element isn't rendered.
+                Some(id) => match self.clip_path(id)? {
+                    None => return Ok(()),
+                    clip_path => clip_path,
+                },
+            },
+        };
+        let mask = match property(node, "mask") {
+            None => None,
+            Some(value) => match Self::url_reference("mask", value)? {
+                None => None,
+                Some(id) => match self.mask(id)? {
+                    None => return Ok(()),
+                    mask => mask,
+                },
+            },
+        };
+        let filter = match property(node, "filter") {
+            None => None,
+            Some(value) => match Self::url_reference("filter", value)? {
+                None => None,
+                Some(id) => match self.filter(id)? {
+                    None => return Ok(()),
+                    Some(filter) => filter,
+                },
+            },
+        };
+
+        if transform == Affine::IDENTITY
+            && opacity == 1.0
+            && clip_path.is_none()
+            && mask.is_none()
+            && filter.is_none()
+            && !matches!(name, "g" | "a" | "switch")
+        {
+            nodes.append(&mut children);
+        } else {
+            nodes.push(Node::Group(Group {
+                transform,
+                opacity,
+                clip_path,
+                mask,
+                filter,
+                children,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Parse an `image` element, or return `None` if it is not rendered.
+    ///
+    /// Images which can't be decoded are not rendered.
+    fn image(&mut self, node: XmlNode<'a, 'input>) -> Result<Option<SvgImage>, SvgError> {
+        let Some(href) = node
+            .attribute((XLINK_NS, "href"))
+            .or_else(|| node.attribute("href"))
+        else {
+            return Ok(None);
+        };
+        let pixmap = self
+            .images
+            .entry(href)
+            .or_insert_with(|| decode_image(href).map(Arc::new))
+            .clone();
+        let Some(pixmap) = pixmap else {
+            return Ok(None);
+        };
+        let size = Size::new(f64::from(pixmap.width()), f64::from(pixmap.height()));
+        let x = self.length(node, "x", Axis::X, 0.0)?;
+        let y = self.length(node, "y", Axis::Y, 0.0)?;
+        let width = self.length(node, "width", Axis::X, size.width)?;
+        let height = self.length(node, "height", Axis::Y, size.height)?;
+        if width <= 0.0 || height <= 0.0 {
+            return Ok(None);
+        }
+        let viewport = Rect::new(x, y, x + width, y + height);
+        let transform = Affine::translate((x, y))
+            * AspectRatio::parse(node)?.view_box_transform(size.to_rect(), viewport.size());
+        Ok(Some(SvgImage {
+            pixmap,
+            transform,
+            viewport,
+        }))
+    }
+
+    /// The outline of a basic shape or path, or `None` if it is not rendered.
+    fn shape_path(&self, node: XmlNode<'_, '_>) -> Result<Option<BezPath>, SvgError> {
+        let length = |name: &str, axis: Axis| self.length(node, name, axis, 0.0);
+        let path = match node.tag_name().name() {
+            "path" => match node.attribute("d") {
+                None => return Ok(None),
+                Some(d) => BezPath::from_svg(d)?,
+            },
+            "rect" => {
+                let x = length("x", Axis::X)?;
+                let y = length("y", Axis::Y)?;
+                let width = length("width", Axis::X)?;
+                let height = length("height", Axis::Y)?;
+                if width <= 0.0 || height <= 0.0 {
+                    return Ok(None);
+                }
+                let rx = node
+                    .attribute("rx")
+                    .map(|rx| self.resolve_length("rx", rx, Axis::X))
+                    .transpose()?;
+                let ry = node
+                    .attribute("ry")
+                    .map(|ry| self.resolve_length("ry", ry, Axis::Y))
+                    .transpose()?;
+                // If only one of the radii is given, it is used for both.
+                let (rx, ry) = match (rx, ry) {
+                    (None, None) => (0.0, 0.0),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (Some(rx), Some(ry)) => (rx, ry),
+                };
+                rounded_rect(
+                    Rect::new(x, y, x + width, y + height),
+                    rx.clamp(0.0, width / 2.0),
+                    ry.clamp(0.0, height / 2.0),
+                )
+            }
+            "circle" | "ellipse" => {
+                let center = Point::new(length("cx", Axis::X)?, length("cy", Axis::Y)?);
+                let radii = if node.has_tag_name("circle") {
+                    let r = length("r", Axis::Diagonal)?;
+                    Vec2::new(r, r)
+                } else {
+                    Vec2::new(length("rx", Axis::X)?, length("ry", Axis::Y)?)
+                };
+                if radii.x <= 0.0 || radii.y <= 0.0 {
+                    return Ok(None);
+                }
+                Ellipse::new(center, radii, 0.0).to_path(TOLERANCE)
+            }
+            "line" => {
+                let mut path = BezPath::new();
+                path.move_to((length("x1", Axis::X)?, length("y1", Axis::Y)?));
+                path.line_to((length("x2", Axis::X)?, length("y2", Axis::Y)?));
+                path
+            }
+            "polyline" | "polygon" => {
+                let Some(points) = node.attribute("points") else {
+                    return Ok(None);
+                };
+                let coords = parse_number_list(points)
+                    .ok_or_else(|| SvgError::invalid_attribute("points", points))?;
+                let mut path = BezPath::new();
+                // An odd number of coordinates is an error, in which case the shape is
+                // rendered up to the last complete point.
+                for (i, point) in coords.chunks_exact(2).enumerate() {
+                    if i == 0 {
+                        path.move_to((point[0], point[1]));
+                    } else {
+                        path.line_to((point[0], point[1]));
+                    }
+                }
+                if node.has_tag_name("polygon") && !path.elements().is_empty() {
+                    path.close_path();
+                }
+                path
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(path))
+    }
+}
+
+/// Decode an image embedded as a `data:` URL.
+#[cfg(feature = "png")]
+fn decode_image(href: &str) -> Option<Pixmap> {
+    let data = href.trim().strip_prefix("data:image/png;base64,")?;
+    Pixmap::from_png(std::io::Cursor::new(decode_base64(data)?)).ok()
+}
+
+#[cfg(not(feature = "png"))]
+fn decode_image(_href: &str) -> Option<Pixmap> {
+    None
+}
+
+/// Decode base64 data, ignoring whitespace.
+#[cfg(feature = "png")]
+fn decode_base64(data: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(data.len() / 4 * 3);
+    let (mut buffer, mut bits) = (0_u32, 0);
+    for c in data.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ if c.is_ascii_whitespace() => continue,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+/// Whether `a` and `b` are both gradients, which can inherit attributes from each other.
+fn is_gradient(a: XmlNode<'_, '_>, b: XmlNode<'_, '_>) -> bool {
+    let is_gradient = |node: XmlNode<'_, '_>| {
+        matches!(node.tag_name().name(), "linearGradient" | "radialGradient")
+    };
+    is_gradient(a) && is_gradient(b)
+}
+
+/// A rectangle with elliptical corners.
+fn rounded_rect(rect: Rect, rx: f64, ry: f64) -> BezPath {
+    if rx == 0.0 || ry == 0.0 {
+        return rect.to_path(TOLERANCE);
+    }
+    // The distance of the control points for approximating a quarter ellipse with a cubic Bézier.
+    const KAPPA: f64 = 0.552_284_749_831;
+    let (kx, ky) = (rx * KAPPA, ry * KAPPA);
+    let Rect { x0, y0, x1, y1 } = rect;
+    let mut path = BezPath::new();
+    path.move_to((x0 + rx, y0));
+    path.line_to((x1 - rx, y0));
+    path.curve_to((x1 - rx + kx, y0), (x1, y0 + ry - ky), (x1, y0 + ry));
+    path.line_to((x1, y1 - ry));
+    path.curve_to((x1, y1 - ry + ky), (x1 - rx + kx, y1), (x1 - rx, y1));
+    path.line_to((x0 + rx, y1));
+    path.curve_to((x0 + rx - kx, y1), (x0, y1 - ry + ky), (x0, y1 - ry));
+    path.line_to((x0, y0 + ry));
+    path.curve_to((x0, y0 + ry - ky), (x0 + rx - kx, y0), (x0 + rx, y0));
+    path.close_path();
+    path
+}
+
+/// Collect the outlines of all shapes in `nodes`, together with their clip rule.
+fn collect_outlines(nodes: &[Node], transform: Affine, outlines: &mut Vec<(BezPath, Fill)>) {
+    for node in nodes {
+        match node {
+            Node::Group(group) => {
+                collect_outlines(&group.children, transform * group.transform, outlines);
+            }
+            Node::Shape(shape) => outlines.push((transform * shape.path.clone(), shape.clip_rule)),
+            // Only shapes contribute to clip paths.
+            Node::Image(_) => {}
+        }
+    }
+}
+
+/// A target that SVG nodes can be drawn into.
+///
+/// This is implemented by [`RenderContext`], and by [`PatternRecorder`] for recording the
+/// content of patterns.
+trait Canvas {
+    fn set_transform(&mut self, transform: Affine);
+    fn set_paint(&mut self, paint: impl Into<PaintType>);
+    fn set_paint_transform(&mut self, transform: Affine);
+    fn set_fill_rule(&mut self, fill_rule: Fill);
+    fn set_stroke(&mut self, stroke: Stroke);
+    fn fill_path(&mut self, path: &BezPath);
+    fn fill_rect(&mut self, rect: &Rect);
+    fn stroke_path(&mut self, path: &BezPath);
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
+    );
+    fn pop_layer(&mut self);
+
+    /// Render the contents of a mask, clipped to `rect` and covering the whole canvas.
+    ///
+    /// Returns `None` if the canvas doesn't support masks.
+    fn render_mask(
+        &self,
+        mask: &SvgMask,
+        transform: Affine,
+        rect: Rect,
+        content_transform: Affine,
+    ) -> Option<Mask>;
+}
+
+impl Canvas for RenderContext {
+    fn set_transform(&mut self, transform: Affine) {
+        Self::set_transform(self, transform);
+    }
+
+    fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        Self::set_paint(self, paint);
+    }
+
+    fn set_paint_transform(&mut self, transform: Affine) {
+        Self::set_paint_transform(self, transform);
+    }
+
+    fn set_fill_rule(&mut self, fill_rule: Fill) {
+        Self::set_fill_rule(self, fill_rule);
+    }
+
+    fn set_stroke(&mut self, stroke: Stroke) {
+        Self::set_stroke(self, stroke);
+    }
+
+    fn fill_path(&mut self, path: &BezPath) {
+        Self::fill_path(self, path);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect) {
+        Self::fill_rect(self, rect);
+    }
+
+    fn stroke_path(&mut self, path: &BezPath) {
+        Self::stroke_path(self, path);
+    }
+
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
+    ) {
+        Self::push_layer(self, clip_path, None, opacity, mask, filter);
+    }
+
+    fn pop_layer(&mut self) {
+        Self::pop_layer(self);
+    }
+
+    fn render_mask(
+        &self,
+        mask: &SvgMask,
+        transform: Affine,
+        rect: Rect,
+        content_transform: Affine,
+    ) -> Option<Mask> {
+        let (width, height) = (self.width(), self.height());
+        let mut mask_ctx = Self::new_with(width, height, *self.render_settings());
+        mask_ctx.set_transform(transform);
+        mask_ctx.push_clip_layer(&rect.to_path(TOLERANCE));
+        render_nodes(&mut mask_ctx, &mask.children, transform * content_transform);
+        Self::pop_layer(&mut mask_ctx);
+        mask_ctx.flush();
+        let mut pixmap = Pixmap::new(width, height);
+        mask_ctx.render(&mut pixmap, &mut Resources::new());
+
+        Some(if mask.luminance {
+            Mask::new_luminance(&pixmap)
+        } else {
+            Mask::new_alpha(&pixmap)
+        })
+    }
+}
+
+/// A canvas that records the content of a pattern into a [`PatternScene`].
+///
+/// Masks can't be recorded, so masked content is drawn without its mask.
+#[derive(Debug, Default)]
+struct PatternRecorder {
+    scene: PatternScene,
+    state: RenderState,
+}
+
+impl Canvas for PatternRecorder {
+    fn set_transform(&mut self, transform: Affine) {
+        self.state.transform = transform;
+    }
+
+    fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        self.state.paint = paint.into();
+    }
+
+    fn set_paint_transform(&mut self, transform: Affine) {
+        self.state.paint_transform = transform;
+    }
+
+    fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.state.fill_rule = fill_rule;
+    }
+
+    fn set_stroke(&mut self, stroke: Stroke) {
+        self.state.stroke = stroke;
+    }
+
+    fn fill_path(&mut self, path: &BezPath) {
+        self.scene.fill_path(path.clone(), &self.state);
+    }
+
+    fn fill_rect(&mut self, rect: &Rect) {
+        self.scene.fill_path(rect.to_path(TOLERANCE), &self.state);
+    }
+
+    fn stroke_path(&mut self, path: &BezPath) {
+        self.scene.stroke_path(path.clone(), &self.state);
+    }
+
+    fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        opacity: Option<f32>,
+        _mask: Option<Mask>,
+        filter: Option<Filter>,
+    ) {
+        self.scene.push(PatternCommand::PushLayer {
+            clip_path: clip_path.cloned(),
+            fill_rule: self.state.fill_rule,
+            transform: self.state.transform,
+            blend_mode: BlendMode::default(),
+            opacity: opacity.unwrap_or(1.0),
+            filter,
+        });
+    }
+
+    fn pop_layer(&mut self) {
+        self.scene.push(PatternCommand::PopLayer);
+    }
+
+    fn render_mask(&self, _: &SvgMask, _: Affine, _: Rect, _: Affine) -> Option<Mask> {
+        None
+    }
+}
+
+fn render_nodes(ctx: &mut impl Canvas, nodes: &[Node], transform: Affine) {
+    for node in nodes {
+        match node {
+            Node::Group(group) => render_group(ctx, group, transform),
+            Node::Shape(shape) => render_shape(ctx, shape, transform),
+            Node::Image(image) => render_image(ctx, image, transform),
+        }
+    }
+}
+
+fn render_group(ctx: &mut impl Canvas, group: &Group, transform: Affine) {
+    if group.opacity == 0.0 {
+        return;
+    }
+    let transform = transform * group.transform;
+    let bbox = bounding_box(&group.children);
+
+    let clip = match &group.clip_path {
+        Some(clip_path) => {
+            // Elements clipped with a bounding box relative clip path are not rendered
+            // if their bounding box is empty.
+            let Some(units) = clip_path.units.transform(bbox) else {
+                return;
+            };
+            Some((transform * clip_path.transform * units, clip_path))
+        }
+        None => None,
+    };
+    let mask = match &group.mask {
+        Some(mask) => {
+            // The same applies to bounding box relative masks.
+            let (Some(units), Some(content_transform)) = (
+                mask.units.transform(bbox),
+                mask.content_units.transform(bbox),
+            ) else {
+                return;
+            };
+            let rect = units.transform_rect_bbox(mask.rect);
+            ctx.render_mask(mask, transform, rect, content_transform)
+        }
+        None => None,
+    };
+
+    let opacity = (group.opacity < 1.0).then_some(group.opacity);
+    let has_layer = clip.is_some() || mask.is_some() || opacity.is_some();
+    if has_layer {
+        let clip_path = clip.map(|(clip_transform, clip_path)| {
+            ctx.set_transform(clip_transform);
+            ctx.set_fill_rule(clip_path.fill_rule);
+            &clip_path.path
+        });
+        ctx.push_layer(clip_path, opacity, mask, None);
+    }
+    // The filter is applied before clipping, masking and group opacity.
+    let filter = group
+        .filter
+        .as_ref()
+        .and_then(|filter| filter.resolve(bbox));
+    let has_filter = filter.is_some();
+    if has_filter {
+        ctx.set_transform(transform);
+        ctx.push_layer(None, None, None, filter);
+    }
+    render_nodes(ctx, &group.children, transform);
+    if has_filter {
+        ctx.pop_layer();
+    }
+    if has_layer {
+        ctx.pop_layer();
+    }
+}
+
+fn render_shape(ctx: &mut impl Canvas, shape: &Shape, transform: Affine) {
+    ctx.set_transform(transform);
+    let bbox = shape.bounding_box();
+    if let Some((paint, fill_rule)) = &shape.fill
+        && set_paint(ctx, paint, bbox)
+    {
+        ctx.set_fill_rule(*fill_rule);
+        ctx.fill_path(&shape.path);
+    }
+    if let Some((paint, stroke)) = &shape.stroke
+        && set_paint(ctx, paint, bbox)
+    {
+        ctx.set_stroke(stroke.clone());
+        ctx.stroke_path(&shape.path);
+    }
+}
+
+fn render_image(ctx: &mut impl Canvas, image: &SvgImage, transform: Affine) {
+    ctx.set_transform(transform);
+    ctx.set_paint_transform(image.transform);
+    ctx.set_paint(Image {
+        image: ImageSource::Pixmap(Arc::clone(&image.pixmap)),
+        sampler: ImageSampler::default(),
+    });
+    ctx.fill_rect(&image.bounds());
+}
+
+/// Set the paint of `ctx` for drawing a shape with the given bounding box.
+///
+/// Returns `false` if nothing should be drawn.
+fn set_paint(ctx: &mut impl Canvas, paint: &SvgPaint, bbox: Option<Rect>) -> bool {
+    match paint {
+        SvgPaint::Color(color) => {
+            ctx.set_paint_transform(Affine::IDENTITY);
+            ctx.set_paint(*color);
+        }
+        SvgPaint::Gradient { gradient, opacity } => {
+            let Some(units) = gradient.units.transform(bbox) else {
+                return false;
+            };
+            ctx.set_paint_transform(units * gradient.transform);
+            ctx.set_paint(gradient.gradient.clone().multiply_alpha(*opacity));
+        }
+        SvgPaint::Pattern { pattern, opacity } => {
+            let Some((pattern, paint_transform)) = record_pattern(pattern, *opacity, bbox) else {
+                return false;
+            };
+            ctx.set_paint_transform(paint_transform);
+            ctx.set_paint(pattern);
+        }
+    }
+    true
+}
+
+/// Record the content of a pattern, so that it can be rasterized at the resolution it is
+/// displayed at.
+///
+/// Returns the pattern paint, together with the paint transform to apply.
+fn record_pattern(
+    pattern: &SvgPattern,
+    opacity: f32,
+    bbox: Option<Rect>,
+) -> Option<(Pattern, Affine)> {
+    let tile = pattern
+        .units
+        .transform(bbox)?
+        .transform_rect_bbox(pattern.tile);
+    let content_transform = match pattern.view_box {
+        Some((view_box, aspect_ratio)) => aspect_ratio.view_box_transform(view_box, tile.size()),
+        None => {
+            let units = pattern.content_units.transform(bbox)?;
+            // Only the scale applies, as the content is positioned relative to the tile.
+            Affine::new([
+                units.as_coeffs()[0],
+                0.0,
+                0.0,
+                units.as_coeffs()[3],
+                0.0,
+                0.0,
+            ])
+        }
+    };
+
+    let mut recorder = PatternRecorder::default();
+    // Pattern paints don't support an alpha multiplier, so apply the opacity to the content.
+    let has_layer = opacity < 1.0;
+    if has_layer {
+        recorder.push_layer(None, Some(opacity), None, None);
+    }
+    render_nodes(&mut recorder, &pattern.children, content_transform);
+    if has_layer {
+        recorder.pop_layer();
+    }
+
+    let paint = Pattern::new(
+        recorder.scene,
+        Rect::from_origin_size(Point::ZERO, tile.size()),
+    );
+    let paint_transform = pattern.transform * Affine::translate(tile.origin().to_vec2());
+    Some((paint, paint_transform))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Svg, SvgError};
+    use crate::color::PremulRgba8;
+    use crate::{Pixmap, RenderContext, Resources};
+
+    fn render(svg: &str) -> Pixmap {
+        let svg = Svg::parse(svg).unwrap();
+        let size = svg.size();
+        let (width, height) = (size.width as u16, size.height as u16);
+        let mut ctx = RenderContext::new(width, height);
+        svg.render(&mut ctx);
+        ctx.flush();
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.render(&mut pixmap, &mut Resources::new());
+        pixmap
+    }
+
+    fn rgba(pixel: PremulRgba8) -> [u8; 4] {
+        [pixel.r, pixel.g, pixel.b, pixel.a]
+    }
+
+    #[test]
+    fn basic_shapes() {
+        let pixmap = render(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="10">
+                <rect width="10" height="10" fill="red"/>
+                <circle cx="15" cy="5" r="4" style="fill: blue"/>
+            </svg>"#,
+        );
+        assert_eq!(rgba(pixmap.sample(5, 5)), [255, 0, 0, 255]);
+        assert_eq!(rgba(pixmap.sample(15, 5)), [0, 0, 255, 255]);
+        assert_eq!(rgba(pixmap.sample(19, 0)), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn view_box_and_use() {
+        let pixmap = render(
+            r##"<svg xmlns="http://www.w3.org/2000/svg"
+                     xmlns:xlink="http://www.w3.org/1999/xlink"
+                     width="20" height="20" viewBox="0 0 10 10">
+                <defs><rect id="r" width="5" height="5" fill="lime"/></defs>
+                <use xlink:href="#r" x="5" y="5"/>
+            </svg>"##,
+        );
+        assert_eq!(rgba(pixmap.sample(5, 5)), [0, 0, 0, 0]);
+        assert_eq!(rgba(pixmap.sample(15, 15)), [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn gradient_clip_and_mask() {
+        let pixmap = render(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+                <linearGradient id="g">
+                    <stop offset="0" stop-color="black"/>
+                    <stop offset="1" stop-color="white"/>
+                </linearGradient>
+                <clipPath id="c"><rect width="10" height="20"/></clipPath>
+                <mask id="m" maskUnits="userSpaceOnUse">
+                    <rect width="20" height="10" fill="white"/>
+                </mask>
+                <rect width="20" height="20" fill="url(#g)" clip-path="url(#c)" mask="url(#m)"/>
+            </svg>"##,
+        );
+        let [r, _, _, a] = rgba(pixmap.sample(1, 1));
+        assert!(r < 40 && a == 255, "{r} {a}");
+        let [r, _, _, _] = rgba(pixmap.sample(9, 1));
+        assert!(r > 100, "{r}");
+        // Clipped.
+        assert_eq!(rgba(pixmap.sample(15, 5))[3], 0);
+        // Masked.
+        assert_eq!(rgba(pixmap.sample(5, 15))[3], 0);
+    }
+
+    #[test]
+    fn pattern() {
+        let pixmap = render(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="8">
+                <pattern id="p" width="4" height="4" patternUnits="userSpaceOnUse">
+                    <rect width="2" height="4" fill="red"/>
+                </pattern>
+                <rect width="8" height="8" fill="url(#p)"/>
+            </svg>"##,
+        );
+        assert_eq!(rgba(pixmap.sample(1, 1)), [255, 0, 0, 255]);
+        assert_eq!(rgba(pixmap.sample(3, 1))[3], 0);
+        assert_eq!(rgba(pixmap.sample(5, 6)), [255, 0, 0, 255]);
+        assert_eq!(rgba(pixmap.sample(7, 6))[3], 0);
+    }
+
+    #[test]
+    fn pattern_with_opacity() {
+        let pixmap = render(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="8" height="8">
+                <pattern id="p" width="4" height="4" patternUnits="userSpaceOnUse">
+                    <rect width="2" height="4" fill="red"/>
+                </pattern>
+                <rect width="8" height="8" fill="url(#p)" fill-opacity="0.5"/>
+            </svg>"##,
+        );
+        let [r, g, b, a] = rgba(pixmap.sample(1, 1));
+        assert!(
+            r.abs_diff(128) <= 1 && r == a && g == 0 && b == 0,
+            "{r} {g} {b} {a}"
+        );
+        assert_eq!(rgba(pixmap.sample(3, 1))[3], 0);
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn embedded_image() {
+        // A 2x1 image with a red and a blue pixel.
+        const PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAIAAAABCAYAAAD0In+KAAAADklEQVR4nGP4z8AAQv8BD/kD/YURmXYAAAAASUVORK5CYII=";
+        let pixmap = render(&alloc::format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="30">
+                <defs>
+                    <image id="i" width="20" height="10" preserveAspectRatio="none"
+                           href="data:image/png;base64,{PNG}"/>
+                </defs>
+                <use href="#i"/>
+                <image y="10" width="20" height="20" href="data:image/png;base64,{PNG}"/>
+            </svg>"##
+        ));
+        assert_eq!(rgba(pixmap.sample(2, 5)), [255, 0, 0, 255]);
+        assert_eq!(rgba(pixmap.sample(17, 5)), [0, 0, 255, 255]);
+        // The second image keeps its aspect ratio, and is centered vertically.
+        assert_eq!(rgba(pixmap.sample(10, 12))[3], 0);
+        assert_eq!(rgba(pixmap.sample(2, 20)), [255, 0, 0, 255]);
+        assert_eq!(rgba(pixmap.sample(10, 28))[3], 0);
+    }
+
+    #[test]
+    fn filter() {
+        let pixmap = render(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+                <filter id="f"><feOffset dx="10" dy="10"/></filter>
+                <rect width="10" height="10" fill="blue" filter="url(#f)"/>
+            </svg>"##,
+        );
+        assert_eq!(rgba(pixmap.sample(5, 5))[3], 0);
+        assert_eq!(rgba(pixmap.sample(15, 15)), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn uses_filters() {
+        let in_pattern = Svg::parse(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20">
+                <filter id="f"><feOffset dx="1"/></filter>
+                <pattern id="p" width="4" height="4" patternUnits="userSpaceOnUse">
+                    <rect width="2" height="4" filter="url(#f)"/>
+                </pattern>
+                <rect width="20" height="20" fill="url(#p)"/>
+            </svg>"##,
+        )
+        .unwrap();
+        assert!(in_pattern.uses_filters());
+        let without = Svg::parse(
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="2" height="2"/></svg>"#,
+        )
+        .unwrap();
+        assert!(!without.uses_filters());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Svg::parse(r#"<svg viewBox="0 0 10"/>"#),
+            Err(SvgError::InvalidAttribute { .. })
+        ));
+        assert!(matches!(
+            Svg::parse(r#"<svg><rect width="10" height="10" fill="bogus"/></svg>"#),
+            Err(SvgError::InvalidAttribute { .. })
+        ));
+        assert!(matches!(
+            Svg::parse(r#"<svg><path d="M 0 0 L"/></svg>"#),
+            Err(SvgError::InvalidPath(_))
+        ));
+        assert!(matches!(
+            Svg::parse(r##"<svg><g id="a"><use href="#a"/></g></svg>"##),
+            Err(SvgError::CyclicReference(_))
+        ));
+        assert!(matches!(Svg::parse("<html/>"), Err(SvgError::NotAnSvg)));
+    }
+}
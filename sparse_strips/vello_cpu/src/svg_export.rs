@@ -0,0 +1,1037 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Export of drawing commands as SVG documents.
+//!
+//! While a capture is active (see [`RenderContext::start_svg_capture`]), each drawing
+//! command is converted into the equivalent SVG element in addition to being rendered.
+//! Layers and clip paths become nested groups, and all paints, clip paths, masks and
+//! filters are emitted as definitions that are referenced by these elements.
+//!
+//! Content that SVG has no equivalent for (sweep gradients, tinted images, images
+//! with mixed or reflecting extend modes and inverted blurred rectangles) is
+//! rasterized with Vello CPU and embedded as a PNG image instead.
+
+use crate::{RenderContext, RenderSettings, Resources};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Display, Formatter};
+use svg::Document;
+use svg::node::Node;
+use svg::node::element::Element;
+use vello_common::color::{AlphaColor, ColorSpaceTag, DynamicColor, Srgb};
+use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
+use vello_common::kurbo::{Affine, BezPath, Cap, Join, Rect, Shape as _, Stroke};
+use vello_common::mask::Mask;
+use vello_common::paint::{ImageSource, PaintType};
+use vello_common::peniko::{
+    BlendMode, Extend, Fill, Gradient, GradientKind, ImageQuality, InterpolationAlphaSpace, Mix,
+};
+use vello_common::pixmap::Pixmap;
+use vello_common::render_state::RenderState;
+use vello_common::util::extract_scales;
+
+/// The number of segments each pair of stops is split into when the gradient is not
+/// interpolated like SVG gradients are.
+const STOP_SUBDIVISIONS: usize = 16;
+
+/// A shape that is drawn with the current paint.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Shape<'a> {
+    /// A filled path.
+    Fill(&'a BezPath),
+    /// A stroked path.
+    Stroke(&'a BezPath),
+    /// A blurred rounded rectangle, see [`RenderContext::fill_blurred_rounded_rect`].
+    BlurredRoundedRect {
+        rect: Rect,
+        radius: f32,
+        std_dev: f32,
+        invert: bool,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupKind {
+    Root,
+    Layer,
+    ClipPath,
+}
+
+/// The way a paint is represented in SVG.
+enum SvgPaint {
+    /// A value for the `fill` or `stroke` attribute.
+    Value { value: String, opacity: f32 },
+    /// An image that is clamped at its edges, which is drawn by clipping the image
+    /// to the shape.
+    ClampedImage {
+        image: String,
+        transform: Affine,
+        opacity: f32,
+        quality: ImageQuality,
+    },
+    /// The paint needs to be rasterized.
+    Raster,
+    /// The paint cannot be exported at all.
+    Unsupported,
+}
+
+/// Drawing commands captured as SVG elements.
+#[derive(Debug)]
+pub(crate) struct SvgCapture {
+    width: u16,
+    height: u16,
+    settings: RenderSettings,
+    defs: Element,
+    /// The stack of open groups, the first one being the root group.
+    groups: Vec<(Element, GroupKind)>,
+    /// Image definitions, keyed by the address of the pixmap.
+    images: Vec<(Arc<Pixmap>, String)>,
+    /// Mask definitions. The masks are retained so that the addresses of their data
+    /// cannot be reused.
+    masks: Vec<(Mask, String)>,
+    next_id: u32,
+}
+
+impl SvgCapture {
+    pub(crate) fn new(width: u16, height: u16, settings: RenderSettings) -> Self {
+        Self {
+            width,
+            height,
+            settings: RenderSettings {
+                num_threads: 0,
+                ..settings
+            },
+            defs: Element::new("defs"),
+            groups: vec![(Element::new("g"), GroupKind::Root)],
+            images: Vec::new(),
+            masks: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Draw a shape with the paint of the given state.
+    pub(crate) fn draw(&mut self, shape: Shape<'_>, state: &RenderState, mask: Option<&Mask>) {
+        let node = match shape {
+            Shape::Fill(path) | Shape::Stroke(path) => match self.paint(shape, state) {
+                SvgPaint::Value { value, opacity } => {
+                    let mut element = path_element(path, state.transform);
+                    if let Shape::Stroke(_) = shape {
+                        element.assign("fill", "none");
+                        element.assign("stroke", value);
+                        if opacity < 1.0 {
+                            element.assign("stroke-opacity", Num::from(opacity).to_string());
+                        }
+                        set_stroke_attributes(&mut element, &state.stroke);
+                    } else {
+                        element.assign("fill", value);
+                        if opacity < 1.0 {
+                            element.assign("fill-opacity", Num::from(opacity).to_string());
+                        }
+                        if state.fill_rule == Fill::EvenOdd {
+                            element.assign("fill-rule", "evenodd");
+                        }
+                    }
+                    Some(element)
+                }
+                SvgPaint::ClampedImage {
+                    image,
+                    transform,
+                    opacity,
+                    quality,
+                } => {
+                    let (outline, fill_rule) = match shape {
+                        Shape::Stroke(path) => (stroke_outline(path, &state.stroke), Fill::NonZero),
+                        _ => (path.clone(), state.fill_rule),
+                    };
+                    let clip = self.clip_path(&outline, fill_rule, state.transform);
+                    let mut element = use_element(&image, state.transform * transform);
+                    if opacity < 1.0 {
+                        element.assign("opacity", Num::from(opacity).to_string());
+                    }
+                    if quality == ImageQuality::Low {
+                        element.assign("style", "image-rendering:pixelated");
+                    }
+                    let mut group = Element::new("g");
+                    group.assign("clip-path", clip);
+                    group.append(element);
+                    Some(group)
+                }
+                SvgPaint::Raster => self.raster(shape, state),
+                SvgPaint::Unsupported => None,
+            },
+            Shape::BlurredRoundedRect {
+                rect,
+                radius,
+                std_dev,
+                invert: false,
+            } => {
+                let color = match state.paint {
+                    PaintType::Solid(color) => color,
+                    _ => AlphaColor::BLACK,
+                };
+                let filter = self.blur_filter(rect, std_dev);
+                let mut element = Element::new("rect");
+                element.assign("x", Num(rect.x0).to_string());
+                element.assign("y", Num(rect.y0).to_string());
+                element.assign("width", Num(rect.width()).to_string());
+                element.assign("height", Num(rect.height()).to_string());
+                element.assign("rx", Num::from(radius).to_string());
+                set_transform(&mut element, state.transform);
+                element.assign("fill", hex(color));
+                if color.components[3] < 1.0 {
+                    element.assign("fill-opacity", Num::from(color.components[3]).to_string());
+                }
+                element.assign("filter", filter);
+                Some(element)
+            }
+            Shape::BlurredRoundedRect { .. } => self.raster(shape, state),
+        };
+
+        let Some(mut node) = node else {
+            return;
+        };
+        if let Some(mask) = mask {
+            let mask = self.mask(mask);
+            let mut group = Element::new("g");
+            group.assign("mask", mask);
+            group.append(node);
+            node = group;
+        }
+        if let Some(mode) = mix_blend_mode(state.blend_mode) {
+            node.assign("style", format!("mix-blend-mode:{mode}"));
+        }
+        self.append(node);
+    }
+
+    /// Open a group for a layer.
+    #[expect(
+        clippy::too_many_arguments,
+        reason = "mirrors `RenderContext::push_layer`"
+    )]
+    pub(crate) fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        fill_rule: Fill,
+        transform: Affine,
+        blend_mode: BlendMode,
+        opacity: f32,
+        mask: Option<&Mask>,
+        filter: Option<&Filter>,
+    ) {
+        let mut group = Element::new("g");
+        if let Some(path) = clip_path {
+            let clip = self.clip_path(path, fill_rule, transform);
+            group.assign("clip-path", clip);
+        }
+        if let Some(mask) = mask {
+            let mask = self.mask(mask);
+            group.assign("mask", mask);
+        }
+        if opacity < 1.0 {
+            group.assign("opacity", Num::from(opacity).to_string());
+        }
+        if let Some(filter) = filter.and_then(|filter| self.filter(filter, transform)) {
+            group.assign("filter", filter);
+        }
+        // Layers are always isolated in Vello, so their content doesn't blend with the
+        // backdrop directly.
+        let style = match mix_blend_mode(blend_mode) {
+            Some(mode) => format!("mix-blend-mode:{mode};isolation:isolate"),
+            None => "isolation:isolate".to_string(),
+        };
+        group.assign("style", style);
+        self.groups.push((group, GroupKind::Layer));
+    }
+
+    /// Close the group of the last layer, including any clip paths that were pushed
+    /// inside of it and not popped yet.
+    pub(crate) fn pop_layer(&mut self) {
+        while self.top_kind() == GroupKind::ClipPath {
+            self.close_group();
+        }
+        if self.top_kind() == GroupKind::Layer {
+            self.close_group();
+        }
+    }
+
+    /// Open a group that is clipped by the given path.
+    pub(crate) fn push_clip_path(&mut self, path: &BezPath, fill_rule: Fill, transform: Affine) {
+        let clip = self.clip_path(path, fill_rule, transform);
+        let mut group = Element::new("g");
+        group.assign("clip-path", clip);
+        self.groups.push((group, GroupKind::ClipPath));
+    }
+
+    /// Close the group of the last clip path.
+    pub(crate) fn pop_clip_path(&mut self) {
+        if self.top_kind() == GroupKind::ClipPath {
+            self.close_group();
+        }
+    }
+
+    /// Close all open groups and serialize the document.
+    pub(crate) fn finish(mut self) -> String {
+        while self.groups.len() > 1 {
+            self.close_group();
+        }
+        let (root, _) = self.groups.pop().unwrap();
+
+        let mut document = Document::new()
+            .set("width", self.width)
+            .set("height", self.height)
+            .set("viewBox", (0, 0, self.width, self.height));
+        if !self.defs.get_children().is_empty() {
+            document = document.add(self.defs);
+        }
+        document.add(root).to_string()
+    }
+
+    fn top_kind(&self) -> GroupKind {
+        self.groups
+            .last()
+            .map_or(GroupKind::Root, |(_, kind)| *kind)
+    }
+
+    fn close_group(&mut self) {
+        let (group, _) = self.groups.pop().unwrap();
+        self.append(group);
+    }
+
+    fn append(&mut self, node: Element) {
+        self.groups
+            .last_mut()
+            .expect("the root group is never closed")
+            .0
+            .append(node);
+    }
+
+    /// Allocate a new ID for a definition, and return it.
+    fn id(&mut self, prefix: &str) -> String {
+        let id = format!("{prefix}{}", self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn paint(&mut self, shape: Shape<'_>, state: &RenderState) -> SvgPaint {
+        match &state.paint {
+            PaintType::Solid(color) => SvgPaint::Value {
+                value: hex(*color),
+                opacity: color.components[3],
+            },
+            PaintType::Gradient(gradient) => match self.gradient(gradient, state.paint_transform) {
+                Some(id) => SvgPaint::Value {
+                    value: format!("url(#{id})"),
+                    opacity: 1.0,
+                },
+                None => SvgPaint::Raster,
+            },
+            // SVG has no mesh gradients.
+            PaintType::MeshGradient(_) => SvgPaint::Raster,
+            // TODO: Export patterns as `pattern` elements instead of rasterizing them.
+            PaintType::Pattern(_) => SvgPaint::Raster,
+            // Custom paints can't be resolved without the resources.
+            PaintType::Custom(_) => SvgPaint::Unsupported,
+            // External textures are only bound at render time.
+            PaintType::ExternalTexture(_) => SvgPaint::Unsupported,
+            PaintType::Image(image) => {
+                let pixmap = match &image.image {
+                    ImageSource::Pixmap(pixmap) => pixmap,
+                    // Registered images can't be resolved without the resources.
+                    ImageSource::OpaqueId { .. } => return SvgPaint::Unsupported,
+                    ImageSource::Raw(_) => return SvgPaint::Raster,
+                };
+                if state.tint.is_some() {
+                    return SvgPaint::Raster;
+                }
+
+                let sampler = &image.sampler;
+                match (sampler.x_extend, sampler.y_extend) {
+                    (Extend::Repeat, Extend::Repeat) => {
+                        let image = self.image(pixmap);
+                        let id = self.image_pattern(
+                            &image,
+                            pixmap,
+                            state.paint_transform,
+                            sampler.quality,
+                        );
+                        SvgPaint::Value {
+                            value: format!("url(#{id})"),
+                            opacity: sampler.alpha,
+                        }
+                    }
+                    (Extend::Pad, Extend::Pad) => {
+                        // An SVG image is not extended beyond its bounds, so it can only
+                        // be used directly if the shape doesn't reach outside of the image.
+                        let Some(inverse) = invert(state.paint_transform) else {
+                            return SvgPaint::Unsupported;
+                        };
+                        let bounds = match shape {
+                            Shape::Stroke(path) => stroke_outline(path, &state.stroke),
+                            _ => shape_path(shape),
+                        }
+                        .bounding_box();
+                        let image_rect =
+                            Rect::new(0.0, 0.0, pixmap.width().into(), pixmap.height().into());
+                        if image_rect.contains_rect(inverse.transform_rect_bbox(bounds)) {
+                            SvgPaint::ClampedImage {
+                                image: self.image(pixmap),
+                                transform: state.paint_transform,
+                                opacity: sampler.alpha,
+                                quality: sampler.quality,
+                            }
+                        } else {
+                            SvgPaint::Raster
+                        }
+                    }
+                    _ => SvgPaint::Raster,
+                }
+            }
+            // Other paints have no SVG equivalent.
+            _ => SvgPaint::Raster,
+        }
+    }
+
+    /// Define a gradient and return its ID, or `None` if it has no SVG equivalent.
+    fn gradient(&mut self, gradient: &Gradient, transform: Affine) -> Option<String> {
+        let mut element = match gradient.kind {
+            GradientKind::Linear(pos) => {
+                let mut element = Element::new("linearGradient");
+                element.assign("x1", Num(pos.start.x).to_string());
+                element.assign("y1", Num(pos.start.y).to_string());
+                element.assign("x2", Num(pos.end.x).to_string());
+                element.assign("y2", Num(pos.end.y).to_string());
+                element
+            }
+            GradientKind::Radial(pos) => {
+                let mut element = Element::new("radialGradient");
+                element.assign("fx", Num(pos.start_center.x).to_string());
+                element.assign("fy", Num(pos.start_center.y).to_string());
+                element.assign("fr", Num::from(pos.start_radius).to_string());
+                element.assign("cx", Num(pos.end_center.x).to_string());
+                element.assign("cy", Num(pos.end_center.y).to_string());
+                element.assign("r", Num::from(pos.end_radius).to_string());
+                element
+            }
+            GradientKind::Sweep(_) => return None,
+        };
+
+        let id = self.id("gradient");
+        element.assign("id", id.clone());
+        element.assign("gradientUnits", "userSpaceOnUse");
+        if transform != Affine::IDENTITY {
+            element.assign(
+                "gradientTransform",
+                format!("matrix({})", Matrix(transform)),
+            );
+        }
+        match gradient.extend {
+            Extend::Pad => {}
+            Extend::Repeat => element.assign("spreadMethod", "repeat"),
+            Extend::Reflect => element.assign("spreadMethod", "reflect"),
+        }
+        for (offset, color) in sample_stops(gradient) {
+            let mut stop = Element::new("stop");
+            stop.assign("offset", Num::from(offset).to_string());
+            stop.assign("stop-color", hex(color));
+            if color.components[3] < 1.0 {
+                stop.assign("stop-opacity", Num::from(color.components[3]).to_string());
+            }
+            element.append(stop);
+        }
+        self.defs.append(element);
+
+        Some(id)
+    }
+
+    /// Define an image and return its ID.
+    fn image(&mut self, pixmap: &Arc<Pixmap>) -> String {
+        if let Some((_, id)) = self.images.iter().find(|(p, _)| Arc::ptr_eq(p, pixmap)) {
+            return id.clone();
+        }
+
+        let id = self.id("image");
+        let mut element = image_element(pixmap);
+        element.assign("id", id.clone());
+        self.defs.append(element);
+        self.images.push((Arc::clone(pixmap), id.clone()));
+        id
+    }
+
+    /// Define a pattern that repeats the given image and return its ID.
+    fn image_pattern(
+        &mut self,
+        image: &str,
+        pixmap: &Pixmap,
+        transform: Affine,
+        quality: ImageQuality,
+    ) -> String {
+        let id = self.id("pattern");
+        let mut pattern = Element::new("pattern");
+        pattern.assign("id", id.clone());
+        pattern.assign("patternUnits", "userSpaceOnUse");
+        pattern.assign("width", pixmap.width());
+        pattern.assign("height", pixmap.height());
+        if transform != Affine::IDENTITY {
+            pattern.assign("patternTransform", format!("matrix({})", Matrix(transform)));
+        }
+        if quality == ImageQuality::Low {
+            pattern.assign("style", "image-rendering:pixelated");
+        }
+        pattern.append(use_element(image, Affine::IDENTITY));
+        self.defs.append(pattern);
+        id
+    }
+
+    /// Define a clip path and return a reference to it.
+    fn clip_path(&mut self, path: &BezPath, fill_rule: Fill, transform: Affine) -> String {
+        let id = self.id("clip");
+        let mut element = path_element(path, transform);
+        if fill_rule == Fill::EvenOdd {
+            element.assign("clip-rule", "evenodd");
+        }
+        let mut clip = Element::new("clipPath");
+        clip.assign("id", id.clone());
+        clip.assign("clipPathUnits", "userSpaceOnUse");
+        clip.append(element);
+        self.defs.append(clip);
+        format!("url(#{id})")
+    }
+
+    /// Define a mask that covers the whole document and return a reference to it.
+    fn mask(&mut self, mask: &Mask) -> String {
+        if let Some((_, id)) = self
+            .masks
+            .iter()
+            .find(|(m, _)| m.data().as_ptr() == mask.data().as_ptr())
+        {
+            return format!("url(#{id})");
+        }
+
+        let mut pixmap = Pixmap::new(mask.width(), mask.height());
+        for (pixel, value) in pixmap.data_mut().iter_mut().zip(mask.data()) {
+            pixel.r = *value;
+            pixel.g = *value;
+            pixel.b = *value;
+            pixel.a = *value;
+        }
+
+        let id = self.id("mask");
+        let mut element = Element::new("mask");
+        element.assign("id", id.clone());
+        element.assign("maskUnits", "userSpaceOnUse");
+        element.assign("x", 0);
+        element.assign("y", 0);
+        element.assign("width", mask.width());
+        element.assign("height", mask.height());
+        element.assign("mask-type", "alpha");
+        element.append(image_element(&pixmap));
+        self.defs.append(element);
+        self.masks.push((mask.clone(), id.clone()));
+        format!("url(#{id})")
+    }
+
+    /// Define a filter for a layer with the given transform and return a reference to
+    /// it, or `None` if the filter isn't supported.
+    fn filter(&mut self, filter: &Filter, transform: Affine) -> Option<String> {
+        // Like in Vello, only filters consisting of a single primitive are supported,
+        // and their parameters are scaled by the transform of the layer.
+        let [primitive] = filter.graph.primitives.as_slice() else {
+            return None;
+        };
+        let (scale_x, scale_y) = extract_scales(&transform);
+        let scale = (scale_x + scale_y) / 2.0;
+        let [a, b, c, d, _, _] = transform.as_coeffs();
+        let offset = |dx: f32, dy: f32| {
+            let (dx, dy) = (f64::from(dx), f64::from(dy));
+            (
+                Num(a * dx + c * dy).to_string(),
+                Num(b * dx + d * dy).to_string(),
+            )
+        };
+
+        let element = match primitive {
+            FilterPrimitive::Flood { color } => {
+                let mut element = Element::new("feFlood");
+                element.assign("flood-color", hex(*color));
+                element.assign("flood-opacity", Num::from(color.components[3]).to_string());
+                element
+            }
+            FilterPrimitive::GaussianBlur {
+                std_deviation,
+                edge_mode,
+            } => {
+                let mut element = Element::new("feGaussianBlur");
+                element.assign("stdDeviation", Num::from(std_deviation * scale).to_string());
+                let edge_mode = match edge_mode {
+                    EdgeMode::Duplicate => "duplicate",
+                    EdgeMode::Wrap => "wrap",
+                    EdgeMode::Mirror => "mirror",
+                    EdgeMode::None => "none",
+                };
+                element.assign("edgeMode", edge_mode);
+                element
+            }
+            FilterPrimitive::DropShadow {
+                dx,
+                dy,
+                std_deviation,
+                color,
+                ..
+            } => {
+                let (dx, dy) = offset(*dx, *dy);
+                let mut element = Element::new("feDropShadow");
+                element.assign("dx", dx);
+                element.assign("dy", dy);
+                element.assign("stdDeviation", Num::from(std_deviation * scale).to_string());
+                element.assign("flood-color", hex(*color));
+                element.assign("flood-opacity", Num::from(color.components[3]).to_string());
+                element
+            }
+            FilterPrimitive::Offset { dx, dy } => {
+                let (dx, dy) = offset(*dx, *dy);
+                let mut element = Element::new("feOffset");
+                element.assign("dx", dx);
+                element.assign("dy", dy);
+                element
+            }
+            _ => return None,
+        };
+
+        // The filter region needs to include the content that is pulled in from
+        // outside of the visible area, as well as the area the filter spreads into.
+        let expansion = filter
+            .filter_expansion(&transform)
+            .union(filter.source_expansion(&transform));
+        let id = self.id("filter");
+        let mut filter = Element::new("filter");
+        filter.assign("id", id.clone());
+        filter.assign("filterUnits", "userSpaceOnUse");
+        filter.assign("primitiveUnits", "userSpaceOnUse");
+        filter.assign("x", Num(expansion.x0).to_string());
+        filter.assign("y", Num(expansion.y0).to_string());
+        filter.assign(
+            "width",
+            Num(f64::from(self.width) + expansion.width()).to_string(),
+        );
+        filter.assign(
+            "height",
+            Num(f64::from(self.height) + expansion.height()).to_string(),
+        );
+        filter.assign("color-interpolation-filters", "sRGB");
+        filter.append(element);
+        self.defs.append(filter);
+        Some(format!("url(#{id})"))
+    }
+
+    /// Define the blur filter of a blurred rounded rectangle and return a reference to it.
+    fn blur_filter(&mut self, rect: Rect, std_dev: f32) -> String {
+        // Same cut-off as in `RenderContext::fill_blurred_rounded_rect`.
+        let region = rect.inflate(f64::from(2.5 * std_dev), f64::from(2.5 * std_dev));
+        let id = self.id("filter");
+        let mut blur = Element::new("feGaussianBlur");
+        blur.assign("stdDeviation", Num::from(std_dev).to_string());
+        let mut filter = Element::new("filter");
+        filter.assign("id", id.clone());
+        filter.assign("filterUnits", "userSpaceOnUse");
+        filter.assign("primitiveUnits", "userSpaceOnUse");
+        filter.assign("x", Num(region.x0).to_string());
+        filter.assign("y", Num(region.y0).to_string());
+        filter.assign("width", Num(region.width()).to_string());
+        filter.assign("height", Num(region.height()).to_string());
+        filter.assign("color-interpolation-filters", "sRGB");
+        filter.append(blur);
+        self.defs.append(filter);
+        format!("url(#{id})")
+    }
+
+    /// Rasterize a shape that can't be represented natively, and return an image element
+    /// that covers the affected area of the document.
+    fn raster(&mut self, shape: Shape<'_>, state: &RenderState) -> Option<Element> {
+        let bounds = match shape {
+            Shape::Fill(path) => state.transform.transform_rect_bbox(path.bounding_box()),
+            Shape::Stroke(path) => state
+                .transform
+                .transform_rect_bbox(stroke_outline(path, &state.stroke).bounding_box()),
+            Shape::BlurredRoundedRect { rect, std_dev, .. } => {
+                let kernel_size = f64::from(2.5 * std_dev);
+                state
+                    .transform
+                    .transform_rect_bbox(rect.abs().inflate(kernel_size, kernel_size))
+            }
+        };
+        let page = Rect::new(0.0, 0.0, self.width.into(), self.height.into());
+        let bounds = bounds.intersect(page).expand();
+        if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+            return None;
+        }
+
+        let width = bounds.width() as u16;
+        let height = bounds.height() as u16;
+        let mut ctx = RenderContext::new_with(width, height, self.settings);
+        ctx.set_paint(state.paint.clone());
+        ctx.set_paint_transform(state.paint_transform);
+        ctx.set_tint(state.tint);
+        ctx.set_stroke(state.stroke.clone());
+        ctx.set_fill_rule(state.fill_rule);
+        ctx.set_transform(Affine::translate((-bounds.x0, -bounds.y0)) * state.transform);
+        match shape {
+            Shape::Fill(path) => ctx.fill_path(path),
+            Shape::Stroke(path) => ctx.stroke_path(path),
+            Shape::BlurredRoundedRect {
+                rect,
+                radius,
+                std_dev,
+                invert,
+            } => ctx.fill_blurred_rounded_rect(&rect, radius, std_dev, invert),
+        }
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.render(&mut pixmap, &mut Resources::new());
+
+        let mut element = image_element(&pixmap);
+        element.assign("x", Num(bounds.x0).to_string());
+        element.assign("y", Num(bounds.y0).to_string());
+        Some(element)
+    }
+}
+
+/// Convert a path into the `d` attribute of a `path` element.
+fn path_element(path: &BezPath, transform: Affine) -> Element {
+    let mut element = Element::new("path");
+    element.assign("d", path.to_svg());
+    set_transform(&mut element, transform);
+    element
+}
+
+/// Create a `use` element that references a definition.
+fn use_element(id: &str, transform: Affine) -> Element {
+    let mut element = Element::new("use");
+    element.assign("href", format!("#{id}"));
+    set_transform(&mut element, transform);
+    element
+}
+
+/// Create an `image` element with the pixmap embedded as a PNG.
+fn image_element(pixmap: &Pixmap) -> Element {
+    let png = pixmap
+        .to_srgb()
+        .into_owned()
+        .into_png()
+        .expect("encoding a PNG into memory should not fail");
+    let mut element = Element::new("image");
+    element.assign("width", pixmap.width());
+    element.assign("height", pixmap.height());
+    element.assign("preserveAspectRatio", "none");
+    element.assign("href", format!("data:image/png;base64,{}", base64(&png)));
+    element
+}
+
+fn set_transform(element: &mut Element, transform: Affine) {
+    if transform != Affine::IDENTITY {
+        element.assign("transform", format!("matrix({})", Matrix(transform)));
+    }
+}
+
+fn set_stroke_attributes(element: &mut Element, stroke: &Stroke) {
+    element.assign("stroke-width", Num(stroke.width).to_string());
+    // SVG doesn't support different caps at the start and end of subpaths.
+    let cap = match stroke.start_cap {
+        Cap::Butt => None,
+        Cap::Round => Some("round"),
+        Cap::Square => Some("square"),
+    };
+    if let Some(cap) = cap {
+        element.assign("stroke-linecap", cap);
+    }
+    match stroke.join {
+        Join::Miter => {
+            element.assign("stroke-miterlimit", Num(stroke.miter_limit).to_string());
+        }
+        Join::Round => element.assign("stroke-linejoin", "round"),
+        Join::Bevel => element.assign("stroke-linejoin", "bevel"),
+    }
+    if !stroke.dash_pattern.is_empty() {
+        let dashes = stroke
+            .dash_pattern
+            .iter()
+            .map(|dash| Num(*dash).to_string())
+            .collect::<Vec<_>>();
+        element.assign("stroke-dasharray", dashes.join(" "));
+        if stroke.dash_offset != 0.0 {
+            element.assign("stroke-dashoffset", Num(stroke.dash_offset).to_string());
+        }
+    }
+}
+
+/// The path of a fill or stroke shape.
+fn shape_path(shape: Shape<'_>) -> BezPath {
+    match shape {
+        Shape::Fill(path) | Shape::Stroke(path) => path.clone(),
+        Shape::BlurredRoundedRect { rect, .. } => rect.to_path(0.1),
+    }
+}
+
+fn stroke_outline(path: &BezPath, stroke: &Stroke) -> BezPath {
+    vello_common::kurbo::stroke(
+        path.iter(),
+        stroke,
+        &vello_common::kurbo::StrokeOpts::default(),
+        0.25,
+    )
+}
+
+fn invert(transform: Affine) -> Option<Affine> {
+    (transform.determinant().abs() > f64::EPSILON).then(|| transform.inverse())
+}
+
+fn mix_blend_mode(blend_mode: BlendMode) -> Option<&'static str> {
+    // Compositing operators other than source-over have no equivalent in SVG and are
+    // ignored.
+    Some(match blend_mode.mix {
+        Mix::Normal => return None,
+        Mix::Multiply => "multiply",
+        Mix::Screen => "screen",
+        Mix::Overlay => "overlay",
+        Mix::Darken => "darken",
+        Mix::Lighten => "lighten",
+        Mix::ColorDodge => "color-dodge",
+        Mix::ColorBurn => "color-burn",
+        Mix::HardLight => "hard-light",
+        Mix::SoftLight => "soft-light",
+        Mix::Difference => "difference",
+        Mix::Exclusion => "exclusion",
+        Mix::Hue => "hue",
+        Mix::Saturation => "saturation",
+        Mix::Color => "color",
+        Mix::Luminosity => "luminosity",
+    })
+}
+
+/// Format the color channels of a color, ignoring alpha.
+fn hex(color: AlphaColor<Srgb>) -> String {
+    let color = color.to_rgba8();
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Sample the stops of a gradient, such that interpolating linearly between the
+/// unpremultiplied sRGB samples approximates the interpolation of the gradient.
+fn sample_stops(gradient: &Gradient) -> Vec<(f32, AlphaColor<Srgb>)> {
+    let stops = &gradient.stops;
+    let to_srgb = |color: DynamicColor| color.to_alpha_color::<Srgb>();
+    let Some(first) = stops.first() else {
+        return Vec::new();
+    };
+
+    let mut samples = vec![(first.offset, to_srgb(first.color))];
+    for pair in stops.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        let start_color = to_srgb(start.color);
+        let end_color = to_srgb(end.color);
+
+        let needs_subdivision = gradient.interpolation_cs != ColorSpaceTag::Srgb
+            || (gradient.interpolation_alpha_space == InterpolationAlphaSpace::Premultiplied
+                && start_color.components[3] != end_color.components[3]);
+        if needs_subdivision && end.offset > start.offset {
+            for i in 1..STOP_SUBDIVISIONS {
+                let t = i as f32 / STOP_SUBDIVISIONS as f32;
+                let color = match gradient.interpolation_alpha_space {
+                    InterpolationAlphaSpace::Premultiplied => start
+                        .color
+                        .interpolate(end.color, gradient.interpolation_cs, gradient.hue_direction)
+                        .eval(t),
+                    InterpolationAlphaSpace::Unpremultiplied => start
+                        .color
+                        .interpolate_unpremultiplied(
+                            end.color,
+                            gradient.interpolation_cs,
+                            gradient.hue_direction,
+                        )
+                        .eval(t),
+                };
+                let offset = start.offset + (end.offset - start.offset) * t;
+                samples.push((offset, to_srgb(color)));
+            }
+        }
+
+        samples.push((end.offset, end_color));
+    }
+
+    samples
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(bits >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// A number formatted compactly, with at most four decimal places.
+#[derive(Clone, Copy, Debug)]
+struct Num(f64);
+
+impl Display for Num {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let rounded = (self.0 * 10_000.0).round() / 10_000.0;
+        if !rounded.is_finite() || rounded == 0.0 {
+            return f.write_str("0");
+        }
+
+        let formatted = format!("{rounded:.4}");
+        f.write_str(formatted.trim_end_matches('0').trim_end_matches('.'))
+    }
+}
+
+impl From<f32> for Num {
+    fn from(value: f32) -> Self {
+        Self(f64::from(value))
+    }
+}
+
+/// The coefficients of a transform, as expected by the `matrix` transform function.
+#[derive(Clone, Copy, Debug)]
+struct Matrix(Affine);
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0.as_coeffs();
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            Num(a),
+            Num(b),
+            Num(c),
+            Num(d),
+            Num(e),
+            Num(g)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::base64;
+    use crate::RenderContext;
+    use crate::color::palette::css;
+    use crate::kurbo::{Affine, BezPath, Circle, Rect, Shape as _, Stroke};
+    use crate::peniko::{BlendMode, Color, ColorStop, Fill, Gradient, Mix};
+    use alloc::string::String;
+    use vello_common::filter_effects::Filter;
+    use vello_common::peniko::ColorStops;
+
+    fn capture(draw: impl FnOnce(&mut RenderContext)) -> String {
+        let mut ctx = RenderContext::new(100, 100);
+        ctx.start_svg_capture();
+        draw(&mut ctx);
+        ctx.finish_svg_capture().unwrap()
+    }
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foob"), "Zm9vYg==");
+    }
+
+    #[test]
+    fn paths_and_paints() {
+        let svg = capture(|ctx| {
+            ctx.set_transform(Affine::translate((10.0, 0.0)));
+            ctx.set_fill_rule(Fill::EvenOdd);
+            ctx.set_paint(css::RED.with_alpha(0.5));
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 10.0, 10.0));
+
+            ctx.reset_transform();
+            ctx.set_stroke(Stroke::new(2.0).with_dashes(1.0, [4.0, 2.0]));
+            ctx.set_paint(
+                Gradient::new_linear((0.0, 0.0), (100.0, 0.0)).with_stops(ColorStops::from(
+                    [
+                        ColorStop::from((0.0, Color::from(css::BLUE))),
+                        ColorStop::from((1.0, Color::from(css::LIME))),
+                    ]
+                    .as_slice(),
+                )),
+            );
+            ctx.set_blend_mode(BlendMode::from(Mix::Multiply));
+            ctx.stroke_path(&Circle::new((50.0, 50.0), 20.0).to_path(0.1));
+        });
+
+        assert!(svg.contains(
+            r##"<path d="M0,0 L10,0 L10,10 L0,10 Z" fill="#ff0000" fill-opacity="0.5" fill-rule="evenodd" transform="matrix(1 0 0 1 10 0)"/>"##
+        ));
+        assert!(svg.contains(
+            r##"<linearGradient gradientUnits="userSpaceOnUse" id="gradient0" x1="0" x2="100" y1="0" y2="0">"##
+        ));
+        assert!(svg.contains(r##"<stop offset="1" stop-color="#00ff00"/>"##));
+        assert!(svg.contains(
+            r##"fill="none" stroke="url(#gradient0)" stroke-dasharray="4 2" stroke-dashoffset="1" stroke-linecap="round" stroke-linejoin="round" stroke-width="2" style="mix-blend-mode:multiply"/>"##
+        ));
+    }
+
+    #[test]
+    fn layers_and_clip_paths() {
+        let svg = capture(|ctx| {
+            let clip = Rect::new(10.0, 10.0, 90.0, 90.0).to_path(0.1);
+            ctx.push_clip_path(&clip);
+            ctx.push_layer(
+                None,
+                None,
+                Some(0.5),
+                None,
+                Some(Filter::from_primitive(
+                    vello_common::filter_effects::FilterPrimitive::Offset { dx: 5.0, dy: 0.0 },
+                )),
+            );
+            ctx.fill_rect(&Rect::new(20.0, 20.0, 40.0, 40.0));
+            ctx.pop_layer();
+            ctx.fill_rect(&Rect::new(0.0, 0.0, 5.0, 5.0));
+        });
+
+        assert!(svg.contains(r#"<clipPath clipPathUnits="userSpaceOnUse" id="clip0">"#));
+        assert!(svg.contains(r#"<feOffset dx="5" dy="0"/>"#));
+        assert!(
+            svg.contains(r#"<g filter="url(#filter1)" opacity="0.5" style="isolation:isolate">"#)
+        );
+        // The clip path is still open after the layer is popped, so the last rectangle
+        // is drawn inside of it.
+        let clip_group = svg.find(r#"<g clip-path="url(#clip0)">"#).unwrap();
+        let last_rect = svg.find(r#"<path d="M0,0 L5,0 L5,5 L0,5 Z""#).unwrap();
+        assert!(clip_group < last_rect);
+        assert!(svg[last_rect..].contains("</g>\n</g>"));
+    }
+
+    #[test]
+    fn sweep_gradients_are_rasterized() {
+        let svg = capture(|ctx| {
+            ctx.set_paint(
+                Gradient::new_sweep((50.0, 50.0), 0.0, 360.0)
+                    .with_stops([css::RED, css::BLUE].as_slice()),
+            );
+            ctx.fill_rect(&Rect::new(10.5, 10.0, 20.0, 30.0));
+            ctx.fill_path(&BezPath::new());
+        });
+
+        assert!(svg.contains(r#"height="20" href="data:image/png;base64,"#));
+        assert!(svg.contains(r#"width="10" x="10" y="10"/>"#));
+    }
+}
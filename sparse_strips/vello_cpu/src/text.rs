@@ -13,8 +13,8 @@
 
 use crate::render::{ATLAS_IMAGE_ID_BASE, DEFAULT_GLYPH_ATLAS_SIZE};
 use crate::{
-    CompositeMode, Image, ImageSource, PaintType, Pixmap, RasterizerSettings, RenderContext,
-    RenderMode, RenderSettings, Resources, color, kurbo, peniko,
+    AntialiasingMode, CompositeMode, Image, ImageSource, PaintType, Pixmap, RasterizerSettings,
+    RenderContext, RenderMode, RenderSettings, Resources, color, kurbo, peniko,
 };
 use alloc::boxed::Box;
 use alloc::sync::Arc;
@@ -74,6 +74,8 @@ impl GlyphAtlasResources {
                 RenderSettings {
                     level,
                     num_threads: 0,
+                    antialiasing_mode: AntialiasingMode::Area,
+                    dithering: false,
                 },
             )),
             pixmaps: Vec::new(),
@@ -224,7 +226,9 @@ impl<'a> CpuGlyphRunBackend<'a> {
     ) where
         Glyphs: Iterator<Item = Glyph> + Clone,
     {
-        let atlas_cacher = if self.atlas_cache_enabled {
+        // Glyphs need to be drawn as outlines while exporting SVG, since the atlas
+        // pages can't be referenced from the document.
+        let atlas_cacher = if self.atlas_cache_enabled && !self.ctx.is_capturing_svg() {
             self.resources
                 .ensure_glyph_resources(self.ctx.render_settings.level);
             let glyph_resources = self
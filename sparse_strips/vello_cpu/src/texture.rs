@@ -0,0 +1,79 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Externally owned textures.
+//!
+//! An [`ExternalTexture`](vello_common::paint::ExternalTexture) paint only refers to its pixels
+//! by a [`TextureId`]. The pixels are supplied when rendering, by passing [`TextureBindings`]
+//! in [`RasterizerSettings::textures`](crate::RasterizerSettings::textures). This makes it
+//! possible to draw content that changes every frame, like video, without registering it as
+//! an image first.
+
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use vello_common::TextureId;
+use vello_common::encode::EncodedPaint;
+use vello_common::pixmap::PixmapRef;
+
+/// Runtime bindings for [externally owned textures](`TextureId`) sampled by external texture
+/// paints.
+///
+/// The bound pixels are premultiplied RGBA8 in the sRGB color space.
+///
+/// Note that changes to the bound pixels are not tracked by
+/// [`SceneSnapshot::damage`](crate::SceneSnapshot::damage), so the regions showing a texture
+/// whose content has changed need to be added to the damage by the caller.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TextureBindings<'a> {
+    textures: HashMap<TextureId, PixmapRef<'a>>,
+}
+
+impl<'a> TextureBindings<'a> {
+    /// Create an empty binding map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert or replace a texture binding.
+    #[inline]
+    pub fn insert(&mut self, texture_id: TextureId, pixels: impl Into<PixmapRef<'a>>) {
+        self.textures.insert(texture_id, pixels.into());
+    }
+
+    /// Get a texture binding.
+    #[inline]
+    pub(crate) fn get(&self, texture_id: TextureId) -> Option<PixmapRef<'a>> {
+        self.textures.get(&texture_id).copied()
+    }
+
+    /// Remove a texture binding.
+    ///
+    /// This returns the removed binding if it existed.
+    #[inline]
+    pub fn remove(&mut self, texture_id: TextureId) -> Option<PixmapRef<'a>> {
+        self.textures.remove(&texture_id)
+    }
+}
+
+/// Log a warning for each texture that is drawn by one of the paints but not bound.
+///
+/// This is done once before rasterizing, as the paints of the unbound textures are skipped
+/// for every wide tile they cover.
+pub(crate) fn warn_unbound(
+    encoded_paints: &[EncodedPaint],
+    textures: Option<&TextureBindings<'_>>,
+) {
+    let mut unbound = Vec::new();
+    for paint in encoded_paints {
+        if let EncodedPaint::ExternalTexture(texture) = paint
+            && textures
+                .and_then(|textures| textures.get(texture.texture_id))
+                .is_none()
+            && !unbound.contains(&texture.texture_id)
+        {
+            log::warn!("Texture {:?} is not bound", texture.texture_id);
+            unbound.push(texture.texture_id);
+        }
+    }
+}
@@ -1,11 +1,8 @@
 // Copyright 2025 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::peniko::ImageQuality;
 use alloc::vec::Vec;
-use vello_common::encode::EncodedImage;
 use vello_common::fearless_simd::{Simd, SimdBase, f32x4, u8x32};
-use vello_common::math::FloatExt;
 use vello_common::tile::Tile;
 use vello_common::util::Div255Ext;
 
@@ -135,21 +132,6 @@ impl<S: Simd> NormalizedMulExt for u8x32<S> {
     }
 }
 
-pub(crate) trait EncodedImageExt {
-    fn has_skew(&self) -> bool;
-    fn nearest_neighbor(&self) -> bool;
-}
-
-impl EncodedImageExt for EncodedImage {
-    fn has_skew(&self) -> bool {
-        !(self.x_advance.y as f32).is_nearly_zero() || !(self.y_advance.x as f32).is_nearly_zero()
-    }
-
-    fn nearest_neighbor(&self) -> bool {
-        self.sampler.quality == ImageQuality::Low
-    }
-}
-
 pub(crate) trait Premultiply {
     fn premultiply(self, alphas: Self) -> Self;
     fn unpremultiply(self, alphas: Self) -> Self;
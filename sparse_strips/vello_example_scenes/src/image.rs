@@ -179,7 +179,7 @@ impl ImageScene {
     /// Read the flower image
     pub fn read_flower_image() -> Pixmap {
         let image_data = include_bytes!("../../../examples/assets/splash-flower.jpg");
-        Self::read_image(image_data)
+        Pixmap::from_jpeg(Cursor::new(image_data)).unwrap()
     }
 
     /// Read the flower image
@@ -30,7 +30,7 @@ use vello_common::paint::ImageSource;
 pub use vello_common::paint::{Paint, PaintType};
 pub use vello_common::peniko::{BlendMode, Fill, FontData, ImageQuality};
 #[cfg(feature = "cpu")]
-use vello_cpu::{RenderContext, Resources as CpuResources};
+use vello_cpu::{ExternalTexture, RenderContext, Resources as CpuResources};
 use vello_hybrid::{Resources as HybridResources, Scene};
 pub use vello_hybrid::{SampleRect, TextureId};
 
@@ -43,6 +43,9 @@ pub struct Capabilities {
     /// Whether the renderer supports externally bound textures and
     /// [`RenderingContext::draw_texture_rects`].
     pub external_textures: bool,
+    /// Whether the renderer supports filter effects, e.g. using
+    /// [`RenderingContext::push_filter_layer`].
+    pub filter_effects: bool,
 }
 
 /// A generic rendering context.
@@ -214,11 +217,30 @@ impl RenderingContext for RenderContext {
 
     fn draw_texture_rects(
         &mut self,
-        _texture_id: TextureId,
-        _quality: ImageQuality,
-        _rects: impl IntoIterator<Item = SampleRect>,
+        texture_id: TextureId,
+        quality: ImageQuality,
+        rects: impl IntoIterator<Item = SampleRect>,
     ) {
-        unimplemented!("vello_cpu does not yet support external textures");
+        let transform = *self.transform();
+        let paint = self.paint().clone();
+        let paint_transform = *self.paint_transform();
+        // Like in `vello_hybrid`, the paint transform doesn't apply to the rectangles.
+        self.reset_paint_transform();
+        for rect in rects {
+            if rect.source_region.is_empty() {
+                continue;
+            }
+            let w = f64::from(rect.source_region.width());
+            let h = f64::from(rect.source_region.height());
+            self.set_transform(transform * rect.transform);
+            self.set_paint(
+                ExternalTexture::new(texture_id, rect.source_region).with_quality(quality),
+            );
+            self.fill_rect(&Rect::new(0.0, 0.0, w, h));
+        }
+        self.set_transform(transform);
+        self.set_paint(paint);
+        self.set_paint_transform(paint_transform);
     }
 }
 
@@ -351,6 +373,8 @@ pub trait ExampleScene {
 
 /// A type-erased example scene.
 pub struct AnyScene<T: RenderingContext> {
+    /// The name of the wrapped scene type.
+    name: &'static str,
     /// The render function that calls the wrapped scene's render method.
     render_fn: RenderFn<T>,
     resources: T::Resources,
@@ -374,6 +398,7 @@ type StatusFn = Box<dyn Fn() -> Option<String>>;
 impl<T: RenderingContext> std::fmt::Debug for AnyScene<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AnyScene")
+            .field("name", &self.name)
             .field("show_tile_grid", &self.show_widetile_columns)
             .finish_non_exhaustive()
     }
@@ -390,7 +415,9 @@ where
         let scene_clone = scene.clone();
         let scene_status = scene.clone();
 
+        let type_name = std::any::type_name::<S>();
         Self {
+            name: type_name.rsplit("::").next().unwrap_or(type_name),
             render_fn: Box::new(move |s, resources, transform| {
                 scene.borrow_mut().render(s, resources, transform);
             }),
@@ -428,6 +455,11 @@ where
         (self.key_handler_fn)(key)
     }
 
+    /// The name of the scene, i.e. the name of the wrapped scene type.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
     /// Get an optional status string from the scene.
     pub fn status(&self) -> Option<String> {
         (self.status_fn)()
@@ -494,14 +526,18 @@ where
         blurred_rounded_rect::BlurredRoundedRectScene::new(),
     ));
     scenes.push(AnyScene::new(clip::ClipScene::new()));
-    scenes.push(AnyScene::new(filter::FilterScene::new()));
+    if capabilities.filter_effects {
+        scenes.push(AnyScene::new(filter::FilterScene::new()));
+    }
     scenes.push(AnyScene::new(blend::BlendScene::new()));
     let flower_source = img_sources[0].clone();
     scenes.push(AnyScene::new(image::ImageScene::new(img_sources)));
     scenes.push(AnyScene::new(multi_image::MultiImageScene::new(
         flower_source,
     )));
-    scenes.push(AnyScene::new(filter_elements::FilterElementsScene::new()));
+    if capabilities.filter_effects {
+        scenes.push(AnyScene::new(filter_elements::FilterElementsScene::new()));
+    }
     scenes.push(AnyScene::new(gradient::GradientExtendScene::new()));
     scenes.push(AnyScene::new(gradient::RadialScene::new()));
     scenes.push(AnyScene::new(path::FillTypesScene::new()));
@@ -535,12 +571,20 @@ where
         AnyScene::new(random_text::RandomTextScene::new()),
         AnyScene::new(simple::SimpleScene::new()),
         AnyScene::new(blurred_rounded_rect::BlurredRoundedRectScene::new()),
-        AnyScene::new(filter::FilterScene::new()),
+    ];
+    if capabilities.filter_effects {
+        scenes.push(AnyScene::new(filter::FilterScene::new()));
+    }
+    scenes.extend([
         AnyScene::new(clip::ClipScene::new()),
         AnyScene::new(blend::BlendScene::new()),
         AnyScene::new(image::ImageScene::new(img_sources.clone())),
         AnyScene::new(multi_image::MultiImageScene::new(img_sources[0].clone())),
-        AnyScene::new(filter_elements::FilterElementsScene::new()),
+    ]);
+    if capabilities.filter_effects {
+        scenes.push(AnyScene::new(filter_elements::FilterElementsScene::new()));
+    }
+    scenes.extend([
         AnyScene::new(gradient::GradientExtendScene::new()),
         AnyScene::new(gradient::RadialScene::new()),
         AnyScene::new(path::FillTypesScene::new()),
@@ -550,7 +594,7 @@ where
         AnyScene::new(path::TrickyStrokesScene::new()),
         AnyScene::new(path::FunkyPathsScene::new()),
         AnyScene::new(path::RobustPathsScene::new()),
-    ];
+    ]);
 
     if capabilities.external_textures {
         scenes.push(AnyScene::new(spritesheet::SpritesheetScene::new()));
@@ -86,7 +86,15 @@ fn render_svg(ctx: &mut impl RenderingContext, items: &[Item], transform: Affine
                 ctx.stroke_path(&stroke_item.path);
             }
             Item::Group(group_item) => {
-                render_svg(ctx, &group_item.children, transform * group_item.affine);
+                let group_transform = transform * group_item.affine;
+                if let Some(filter) = &group_item.filter {
+                    ctx.set_transform(group_transform);
+                    ctx.push_filter_layer(filter.clone());
+                }
+                render_svg(ctx, &group_item.children, group_transform);
+                if group_item.filter.is_some() {
+                    ctx.pop_layer();
+                }
                 ctx.set_transform(transform);
             }
         }
@@ -117,6 +117,7 @@ impl AppState {
                 &self.scene,
                 self.scenes[self.current_scene].resources_mut(),
                 &render_size,
+                &vello_hybrid::WebGlTextureBindings::new(),
             )
             .unwrap();
         self.need_render = false;
@@ -336,7 +337,10 @@ pub async fn run_interactive(canvas_width: u16, canvas_height: u16) {
 
     let scenes = {
         let v = vello_example_scenes::get_example_scenes(
-            vello_example_scenes::Capabilities::default(),
+            vello_example_scenes::Capabilities {
+                filter_effects: true,
+                ..Default::default()
+            },
             vec![
                 ImageSource::opaque_id(ImageId::new(0)),
                 ImageSource::opaque_id(ImageId::new(1)),
@@ -528,6 +532,11 @@ pub async fn render_scene(scene: Scene, width: u16, height: u16) {
     let mut resources = vello_hybrid::Resources::new();
 
     renderer
-        .render(&scene, &mut resources, &render_size)
+        .render(
+            &scene,
+            &mut resources,
+            &render_size,
+            &vello_hybrid::WebGlTextureBindings::new(),
+        )
         .unwrap();
 }
@@ -443,7 +443,10 @@ pub async fn run_interactive(canvas_width: u16, canvas_height: u16) {
         .unwrap();
 
     let scenes = vello_example_scenes::get_example_scenes(
-        vello_example_scenes::Capabilities::default(),
+        vello_example_scenes::Capabilities {
+            filter_effects: true,
+            ..Default::default()
+        },
         vec![
             ImageSource::opaque_id(ImageId::new(0)),
             ImageSource::opaque_id(ImageId::new(1)),
@@ -70,6 +70,7 @@ fn main() {
         ];
         let capabilities = Capabilities {
             external_textures: true,
+            filter_effects: true,
         };
         let scenes = if svg_paths.is_empty() {
             get_example_scenes(capabilities, None, img_sources)
@@ -85,6 +86,7 @@ fn main() {
         get_example_scenes(
             Capabilities {
                 external_textures: true,
+                filter_effects: true,
             },
             vec![
                 ImageSource::opaque_id(ImageId::new(0)),
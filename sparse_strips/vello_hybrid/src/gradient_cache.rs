@@ -28,6 +28,12 @@ pub(crate) struct GradientRampCache {
     retained_count: u32,
     /// SIMD level used for gradient LUT generation.
     level: Level,
+    /// Whether the ramps are stored with extra precision, so that they can be dithered.
+    ///
+    /// In that case, each entry of a ramp takes up two texels: the first one stores the
+    /// color rounded down to 8 bits, and the second one the remaining fraction, scaled up to
+    /// 8 bits.
+    dithering: bool,
     /// Scratch space for maintaining the cache.
     scratch: ScratchSpace,
 }
@@ -55,7 +61,7 @@ struct ScratchSpace {
 
 impl GradientRampCache {
     /// Create a new gradient ramp cache with the specified retained count.
-    pub(crate) fn new(retained_count: u32, level: Level) -> Self {
+    pub(crate) fn new(retained_count: u32, level: Level, dithering: bool) -> Self {
         Self {
             epoch: 0,
             cache: HashMap::new(),
@@ -63,6 +69,7 @@ impl GradientRampCache {
             has_changed: false,
             retained_count,
             level,
+            dithering,
             scratch: ScratchSpace::default(),
         }
     }
@@ -83,7 +90,7 @@ impl GradientRampCache {
 
         // Generate new gradient LUT.
         let lut_start = self.luts.len() as u32 / BYTES_PER_TEXEL;
-        let width = dispatch!(self.level, simd => generate_gradient_lut_impl(simd, gradient, &mut self.luts))
+        let width = dispatch!(self.level, simd => generate_gradient_lut_impl(simd, gradient, self.dithering, &mut self.luts))
             as u32;
         let cached_ramp = CachedRamp { width, lut_start };
         self.has_changed = true;
@@ -102,6 +109,16 @@ impl GradientRampCache {
         self.evict(entries_to_remove_count);
     }
 
+    /// Whether the ramps are stored with extra precision and should be dithered.
+    pub(crate) fn dithering(&self) -> bool {
+        self.dithering
+    }
+
+    /// Get the number of texels each entry of a ramp takes up.
+    fn texels_per_entry(&self) -> u32 {
+        if self.dithering { 2 } else { 1 }
+    }
+
     /// Get the size of the packed luts.
     pub(crate) fn luts_size(&self) -> usize {
         self.luts.len()
@@ -204,10 +221,12 @@ impl GradientRampCache {
         prefix_sum.push(0);
         let mut write_pos = 0;
         let mut read_pos = 0;
+        let texels_per_entry = self.texels_per_entry();
 
         for (_, ramp) in ramps_to_remove.iter() {
+            let texels = ramp.width * texels_per_entry;
             let remove_start = (ramp.lut_start * BYTES_PER_TEXEL) as usize;
-            let remove_end = remove_start + (ramp.width * BYTES_PER_TEXEL) as usize;
+            let remove_end = remove_start + (texels * BYTES_PER_TEXEL) as usize;
             // First, copy all the LUT entries before the removed entry to the new
             // write position.
             if read_pos < remove_start {
@@ -217,7 +236,7 @@ impl GradientRampCache {
 
             // Update the read position as well as the prefix sum.
             read_pos = remove_end;
-            prefix_sum.push(prefix_sum.last().unwrap() + ramp.width);
+            prefix_sum.push(prefix_sum.last().unwrap() + texels);
         }
 
         // Handle the tail if it exists.
@@ -261,7 +280,7 @@ fn reuse_vec<T, U>(mut v: Vec<T>) -> Vec<U> {
 /// Cached gradient ramp data with metadata.
 #[derive(Debug, Clone)]
 pub(crate) struct CachedRamp {
-    /// Width of this gradient's LUT.
+    /// Width of this gradient's LUT, i.e. its number of entries.
     pub width: u32,
     /// Offset in luts where this ramp starts.
     pub lut_start: u32,
@@ -273,16 +292,39 @@ pub(crate) struct CachedRamp {
 // The current approach allocates LUT in OnceCell and then copies to output, keeping
 // both allocations alive.
 #[inline(always)]
+#[allow(
+    clippy::cast_possible_truncation,
+    reason = "The components are scaled to the range of u8 before the conversion"
+)]
 fn generate_gradient_lut_impl<S: Simd>(
     simd: S,
     gradient: &EncodedGradient,
+    dithering: bool,
     output: &mut Vec<u8>,
 ) -> usize {
-    let lut = gradient.u8_lut(simd);
-    let bytes: &[u8] = bytemuck::cast_slice(lut.lut());
-    output.reserve(bytes.len());
-    output.extend_from_slice(bytes);
-    lut.width()
+    if dithering {
+        let lut = gradient.f32_lut(simd);
+        output.reserve(lut.width() * 2 * BYTES_PER_TEXEL as usize);
+        for entry in lut.lut() {
+            let scaled = entry.map(|c| c.clamp(0.0, 1.0) * 255.0);
+            // The components are not negative, so truncating rounds them down.
+            let high = scaled.map(|c| c as u8);
+            output.extend(high);
+            output.extend(
+                scaled
+                    .iter()
+                    .zip(high)
+                    .map(|(c, high)| ((c - f32::from(high)) * 255.0 + 0.5) as u8),
+            );
+        }
+        lut.width()
+    } else {
+        let lut = gradient.u8_lut(simd);
+        let bytes: &[u8] = bytemuck::cast_slice(lut.lut());
+        output.reserve(bytes.len());
+        output.extend_from_slice(bytes);
+        lut.width()
+    }
 }
 
 #[cfg(test)]
@@ -349,7 +391,7 @@ mod tests {
 
     #[test]
     fn test_cache_empty() {
-        let mut cache = GradientRampCache::new(5, Level::baseline());
+        let mut cache = GradientRampCache::new(5, Level::baseline(), false);
         cache.maintain();
 
         assert_eq!(cache.cache.len(), 0);
@@ -360,7 +402,7 @@ mod tests {
 
     #[test]
     fn test_unique_entry_creation() {
-        let mut cache = GradientRampCache::new(5, Level::baseline());
+        let mut cache = GradientRampCache::new(5, Level::baseline(), false);
         insert_entries(&mut cache, 4);
         cache.maintain();
 
@@ -371,7 +413,7 @@ mod tests {
 
     #[test]
     fn test_no_eviction_under_limit() {
-        let mut cache = GradientRampCache::new(5, Level::baseline());
+        let mut cache = GradientRampCache::new(5, Level::baseline(), false);
         insert_entries(&mut cache, 4);
         cache.maintain();
 
@@ -380,7 +422,7 @@ mod tests {
 
     #[test]
     fn test_no_eviction_at_limit() {
-        let mut cache = GradientRampCache::new(5, Level::baseline());
+        let mut cache = GradientRampCache::new(5, Level::baseline(), false);
         insert_entries(&mut cache, 5);
         cache.maintain();
 
@@ -389,7 +431,7 @@ mod tests {
 
     #[test]
     fn test_eviction_over_limit() {
-        let mut cache = GradientRampCache::new(5, Level::baseline());
+        let mut cache = GradientRampCache::new(5, Level::baseline(), false);
         insert_entries(&mut cache, 10);
         cache.maintain();
 
@@ -399,7 +441,7 @@ mod tests {
 
     #[test]
     fn test_lut_compaction_and_offset_updates() {
-        let mut cache = GradientRampCache::new(2, Level::baseline());
+        let mut cache = GradientRampCache::new(2, Level::baseline(), false);
 
         // Start from 1 to keep LUT sizes consistent, making it easier to test LUT size
         // before and after eviction.
@@ -442,7 +484,7 @@ mod tests {
 
     #[test]
     fn test_correct_lru_eviction() {
-        let mut cache = GradientRampCache::new(3, Level::baseline());
+        let mut cache = GradientRampCache::new(3, Level::baseline(), false);
 
         // Insert 3 gradients to fill the cache
         let gradient1 = create_gradient(0.1);
@@ -499,7 +541,7 @@ mod tests {
 
     #[test]
     fn test_take_and_restore_luts() {
-        let mut cache = GradientRampCache::new(5, Level::baseline());
+        let mut cache = GradientRampCache::new(5, Level::baseline(), false);
 
         let gradient1 = create_gradient(0.1);
         let gradient2 = create_gradient(0.2);
@@ -532,7 +574,7 @@ mod tests {
 
     #[test]
     fn test_lut_start_invalidation() {
-        let mut cache = GradientRampCache::new(2, Level::baseline());
+        let mut cache = GradientRampCache::new(2, Level::baseline(), false);
 
         let gradient_1 = create_encoded_gradient(create_gradient(0.1));
         let gradient_2 = create_encoded_gradient(create_gradient(0.2));
@@ -558,4 +600,43 @@ mod tests {
             cache.cache.get(&gradient_3.cache_key).unwrap().0.lut_start
         );
     }
+
+    #[test]
+    fn test_dithered_ramps() {
+        let mut cache = GradientRampCache::new(2, Level::baseline(), true);
+        assert!(cache.dithering());
+
+        let gradient = create_encoded_gradient(create_gradient(0.3));
+        let (lut_start, width) = cache.get_or_create_ramp(&gradient);
+        assert_eq!(lut_start, 0);
+        assert_eq!(cache.luts_size(), (2 * width * BYTES_PER_TEXEL) as usize);
+
+        // The two texels of each entry together store the color with more than 8 bits.
+        let lut = gradient.f32_lut(vello_common::fearless_simd::Fallback::new());
+        for (entry, texels) in lut.lut().iter().zip(cache.luts.chunks_exact(8)) {
+            for (i, component) in entry.iter().enumerate() {
+                let stored = (f32::from(texels[i]) + f32::from(texels[i + 4]) / 255.0) / 255.0;
+                assert!((stored - component).abs() <= 0.5 / (255.0 * 255.0) + 1e-6);
+            }
+        }
+
+        // Evicting ramps accounts for the texels of both halves of the entries.
+        for i in 4..7 {
+            insert_entry(&mut cache, create_gradient(i as f32 / 10.0));
+        }
+        cache.maintain();
+        let mut offsets: Vec<_> = cache
+            .cache
+            .values()
+            .map(|(ramp, _)| (ramp.lut_start, ramp.width))
+            .collect();
+        offsets.sort();
+        assert_eq!(offsets[0].0, 0);
+        assert_eq!(offsets[1].0, 2 * offsets[0].1);
+        let total_width: u32 = offsets.iter().map(|(_, width)| width).sum();
+        assert_eq!(
+            cache.luts.len(),
+            (2 * total_width * BYTES_PER_TEXEL) as usize
+        );
+    }
 }
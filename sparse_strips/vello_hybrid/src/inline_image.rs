@@ -0,0 +1,94 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Uploading of images that are drawn without being registered first.
+//!
+//! Image paints usually refer to images that were uploaded into the atlas beforehand. Paints
+//! that carry their pixels instead, either as a [`Pixmap`] or as a [`RawImage`] in another
+//! pixel format, are converted to premultiplied RGBA8 and uploaded into the atlas right
+//! before the scene is rendered, and deallocated again afterwards.
+//!
+//! [`RawImage`]: vello_common::raw_image::RawImage
+
+use crate::RenderError;
+use crate::render::common::IMAGE_PADDING;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use vello_common::encode::EncodedPaint;
+use vello_common::image_cache::ImageCache;
+use vello_common::paint::{ImageId, ImageSource};
+use vello_common::pixmap::Pixmap;
+
+/// Upload the pixels of all image paints that don't refer to an uploaded image into the
+/// atlas, and point the paints at the uploaded images.
+///
+/// The original sources of the paints are added to `sources`, together with the index of
+/// the paint, and need to be put back with [`restore_inline_images`] once the scene has been
+/// rendered. The ids of the allocated images are added to `image_ids`, even if an error
+/// occurs, and need to be deallocated (and cleared) afterwards as well.
+pub(crate) fn upload_inline_images<T>(
+    backend: &mut T,
+    image_cache: &mut ImageCache,
+    encoded_paints: &mut [EncodedPaint],
+    sources: &mut Vec<(usize, ImageSource)>,
+    image_ids: &mut Vec<ImageId>,
+    mut write_to_atlas: impl FnMut(&mut T, &ImageCache, ImageId, &Pixmap),
+) -> Result<(), RenderError> {
+    // Images that are drawn several times are only uploaded once, keyed by their address.
+    let mut uploaded: Vec<(*const (), ImageId)> = Vec::new();
+    for (idx, paint) in encoded_paints.iter_mut().enumerate() {
+        let EncodedPaint::Image(image) = paint else {
+            continue;
+        };
+        let key = match &image.source {
+            ImageSource::Pixmap(pixmap) => Arc::as_ptr(pixmap).cast::<()>(),
+            ImageSource::Raw(raw) => Arc::as_ptr(raw).cast::<()>(),
+            ImageSource::OpaqueId { .. } => continue,
+        };
+
+        let image_id = match uploaded.iter().find(|(k, _)| *k == key) {
+            Some((_, image_id)) => *image_id,
+            None => {
+                let converted;
+                let pixmap = match &image.source {
+                    ImageSource::Pixmap(pixmap) => &**pixmap,
+                    ImageSource::Raw(raw) => {
+                        converted = raw.to_pixmap();
+                        &converted
+                    }
+                    ImageSource::OpaqueId { .. } => unreachable!("uploaded images are skipped"),
+                };
+                let image_id = image_cache.allocate(
+                    u32::from(pixmap.width()),
+                    u32::from(pixmap.height()),
+                    IMAGE_PADDING,
+                )?;
+                image_ids.push(image_id);
+                write_to_atlas(backend, image_cache, image_id, pixmap);
+                uploaded.push((key, image_id));
+                image_id
+            }
+        };
+
+        let may_have_transparency = image.source.may_have_transparency();
+        let source = core::mem::replace(
+            &mut image.source,
+            ImageSource::opaque_id_with_transparency_hint(image_id, may_have_transparency),
+        );
+        sources.push((idx, source));
+    }
+
+    Ok(())
+}
+
+/// Put back the sources of the image paints that were replaced by [`upload_inline_images`].
+pub(crate) fn restore_inline_images(
+    encoded_paints: &mut [EncodedPaint],
+    sources: Vec<(usize, ImageSource)>,
+) {
+    for (idx, source) in sources {
+        if let EncodedPaint::Image(image) = &mut encoded_paints[idx] {
+            image.source = source;
+        }
+    }
+}
@@ -47,6 +47,9 @@ extern crate alloc;
 
 pub(crate) mod filter;
 mod gradient_cache;
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+mod inline_image;
+mod pattern;
 mod render;
 mod resources;
 mod sampling;
@@ -64,7 +67,9 @@ pub use render::{Config, GpuStrip, RenderSize};
 #[cfg(all(feature = "webgl", feature = "probe"))]
 pub use render::{Probe, ProbeResult};
 #[cfg(feature = "webgl")]
-pub use render::{WebGlAtlasWriter, WebGlRenderer, WebGlTextureWithDimensions};
+pub use render::{
+    WebGlAtlasWriter, WebGlRenderer, WebGlTextureBindings, WebGlTextureWithDimensions,
+};
 #[cfg(all(feature = "webgl", feature = "probe"))]
 pub use render::{WebGlPendingProbe, WebGlProbeError, WebGlProbeStatus};
 pub use resources::Resources;
@@ -91,9 +96,9 @@ pub enum RenderError {
     #[error("No slots available for rendering")]
     SlotsExhausted,
     /// An allocation error occurred while trying to allocate a new image. This can happen
-    /// if the scene contains filter layers, which need space in the image atlas for intermediate
-    /// storage.
-    #[error("Filter atlas allocation failed: {0}")]
+    /// if the scene contains filter layers or patterns, which need space in the image atlas for
+    /// intermediate storage.
+    #[error("Atlas allocation failed: {0}")]
     AtlasError(#[from] vello_common::multi_atlas::AtlasError),
     /// A draw referenced a [`TextureId`] that was not provided at render time.
     #[error("Missing texture binding for {0:?}")]
@@ -0,0 +1,242 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of pattern tiles.
+//!
+//! A pattern is drawn by rendering a single tile of it into the image atlas, at the resolution
+//! it is displayed at, and then repeating that tile like an image. As the atlas is only
+//! available while rendering, the scene records the tiles it needs together with the image
+//! paints that sample them, and the renderer draws the tiles into the atlas right before it
+//! renders the scene.
+//!
+//! The content of a tile is rendered without access to the images of the atlas, so images,
+//! nested patterns and filters inside of patterns are not supported yet.
+
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use crate::{RenderError, Scene};
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use alloc::vec::Vec;
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use vello_common::encode::EncodedPaint;
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use vello_common::image_cache::ImageCache;
+use vello_common::kurbo::{Affine, Vec2};
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use vello_common::kurbo::{Rect, Shape};
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use vello_common::multi_atlas::{AtlasConfig, AtlasId};
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use vello_common::paint::PaintType;
+use vello_common::paint::{Image, ImageId, ImageSource};
+use vello_common::pattern::Pattern;
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+use vello_common::pattern::PatternCommand;
+use vello_common::peniko::{Extend, ImageSampler};
+use vello_common::util::extract_scales;
+
+/// The largest width or height (in pixels) of a rendered pattern tile.
+const MAX_TILE_SIZE: f64 = 1024.0;
+
+/// A pattern tile that needs to be rendered into the atlas before the scene is rendered.
+#[derive(Debug)]
+#[cfg_attr(
+    not(any(feature = "webgl", feature = "wgpu")),
+    expect(dead_code, reason = "tiles are only rendered by the GPU backends")
+)]
+pub(crate) struct PatternTile {
+    pattern: Pattern,
+    /// The transform from the coordinate system of the pattern's scene to the pixels of
+    /// the tile.
+    transform: Affine,
+    width: u16,
+    height: u16,
+    /// The index of the encoded image paint that samples the tile.
+    paint_idx: usize,
+}
+
+impl PatternTile {
+    /// Create the tile for drawing `pattern` with the given transform from the paint to the
+    /// device coordinate system, sampled by the encoded paint at `paint_idx`.
+    ///
+    /// Returns the tile together with the image paint to encode at `paint_idx`, and the
+    /// transform from the coordinate system of that image to the coordinate system of the
+    /// paint. The image doesn't refer to an allocated image until
+    /// [`prepare_pattern_tiles`] is called.
+    ///
+    /// Returns `None` if the tile of the pattern is empty.
+    pub(crate) fn new(
+        pattern: &Pattern,
+        paint_transform: Affine,
+        paint_idx: usize,
+    ) -> Option<(Self, Image, Affine)> {
+        let tile = pattern.tile;
+        if !(tile.width() > 0.0 && tile.height() > 0.0) {
+            return None;
+        }
+
+        // Render the tile at roughly the resolution it will be displayed at.
+        let (scale_x, scale_y) = extract_scales(&(paint_transform * pattern.transform));
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the size is clamped to the range of u16"
+        )]
+        let tile_size = |length: f64, scale: f32| {
+            (length * f64::from(scale)).ceil().clamp(1.0, MAX_TILE_SIZE) as u16
+        };
+        let width = tile_size(tile.width(), scale_x);
+        let height = tile_size(tile.height(), scale_y);
+        let scale = Vec2::new(
+            f64::from(width) / tile.width(),
+            f64::from(height) / tile.height(),
+        );
+
+        let image = Image {
+            // This id is never allocated, and is replaced once the tile has been rendered.
+            image: ImageSource::opaque_id(ImageId::new(u32::MAX)),
+            sampler: ImageSampler {
+                x_extend: Extend::Repeat,
+                y_extend: Extend::Repeat,
+                ..Default::default()
+            },
+        };
+        let image_transform = pattern.transform
+            * Affine::translate(tile.origin().to_vec2())
+            * Affine::scale_non_uniform(1.0 / scale.x, 1.0 / scale.y);
+        let pattern_tile = Self {
+            pattern: pattern.clone(),
+            transform: Affine::scale_non_uniform(scale.x, scale.y)
+                * Affine::translate(-tile.origin().to_vec2()),
+            width,
+            height,
+            paint_idx,
+        };
+
+        Some((pattern_tile, image, image_transform))
+    }
+
+    /// Record the content of the tile into `scene`, at the given offset.
+    #[cfg(any(feature = "webgl", feature = "wgpu"))]
+    fn record(&self, scene: &mut Scene, offset: [u16; 2]) {
+        let offset = Vec2::new(f64::from(offset[0]), f64::from(offset[1]));
+        let transform = Affine::translate(offset) * self.transform;
+
+        scene.reset_transform();
+        scene.push_clip_layer(
+            &Rect::from_origin_size(
+                offset.to_point(),
+                (f64::from(self.width), f64::from(self.height)),
+            )
+            .to_path(0.1),
+        );
+        for command in self.pattern.scene.commands() {
+            match command {
+                PatternCommand::Fill { path, state } | PatternCommand::Stroke { path, state } => {
+                    if matches!(state.paint, PaintType::Image(_) | PaintType::Pattern(_)) {
+                        log::warn!(
+                            "Images and patterns inside of patterns are not supported yet, \
+                             skipping them"
+                        );
+                        continue;
+                    }
+                    let mut state = state.clone();
+                    state.transform = transform * state.transform;
+                    scene.restore_state(state);
+                    if matches!(command, PatternCommand::Fill { .. }) {
+                        scene.fill_path(path);
+                    } else {
+                        scene.stroke_path(path);
+                    }
+                }
+                PatternCommand::PushLayer {
+                    clip_path,
+                    fill_rule,
+                    transform: layer_transform,
+                    blend_mode,
+                    opacity,
+                    filter,
+                } => {
+                    if filter.is_some() {
+                        log::warn!(
+                            "Filters inside of patterns are not supported yet, drawing the layer \
+                             without its filter"
+                        );
+                    }
+                    scene.set_transform(transform * *layer_transform);
+                    scene.set_fill_rule(*fill_rule);
+                    scene.push_layer(
+                        clip_path.as_ref(),
+                        Some(*blend_mode),
+                        Some(*opacity),
+                        None,
+                        None,
+                    );
+                }
+                PatternCommand::PopLayer => scene.pop_layer(),
+            }
+        }
+        scene.pop_layer();
+    }
+}
+
+/// Render the pattern tiles of a scene into the atlas, and point the image paints that
+/// sample them at the rendered tiles.
+///
+/// For each atlas that tiles are allocated in, `render_to_atlas` is called with a scene that
+/// draws the tiles. The ids of the allocated images are added to `image_ids`, even if an
+/// error occurs, and need to be deallocated (and cleared) once the scene has been rendered.
+#[cfg(any(feature = "webgl", feature = "wgpu"))]
+pub(crate) fn prepare_pattern_tiles<T>(
+    backend: &mut T,
+    tiles: &[PatternTile],
+    image_cache: &mut ImageCache,
+    encoded_paints: &mut [EncodedPaint],
+    image_ids: &mut Vec<ImageId>,
+    mut render_to_atlas: impl FnMut(
+        &mut T,
+        &Scene,
+        u32,
+        AtlasConfig,
+        AtlasId,
+    ) -> Result<(), RenderError>,
+) -> Result<(), RenderError> {
+    if tiles.is_empty() {
+        return Ok(());
+    }
+
+    let first_image = image_ids.len();
+    let mut atlas_ids = Vec::new();
+    for tile in tiles {
+        let image_id = image_cache.allocate(u32::from(tile.width), u32::from(tile.height), 0)?;
+        image_ids.push(image_id);
+        if let EncodedPaint::Image(image) = &mut encoded_paints[tile.paint_idx] {
+            image.source = ImageSource::opaque_id(image_id);
+        }
+        let atlas_id = image_cache.get(image_id).unwrap().atlas_id;
+        if !atlas_ids.contains(&atlas_id) {
+            atlas_ids.push(atlas_id);
+        }
+    }
+
+    let atlas_config = *image_cache.atlas_manager().config();
+    let atlas_count = u32::try_from(image_cache.atlas_count()).unwrap();
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "atlas dimensions are configured to fit in u16"
+    )]
+    let mut scene = Scene::new(
+        atlas_config.atlas_size.0 as u16,
+        atlas_config.atlas_size.1 as u16,
+    );
+    for atlas_id in atlas_ids {
+        scene.reset();
+        for (tile, image_id) in tiles.iter().zip(&image_ids[first_image..]) {
+            let resource = image_cache.get(*image_id).unwrap();
+            if resource.atlas_id == atlas_id {
+                tile.record(&mut scene, resource.offset);
+            }
+        }
+        render_to_atlas(backend, &scene, atlas_count, atlas_config, atlas_id)?;
+    }
+
+    Ok(())
+}
@@ -11,6 +11,9 @@
 use bytemuck::{Pod, Zeroable};
 use vello_common::multi_atlas::AtlasConfig;
 
+#[cfg(any(feature = "wgpu", feature = "webgl"))]
+use crate::Scene;
+
 // GPU paint structure sizes in texels (1 texel = 16 bytes for RGBA32Uint texture format).
 pub(crate) const GPU_ENCODED_IMAGE_SIZE_TEXELS: u32 = (size_of::<GpuEncodedImage>() / 16) as u32;
 pub(crate) const GPU_LINEAR_GRADIENT_SIZE_TEXELS: u32 =
@@ -25,6 +28,10 @@ pub(crate) const GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS: u32 =
 // we can pass 1 instead of 0 here.
 pub(crate) const IMAGE_PADDING: u16 = 0;
 
+/// Flag in the packed image parameters marking images that are sampled from the external
+/// texture instead of the atlas.
+pub(crate) const EXTERNAL_IMAGE_SOURCE_FLAG: u32 = 1 << 14;
+
 pub(crate) fn normalize_atlas_config(
     config: &mut AtlasConfig,
     max_texture_dimension_2d: u32,
@@ -81,6 +88,33 @@ mod tests {
     }
 }
 
+/// The permutation of the `render_strips` shader that is used to render a scene.
+///
+/// See the `permutations` file in `vello_sparse_shaders` for the available permutations.
+#[cfg(any(feature = "wgpu", feature = "webgl"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StripShaderVariant {
+    /// The general shader, which supports all features.
+    General = 0,
+    /// A shader without support for non-default blend modes.
+    NoBlend = 1,
+}
+
+#[cfg(any(feature = "wgpu", feature = "webgl"))]
+impl StripShaderVariant {
+    /// The number of variants.
+    pub(crate) const COUNT: usize = 2;
+
+    /// Select the most specialized variant which can render `scene`.
+    pub(crate) fn for_scene(scene: &Scene) -> Self {
+        if scene.constraints.use_default_blending_everywhere() {
+            Self::NoBlend
+        } else {
+            Self::General
+        }
+    }
+}
+
 /// Dimensions of the rendering target.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct RenderSize {
@@ -217,9 +251,9 @@ pub(crate) struct GpuEncodedImage {
     /// Transform matrix [a, b, c, d, tx, ty].
     pub transform: [f32; 6],
     /// Premultiplied tint color packed as RGBA8 unorm (`pack4x8unorm` layout).
-    /// A value of `0` means no tint is applied.
+    /// A value of `0` together with the alpha mask mode means no tint is applied.
     pub tint: u32,
-    /// [`TintMode`](vello_common::paint::TintMode) discriminant. Only meaningful when `tint != 0`.
+    /// [`TintMode`](vello_common::paint::TintMode) discriminant.
     pub tint_mode: u32,
     /// Number of transparent padding pixels around the image in the atlas.
     pub image_padding: u32,
@@ -311,21 +345,27 @@ pub(crate) struct GpuSweepGradient {
     pub _padding: [u32; 2],
 }
 
-// Constants for packing extend_mode and texture_width.
-const EXTEND_MODE_MASK: u32 = 1 << 30;
-const TEXTURE_WIDTH_MASK: u32 = !EXTEND_MODE_MASK;
+// Constants for packing extend_mode, the dithering flag and texture_width.
+const DITHERED_FLAG: u32 = 1 << 28;
+const TEXTURE_WIDTH_MASK: u32 = DITHERED_FLAG - 1;
 
-/// Pack `extend_mode` and `texture_width` into a single u32.
+/// Pack `extend_mode`, the dithering flag and `texture_width` into a single u32.
 /// `extend_mode`: 0=Pad, 1=Repeat, 2=Reflect (stored in bits 30 & 31)
-/// `texture_width`: stored in bits 0-29 (max value: 2^30-1)
+/// `dithered`: whether the ramp is stored with extra precision and dithered (stored in bit 28)
+/// `texture_width`: stored in bits 0-27 (max value: 2^28-1)
 #[inline(always)]
-pub(crate) fn pack_texture_width_and_extend_mode(texture_width: u32, extend_mode: u32) -> u32 {
+pub(crate) fn pack_texture_width_and_extend_mode(
+    texture_width: u32,
+    extend_mode: u32,
+    dithered: bool,
+) -> u32 {
     debug_assert!(extend_mode <= 2, "extend_mode must be less or equal to 2");
     debug_assert!(
         texture_width <= TEXTURE_WIDTH_MASK,
         "texture_width {texture_width} exceeds maximum value {TEXTURE_WIDTH_MASK}"
     );
-    (extend_mode << 30) | (texture_width & TEXTURE_WIDTH_MASK)
+    let dithered = if dithered { DITHERED_FLAG } else { 0 };
+    (extend_mode << 30) | dithered | (texture_width & TEXTURE_WIDTH_MASK)
 }
 
 /// Pack radial gradient `kind` and `f_is_swapped` into a single u32.
@@ -378,12 +418,19 @@ pub(crate) fn pack_image_params(
 /// The tint color is premultiplied before packing into a u32 in the same layout
 /// as WGSL `pack4x8unorm`. Returns `(0, 0)` when no tint is specified, which
 /// the shader interprets as "no tint".
+///
+/// Fully transparent tints are packed as a transparent multiply tint, so that they can be told
+/// apart from no tint at all. Both tint modes turn every pixel transparent in that case.
 #[inline(always)]
 pub(crate) fn pack_tint(tint: Option<vello_common::paint::Tint>) -> (u32, u32) {
     match tint {
         Some(t) => {
             let color = t.color.premultiply().to_rgba8().to_u32();
-            (color, t.mode.as_u32())
+            if color == 0 {
+                (0, vello_common::paint::TintMode::Multiply.as_u32())
+            } else {
+                (color, t.mode.as_u32())
+            }
         }
         None => (0, 0),
     }
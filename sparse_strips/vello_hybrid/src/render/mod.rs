@@ -21,7 +21,9 @@ pub use common::{Config, GpuStrip, RenderSize};
 #[cfg(all(feature = "webgl", feature = "probe"))]
 pub use vello_common::probe::{Probe, ProbeResult};
 #[cfg(feature = "webgl")]
-pub use webgl::{WebGlAtlasWriter, WebGlRenderer, WebGlTextureWithDimensions};
+pub use webgl::{
+    WebGlAtlasWriter, WebGlRenderer, WebGlTextureBindings, WebGlTextureWithDimensions,
+};
 #[cfg(all(feature = "webgl", feature = "probe"))]
 pub use webgl::{WebGlPendingProbe, WebGlProbeError, WebGlProbeStatus};
 #[cfg(feature = "wgpu")]
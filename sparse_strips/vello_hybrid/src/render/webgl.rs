@@ -25,20 +25,24 @@ use crate::{
     GpuStrip, RenderError, RenderSettings, RenderSize, Resources,
     filter::{FilterContext, FilterInstanceData, FilterPassState, FilterPassTarget},
     gradient_cache::GradientRampCache,
+    inline_image::{restore_inline_images, upload_inline_images},
+    pattern::prepare_pattern_tiles,
     render::{
         Config,
         common::{
-            GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS, GPU_ENCODED_IMAGE_SIZE_TEXELS,
-            GPU_LINEAR_GRADIENT_SIZE_TEXELS, GPU_RADIAL_GRADIENT_SIZE_TEXELS,
-            GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect, GpuEncodedImage,
-            GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient, GpuSweepGradient,
-            normalize_atlas_config, pack_image_offset, pack_image_params, pack_image_size,
-            pack_radial_kind_and_swapped, pack_texture_width_and_extend_mode, pack_tint,
+            EXTERNAL_IMAGE_SOURCE_FLAG, GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS,
+            GPU_ENCODED_IMAGE_SIZE_TEXELS, GPU_LINEAR_GRADIENT_SIZE_TEXELS,
+            GPU_RADIAL_GRADIENT_SIZE_TEXELS, GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect,
+            GpuEncodedImage, GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient,
+            GpuSweepGradient, StripShaderVariant, normalize_atlas_config, pack_image_offset,
+            pack_image_params, pack_image_size, pack_radial_kind_and_swapped,
+            pack_texture_width_and_extend_mode, pack_tint,
         },
     },
     scene::Scene,
     schedule::{
-        LoadOp, RendererBackend, RootRenderTarget, Scheduler, SchedulerState, StripPassRenderTarget,
+        ExternalTextureRun, LoadOp, RendererBackend, RootRenderTarget, Scheduler, SchedulerState,
+        StripPassRenderTarget,
     },
 };
 use alloc::sync::Arc;
@@ -50,6 +54,7 @@ use core::fmt::Debug;
 use core::ops::Deref;
 #[cfg(feature = "text")]
 use glifo::{GLYPH_PADDING, PendingClearRect};
+use hashbrown::HashMap;
 use resource::{Buffer, FragmentShader, Framebuffer, Program, Texture, VertexArray, VertexShader};
 #[cfg(feature = "probe")]
 use thiserror::Error;
@@ -61,17 +66,18 @@ use vello_common::multi_atlas::{AtlasConfig, AtlasId};
 use vello_common::probe::Probe;
 use vello_common::render_graph::LayerId;
 use vello_common::{
+    TextureId,
     coarse::WideTile,
     encode::{
-        EncodedBlurredRoundedRectangle, EncodedGradient, EncodedKind, EncodedPaint,
-        MAX_GRADIENT_LUT_SIZE, RadialKind,
+        EncodedBlurredRoundedRectangle, EncodedExternalTexture, EncodedGradient, EncodedKind,
+        EncodedPaint, MAX_GRADIENT_LUT_SIZE, RadialKind,
     },
     paint::{ImageId, ImageSource},
     peniko::{self},
     pixmap::Pixmap,
     tile::Tile,
 };
-use vello_sparse_shaders::{clear_slots, filters, render_strips};
+use vello_sparse_shaders::{clear_slots, filters, render_strips, render_strips_no_blend};
 #[cfg(feature = "probe")]
 use web_sys::WebGlSync;
 use web_sys::wasm_bindgen::{JsCast, JsValue};
@@ -102,6 +108,46 @@ fn get_max_texture_array_layers(gl: &WebGl2RenderingContext) -> u32 {
         .unwrap() as u32
 }
 
+/// Runtime bindings for [externally owned textures](`TextureId`) sampled by external texture
+/// paints.
+#[derive(Debug, Default, Clone)]
+pub struct WebGlTextureBindings {
+    textures: HashMap<TextureId, WebGlTexture>,
+}
+
+impl WebGlTextureBindings {
+    /// Create an empty binding map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert or replace a texture binding.
+    ///
+    /// The texture must be a `TEXTURE_2D` created with the renderer's WebGL context, with a
+    /// float-sampleable format such as `RGBA8`. Only mip level 0 is read, and the texture must
+    /// be complete without mipmaps, e.g. by setting `TEXTURE_MIN_FILTER` to `NEAREST` or
+    /// `LINEAR`; WebGL returns transparent black for incomplete textures.
+    #[inline]
+    pub fn insert(&mut self, texture_id: TextureId, texture: WebGlTexture) {
+        self.textures.insert(texture_id, texture);
+    }
+
+    /// Get a texture binding.
+    #[inline]
+    fn get(&self, texture_id: TextureId) -> Option<&WebGlTexture> {
+        self.textures.get(&texture_id)
+    }
+
+    /// Remove a texture binding.
+    ///
+    /// This returns the removed [`WebGlTexture`] binding if it existed.
+    #[inline]
+    pub fn remove(&mut self, texture_id: TextureId) -> Option<WebGlTexture> {
+        self.textures.remove(&texture_id)
+    }
+}
+
 /// Vello Hybrid's WebGL2 Renderer.
 #[derive(Debug)]
 pub struct WebGlRenderer {
@@ -328,7 +374,8 @@ impl WebGlRenderer {
         // and the maximum gradient LUT size - worst case scenario.
         let max_gradient_cache_size =
             max_texture_dimension_2d * max_texture_dimension_2d / MAX_GRADIENT_LUT_SIZE as u32;
-        let gradient_cache = GradientRampCache::new(max_gradient_cache_size, settings.level);
+        let gradient_cache =
+            GradientRampCache::new(max_gradient_cache_size, settings.level, settings.dithering);
         let filter_context = FilterContext::new(settings.atlas_config);
 
         Self {
@@ -348,11 +395,18 @@ impl WebGlRenderer {
     /// Render `scene` using WebGL2
     ///
     /// This method creates GPU resources as needed and schedules potentially multiple draw calls.
+    ///
+    /// Every [`TextureId`] referenced by the scene must have a binding; this returns
+    /// [`RenderError::MissingTextureBinding`] otherwise. See [`WebGlTextureBindings::insert`] for
+    /// the requirements on the bound textures.
+    ///
+    /// To render without any texture bindings, you can pass an empty [`WebGlTextureBindings`].
     pub fn render(
         &mut self,
         scene: &Scene,
         resources: &mut Resources,
         render_size: &RenderSize,
+        texture_bindings: &WebGlTextureBindings,
     ) -> Result<(), RenderError> {
         debug_assert_eq!(
             RenderSize {
@@ -369,7 +423,13 @@ impl WebGlRenderer {
                 self,
                 |renderer, glyph_renderer, atlas_count, atlas_config, atlas_id| {
                     renderer
-                        .render_to_atlas(glyph_renderer, atlas_count, atlas_config, atlas_id)
+                        .render_to_atlas(
+                            glyph_renderer,
+                            atlas_count,
+                            atlas_config,
+                            atlas_id,
+                            texture_bindings,
+                        )
                         .expect("Failed to render glyphs to atlas");
                 },
                 |renderer, image_cache, upload, dst_x, dst_y| {
@@ -383,13 +443,67 @@ impl WebGlRenderer {
             );
         }
 
-        self.render_scene(
-            scene,
+        let mut frame_images = Vec::new();
+        let mut inline_sources = Vec::new();
+        // The encoded paints are borrowed again when rendering the scene, so the borrow needs
+        // to end before that.
+        let mut encoded_paints = scene.encoded_paints.borrow_mut();
+        let result = upload_inline_images(
+            self,
             &mut resources.image_cache,
-            render_size,
-            true,
-            RootRenderTarget::UserSurface,
-        )?;
+            &mut encoded_paints,
+            &mut inline_sources,
+            &mut frame_images,
+            |renderer, image_cache, image_id, pixmap| {
+                renderer.write_to_atlas(image_cache, image_id, pixmap, None);
+            },
+        )
+        .and_then(|()| {
+            prepare_pattern_tiles(
+                self,
+                &scene.pattern_tiles,
+                &mut resources.image_cache,
+                &mut encoded_paints,
+                &mut frame_images,
+                |renderer, tile_scene, atlas_count, atlas_config, atlas_id| {
+                    renderer.render_to_atlas(
+                        tile_scene,
+                        atlas_count,
+                        atlas_config,
+                        atlas_id,
+                        texture_bindings,
+                    )
+                },
+            )
+        });
+        drop(encoded_paints);
+        let result = result.and_then(|()| {
+            self.render_scene(
+                scene,
+                &mut resources.image_cache,
+                render_size,
+                true,
+                RootRenderTarget::UserSurface,
+                texture_bindings,
+            )
+        });
+
+        // The inline images and pattern tiles are only needed for this frame.
+        restore_inline_images(&mut scene.encoded_paints.borrow_mut(), inline_sources);
+        for image_id in frame_images {
+            resources.deallocate_image(image_id, |image_resource| {
+                self.clear_atlas_region(
+                    image_resource.atlas_id,
+                    [
+                        u32::from(image_resource.offset[0]),
+                        u32::from(image_resource.offset[1]),
+                    ],
+                    u32::from(image_resource.width),
+                    u32::from(image_resource.height),
+                );
+            });
+        }
+        result?;
 
         #[cfg(feature = "text")]
         {
@@ -415,6 +529,10 @@ impl WebGlRenderer {
     /// ensuring atlas content is committed before any subsequent
     /// [`render`](Self::render) call (the two methods share GPU resources that
     /// are staged by `queue.write_*` and only applied on the next `queue.submit`).
+    ///
+    /// `texture_bindings` provides [externally bound textures](`WebGlTextureBindings`)
+    /// referenced by the scene. Pass `&WebGlTextureBindings::new()` if the scene does
+    /// not use any.
     #[doc(hidden)]
     pub fn render_to_atlas(
         &mut self,
@@ -422,6 +540,7 @@ impl WebGlRenderer {
         atlas_count: u32,
         atlas_config: AtlasConfig,
         atlas_id: AtlasId,
+        texture_bindings: &WebGlTextureBindings,
     ) -> Result<(), RenderError> {
         self.programs
             .maybe_resize_atlas_texture_array(&self.gl, atlas_count);
@@ -472,6 +591,7 @@ impl WebGlRenderer {
             &atlas_render_size,
             false,
             RootRenderTarget::AtlasLayer,
+            texture_bindings,
         );
         self.dummy_image_cache = Some(dummy_image_cache);
 
@@ -590,6 +710,7 @@ impl WebGlRenderer {
             &render_size,
             true,
             RootRenderTarget::AtlasLayer,
+            &WebGlTextureBindings::new(),
         );
         let probe_framebuffer = self
             .programs
@@ -627,6 +748,7 @@ impl WebGlRenderer {
         render_size: &RenderSize,
         clear: bool,
         root_output_target: RootRenderTarget,
+        texture_bindings: &WebGlTextureBindings,
     ) -> Result<(), RenderError> {
         if !self.filter_context.filter_textures.is_empty() {
             self.programs.clear_filter_atlas_textures(&self.gl);
@@ -641,7 +763,7 @@ impl WebGlRenderer {
         self.filter_context
             .prepare(&scene.render_graph, image_cache, &mut encoded_paints)?;
 
-        self.prepare_gpu_encoded_paints(&encoded_paints, image_cache);
+        self.prepare_gpu_encoded_paints(&encoded_paints, image_cache, texture_bindings)?;
 
         self.programs
             .maybe_resize_atlas_texture_array(&self.gl, image_cache.atlas_count() as u32);
@@ -668,11 +790,13 @@ impl WebGlRenderer {
         }
         self.programs.resources.depth_cleared_this_frame = false;
         let mut ctx = WebGlRendererContext {
+            strip_variant: StripShaderVariant::for_scene(scene),
             programs: &mut self.programs,
             gl: &self.gl,
             image_cache,
             filter_context: &self.filter_context,
             filter_pass_state: &mut self.filter_pass_state,
+            texture_bindings,
         };
         self.scheduler.do_scene(
             &mut self.scheduler_state,
@@ -717,6 +841,10 @@ impl WebGlRenderer {
     ///
     /// This is the WebGL analogue of the wgpu Renderer's `upload_image` method.
     /// It allocates space in the image cache and uploads the image data to the atlas texture.
+    ///
+    /// The image is always sampled at its full size, see
+    /// [`upload_image_with_mip_levels`](Self::upload_image_with_mip_levels) for images that are
+    /// drawn minified.
     pub fn upload_image<T: WebGlAtlasWriter>(
         &mut self,
         resources: &mut Resources,
@@ -725,6 +853,27 @@ impl WebGlRenderer {
         self.upload_image_with(&mut resources.image_cache, writer, IMAGE_PADDING)
     }
 
+    /// Upload a pixmap like [`upload_image`](Self::upload_image), together with its mip levels.
+    ///
+    /// Minified draws with medium or high quality sample from the mip levels, so that they
+    /// don't alias. The levels take up about a third more space in the atlas.
+    pub fn upload_image_with_mip_levels(
+        &mut self,
+        resources: &mut Resources,
+        pixmap: &Pixmap,
+    ) -> ImageId {
+        let image_id = self.upload_image(resources, pixmap);
+        resources.build_mip_levels(
+            self,
+            image_id,
+            pixmap,
+            |renderer, image_cache, level_id, level| {
+                renderer.write_to_atlas(image_cache, level_id, level, None);
+            },
+        );
+        image_id
+    }
+
     pub(crate) fn upload_image_with<T: WebGlAtlasWriter>(
         &mut self,
         image_cache: &mut ImageCache,
@@ -774,7 +923,7 @@ impl WebGlRenderer {
 
     /// Destroy an image from the cache and clear the allocated slot in the atlas.
     pub fn destroy_image(&mut self, resources: &mut Resources, image_id: ImageId) {
-        if let Some(image_resource) = resources.image_cache.deallocate(image_id) {
+        resources.deallocate_image(image_id, |image_resource| {
             let padding = image_resource.padding as u32;
             self.clear_atlas_region(
                 image_resource.atlas_id,
@@ -785,7 +934,7 @@ impl WebGlRenderer {
                 image_resource.width as u32 + padding * 2,
                 image_resource.height as u32 + padding * 2,
             );
-        }
+        });
     }
 
     /// Returns a reference to the underlying atlas texture array.
@@ -838,7 +987,8 @@ impl WebGlRenderer {
         &mut self,
         encoded_paints: &[EncodedPaint],
         image_cache: &ImageCache,
-    ) {
+        texture_bindings: &WebGlTextureBindings,
+    ) -> Result<(), RenderError> {
         self.encoded_paints
             .resize_with(encoded_paints.len(), || GPU_PAINT_PLACEHOLDER);
         self.paint_idxs.resize(encoded_paints.len() + 1, 0);
@@ -851,7 +1001,20 @@ impl WebGlRenderer {
                     if let ImageSource::OpaqueId { id: image_id, .. } = img.source {
                         let image_resource: Option<&ImageResource> = image_cache.get(image_id);
                         if let Some(image_resource) = image_resource {
-                            let gpu_image = self.encode_image_paint(img, image_resource);
+                            let level_resource = image_cache
+                                .get_mip_level(image_id, img.mip_level())
+                                .unwrap_or(image_resource);
+                            let gpu_image = if core::ptr::eq(level_resource, image_resource) {
+                                self.encode_image_paint(img, image_resource)
+                            } else {
+                                // The image is drawn minified, so sample from a level of its
+                                // mip pyramid instead.
+                                let level_image = img.with_level_size(
+                                    (image_resource.width, image_resource.height),
+                                    (level_resource.width, level_resource.height),
+                                );
+                                self.encode_image_paint(&level_image, level_resource)
+                            };
                             self.encoded_paints[encoded_paint_idx] = gpu_image;
                             current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
                         }
@@ -871,9 +1034,12 @@ impl WebGlRenderer {
                     self.encoded_paints[encoded_paint_idx] = gpu_gradient;
                     current_idx += gradient_size_texels;
                 }
-                EncodedPaint::ExternalTexture(_external_texture) => {
-                    // TODO: External textures are not yet supported.
-                    log::warn!("External textures are not yet supported in the WebGL backend");
+                EncodedPaint::ExternalTexture(img) => {
+                    if texture_bindings.get(img.texture_id).is_none() {
+                        return Err(RenderError::MissingTextureBinding(img.texture_id));
+                    }
+                    self.encoded_paints[encoded_paint_idx] =
+                        self.encode_external_texture_paint(img);
                     current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
                 }
                 EncodedPaint::BlurredRoundedRect(blurred_rect) => {
@@ -881,9 +1047,15 @@ impl WebGlRenderer {
                         Self::encode_blurred_rounded_rect_paint(blurred_rect);
                     current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
                 }
+                EncodedPaint::MeshGradient(_) | EncodedPaint::Custom(_) => {
+                    unreachable!(
+                        "Mesh gradients and custom paints are never encoded by vello_hybrid"
+                    )
+                }
             }
         }
         self.paint_idxs[encoded_paints.len()] = current_idx;
+        Ok(())
     }
 
     fn encode_image_paint(
@@ -913,6 +1085,30 @@ impl WebGlRenderer {
         })
     }
 
+    fn encode_external_texture_paint(&self, image: &EncodedExternalTexture) -> GpuEncodedPaint {
+        let transform = image.transform.as_coeffs().map(|x| x as f32);
+        let region = image.source_region;
+        let image_size = pack_image_size(region.width(), region.height());
+        let image_offset = pack_image_offset(region.x0, region.y0);
+        let image_params = pack_image_params(
+            image.sampler.quality as u32,
+            image.sampler.x_extend as u32,
+            image.sampler.y_extend as u32,
+            0,
+        ) | EXTERNAL_IMAGE_SOURCE_FLAG;
+        let (tint, tint_mode) = pack_tint(image.tint);
+
+        GpuEncodedPaint::Image(GpuEncodedImage {
+            image_params,
+            image_size,
+            image_offset,
+            transform,
+            tint,
+            tint_mode,
+            image_padding: 0,
+        })
+    }
+
     fn encode_gradient_paint(
         &self,
         gradient: &EncodedGradient,
@@ -925,8 +1121,11 @@ impl WebGlRenderer {
             peniko::Extend::Repeat => 1,
             peniko::Extend::Reflect => 2,
         };
-        let texture_width_and_extend_mode =
-            pack_texture_width_and_extend_mode(gradient_width, extend_mode);
+        let texture_width_and_extend_mode = pack_texture_width_and_extend_mode(
+            gradient_width,
+            extend_mode,
+            self.gradient_cache.dithering(),
+        );
 
         match &gradient.kind {
             EncodedKind::Linear(_) => GpuEncodedPaint::LinearGradient(GpuLinearGradient {
@@ -1019,10 +1218,10 @@ fn clear_atlas_region(renderer: &mut WebGlRenderer, rect: &PendingClearRect) {
 /// Contains the WebGL programs and resources for rendering.
 #[derive(Debug)]
 struct WebGlPrograms {
-    /// Program for rendering wide tile commands.
-    strip_program: Program,
-    /// Uniform locations for the strip program
-    strip_uniforms: StripUniforms,
+    /// Programs for rendering wide tile commands, indexed by [`StripShaderVariant`].
+    strip_programs: [Program; StripShaderVariant::COUNT],
+    /// Uniform locations for each of the strip programs.
+    strip_uniforms: [StripUniforms; StripShaderVariant::COUNT],
     /// Program for clearing slots in slot textures.
     clear_program: Program,
     /// Uniform locations for the `clear_program`.
@@ -1050,7 +1249,7 @@ struct FilterPassUniforms {
     original_tex: WebGlUniformLocation,
 }
 
-/// Uniform locations for `strip_program`.
+/// Uniform locations for a strip program.
 #[derive(Debug)]
 struct StripUniforms {
     /// Config uniform block index for vertex shader.
@@ -1185,11 +1384,19 @@ impl WebGlPrograms {
         filter_context: &FilterContext,
         slot_count: usize,
     ) -> Self {
-        let strip_program = create_shader_program(
-            &gl,
-            render_strips::VERTEX_SOURCE,
-            render_strips::FRAGMENT_SOURCE,
-        );
+        // The programs, in the order of `StripShaderVariant`.
+        let strip_programs = [
+            create_shader_program(
+                &gl,
+                render_strips::VERTEX_SOURCE,
+                render_strips::FRAGMENT_SOURCE,
+            ),
+            create_shader_program(
+                &gl,
+                render_strips_no_blend::VERTEX_SOURCE,
+                render_strips_no_blend::FRAGMENT_SOURCE,
+            ),
+        ];
         let clear_program = create_shader_program(
             &gl,
             clear_slots::VERTEX_SOURCE,
@@ -1199,7 +1406,9 @@ impl WebGlPrograms {
             create_shader_program(&gl, filters::VERTEX_SOURCE, filters::FRAGMENT_SOURCE);
         let filter_uniforms = get_filter_pass_uniforms(&gl, &filter_program);
 
-        let strip_uniforms = get_strip_uniforms(&gl, &strip_program);
+        let strip_uniforms = strip_programs
+            .each_ref()
+            .map(|program| get_strip_uniforms(&gl, program));
         let clear_uniforms = get_clear_uniforms(&gl, &clear_program);
 
         let resources = create_webgl_resources(&gl, image_cache, filter_context, slot_count);
@@ -1217,7 +1426,7 @@ impl WebGlPrograms {
         );
 
         Self {
-            strip_program,
+            strip_programs,
             clear_program,
             filter_program,
             filter_uniforms,
@@ -2076,7 +2285,10 @@ fn create_shader_program(
     program
 }
 
-/// Get the  uniform locations for the `render_strips` program.
+/// Get the uniform locations for a `render_strips` program.
+///
+/// The identifiers in the generated GLSL are derived from the bindings, so they are the same
+/// for all permutations of the shader.
 fn get_strip_uniforms(gl: &WebGl2RenderingContext, program: &Program) -> StripUniforms {
     let config_vs_name = render_strips::vertex::CONFIG;
     let config_vs_block_index = gl.get_uniform_block_index(program, config_vs_name);
@@ -2538,11 +2750,14 @@ fn initialize_clear_vao(gl: &WebGl2RenderingContext, resources: &WebGlResources)
 // the GPU must finish drawing before the next `upload_strip_pair` can be executed (effectively pausing
 // execution). Investigate a buffer pool or creating a new buffer per pass.
 struct WebGlRendererContext<'a> {
+    /// The variant of the `render_strips` shader used for the current scene.
+    strip_variant: StripShaderVariant,
     programs: &'a mut WebGlPrograms,
     gl: &'a WebGl2RenderingContext,
     image_cache: &'a ImageCache,
     filter_context: &'a FilterContext,
     filter_pass_state: &'a mut FilterPassState,
+    texture_bindings: &'a WebGlTextureBindings,
 }
 
 impl WebGlRendererContext<'_> {
@@ -2551,6 +2766,7 @@ impl WebGlRendererContext<'_> {
         &mut self,
         opaque_strips: &[GpuStrip],
         alpha_strips: &[GpuStrip],
+        external_texture_runs: &[ExternalTextureRun],
         target: StripPassRenderTarget,
         load: LoadOp,
     ) {
@@ -2612,12 +2828,12 @@ impl WebGlRendererContext<'_> {
                 );
                 self.gl.bind_buffer_base(
                     WebGl2RenderingContext::UNIFORM_BUFFER,
-                    self.programs.strip_uniforms.config_vs_block_index,
+                    self.programs.strip_uniforms[self.strip_variant as usize].config_vs_block_index,
                     Some(buf),
                 );
                 self.gl.bind_buffer_base(
                     WebGl2RenderingContext::UNIFORM_BUFFER,
-                    self.programs.strip_uniforms.config_fs_block_index,
+                    self.programs.strip_uniforms[self.strip_variant as usize].config_fs_block_index,
                     Some(buf),
                 );
 
@@ -2639,12 +2855,12 @@ impl WebGlRendererContext<'_> {
 
                 self.gl.bind_buffer_base(
                     WebGl2RenderingContext::UNIFORM_BUFFER,
-                    self.programs.strip_uniforms.config_vs_block_index,
+                    self.programs.strip_uniforms[self.strip_variant as usize].config_vs_block_index,
                     Some(&self.programs.resources.view_config_buffer),
                 );
                 self.gl.bind_buffer_base(
                     WebGl2RenderingContext::UNIFORM_BUFFER,
-                    self.programs.strip_uniforms.config_fs_block_index,
+                    self.programs.strip_uniforms[self.strip_variant as usize].config_fs_block_index,
                     Some(&self.programs.resources.view_config_buffer),
                 );
 
@@ -2667,12 +2883,12 @@ impl WebGlRendererContext<'_> {
                 // Use slot config buffer for rendering to a slot texture.
                 self.gl.bind_buffer_base(
                     WebGl2RenderingContext::UNIFORM_BUFFER,
-                    self.programs.strip_uniforms.config_vs_block_index,
+                    self.programs.strip_uniforms[self.strip_variant as usize].config_vs_block_index,
                     Some(&self.programs.resources.slot_config_buffer),
                 );
                 self.gl.bind_buffer_base(
                     WebGl2RenderingContext::UNIFORM_BUFFER,
-                    self.programs.strip_uniforms.config_fs_block_index,
+                    self.programs.strip_uniforms[self.strip_variant as usize].config_fs_block_index,
                     Some(&self.programs.resources.slot_config_buffer),
                 );
 
@@ -2694,7 +2910,9 @@ impl WebGlRendererContext<'_> {
         }
 
         // Use the strip program.
-        self.gl.use_program(Some(&self.programs.strip_program));
+        self.gl.use_program(Some(
+            &self.programs.strip_programs[self.strip_variant as usize],
+        ));
 
         // Set up attributes.
         self.gl
@@ -2706,8 +2924,10 @@ impl WebGlRendererContext<'_> {
             WebGl2RenderingContext::TEXTURE_2D,
             Some(&self.programs.resources.alphas_texture),
         );
-        self.gl
-            .uniform1i(Some(&self.programs.strip_uniforms.alphas_texture), 0);
+        self.gl.uniform1i(
+            Some(&self.programs.strip_uniforms[self.strip_variant as usize].alphas_texture),
+            0,
+        );
 
         let clip_texture_idx = match &target {
             StripPassRenderTarget::SlotTexture(1) => 0,
@@ -2718,8 +2938,10 @@ impl WebGlRendererContext<'_> {
             WebGl2RenderingContext::TEXTURE_2D,
             Some(&self.programs.resources.slot_textures[clip_texture_idx]),
         );
-        self.gl
-            .uniform1i(Some(&self.programs.strip_uniforms.clip_input_texture), 1);
+        self.gl.uniform1i(
+            Some(&self.programs.strip_uniforms[self.strip_variant as usize].clip_input_texture),
+            1,
+        );
 
         // Bind atlas texture array for image rendering
         self.gl.active_texture(WebGl2RenderingContext::TEXTURE2);
@@ -2727,8 +2949,10 @@ impl WebGlRendererContext<'_> {
             WebGl2RenderingContext::TEXTURE_2D_ARRAY,
             Some(&self.programs.resources.atlas_texture_array.texture),
         );
-        self.gl
-            .uniform1i(Some(&self.programs.strip_uniforms.atlas_texture_array), 2);
+        self.gl.uniform1i(
+            Some(&self.programs.strip_uniforms[self.strip_variant as usize].atlas_texture_array),
+            2,
+        );
 
         // Bind encoded paints texture for image metadata
         self.gl.active_texture(WebGl2RenderingContext::TEXTURE3);
@@ -2737,11 +2961,17 @@ impl WebGlRendererContext<'_> {
             Some(&self.programs.resources.encoded_paints_texture),
         );
         self.gl.uniform1i(
-            Some(&self.programs.strip_uniforms.encoded_paints_texture_fs),
+            Some(
+                &self.programs.strip_uniforms[self.strip_variant as usize]
+                    .encoded_paints_texture_fs,
+            ),
             3,
         );
         self.gl.uniform1i(
-            Some(&self.programs.strip_uniforms.encoded_paints_texture_vs),
+            Some(
+                &self.programs.strip_uniforms[self.strip_variant as usize]
+                    .encoded_paints_texture_vs,
+            ),
             3,
         );
 
@@ -2751,18 +2981,22 @@ impl WebGlRendererContext<'_> {
             WebGl2RenderingContext::TEXTURE_2D,
             Some(&self.programs.resources.gradient_texture),
         );
-        self.gl
-            .uniform1i(Some(&self.programs.strip_uniforms.gradient_texture), 4);
+        self.gl.uniform1i(
+            Some(&self.programs.strip_uniforms[self.strip_variant as usize].gradient_texture),
+            4,
+        );
 
-        // We don't support external textures in our WebGL backend yet; instead we bind a
-        // placeholder so the shader's sampler binding is satisfied.
+        // Bind a placeholder so the shader's sampler binding is satisfied. External textures are
+        // bound per run when drawing the alpha strips.
         self.gl.active_texture(WebGl2RenderingContext::TEXTURE5);
         self.gl.bind_texture(
             WebGl2RenderingContext::TEXTURE_2D,
             Some(&self.programs.resources.placeholder_external_texture),
         );
-        self.gl
-            .uniform1i(Some(&self.programs.strip_uniforms.external_texture), 5);
+        self.gl.uniform1i(
+            Some(&self.programs.strip_uniforms[self.strip_variant as usize].external_texture),
+            5,
+        );
 
         // TODO: Today, we only support early-z rejection on the final view. If we wanted to support
         // intermediate layers, we would require separate depth buffers for each target. We can explore
@@ -2802,45 +3036,16 @@ impl WebGlRendererContext<'_> {
 
             // Alpha pass: back-to-front, depth test ON, depth write OFF, blend ON.
             if alpha_count > 0 {
-                // Rebind attribute pointers with offset to start at the alpha portion
-                // of the buffer.
-                let alpha_byte_offset = opaque_count * STRIP_STRIDE;
-                for i in 0..STRIP_ATTR_COUNT {
-                    self.gl.vertex_attrib_i_pointer_with_i32(
-                        i as u32,
-                        1,
-                        WebGl2RenderingContext::UNSIGNED_INT,
-                        STRIP_STRIDE,
-                        i * 4 + alpha_byte_offset,
-                    );
-                }
-
                 self.gl.depth_mask(false);
                 self.gl.enable(WebGl2RenderingContext::BLEND);
-                self.gl.draw_arrays_instanced(
-                    WebGl2RenderingContext::TRIANGLE_STRIP,
-                    0,
-                    4,
-                    alpha_count,
-                );
-
-                // Restore attribute offsets to base for subsequent passes.
-                for i in 0..STRIP_ATTR_COUNT {
-                    self.gl.vertex_attrib_i_pointer_with_i32(
-                        i as u32,
-                        1,
-                        WebGl2RenderingContext::UNSIGNED_INT,
-                        STRIP_STRIDE,
-                        i * 4,
-                    );
-                }
+                self.draw_alpha_strips(opaque_count, alpha_count, external_texture_runs);
             }
 
             // Restore state.
             self.gl.disable(WebGl2RenderingContext::DEPTH_TEST);
             self.gl.depth_mask(true);
             self.gl.enable(WebGl2RenderingContext::BLEND);
-        } else {
+        } else if external_texture_runs.is_empty() {
             // Slot texture / intermediate: single draw with blending, no depth.
             self.gl.draw_arrays_instanced(
                 WebGl2RenderingContext::TRIANGLE_STRIP,
@@ -2848,12 +3053,84 @@ impl WebGlRendererContext<'_> {
                 4,
                 opaque_count + alpha_count,
             );
+        } else {
+            debug_assert_eq!(
+                opaque_count, 0,
+                "The scheduler only allows the final view to have opaque strips"
+            );
+            self.draw_alpha_strips(opaque_count, alpha_count, external_texture_runs);
         }
 
         // Clean up.
         self.gl.bind_vertex_array(None);
     }
 
+    /// Draw the alpha strips, which start at `alpha_start` in the strips buffer, binding the
+    /// external texture of each run.
+    fn draw_alpha_strips(
+        &self,
+        alpha_start: i32,
+        alpha_count: i32,
+        external_texture_runs: &[ExternalTextureRun],
+    ) {
+        if external_texture_runs.is_empty() {
+            self.draw_strip_range(alpha_start, alpha_count);
+            return;
+        }
+
+        // Each run is drawn with a different external texture binding. Runs go from
+        // `run.strips_start` to the next run's `strips_start`; the last run goes to the end of
+        // the strips buffer.
+        self.gl.active_texture(WebGl2RenderingContext::TEXTURE5);
+        for (i, run) in external_texture_runs.iter().enumerate() {
+            let texture = self
+                .texture_bindings
+                .get(run.texture_id)
+                .expect("external texture bindings were validated during paint preparation");
+            self.gl
+                .bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(texture));
+            let start = run.strips_start as i32;
+            let end = external_texture_runs
+                .get(i + 1)
+                .map_or(alpha_count, |next| next.strips_start as i32);
+            self.draw_strip_range(alpha_start + start, end - start);
+        }
+    }
+
+    /// Draw `count` strips starting at `start` in the strips buffer.
+    fn draw_strip_range(&self, start: i32, count: i32) {
+        if start != 0 {
+            // WebGL2 has no base instance, so rebind the attribute pointers with an offset to
+            // start at the given strip.
+            let byte_offset = start * STRIP_STRIDE;
+            for i in 0..STRIP_ATTR_COUNT {
+                self.gl.vertex_attrib_i_pointer_with_i32(
+                    i as u32,
+                    1,
+                    WebGl2RenderingContext::UNSIGNED_INT,
+                    STRIP_STRIDE,
+                    i * 4 + byte_offset,
+                );
+            }
+        }
+
+        self.gl
+            .draw_arrays_instanced(WebGl2RenderingContext::TRIANGLE_STRIP, 0, 4, count);
+
+        if start != 0 {
+            // Restore attribute offsets to base for subsequent passes.
+            for i in 0..STRIP_ATTR_COUNT {
+                self.gl.vertex_attrib_i_pointer_with_i32(
+                    i as u32,
+                    1,
+                    WebGl2RenderingContext::UNSIGNED_INT,
+                    STRIP_STRIDE,
+                    i * 4,
+                );
+            }
+        }
+    }
+
     /// Clear specific slots from a slot texture.
     fn do_clear_slots_render_pass(&mut self, ix: usize, slot_indices: &[u32]) {
         if slot_indices.is_empty() {
@@ -2928,11 +3205,17 @@ impl RendererBackend for WebGlRendererContext<'_> {
         &mut self,
         opaque_strips: &[GpuStrip],
         alpha_strips: &[GpuStrip],
-        _external_texture_runs: &[crate::schedule::ExternalTextureRun],
+        external_texture_runs: &[ExternalTextureRun],
         target: StripPassRenderTarget,
         load_op: LoadOp,
     ) {
-        self.do_strip_render_pass(opaque_strips, alpha_strips, target, load_op);
+        self.do_strip_render_pass(
+            opaque_strips,
+            alpha_strips,
+            external_texture_runs,
+            target,
+            load_op,
+        );
     }
 
     fn apply_filter(&mut self, layer_id: LayerId) {
@@ -3101,8 +3384,10 @@ impl WebGlAtlasWriter for Pixmap {
             Some(atlas_texture_array),
         );
 
-        // Convert pixmap data to the format expected by WebGL
-        let rgba_data = self.data_as_u8_slice();
+        // Convert pixmap data to the format expected by WebGL. The shaders expect the pixels
+        // of all images to be in sRGB.
+        let pixmap = self.to_srgb();
+        let rgba_data = pixmap.data_as_u8_slice();
 
         // Upload the image data to the specific layer and region of the atlas texture array
         gl.tex_sub_image_3d_with_opt_u8_array(
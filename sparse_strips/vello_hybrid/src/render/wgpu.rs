@@ -23,15 +23,18 @@ use crate::{
     GpuStrip, RenderError, RenderSettings, RenderSize, Resources,
     filter::{FilterContext, FilterInstanceData, FilterPassState, FilterPassTarget},
     gradient_cache::GradientRampCache,
+    inline_image::{restore_inline_images, upload_inline_images},
+    pattern::prepare_pattern_tiles,
     render::{
         Config,
         common::{
-            GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS, GPU_ENCODED_IMAGE_SIZE_TEXELS,
-            GPU_LINEAR_GRADIENT_SIZE_TEXELS, GPU_RADIAL_GRADIENT_SIZE_TEXELS,
-            GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect, GpuEncodedImage,
-            GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient, GpuSweepGradient,
-            normalize_atlas_config, pack_image_offset, pack_image_params, pack_image_size,
-            pack_radial_kind_and_swapped, pack_texture_width_and_extend_mode, pack_tint,
+            EXTERNAL_IMAGE_SOURCE_FLAG, GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS,
+            GPU_ENCODED_IMAGE_SIZE_TEXELS, GPU_LINEAR_GRADIENT_SIZE_TEXELS,
+            GPU_RADIAL_GRADIENT_SIZE_TEXELS, GPU_SWEEP_GRADIENT_SIZE_TEXELS, GpuBlurredRoundedRect,
+            GpuEncodedImage, GpuEncodedPaint, GpuLinearGradient, GpuRadialGradient,
+            GpuSweepGradient, StripShaderVariant, normalize_atlas_config, pack_image_offset,
+            pack_image_params, pack_image_size, pack_radial_kind_and_swapped,
+            pack_texture_width_and_extend_mode, pack_tint,
         },
     },
     scene::Scene,
@@ -76,8 +79,6 @@ const GPU_PAINT_PLACEHOLDER: GpuEncodedPaint = GpuEncodedPaint::LinearGradient(G
     transform: [0.0; 6],
 });
 
-const EXTERNAL_IMAGE_SOURCE_FLAG: u32 = 1 << 14;
-
 /// Options for the renderer
 #[derive(Debug)]
 pub struct RenderTargetConfig {
@@ -208,7 +209,8 @@ impl Renderer {
         // and the maximum gradient LUT size - worst case scenario.
         let max_gradient_cache_size =
             max_texture_dimension_2d * max_texture_dimension_2d / MAX_GRADIENT_LUT_SIZE as u32;
-        let gradient_cache = GradientRampCache::new(max_gradient_cache_size, settings.level);
+        let gradient_cache =
+            GradientRampCache::new(max_gradient_cache_size, settings.level, settings.dithering);
 
         let filter_context = FilterContext::new(settings.atlas_config);
         Self {
@@ -337,28 +339,88 @@ impl Renderer {
         let mut encoded_paints = scene.encoded_paints.borrow_mut();
         let scene_paint_count = encoded_paints.len();
 
-        self.prepare_filter_textures(
-            scene,
-            device,
-            encoder,
+        let mut frame_images = Vec::new();
+        let mut inline_sources = Vec::new();
+        let result = upload_inline_images(
+            self,
             &mut resources.image_cache,
             &mut encoded_paints,
-        )?;
+            &mut inline_sources,
+            &mut frame_images,
+            |renderer, image_cache, image_id, pixmap| {
+                renderer.write_to_atlas(
+                    image_cache,
+                    device,
+                    queue,
+                    encoder,
+                    image_id,
+                    pixmap,
+                    None,
+                );
+            },
+        )
+        .and_then(|()| {
+            prepare_pattern_tiles(
+                self,
+                &scene.pattern_tiles,
+                &mut resources.image_cache,
+                &mut encoded_paints,
+                &mut frame_images,
+                |renderer, tile_scene, atlas_count, atlas_config, atlas_id| {
+                    renderer.render_to_atlas(
+                        tile_scene,
+                        atlas_count,
+                        atlas_config,
+                        device,
+                        queue,
+                        atlas_id,
+                        texture_bindings,
+                    )
+                },
+            )
+        })
+        .and_then(|()| {
+            self.prepare_filter_textures(
+                scene,
+                device,
+                encoder,
+                &mut resources.image_cache,
+                &mut encoded_paints,
+            )?;
 
-        let result = self.render_scene(
-            scene,
-            device,
-            queue,
-            encoder,
-            render_size,
-            view,
-            &resources.image_cache,
-            &encoded_paints,
-            true,
-            RootRenderTarget::UserSurface,
-            texture_bindings,
-        );
+            self.render_scene(
+                scene,
+                device,
+                queue,
+                encoder,
+                render_size,
+                view,
+                &resources.image_cache,
+                &encoded_paints,
+                true,
+                RootRenderTarget::UserSurface,
+                texture_bindings,
+            )
+        });
 
+        // The inline images and pattern tiles are only needed for this frame.
+        restore_inline_images(&mut encoded_paints, inline_sources);
+        for image_id in frame_images {
+            resources.deallocate_image(image_id, |image_resource| {
+                self.clear_atlas_region(
+                    device,
+                    queue,
+                    encoder,
+                    image_resource.atlas_id,
+                    [
+                        u32::from(image_resource.offset[0]),
+                        u32::from(image_resource.offset[1]),
+                    ],
+                    u32::from(image_resource.width),
+                    u32::from(image_resource.height),
+                );
+            });
+        }
         encoded_paints.truncate(scene_paint_count);
         #[cfg(feature = "text")]
         resources.after_render(self, |renderer, rect| {
@@ -510,6 +572,7 @@ impl Renderer {
             Self::clear_view(encoder, view);
         }
         let mut ctx = RendererContext {
+            strip_variant: StripShaderVariant::for_scene(scene),
             programs: &mut self.programs,
             device,
             queue,
@@ -564,6 +627,10 @@ impl Renderer {
     /// 1. Reserves space in the image cache
     /// 2. Writes the image data directly to the atlas
     /// 3. Returns the `ImageId` for use in rendering
+    ///
+    /// The image is always sampled at its full size, see
+    /// [`upload_image_with_mip_levels`](Self::upload_image_with_mip_levels) for images that are
+    /// drawn minified.
     pub fn upload_image<T: AtlasWriter>(
         &mut self,
         resources: &mut Resources,
@@ -582,6 +649,30 @@ impl Renderer {
         )
     }
 
+    /// Upload a pixmap like [`upload_image`](Self::upload_image), together with its mip levels.
+    ///
+    /// Minified draws with medium or high quality sample from the mip levels, so that they
+    /// don't alias. The levels take up about a third more space in the atlas.
+    pub fn upload_image_with_mip_levels(
+        &mut self,
+        resources: &mut Resources,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        pixmap: &Pixmap,
+    ) -> vello_common::paint::ImageId {
+        let image_id = self.upload_image(resources, device, queue, encoder, pixmap);
+        resources.build_mip_levels(
+            self,
+            image_id,
+            pixmap,
+            |renderer, image_cache, level_id, level| {
+                renderer.write_to_atlas(image_cache, device, queue, encoder, level_id, level, None);
+            },
+        );
+        image_id
+    }
+
     pub(crate) fn upload_image_with<T: AtlasWriter>(
         &mut self,
         image_cache: &mut ImageCache,
@@ -651,7 +742,7 @@ impl Renderer {
         encoder: &mut CommandEncoder,
         image_id: vello_common::paint::ImageId,
     ) {
-        if let Some(image_resource) = resources.image_cache.deallocate(image_id) {
+        resources.deallocate_image(image_id, |image_resource| {
             let padding = image_resource.padding as u32;
 
             self.clear_atlas_region(
@@ -666,7 +757,7 @@ impl Renderer {
                 image_resource.width as u32 + padding * 2,
                 image_resource.height as u32 + padding * 2,
             );
-        }
+        });
     }
 
     /// Returns a reference to the underlying atlas texture array.
@@ -751,7 +842,20 @@ impl Renderer {
                     if let ImageSource::OpaqueId { id: image_id, .. } = img.source {
                         let image_resource: Option<&ImageResource> = image_cache.get(image_id);
                         if let Some(image_resource) = image_resource {
-                            let image_paint = self.encode_image_paint(img, image_resource);
+                            let level_resource = image_cache
+                                .get_mip_level(image_id, img.mip_level())
+                                .unwrap_or(image_resource);
+                            let image_paint = if core::ptr::eq(level_resource, image_resource) {
+                                self.encode_image_paint(img, image_resource)
+                            } else {
+                                // The image is drawn minified, so sample from a level of its
+                                // mip pyramid instead.
+                                let level_image = img.with_level_size(
+                                    (image_resource.width, image_resource.height),
+                                    (level_resource.width, level_resource.height),
+                                );
+                                self.encode_image_paint(&level_image, level_resource)
+                            };
                             self.encoded_paints[encoded_paint_idx] = image_paint;
                             current_idx += GPU_ENCODED_IMAGE_SIZE_TEXELS;
                         }
@@ -784,6 +888,11 @@ impl Renderer {
                         Self::encode_blurred_rounded_rect_paint(blurred_rect);
                     current_idx += GPU_BLURRED_ROUNDED_RECT_SIZE_TEXELS;
                 }
+                EncodedPaint::MeshGradient(_) | EncodedPaint::Custom(_) => {
+                    unreachable!(
+                        "Mesh gradients and custom paints are never encoded by vello_hybrid"
+                    )
+                }
             }
         }
         self.paint_idxs[encoded_paints.len()] = current_idx;
@@ -853,8 +962,11 @@ impl Renderer {
             peniko::Extend::Repeat => 1,
             peniko::Extend::Reflect => 2,
         };
-        let texture_width_and_extend_mode =
-            pack_texture_width_and_extend_mode(gradient_width, extend_mode);
+        let texture_width_and_extend_mode = pack_texture_width_and_extend_mode(
+            gradient_width,
+            extend_mode,
+            self.gradient_cache.dithering(),
+        );
 
         match &gradient.kind {
             EncodedKind::Linear(_) => GpuEncodedPaint::LinearGradient(GpuLinearGradient {
@@ -960,17 +1072,24 @@ fn clear_atlas_region(queue: &Queue, renderer: &mut Renderer, rect: &PendingClea
     );
 }
 
-/// Defines the GPU resources and pipelines for rendering.
+/// The pipelines for rendering strips with one variant of the `render_strips` shader.
 #[derive(Debug)]
-struct Programs {
+struct StripPipelines {
     /// Pipelines for rendering strips to slot textures (depth test OFF, depth write OFF, blending ON).
     /// The first pipeline should be used for color attachments in the native pixel format,
     /// the second for color attachments in RGBA8.
-    slot_strip_pipelines: [RenderPipeline; 2],
+    slot: [RenderPipeline; 2],
     /// Alpha pipelines for rendering strips to Output targets (depth test ON, depth write OFF, blending ON).
-    alpha_strip_pipelines: [RenderPipeline; 2],
+    alpha: [RenderPipeline; 2],
     /// Opaque pipelines for rendering strips to Output targets (depth test ON, depth write ON, blending OFF).
-    opaque_strip_pipelines: [RenderPipeline; 2],
+    opaque: [RenderPipeline; 2],
+}
+
+/// Defines the GPU resources and pipelines for rendering.
+#[derive(Debug)]
+struct Programs {
+    /// Strip pipelines for each [`StripShaderVariant`], indexed by the variant.
+    strip_pipelines: [StripPipelines; StripShaderVariant::COUNT],
     /// Depth texture for early-z rejection on the Output target.
     depth_texture: Texture,
     /// View for the depth texture.
@@ -1284,11 +1403,6 @@ impl Programs {
                 }],
             });
 
-        let strip_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Strip Shader"),
-            source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::RENDER_STRIPS.into()),
-        });
-
         let clear_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Clear Slots Shader"),
             source: wgpu::ShaderSource::Wgsl(vello_sparse_shaders::wgsl::CLEAR_SLOTS.into()),
@@ -1322,39 +1436,42 @@ impl Programs {
             attributes: &GpuStrip::vertex_attributes(),
         };
 
-        let create_strip_pipelines =
-            |label, blend, depth_stencil: Option<wgpu::DepthStencilState>| -> [RenderPipeline; 2] {
-                core::array::from_fn(|i| {
-                    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                        label: Some(label),
-                        layout: Some(&strip_pipeline_layout),
-                        vertex: wgpu::VertexState {
-                            module: &strip_shader,
-                            entry_point: Some("vs_main"),
-                            buffers: core::slice::from_ref(&strip_vertex_state),
-                            compilation_options: PipelineCompilationOptions::default(),
-                        },
-                        fragment: Some(wgpu::FragmentState {
-                            module: &strip_shader,
-                            entry_point: Some("fs_main"),
-                            targets: &[Some(ColorTargetState {
-                                format: strip_formats[i],
-                                blend,
-                                write_mask: ColorWrites::ALL,
-                            })],
-                            compilation_options: PipelineCompilationOptions::default(),
-                        }),
-                        primitive: wgpu::PrimitiveState {
-                            topology: wgpu::PrimitiveTopology::TriangleStrip,
-                            ..Default::default()
-                        },
-                        depth_stencil: depth_stencil.clone(),
-                        multisample: wgpu::MultisampleState::default(),
-                        multiview_mask: None,
-                        cache: None,
-                    })
+        let create_strip_pipelines = |strip_shader: &wgpu::ShaderModule,
+                                      label,
+                                      blend,
+                                      depth_stencil: Option<wgpu::DepthStencilState>|
+         -> [RenderPipeline; 2] {
+            core::array::from_fn(|i| {
+                device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(label),
+                    layout: Some(&strip_pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: strip_shader,
+                        entry_point: Some("vs_main"),
+                        buffers: core::slice::from_ref(&strip_vertex_state),
+                        compilation_options: PipelineCompilationOptions::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: strip_shader,
+                        entry_point: Some("fs_main"),
+                        targets: &[Some(ColorTargetState {
+                            format: strip_formats[i],
+                            blend,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                        compilation_options: PipelineCompilationOptions::default(),
+                    }),
+                    primitive: wgpu::PrimitiveState {
+                        topology: wgpu::PrimitiveTopology::TriangleStrip,
+                        ..Default::default()
+                    },
+                    depth_stencil: depth_stencil.clone(),
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview_mask: None,
+                    cache: None,
                 })
-            };
+            })
+        };
 
         let depth_stencil = |depth_write_enabled| wgpu::DepthStencilState {
             format: depth_format,
@@ -1364,21 +1481,40 @@ impl Programs {
             bias: wgpu::DepthBiasState::default(),
         };
 
-        // Slot pipelines: depth test OFF, depth write OFF, blending ON.
-        let slot_strip_pipelines = create_strip_pipelines(
-            "Strip Slot Pipeline",
-            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-            None,
-        );
-        // Alpha pipelines: depth test ON (LessEqual), depth write OFF, blending ON.
-        let alpha_strip_pipelines = create_strip_pipelines(
-            "Strip Alpha Pipeline",
-            Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-            Some(depth_stencil(false)),
-        );
-        // Opaque pipelines: depth test ON (LessEqual), depth write ON, blending OFF.
-        let opaque_strip_pipelines =
-            create_strip_pipelines("Strip Opaque Pipeline", None, Some(depth_stencil(true)));
+        // The shader sources, in the order of `StripShaderVariant`.
+        let strip_shader_sources = [
+            vello_sparse_shaders::wgsl::RENDER_STRIPS,
+            vello_sparse_shaders::wgsl::RENDER_STRIPS_NO_BLEND,
+        ];
+        let strip_pipelines = strip_shader_sources.map(|source| {
+            let strip_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Strip Shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            StripPipelines {
+                // Slot pipelines: depth test OFF, depth write OFF, blending ON.
+                slot: create_strip_pipelines(
+                    &strip_shader,
+                    "Strip Slot Pipeline",
+                    Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    None,
+                ),
+                // Alpha pipelines: depth test ON (LessEqual), depth write OFF, blending ON.
+                alpha: create_strip_pipelines(
+                    &strip_shader,
+                    "Strip Alpha Pipeline",
+                    Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    Some(depth_stencil(false)),
+                ),
+                // Opaque pipelines: depth test ON (LessEqual), depth write ON, blending OFF.
+                opaque: create_strip_pipelines(
+                    &strip_shader,
+                    "Strip Opaque Pipeline",
+                    None,
+                    Some(depth_stencil(true)),
+                ),
+            }
+        });
 
         let clear_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Clear Slots Pipeline"),
@@ -1753,9 +1889,7 @@ impl Programs {
         let depth_texture_view = depth_texture.create_view(&TextureViewDescriptor::default());
 
         Self {
-            slot_strip_pipelines,
-            alpha_strip_pipelines,
-            opaque_strip_pipelines,
+            strip_pipelines,
             depth_texture,
             depth_texture_view,
             depth_cleared_this_frame: false,
@@ -2592,6 +2726,8 @@ impl Programs {
 /// A struct containing references to the many objects needed to get work
 /// scheduled onto the GPU.
 struct RendererContext<'a> {
+    /// The variant of the `render_strips` shader used for the current scene.
+    strip_variant: StripShaderVariant,
     programs: &'a mut Programs,
     device: &'a Device,
     queue: &'a Queue,
@@ -2829,13 +2965,14 @@ impl RendererContext<'_> {
         render_pass.set_bind_group(3, &self.programs.resources.gradient_bind_group, &[]);
         render_pass.set_vertex_buffer(0, self.programs.resources.strips_buffer.slice(..));
 
+        let pipelines = &self.programs.strip_pipelines[self.strip_variant as usize];
         if opaque_count > 0 {
             // Opaque pass
             debug_assert!(
                 is_final_view,
                 "The scheduler only allows the final view to have opaque strips"
             );
-            render_pass.set_pipeline(&self.programs.opaque_strip_pipelines[pipeline_idx]);
+            render_pass.set_pipeline(&pipelines.opaque[pipeline_idx]);
             render_pass.set_bind_group(1, &self.programs.resources.atlas_bind_group, &[]);
             render_pass.draw(0..4, 0..opaque_count);
         }
@@ -2843,9 +2980,9 @@ impl RendererContext<'_> {
         if alpha_count > 0 {
             // Alpha pass
             if is_final_view {
-                render_pass.set_pipeline(&self.programs.alpha_strip_pipelines[pipeline_idx]);
+                render_pass.set_pipeline(&pipelines.alpha[pipeline_idx]);
             } else {
-                render_pass.set_pipeline(&self.programs.slot_strip_pipelines[pipeline_idx]);
+                render_pass.set_pipeline(&pipelines.slot[pipeline_idx]);
             }
 
             let alpha_start = opaque_count;
@@ -3185,7 +3322,8 @@ impl AtlasWriter for Pixmap {
                 },
                 aspect: wgpu::TextureAspect::All,
             },
-            self.data_as_u8_slice(),
+            // The shaders expect the pixels of all images to be in sRGB.
+            self.to_srgb().data_as_u8_slice(),
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
                 bytes_per_row: Some(4 * width),
@@ -3,12 +3,15 @@
 
 //! Persistent renderer resources shared across frames.
 
+use crate::render::common::IMAGE_PADDING;
 #[cfg(feature = "text")]
 use crate::text::GlyphAtlasResources;
 #[cfg(feature = "text")]
 use glifo::GlyphPrepCache;
-use vello_common::image_cache::ImageCache;
+use vello_common::image_cache::{ImageCache, ImageResource};
 use vello_common::multi_atlas::AtlasConfig;
+use vello_common::paint::ImageId;
+use vello_common::pixmap::Pixmap;
 
 /// Persistent resources required by Vello Hybrid for rendering.
 #[derive(Debug)]
@@ -32,6 +35,50 @@ impl Resources {
             glyph_resources: None,
         }
     }
+
+    /// Allocate the mip levels of an image that was uploaded from `pixmap`, and call
+    /// `upload_to_atlas` for each of them to write it into the atlas.
+    pub(crate) fn build_mip_levels<T>(
+        &mut self,
+        backend: &mut T,
+        id: ImageId,
+        pixmap: &Pixmap,
+        mut upload_to_atlas: impl FnMut(&mut T, &ImageCache, ImageId, &Pixmap),
+    ) {
+        let mut previous: Option<Pixmap> = None;
+        loop {
+            let pixmap = previous.as_ref().unwrap_or(pixmap);
+            if pixmap.width() <= 1 && pixmap.height() <= 1 {
+                break;
+            }
+            let next = pixmap.downsample();
+            // If the atlas is full, the image is sampled from the highest level that could be
+            // allocated instead.
+            let Ok(level_id) = self.image_cache.allocate_mip_level(
+                id,
+                u32::from(next.width()),
+                u32::from(next.height()),
+                IMAGE_PADDING,
+            ) else {
+                break;
+            };
+            upload_to_atlas(backend, &self.image_cache, level_id, &next);
+            previous = Some(next);
+        }
+    }
+
+    /// Deallocate an image and all of its mip levels, calling `clear` for each of the freed
+    /// atlas regions.
+    pub(crate) fn deallocate_image(&mut self, id: ImageId, mut clear: impl FnMut(&ImageResource)) {
+        for level_id in self.image_cache.take_mip_levels(id) {
+            if let Some(level) = self.image_cache.deallocate(level_id) {
+                clear(&level);
+            }
+        }
+        if let Some(image) = self.image_cache.deallocate(id) {
+            clear(&image);
+        }
+    }
 }
 
 impl Default for Resources {
@@ -5,6 +5,7 @@
 
 #[cfg(feature = "text")]
 use crate::Resources;
+use crate::pattern::PatternTile;
 use crate::sampling::SampleRect;
 #[cfg(feature = "text")]
 use crate::text::GlyphRunBuilder;
@@ -16,18 +17,20 @@ use vello_common::TextureId;
 use vello_common::blurred_rounded_rect::BlurredRoundedRectangle;
 use vello_common::clip::ClipContext;
 use vello_common::coarse::{MODE_HYBRID, Wide, WideTilesBbox};
-use vello_common::encode::{EncodeExt, EncodedExternalTexture, EncodedPaint};
+use vello_common::encode::{EncodeExt, EncodedPaint};
 use vello_common::fearless_simd::Level;
 use vello_common::filter_effects::Filter;
 use vello_common::geometry::RectU16;
 use vello_common::kurbo::{Affine, BezPath, Rect, Shape, Stroke};
 use vello_common::mask::Mask;
 use vello_common::multi_atlas::AtlasConfig;
-use vello_common::paint::{Paint, PaintType, Tint};
+use vello_common::paint::{ExternalTexture, Paint, PaintType, Tint};
+use vello_common::pattern::Pattern;
 #[cfg(feature = "text")]
 use vello_common::peniko::FontData;
 use vello_common::peniko::color::palette::css::BLACK;
-use vello_common::peniko::{BlendMode, Compose, Extend, Fill, ImageQuality, ImageSampler, Mix};
+use vello_common::peniko::color::{AlphaColor, Srgb};
+use vello_common::peniko::{BlendMode, Compose, Fill, ImageQuality, Mix};
 use vello_common::render_graph::{RenderGraph, RenderNodeKind};
 use vello_common::render_state::RenderState;
 use vello_common::strip_generator::{GenerationMode, StripGenerator, StripStorage};
@@ -127,6 +130,7 @@ impl Default for SceneConstraints {
 
 impl SceneConstraints {
     const DEFAULT_BLENDING_ONLY: u32 = 1 << 0;
+    const DEFAULT_BLENDING_EVERYWHERE: u32 = 1 << 1;
 
     /// Create a new, unconstrained set of scene constraints.
     #[inline(always)]
@@ -147,14 +151,35 @@ impl SceneConstraints {
         Self(self.0 | Self::DEFAULT_BLENDING_ONLY)
     }
 
+    /// Caller guarantees that the scene will only use the default (normal, source-over)
+    /// blend mode, in the root layer as well as in all nested layers.
+    ///
+    /// This implies [`default_blending_only`](Self::default_blending_only), and additionally
+    /// allows the renderer to use a specialized shader without support for blend modes.
+    ///
+    /// # Panics
+    ///
+    /// The renderer will panic if a non-default blend mode is used in any layer.
+    #[inline(always)]
+    pub fn default_blending_everywhere(self) -> Self {
+        Self(self.0 | Self::DEFAULT_BLENDING_ONLY | Self::DEFAULT_BLENDING_EVERYWHERE)
+    }
+
     #[inline(always)]
     fn use_default_blending_only(&self) -> bool {
         (self.0 & Self::DEFAULT_BLENDING_ONLY) != 0
     }
 
+    #[inline(always)]
+    pub(crate) fn use_default_blending_everywhere(&self) -> bool {
+        (self.0 & Self::DEFAULT_BLENDING_EVERYWHERE) != 0
+    }
+
     #[inline(always)]
     fn assert_blend_mode(&self, blend_mode: BlendMode, nested_layer: bool) {
-        if self.use_default_blending_only() && !nested_layer {
+        if self.use_default_blending_everywhere()
+            || (self.use_default_blending_only() && !nested_layer)
+        {
             assert!(
                 blend_mode == DEFAULT_BLEND_MODE,
                 "scene constrained to default blending"
@@ -182,6 +207,15 @@ pub struct RenderSettings {
     pub atlas_config: AtlasConfig,
     /// Constraints on the scene that the renderer can exploit for optimisation.
     pub constraints: SceneConstraints,
+    /// Whether to apply ordered dithering to gradients.
+    ///
+    /// Smooth gradients with a low contrast can show visible bands, because only a few
+    /// distinct 8-bit values are available to represent them. Dithering breaks up those bands
+    /// into a fine noise pattern. This requires storing the gradient ramps with twice the
+    /// precision, so it uses more memory for them.
+    ///
+    /// This setting is only used by renderers, and ignored by [`Scene::new_with`].
+    pub dithering: bool,
 }
 
 impl Default for RenderSettings {
@@ -190,6 +224,7 @@ impl Default for RenderSettings {
             level: Level::try_detect().unwrap_or(Level::baseline()),
             atlas_config: AtlasConfig::default(),
             constraints: SceneConstraints::new(),
+            dithering: false,
         }
     }
 }
@@ -201,7 +236,7 @@ impl Default for RenderSettings {
 #[derive(Debug)]
 pub struct Scene {
     /// Constraints on the scene that the renderer can exploit for optimisation.
-    constraints: SceneConstraints,
+    pub(crate) constraints: SceneConstraints,
     /// Width of the rendering surface in pixels.
     pub(crate) width: u16,
     /// Height of the rendering surface in pixels.
@@ -237,6 +272,8 @@ pub struct Scene {
     /// process one coarse batch before processing another fast path strip batch.
     /// Only meaningful in [`StripPathMode::Interleaved`] mode.
     pub(crate) coarse_batch_splits: Vec<usize>,
+    /// The pattern tiles that need to be rendered into the atlas before rendering the scene.
+    pub(crate) pattern_tiles: Vec<PatternTile>,
 }
 
 // We use this macro instead of a method to avoid borrowing issues in the corresponding methods.
@@ -322,6 +359,7 @@ impl Scene {
             fast_strips_buffer: FastStripsBuffer::default(),
             strip_path_mode: StripPathMode::FastOnly,
             coarse_batch_splits: Vec::new(),
+            pattern_tiles: Vec::new(),
         }
     }
 
@@ -348,37 +386,58 @@ impl Scene {
                 self.render_state.transform * self.render_state.paint_transform,
                 self.render_state.tint,
             ),
+            PaintType::MeshGradient(m) => {
+                log::warn!("Mesh gradients are not yet supported, using their average color");
+                m.average_color().into()
+            }
+            PaintType::Pattern(p) => self.encode_pattern(&p),
+            PaintType::Custom(_) => {
+                log::warn!("Custom paints are not supported, drawing nothing instead");
+                AlphaColor::<Srgb>::TRANSPARENT.into()
+            }
+            PaintType::ExternalTexture(t) => t.encode_into(
+                &mut self.encoded_paints.borrow_mut(),
+                self.render_state.transform * self.render_state.paint_transform,
+                self.render_state.tint,
+            ),
+            _ => {
+                log::warn!("Unsupported paint, drawing nothing instead");
+                AlphaColor::<Srgb>::TRANSPARENT.into()
+            }
         }
     }
 
+    /// Encode a pattern into an image paint that samples a tile of it, which is rendered
+    /// into the atlas when the scene is rendered.
+    fn encode_pattern(&mut self, pattern: &Pattern) -> Paint {
+        let paint_transform = self.render_state.transform * self.render_state.paint_transform;
+        let mut encoded_paints = self.encoded_paints.borrow_mut();
+        let Some((tile, image, image_transform)) =
+            PatternTile::new(pattern, paint_transform, encoded_paints.len())
+        else {
+            return AlphaColor::<Srgb>::TRANSPARENT.into();
+        };
+        self.pattern_tiles.push(tile);
+        image.encode_into(&mut encoded_paints, paint_transform * image_transform, None)
+    }
+
     /// Encode the current external texture into a [`Paint`] that can be used for rendering.
     fn encode_external_texture_paint(
         &mut self,
         texture_id: TextureId,
         source_region: RectU16,
         quality: ImageQuality,
-        x_extend: Extend,
-        y_extend: Extend,
         transform: Affine,
     ) -> Paint {
-        let idx = self.encoded_paints.borrow().len();
-        let encoded = EncodedExternalTexture {
-            texture_id,
-            source_region,
-            sampler: ImageSampler {
-                x_extend,
-                y_extend,
-                quality,
-                alpha: 1.0,
-            },
-            may_have_transparency: true,
-            transform: transform.inverse(),
-            tint: self.render_state.tint,
-        };
-        self.encoded_paints
-            .borrow_mut()
-            .push(EncodedPaint::ExternalTexture(encoded));
-        Paint::Indexed(vello_common::paint::IndexedPaint::new(idx))
+        // The destination is always the transformed source region, so padding only affects
+        // the filter taps at the edges of the region.
+        ExternalTexture::new(texture_id, source_region)
+            .with_quality(quality)
+            .encode_into(
+                &mut self.encoded_paints.borrow_mut(),
+                transform,
+                self.render_state.tint,
+            )
     }
 
     /// Fill a path with the current paint and fill rule.
@@ -562,6 +621,9 @@ impl Scene {
     /// [source regions][`SampleRect::source_region`] must be within bounds of that texture. The
     /// texture is treated as premultiplied alpha in the render target's color space. See the
     /// backend's binding type for more information on texture requirements.
+    ///
+    /// To fill arbitrary shapes with a texture or to tile it, set an [`ExternalTexture`] as the
+    /// [paint](Self::set_paint) instead.
     #[expect(
         clippy::cast_possible_truncation,
         reason = "f64→f32 truncation is acceptable for pixel coordinates"
@@ -572,17 +634,6 @@ impl Scene {
         quality: ImageQuality,
         rects: impl IntoIterator<Item = SampleRect>,
     ) {
-        // This API currently doesn't take extend mode parameters: as of writing, the
-        // `render_strips.wgsl` shader does not use extend modes to sample across boundaries, i.e.,
-        // sampling near a boundary doesn't take extend modes into account when determining where
-        // the sample should be taken.
-        //
-        // Because in this API the destination drawn is always the transformed input rect, this
-        // means extend modes don't currently materially impact rendering. In general drawing with
-        // an external texture brush, extend modes would matter, so we still encode them.
-        let x_extend = Extend::Pad;
-        let y_extend = Extend::Pad;
-
         if self.can_emit_fast_strips() {
             for rect in rects {
                 if rect.source_region.is_empty() {
@@ -600,8 +651,6 @@ impl Scene {
                         texture_id,
                         rect.source_region,
                         quality,
-                        x_extend,
-                        y_extend,
                         transform,
                     );
                     let dst_rect = Rect::new(0., 0., w, h);
@@ -632,8 +681,6 @@ impl Scene {
                     texture_id,
                     rect.source_region,
                     quality,
-                    x_extend,
-                    y_extend,
                     transform,
                 );
 
@@ -661,8 +708,6 @@ impl Scene {
                         texture_id,
                         rect.source_region,
                         quality,
-                        x_extend,
-                        y_extend,
                         transform,
                     );
                     let dst_rect = Rect::new(0., 0., w, h);
@@ -1111,6 +1156,7 @@ impl Scene {
         self.fast_strips_buffer.clear();
         self.strip_path_mode = StripPathMode::FastOnly;
         self.coarse_batch_splits.clear();
+        self.pattern_tiles.clear();
 
         self.layer_id_next = 0;
         self.render_graph.clear();
@@ -1474,6 +1520,26 @@ mod tests {
         assert!(scene.wide.has_layers());
     }
 
+    #[test]
+    #[should_panic(expected = "scene constrained to default blending")]
+    fn default_blending_everywhere_rejects_nested_blend_layer() {
+        let mut scene = make_scene(SceneConstraints::new().default_blending_everywhere());
+        scene.push_layer(None, None, Some(0.5), None, None);
+        scene.push_blend_layer(BlendMode::new(Mix::Multiply, Compose::SrcOver));
+    }
+
+    #[test]
+    fn default_blending_everywhere_implies_default_blending_only() {
+        let mut scene = make_scene(SceneConstraints::new().default_blending_everywhere());
+        scene.set_paint(Color::from_rgba8(255, 0, 0, 255));
+        scene.fill_rect(&small_rect());
+        scene.push_layer(None, None, Some(0.5), None, None);
+        scene.fill_rect(&small_rect());
+        scene.pop_layer();
+
+        assert_eq!(scene.strip_path_mode, StripPathMode::Interleaved);
+    }
+
     #[test]
     fn reset_restores_fast_only() {
         let mut scene = unconstrained();
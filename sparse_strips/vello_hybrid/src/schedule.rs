@@ -1681,6 +1681,11 @@ impl Scheduler {
                     }
                     Some(EncodedPaint::Gradient(g)) => !g.may_have_transparency,
                     Some(EncodedPaint::BlurredRoundedRect(_)) => false,
+                    Some(EncodedPaint::MeshGradient(_) | EncodedPaint::Custom(_)) => {
+                        unreachable!(
+                            "Mesh gradients and custom paints are never encoded by vello_hybrid"
+                        )
+                    }
                     None => unreachable!("Paint must be in encoded paints"),
                 }
             }
@@ -1860,7 +1865,16 @@ impl Scheduler {
                         external_texture_id: None,
                     }
                 }
-                _ => unimplemented!("Unsupported image source"),
+                // Images that aren't uploaded yet are only uploaded by `Renderer::render`, so
+                // they are missing when rendering a scene into the atlas.
+                ImageSource::Pixmap(_) | ImageSource::Raw(_) => {
+                    log::warn!("Image has not been uploaded into the atlas, drawing nothing");
+                    ProcessedPaint {
+                        payload: 0,
+                        paint: (COLOR_SOURCE_PAYLOAD << 30) | (PAINT_TYPE_SOLID << 27),
+                        external_texture_id: None,
+                    }
+                }
             },
             EncodedPaint::ExternalTexture(texture) => {
                 let paint_packed = (COLOR_SOURCE_PAYLOAD << 29)
@@ -1901,6 +1915,9 @@ impl Scheduler {
                     external_texture_id: None,
                 }
             }
+            EncodedPaint::MeshGradient(_) | EncodedPaint::Custom(_) => {
+                unreachable!("Mesh gradients and custom paints are never encoded by vello_hybrid")
+            }
         }
     }
 }
@@ -0,0 +1,1202 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The drawing context of a single page.
+
+use crate::PdfDocument;
+use crate::paint::is_native_gradient;
+use crate::writer::{Matrix, Num};
+use std::fmt::Write as _;
+use vello_common::filter_effects::Filter;
+use vello_common::kurbo::{
+    Affine, BezPath, Cap, Join, PathEl, Point, Rect, Shape as _, Stroke, StrokeOpts,
+};
+use vello_common::mask::Mask;
+use vello_common::paint::{ImageSource, PaintType, Tint};
+use vello_common::pattern::{Pattern, PatternCommand};
+use vello_common::peniko::{BlendMode, Extend, Fill, ImageQuality, Mix};
+use vello_common::pixmap::Pixmap;
+use vello_common::render_state::RenderState;
+use vello_cpu::{RenderContext, RenderSettings, Resources};
+
+/// The tolerance used when flattening or stroking paths ourselves.
+const TOLERANCE: f64 = 0.1;
+
+/// A drawing context for a single page of a [`PdfDocument`].
+///
+/// The API mirrors the one of `vello_cpu::RenderContext`: paths, strokes, solid colors,
+/// linear and radial gradients, images, patterns as well as clip, blend, opacity and mask
+/// layers are converted into the equivalent PDF vector graphics. Content without a PDF
+/// equivalent (filters, sweep gradients, repeating gradients, tinted images, and
+/// blurred rounded rectangles) is rasterized with Vello CPU and embedded as an image.
+/// Patterns are rasterized as well if their content contains anything but solid colors,
+/// gradients and layers without filters.
+///
+/// Coordinates are given in points, with the origin at the top left of the page.
+/// Images referenced via [`ImageSource::OpaqueId`] cannot be resolved and are skipped.
+///
+/// The page is only added to the document once [`PdfContext::finish`] is called.
+#[derive(Debug)]
+pub struct PdfContext<'a> {
+    doc: &'a mut PdfDocument,
+    width: u16,
+    height: u16,
+    pub(crate) state: RenderState,
+    mask: Option<Mask>,
+    filter: Option<Filter>,
+    aliasing_threshold: Option<u8>,
+    /// The content stream of the page and of each open layer, innermost last.
+    scopes: Vec<Scope>,
+    /// The bounding box of the content streams: the page, or the tile of the pattern
+    /// that is being written.
+    bbox: [f64; 4],
+    /// The context that receives all drawing operations while a filter layer is open.
+    raster: Option<RasterLayer>,
+}
+
+#[derive(Debug, Default)]
+struct Scope {
+    content: String,
+    /// The number of clip paths that were pushed within this scope.
+    clip_depth: usize,
+    /// How the scope is composited into its parent, or `None` for the page itself.
+    props: Option<LayerProps>,
+}
+
+#[derive(Debug)]
+struct LayerProps {
+    blend_mode: BlendMode,
+    opacity: f32,
+    mask: Option<Mask>,
+}
+
+#[derive(Debug)]
+struct RasterLayer {
+    ctx: RenderContext,
+    /// The number of layers pushed on top of the filter layer.
+    depth: usize,
+    /// The last used mask and its resampled version.
+    scaled_mask: Option<(Mask, Mask)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Shape<'p> {
+    Fill(&'p BezPath),
+    Stroke(&'p BezPath),
+    BlurredRoundedRect {
+        rect: Rect,
+        radius: f32,
+        std_dev: f32,
+        invert: bool,
+    },
+}
+
+impl<'a> PdfContext<'a> {
+    pub(crate) fn new(doc: &'a mut PdfDocument, width: u16, height: u16) -> Self {
+        Self {
+            doc,
+            width,
+            height,
+            state: RenderState::default(),
+            mask: None,
+            filter: None,
+            aliasing_threshold: None,
+            scopes: vec![Scope::default()],
+            bbox: [0.0, 0.0, f64::from(width), f64::from(height)],
+            raster: None,
+        }
+    }
+
+    /// Fill a path.
+    pub fn fill_path(&mut self, path: &BezPath) {
+        self.draw(Shape::Fill(path));
+    }
+
+    /// Stroke a path.
+    pub fn stroke_path(&mut self, path: &BezPath) {
+        self.draw(Shape::Stroke(path));
+    }
+
+    /// Fill a rectangle.
+    pub fn fill_rect(&mut self, rect: &Rect) {
+        self.fill_path(&rect.to_path(TOLERANCE));
+    }
+
+    /// Stroke a rectangle.
+    pub fn stroke_rect(&mut self, rect: &Rect) {
+        self.stroke_path(&rect.to_path(TOLERANCE));
+    }
+
+    /// Fill a blurred rectangle with the given corner radius and standard deviation.
+    ///
+    /// See `vello_cpu::RenderContext::fill_blurred_rounded_rect` for details. Since PDF has
+    /// no equivalent, the rectangle is always rasterized.
+    pub fn fill_blurred_rounded_rect(
+        &mut self,
+        rect: &Rect,
+        radius: f32,
+        std_dev: f32,
+        invert: bool,
+    ) {
+        self.draw(Shape::BlurredRoundedRect {
+            rect: rect.abs(),
+            radius,
+            std_dev,
+            invert,
+        });
+    }
+
+    /// Push a new layer with the given properties.
+    ///
+    /// Note that the mask, if provided, needs to have the same size as the page. Otherwise,
+    /// it will be ignored. In addition to that, the mask will not be affected by the current
+    /// transformation matrix in place.
+    pub fn push_layer(
+        &mut self,
+        clip_path: Option<&BezPath>,
+        blend_mode: Option<BlendMode>,
+        opacity: Option<f32>,
+        mask: Option<Mask>,
+        filter: Option<Filter>,
+    ) {
+        let mask = mask.filter(|m| m.width() == self.width && m.height() == self.height);
+
+        if self.raster.is_some() {
+            self.sync_raster();
+            let raster = self.raster.as_mut().unwrap();
+            let mask = mask.map(|mask| raster.scale_mask(&mask));
+            raster
+                .ctx
+                .push_layer(clip_path, blend_mode, opacity, mask, filter);
+            raster.depth += 1;
+            return;
+        }
+
+        let props = LayerProps {
+            blend_mode: blend_mode.unwrap_or_default(),
+            opacity: opacity.unwrap_or(1.0),
+            mask,
+        };
+
+        if let Some(filter) = filter {
+            // Everything until the matching `pop_layer` is rasterized. The clip path and the
+            // filter are applied by Vello CPU, while the blend mode, opacity and mask are
+            // applied when compositing the result.
+            self.push_scope(props, None);
+            let scale = f64::from(self.doc.raster_scale());
+            let width = scaled_size(self.width, scale);
+            let height = scaled_size(self.height, scale);
+            self.raster = Some(RasterLayer {
+                ctx: RenderContext::new_with(width, height, raster_settings()),
+                depth: 0,
+                scaled_mask: None,
+            });
+            self.sync_raster();
+            let raster = self.raster.as_mut().unwrap();
+            raster
+                .ctx
+                .push_layer(clip_path, None, None, None, Some(filter));
+        } else {
+            let clip = clip_path.map(|path| (path, self.state.transform, self.state.fill_rule));
+            self.push_scope(props, clip);
+        }
+    }
+
+    /// Push a new clip layer.
+    pub fn push_clip_layer(&mut self, path: &BezPath) {
+        self.push_layer(Some(path), None, None, None, None);
+    }
+
+    /// Push a new blend layer.
+    pub fn push_blend_layer(&mut self, blend_mode: BlendMode) {
+        self.push_layer(None, Some(blend_mode), None, None, None);
+    }
+
+    /// Push a new opacity layer.
+    pub fn push_opacity_layer(&mut self, opacity: f32) {
+        self.push_layer(None, None, Some(opacity), None, None);
+    }
+
+    /// Push a new mask layer. The mask needs to have the same dimensions as the
+    /// page. The mask will not be affected by the current transform in place.
+    pub fn push_mask_layer(&mut self, mask: Mask) {
+        self.push_layer(None, None, None, Some(mask), None);
+    }
+
+    /// Push a filter layer that affects all subsequent drawing operations.
+    ///
+    /// The contents of the layer are rasterized.
+    pub fn push_filter_layer(&mut self, filter: Filter) {
+        self.push_layer(None, None, None, None, Some(filter));
+    }
+
+    /// Pop the last-pushed layer.
+    pub fn pop_layer(&mut self) {
+        if let Some(raster) = &mut self.raster {
+            raster.ctx.pop_layer();
+            if raster.depth > 0 {
+                raster.depth -= 1;
+                return;
+            }
+
+            let mut raster = self.raster.take().unwrap();
+            raster.ctx.flush();
+            let mut pixmap = Pixmap::new(raster.ctx.width(), raster.ctx.height());
+            raster.ctx.render(&mut pixmap, &mut Resources::new());
+            let bbox = Rect::new(0.0, 0.0, f64::from(self.width), f64::from(self.height));
+            self.draw_raster(&pixmap, bbox);
+        }
+
+        if self.scopes.len() > 1 {
+            self.pop_scope();
+        }
+    }
+
+    /// Set the aliasing threshold.
+    ///
+    /// This only has an effect on rasterized content.
+    pub fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>) {
+        self.aliasing_threshold = aliasing_threshold;
+    }
+
+    /// Set the current stroke.
+    pub fn set_stroke(&mut self, stroke: Stroke) {
+        self.state.stroke = stroke;
+    }
+
+    /// Get the current stroke.
+    pub fn stroke(&self) -> &Stroke {
+        &self.state.stroke
+    }
+
+    /// Set the current paint.
+    pub fn set_paint(&mut self, paint: impl Into<PaintType>) {
+        self.state.paint = paint.into();
+    }
+
+    /// Get the current paint.
+    pub fn paint(&self) -> &PaintType {
+        &self.state.paint
+    }
+
+    /// Set the tint applied to image paints.
+    pub fn set_tint(&mut self, tint: Option<Tint>) {
+        self.state.tint = tint;
+    }
+
+    /// Reset the tint.
+    pub fn reset_tint(&mut self) {
+        self.state.tint = None;
+    }
+
+    /// Set the current blend mode.
+    ///
+    /// Only the mix modes are supported, composition modes other than source-over are
+    /// ignored for vector content.
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.state.blend_mode = blend_mode;
+    }
+
+    /// Get the current blend mode.
+    pub fn blend_mode(&self) -> BlendMode {
+        self.state.blend_mode
+    }
+
+    /// Set the current paint transform.
+    ///
+    /// The paint transform is applied to the paint after the transform of the geometry
+    /// the paint is drawn in, i.e., the paint transform is applied after the global transform.
+    pub fn set_paint_transform(&mut self, paint_transform: Affine) {
+        self.state.paint_transform = paint_transform;
+    }
+
+    /// Get the current paint transform.
+    pub fn paint_transform(&self) -> &Affine {
+        &self.state.paint_transform
+    }
+
+    /// Reset the current paint transform.
+    pub fn reset_paint_transform(&mut self) {
+        self.state.paint_transform = Affine::IDENTITY;
+    }
+
+    /// Set the current fill rule.
+    pub fn set_fill_rule(&mut self, fill_rule: Fill) {
+        self.state.fill_rule = fill_rule;
+    }
+
+    /// Get the current fill rule.
+    pub fn fill_rule(&self) -> &Fill {
+        &self.state.fill_rule
+    }
+
+    /// Set the mask to use for path-painting operations. The mask needs to have the
+    /// same size as the page.
+    pub fn set_mask(&mut self, mask: Mask) {
+        self.mask = Some(mask);
+    }
+
+    /// Reset the mask.
+    pub fn reset_mask(&mut self) {
+        self.mask = None;
+    }
+
+    /// Set the current transform.
+    pub fn set_transform(&mut self, transform: Affine) {
+        self.state.transform = transform;
+    }
+
+    /// Get the current transform.
+    pub fn transform(&self) -> &Affine {
+        &self.state.transform
+    }
+
+    /// Reset the current transform.
+    pub fn reset_transform(&mut self) {
+        self.state.transform = Affine::IDENTITY;
+    }
+
+    /// Set a filter that is applied to each subsequent drawing operation.
+    pub fn set_filter_effect(&mut self, filter: Filter) {
+        self.filter = Some(filter);
+    }
+
+    /// Reset the filter effect.
+    pub fn reset_filter_effect(&mut self) {
+        self.filter = None;
+    }
+
+    /// Push a new clip path to the clip stack.
+    pub fn push_clip_path(&mut self, path: &BezPath) {
+        if self.raster.is_some() {
+            self.sync_raster();
+            self.raster.as_mut().unwrap().ctx.push_clip_path(path);
+            return;
+        }
+
+        let transform = self.state.transform;
+        let fill_rule = self.state.fill_rule;
+        let scope = self.scope();
+        scope.content.push_str("q ");
+        write_clip(&mut scope.content, path, transform, fill_rule);
+        scope.clip_depth += 1;
+    }
+
+    /// Pop a clip path from the clip stack.
+    pub fn pop_clip_path(&mut self) {
+        if let Some(raster) = &mut self.raster {
+            raster.ctx.pop_clip_path();
+            return;
+        }
+
+        let scope = self.scope();
+        if scope.clip_depth > 0 {
+            scope.content.push_str("Q\n");
+            scope.clip_depth -= 1;
+        }
+    }
+
+    /// Return the width of the page in points.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Return the height of the page in points.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Take current rendering state and reset the existing state to its default.
+    pub fn take_current_state(&mut self) -> RenderState {
+        core::mem::take(&mut self.state)
+    }
+
+    /// Save a copy of the current rendering state.
+    pub fn save_current_state(&mut self) -> RenderState {
+        self.state.clone()
+    }
+
+    /// Restore rendering state.
+    pub fn restore_state(&mut self, state: RenderState) {
+        self.state = state;
+    }
+
+    /// Finish the page and add it to the document.
+    ///
+    /// Layers that are still open are popped.
+    pub fn finish(mut self) {
+        while self.raster.is_some() || self.scopes.len() > 1 {
+            self.pop_layer();
+        }
+
+        let mut root = self.scopes.pop().unwrap();
+        for _ in 0..root.clip_depth {
+            root.content.push_str("Q\n");
+        }
+        let form = self.doc.form(&root.content, self.bbox, None);
+        let form = self.doc.xobject(form);
+        self.doc.add_page(self.width, self.height, &form);
+    }
+
+    fn scope(&mut self) -> &mut Scope {
+        self.scopes.last_mut().unwrap()
+    }
+
+    fn push_scope(&mut self, props: LayerProps, clip: Option<(&BezPath, Affine, Fill)>) {
+        let content = &mut self.scope().content;
+        content.push_str("q ");
+        if let Some((path, transform, fill_rule)) = clip {
+            write_clip(content, path, transform, fill_rule);
+        }
+        self.scopes.push(Scope {
+            props: Some(props),
+            ..Default::default()
+        });
+    }
+
+    fn pop_scope(&mut self) {
+        let mut scope = self.scopes.pop().unwrap();
+        let props = scope.props.take().unwrap();
+        for _ in 0..scope.clip_depth {
+            scope.content.push_str("Q\n");
+        }
+
+        let form = self.doc.form(&scope.content, self.bbox, Some("/DeviceRGB"));
+        let form = self.doc.xobject(form);
+        let gstate = self.gstate(props.blend_mode, props.opacity, props.opacity);
+        let mask_gstate = props.mask.map(|mask| self.doc.mask_gstate(&mask));
+
+        let content = &mut self.scope().content;
+        if let Some(gstate) = gstate {
+            write!(content, "{gstate} gs ").unwrap();
+        }
+        if let Some(mask_gstate) = mask_gstate {
+            write!(content, "{mask_gstate} gs ").unwrap();
+        }
+        writeln!(content, "{form} Do Q").unwrap();
+    }
+
+    /// Return a graphics state for the given blend mode and alpha values, or `None` if
+    /// it would have no effect.
+    fn gstate(
+        &mut self,
+        blend_mode: BlendMode,
+        fill_alpha: f32,
+        stroke_alpha: f32,
+    ) -> Option<String> {
+        let blend_mode = blend_mode_name(blend_mode);
+        if blend_mode == "Normal" && fill_alpha >= 1.0 && stroke_alpha >= 1.0 {
+            return None;
+        }
+
+        Some(self.doc.ext_gstate(format!(
+            "<< /Type /ExtGState /BM /{blend_mode} /ca {} /CA {} >>",
+            Num::from(fill_alpha.clamp(0.0, 1.0)),
+            Num::from(stroke_alpha.clamp(0.0, 1.0))
+        )))
+    }
+
+    fn draw(&mut self, shape: Shape<'_>) {
+        if self.raster.is_some() {
+            self.sync_raster();
+            let raster = self.raster.as_mut().unwrap();
+            draw_shape(&mut raster.ctx, shape);
+            return;
+        }
+
+        if let Some(filter) = self.filter.clone() {
+            // Rasterize the drawing operation in its own filter layer.
+            self.push_filter_layer(filter);
+            self.draw(shape);
+            self.pop_layer();
+            return;
+        }
+
+        if let Some(mask) = self.mask.clone() {
+            // The blend mode is applied when compositing the masked group, since blending
+            // inside of the isolated group would only blend with transparent black.
+            let props = LayerProps {
+                blend_mode: self.state.blend_mode,
+                opacity: 1.0,
+                mask: Some(mask),
+            };
+            self.push_scope(props, None);
+            self.draw_vector(shape, BlendMode::default());
+            self.pop_scope();
+        } else {
+            self.draw_vector(shape, self.state.blend_mode);
+        }
+    }
+
+    /// Draw a shape into the current content stream, falling back to rasterization for
+    /// paints that cannot be represented natively.
+    fn draw_vector(&mut self, shape: Shape<'_>, blend_mode: BlendMode) {
+        let transform = self.state.transform;
+        let paint_transform = transform * self.state.paint_transform;
+
+        let path = match shape {
+            Shape::Fill(path) | Shape::Stroke(path) => path,
+            Shape::BlurredRoundedRect { .. } => return self.rasterize(shape, blend_mode),
+        };
+        if path.elements().is_empty() {
+            return;
+        }
+
+        match self.state.paint.clone() {
+            PaintType::Solid(color) => {
+                let [r, g, b, a] = color.components;
+                let gstate = self.gstate(blend_mode, a, a);
+                self.draw_path(shape, gstate, None, &ColorOp::Rgb([r, g, b]));
+            }
+            PaintType::Gradient(gradient) => {
+                let bbox = self.bbox;
+                let Some(pattern) = self.doc.gradient(&gradient, paint_transform, bbox) else {
+                    return self.rasterize(shape, blend_mode);
+                };
+                let gstate = self.gstate(blend_mode, 1.0, 1.0);
+                let color_op = ColorOp::Pattern(pattern.pattern);
+                self.draw_path(shape, gstate, pattern.alpha, &color_op);
+            }
+            PaintType::Pattern(pattern) => {
+                let Some(pattern) = self.tiling_pattern(&pattern, paint_transform) else {
+                    return self.rasterize(shape, blend_mode);
+                };
+                let gstate = self.gstate(blend_mode, 1.0, 1.0);
+                self.draw_path(shape, gstate, None, &ColorOp::Pattern(pattern));
+            }
+            // TODO: Export mesh gradients as PDF mesh shadings instead of rasterizing them.
+            PaintType::MeshGradient(_) => self.rasterize(shape, blend_mode),
+            // Custom paints are registered with the resources of a renderer, which are not
+            // available here.
+            PaintType::Custom(_) => {}
+            // External textures are only bound when rendering with a renderer.
+            PaintType::ExternalTexture(_) => {}
+            PaintType::Image(image) => {
+                let pixmap = match &image.image {
+                    ImageSource::Pixmap(pixmap) => pixmap.clone(),
+                    // There is no way to resolve opaque images without the resources
+                    // of a renderer.
+                    ImageSource::OpaqueId { .. } => return,
+                    // Converting the pixels on every draw would embed a new copy each time.
+                    ImageSource::Raw(_) => return self.rasterize(shape, blend_mode),
+                };
+                let sampler = image.sampler;
+                let extends = (sampler.x_extend, sampler.y_extend);
+                if self.state.tint.is_some()
+                    || !matches!(
+                        extends,
+                        (Extend::Pad, Extend::Pad) | (Extend::Repeat, Extend::Repeat)
+                    )
+                {
+                    return self.rasterize(shape, blend_mode);
+                }
+
+                let name = self
+                    .doc
+                    .cached_image(&pixmap, sampler.quality != ImageQuality::Low);
+                let gstate = self.gstate(blend_mode, sampler.alpha, sampler.alpha);
+                let (width, height) = (pixmap.width(), pixmap.height());
+
+                if extends.0 == Extend::Repeat {
+                    let pattern = self
+                        .doc
+                        .image_pattern(&name, width, height, paint_transform);
+                    self.draw_path(shape, gstate, None, &ColorOp::Pattern(pattern));
+                } else {
+                    // Padded images are drawn clipped to the shape. Note that unlike in
+                    // Vello CPU, the edge pixels are not extended beyond the image bounds.
+                    let (outline, fill_rule) = self.fill_outline(shape);
+                    let image_transform = paint_transform
+                        * Affine::new([
+                            f64::from(width),
+                            0.0,
+                            0.0,
+                            -f64::from(height),
+                            0.0,
+                            f64::from(height),
+                        ]);
+                    let content = &mut self.scope().content;
+                    content.push_str("q ");
+                    if let Some(gstate) = gstate {
+                        write!(content, "{gstate} gs ").unwrap();
+                    }
+                    write_clip(content, &outline, Affine::IDENTITY, fill_rule);
+                    writeln!(content, "{} cm {name} Do Q", Matrix(image_transform)).unwrap();
+                }
+            }
+            // Paints without a native representation are rasterized.
+            _ => self.rasterize(shape, blend_mode),
+        }
+    }
+
+    /// Write the content of a pattern into a tiling pattern, given the transform from the
+    /// coordinate system of the paint to the current content stream, and return its
+    /// resource name.
+    ///
+    /// Returns `None` if the content of the pattern cannot be represented natively, in
+    /// which case it needs to be rasterized instead.
+    fn tiling_pattern(&mut self, pattern: &Pattern, transform: Affine) -> Option<String> {
+        let tile = pattern.tile;
+        let commands = pattern.scene.commands();
+        let native = commands.iter().all(|command| match command {
+            PatternCommand::Fill { state, .. } | PatternCommand::Stroke { state, .. } => {
+                match &state.paint {
+                    PaintType::Solid(_) => true,
+                    PaintType::Gradient(gradient) => is_native_gradient(gradient),
+                    _ => false,
+                }
+            }
+            PatternCommand::PushLayer { filter, .. } => filter.is_none(),
+            PatternCommand::PopLayer => true,
+        });
+        if !(native && tile.width() > 0.0 && tile.height() > 0.0) {
+            return None;
+        }
+
+        // Replay the commands into a fresh content stream, in the coordinate system of the
+        // pattern's scene.
+        let bbox = [tile.x0, tile.y0, tile.x1, tile.y1];
+        let scopes = core::mem::replace(&mut self.scopes, vec![Scope::default()]);
+        let outer_bbox = core::mem::replace(&mut self.bbox, bbox);
+        let state = self.take_current_state();
+        let mask = self.mask.take();
+        let filter = self.filter.take();
+        for command in commands {
+            match command {
+                PatternCommand::Fill { path, state } => {
+                    self.state = state.clone();
+                    self.draw(Shape::Fill(path));
+                }
+                PatternCommand::Stroke { path, state } => {
+                    self.state = state.clone();
+                    self.draw(Shape::Stroke(path));
+                }
+                PatternCommand::PushLayer {
+                    clip_path,
+                    fill_rule,
+                    transform,
+                    blend_mode,
+                    opacity,
+                    ..
+                } => {
+                    self.state.transform = *transform;
+                    self.state.fill_rule = *fill_rule;
+                    self.push_layer(
+                        clip_path.as_ref(),
+                        Some(*blend_mode),
+                        Some(*opacity),
+                        None,
+                        None,
+                    );
+                }
+                PatternCommand::PopLayer => self.pop_layer(),
+            }
+        }
+        while self.scopes.len() > 1 {
+            self.pop_scope();
+        }
+        let content = core::mem::replace(&mut self.scopes, scopes)
+            .pop()
+            .unwrap()
+            .content;
+        self.bbox = outer_bbox;
+        self.state = state;
+        self.mask = mask;
+        self.filter = filter;
+
+        Some(
+            self.doc
+                .tiling_pattern(&content, bbox, transform * pattern.transform),
+        )
+    }
+
+    /// Fill or stroke a path with the given color operator.
+    fn draw_path(
+        &mut self,
+        shape: Shape<'_>,
+        gstate: Option<String>,
+        alpha_gstate: Option<String>,
+        color_op: &ColorOp,
+    ) {
+        let transform = self.state.transform;
+        let fill_rule = self.state.fill_rule;
+        let stroke = self.state.stroke.clone();
+        let invertible = transform.determinant().abs() > 1e-9;
+
+        // Strokes are only written natively if the transform can be set as the current
+        // transformation matrix. Otherwise, the stroke outline is filled instead.
+        let native_stroke = match shape {
+            Shape::Stroke(path) if invertible => Some(path),
+            _ => None,
+        };
+        let (outline, fill_rule) = if native_stroke.is_some() {
+            (BezPath::new(), fill_rule)
+        } else {
+            self.fill_outline(shape)
+        };
+
+        let content = &mut self.scope().content;
+        content.push_str("q ");
+        if let Some(gstate) = gstate {
+            write!(content, "{gstate} gs ").unwrap();
+        }
+        if let Some(alpha_gstate) = alpha_gstate {
+            write!(content, "{alpha_gstate} gs ").unwrap();
+        }
+        color_op.write(content, native_stroke.is_some());
+
+        if let Some(path) = native_stroke {
+            write!(content, "{} cm ", Matrix(transform)).unwrap();
+            write_stroke_style(content, &stroke);
+            write_path(content, path, Affine::IDENTITY);
+            content.push_str("S Q\n");
+        } else {
+            write_path(content, &outline, Affine::IDENTITY);
+            let op = match fill_rule {
+                Fill::NonZero => "f",
+                Fill::EvenOdd => "f*",
+            };
+            writeln!(content, "{op} Q").unwrap();
+        }
+    }
+
+    /// Return the area covered by the shape as a path in page coordinates.
+    fn fill_outline(&self, shape: Shape<'_>) -> (BezPath, Fill) {
+        let transform = self.state.transform;
+        match shape {
+            Shape::Fill(path) => (transform * path.clone(), self.state.fill_rule),
+            Shape::Stroke(path) => {
+                let outline = vello_common::kurbo::stroke(
+                    path.iter(),
+                    &self.state.stroke,
+                    &StrokeOpts::default(),
+                    TOLERANCE,
+                );
+                (transform * outline, Fill::NonZero)
+            }
+            Shape::BlurredRoundedRect { rect, std_dev, .. } => {
+                let kernel_size = 2.5 * f64::from(std_dev);
+                let rect = rect.inflate(kernel_size, kernel_size);
+                (transform * rect.to_path(TOLERANCE), Fill::NonZero)
+            }
+        }
+    }
+
+    /// Rasterize a single shape with Vello CPU and embed the result.
+    fn rasterize(&mut self, shape: Shape<'_>, blend_mode: BlendMode) {
+        let (outline, _) = self.fill_outline(shape);
+        let page = Rect::new(0.0, 0.0, f64::from(self.width), f64::from(self.height));
+        let bbox = outline.bounding_box().intersect(page).expand();
+        if bbox.is_zero_area() {
+            return;
+        }
+
+        let scale = f64::from(self.doc.raster_scale());
+        let width = (bbox.width() * scale)
+            .ceil()
+            .clamp(1.0, f64::from(u16::MAX)) as u16;
+        let height = (bbox.height() * scale)
+            .ceil()
+            .clamp(1.0, f64::from(u16::MAX)) as u16;
+        let mut ctx = RenderContext::new_with(width, height, raster_settings());
+        let mut state = self.state.clone();
+        state.transform = Affine::scale_non_uniform(
+            f64::from(width) / bbox.width(),
+            f64::from(height) / bbox.height(),
+        ) * Affine::translate((-bbox.x0, -bbox.y0))
+            * state.transform;
+        state.blend_mode = BlendMode::default();
+        ctx.restore_state(state);
+        ctx.set_aliasing_threshold(self.aliasing_threshold);
+        draw_shape(&mut ctx, shape);
+        ctx.flush();
+
+        let mut pixmap = Pixmap::new(width, height);
+        ctx.render(&mut pixmap, &mut Resources::new());
+
+        let gstate = self.gstate(blend_mode, 1.0, 1.0);
+        let content = &mut self.scope().content;
+        content.push_str("q ");
+        if let Some(gstate) = gstate {
+            write!(content, "{gstate} gs ").unwrap();
+        }
+        self.draw_raster(&pixmap, bbox);
+        self.scope().content.push_str("Q\n");
+    }
+
+    /// Draw a rasterized image into the given area of the page.
+    fn draw_raster(&mut self, pixmap: &Pixmap, bbox: Rect) {
+        let name = self.doc.image(pixmap, true);
+        let placement = Affine::new([bbox.width(), 0.0, 0.0, -bbox.height(), bbox.x0, bbox.y1]);
+        writeln!(
+            self.scope().content,
+            "q {} cm {name} Do Q",
+            Matrix(placement)
+        )
+        .unwrap();
+    }
+
+    /// Apply the current state to the raster context.
+    fn sync_raster(&mut self) {
+        let raster = self.raster.as_mut().unwrap();
+        let scale = f64::from(raster.ctx.width()) / f64::from(self.width);
+        let mut state = self.state.clone();
+        state.transform = Affine::scale(scale) * state.transform;
+        raster.ctx.restore_state(state);
+        raster.ctx.set_aliasing_threshold(self.aliasing_threshold);
+        match &self.mask {
+            Some(mask) if mask.width() == self.width && mask.height() == self.height => {
+                let mask = raster.scale_mask(mask);
+                raster.ctx.set_mask(mask);
+            }
+            _ => raster.ctx.reset_mask(),
+        }
+    }
+}
+
+impl RasterLayer {
+    /// Resample a mask from page size to the size of the raster context.
+    fn scale_mask(&mut self, mask: &Mask) -> Mask {
+        if let Some((original, scaled)) = &self.scaled_mask
+            && original.data().as_ptr() == mask.data().as_ptr()
+        {
+            return scaled.clone();
+        }
+
+        let width = self.ctx.width();
+        let height = self.ctx.height();
+        let mut data = Vec::with_capacity(usize::from(width) * usize::from(height));
+        for y in 0..height {
+            let src_y = (u32::from(y) * u32::from(mask.height()) / u32::from(height)) as u16;
+            for x in 0..width {
+                let src_x = (u32::from(x) * u32::from(mask.width()) / u32::from(width)) as u16;
+                data.push(mask.sample(src_x, src_y));
+            }
+        }
+
+        let scaled = Mask::from_parts(data, width, height);
+        self.scaled_mask = Some((mask.clone(), scaled.clone()));
+        scaled
+    }
+}
+
+/// The operator setting the color used for painting.
+#[derive(Debug)]
+enum ColorOp {
+    Rgb([f32; 3]),
+    /// The resource name of a pattern.
+    Pattern(String),
+}
+
+impl ColorOp {
+    fn write(&self, out: &mut String, stroking: bool) {
+        match (self, stroking) {
+            (Self::Rgb([r, g, b]), false) => {
+                write!(
+                    out,
+                    "{} {} {} rg ",
+                    Num::from(*r),
+                    Num::from(*g),
+                    Num::from(*b)
+                )
+                .unwrap();
+            }
+            (Self::Rgb([r, g, b]), true) => {
+                write!(
+                    out,
+                    "{} {} {} RG ",
+                    Num::from(*r),
+                    Num::from(*g),
+                    Num::from(*b)
+                )
+                .unwrap();
+            }
+            (Self::Pattern(name), false) => write!(out, "/Pattern cs {name} scn ").unwrap(),
+            (Self::Pattern(name), true) => write!(out, "/Pattern CS {name} SCN ").unwrap(),
+        }
+    }
+}
+
+fn raster_settings() -> RenderSettings {
+    RenderSettings {
+        num_threads: 0,
+        ..Default::default()
+    }
+}
+
+fn scaled_size(size: u16, scale: f64) -> u16 {
+    (f64::from(size) * scale)
+        .ceil()
+        .clamp(1.0, f64::from(u16::MAX)) as u16
+}
+
+fn draw_shape(ctx: &mut RenderContext, shape: Shape<'_>) {
+    match shape {
+        Shape::Fill(path) => ctx.fill_path(path),
+        Shape::Stroke(path) => ctx.stroke_path(path),
+        Shape::BlurredRoundedRect {
+            rect,
+            radius,
+            std_dev,
+            invert,
+        } => ctx.fill_blurred_rounded_rect(&rect, radius, std_dev, invert),
+    }
+}
+
+fn blend_mode_name(blend_mode: BlendMode) -> &'static str {
+    // PDF has no equivalent to the Porter-Duff composition modes, so only the mix
+    // function is taken into account.
+    match blend_mode.mix {
+        Mix::Normal => "Normal",
+        Mix::Multiply => "Multiply",
+        Mix::Screen => "Screen",
+        Mix::Overlay => "Overlay",
+        Mix::Darken => "Darken",
+        Mix::Lighten => "Lighten",
+        Mix::ColorDodge => "ColorDodge",
+        Mix::ColorBurn => "ColorBurn",
+        Mix::HardLight => "HardLight",
+        Mix::SoftLight => "SoftLight",
+        Mix::Difference => "Difference",
+        Mix::Exclusion => "Exclusion",
+        Mix::Hue => "Hue",
+        Mix::Saturation => "Saturation",
+        Mix::Color => "Color",
+        Mix::Luminosity => "Luminosity",
+    }
+}
+
+/// Write the construction operators of a path.
+fn write_path(out: &mut String, path: &BezPath, transform: Affine) {
+    let mut start = Point::ZERO;
+    let mut last = Point::ZERO;
+    for el in path.elements() {
+        match transform * *el {
+            PathEl::MoveTo(p) => {
+                write!(out, "{} {} m ", Num(p.x), Num(p.y)).unwrap();
+                start = p;
+                last = p;
+            }
+            PathEl::LineTo(p) => {
+                write!(out, "{} {} l ", Num(p.x), Num(p.y)).unwrap();
+                last = p;
+            }
+            PathEl::QuadTo(p1, p2) => {
+                let c1 = last + (p1 - last) * (2.0 / 3.0);
+                let c2 = p2 + (p1 - p2) * (2.0 / 3.0);
+                write!(
+                    out,
+                    "{} {} {} {} {} {} c ",
+                    Num(c1.x),
+                    Num(c1.y),
+                    Num(c2.x),
+                    Num(c2.y),
+                    Num(p2.x),
+                    Num(p2.y)
+                )
+                .unwrap();
+                last = p2;
+            }
+            PathEl::CurveTo(p1, p2, p3) => {
+                write!(
+                    out,
+                    "{} {} {} {} {} {} c ",
+                    Num(p1.x),
+                    Num(p1.y),
+                    Num(p2.x),
+                    Num(p2.y),
+                    Num(p3.x),
+                    Num(p3.y)
+                )
+                .unwrap();
+                last = p3;
+            }
+            PathEl::ClosePath => {
+                out.push_str("h ");
+                last = start;
+            }
+        }
+    }
+}
+
+/// Intersect the clipping path with the given path.
+fn write_clip(out: &mut String, path: &BezPath, transform: Affine, fill_rule: Fill) {
+    if path.elements().is_empty() {
+        // An empty clip path clips everything.
+        out.push_str("0 0 0 0 re W n\n");
+        return;
+    }
+
+    write_path(out, path, transform);
+    match fill_rule {
+        Fill::NonZero => out.push_str("W n\n"),
+        Fill::EvenOdd => out.push_str("W* n\n"),
+    }
+}
+
+fn write_stroke_style(out: &mut String, stroke: &Stroke) {
+    let cap = match stroke.start_cap {
+        Cap::Butt => 0,
+        Cap::Round => 1,
+        Cap::Square => 2,
+    };
+    let join = match stroke.join {
+        Join::Miter => 0,
+        Join::Round => 1,
+        Join::Bevel => 2,
+    };
+    write!(
+        out,
+        "{} w {cap} J {join} j {} M ",
+        Num(stroke.width),
+        Num(stroke.miter_limit.max(1.0))
+    )
+    .unwrap();
+    if !stroke.dash_pattern.is_empty() {
+        out.push('[');
+        for (idx, dash) in stroke.dash_pattern.iter().enumerate() {
+            if idx > 0 {
+                out.push(' ');
+            }
+            write!(out, "{}", Num(*dash)).unwrap();
+        }
+        write!(out, "] {} d ", Num(stroke.dash_offset)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PdfDocument;
+    use crate::tests::streams;
+    use vello_common::color::palette::css;
+    use vello_common::filter_effects::{Filter, FilterFunction};
+    use vello_common::kurbo::{Affine, Circle, Point, Rect, Shape, Stroke};
+    use vello_common::pattern::{Pattern, PatternCommand, PatternScene};
+    use vello_common::peniko::{BlendMode, Color, ColorStop, Compose, Fill, Gradient, Mix};
+    use vello_common::render_state::RenderState;
+
+    #[test]
+    fn vector_content() {
+        let mut document = PdfDocument::new();
+        let mut page = document.page(100, 100);
+
+        page.set_paint(css::RED.with_alpha(0.5));
+        page.fill_rect(&Rect::new(10.0, 10.0, 50.0, 50.0));
+
+        page.push_layer(
+            Some(&Rect::new(0.0, 0.0, 50.0, 100.0).to_path(0.1)),
+            Some(BlendMode::new(Mix::Multiply, Compose::SrcOver)),
+            None,
+            None,
+            None,
+        );
+        page.set_paint(Gradient::new_linear((0.0, 0.0), (100.0, 0.0)).with_stops([
+            ColorStop::from((0.0, Color::from_rgb8(0, 0, 255))),
+            ColorStop::from((1.0, Color::from_rgb8(0, 255, 0))),
+        ]));
+        page.set_stroke(Stroke::new(4.0));
+        page.stroke_path(&Circle::new(Point::new(50.0, 50.0), 20.0).to_path(0.1));
+        page.pop_layer();
+        page.finish();
+
+        let pdf = document.finish();
+        let content = streams(&pdf);
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        assert!(content.contains("1 0 0 rg 10 10 m 50 10 l 50 50 l 10 50 l h f Q"));
+        assert!(pdf.contains("/ca 0.5"));
+        assert!(content.contains("0 0 m 50 0 l 50 100 l 0 100 l h W n"));
+        assert!(pdf.contains("/BM /Multiply"));
+        assert!(pdf.contains("/ShadingType 2 /Coords [0 0 100 0]"));
+        assert!(content.contains("/Pattern CS /P0 SCN"));
+        assert!(content.contains("4 w 1 J 1 j"));
+        assert!(content.contains(" S Q"));
+        assert!(!pdf.contains("/Subtype /Image"));
+    }
+
+    #[test]
+    fn filter_layers_are_rasterized() {
+        let mut document = PdfDocument::new();
+        document.set_raster_scale(1.0);
+        let mut page = document.page(40, 20);
+
+        page.push_filter_layer(Filter::from_function(FilterFunction::Blur { radius: 2.0 }));
+        page.set_paint(css::BLUE);
+        page.fill_rect(&Rect::new(5.0, 5.0, 15.0, 15.0));
+        page.pop_layer();
+        page.finish();
+
+        let pdf = document.finish();
+        let content = streams(&pdf);
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.contains("/Subtype /Image /Width 40 /Height 20"));
+        assert!(content.contains("q 40 0 0 -20 0 20 cm /X0 Do Q"));
+        // The rectangle itself is not written as a path.
+        assert!(!content.contains(" re f") && !content.contains("h f"));
+    }
+
+    #[test]
+    fn patterns_are_tiling_patterns() {
+        let mut scene = PatternScene::new();
+        let state = RenderState {
+            paint: css::RED.into(),
+            ..Default::default()
+        };
+        scene.fill_path(Rect::new(0.0, 0.0, 1.0, 1.0).to_path(0.1), &state);
+        let pattern = Pattern::new(scene, Rect::new(0.0, 0.0, 2.0, 2.0));
+
+        let mut document = PdfDocument::new();
+        let mut page = document.page(20, 20);
+        page.set_paint(pattern);
+        page.set_paint_transform(Affine::scale(4.0));
+        page.fill_rect(&Rect::new(0.0, 0.0, 20.0, 20.0));
+        page.finish();
+
+        let pdf = document.finish();
+        let content = streams(&pdf);
+        let pdf = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.contains(
+            "/PatternType 1 /PaintType 1 /TilingType 1 /BBox [0 0 2 2] /XStep 2 /YStep 2"
+        ));
+        assert!(pdf.contains("/Matrix [4 0 0 4 0 0]"));
+        assert!(content.contains("1 0 0 rg 0 0 m 1 0 l 1 1 l 0 1 l h f Q"));
+        assert!(content.contains("/Pattern cs /P0 scn"));
+        assert!(!pdf.contains("/Subtype /Image"));
+    }
+
+    #[test]
+    fn patterns_with_filters_are_rasterized() {
+        let state = RenderState {
+            paint: css::RED.into(),
+            ..Default::default()
+        };
+        let scene = PatternScene::from(vec![
+            PatternCommand::PushLayer {
+                clip_path: None,
+                fill_rule: Fill::NonZero,
+                transform: Affine::IDENTITY,
+                blend_mode: BlendMode::default(),
+                opacity: 1.0,
+                filter: Some(Filter::from_function(FilterFunction::Blur { radius: 1.0 })),
+            },
+            PatternCommand::Fill {
+                path: Rect::new(0.0, 0.0, 1.0, 1.0).to_path(0.1),
+                state,
+            },
+            PatternCommand::PopLayer,
+        ]);
+
+        let mut document = PdfDocument::new();
+        let mut page = document.page(20, 20);
+        page.set_paint(Pattern::new(scene, Rect::new(0.0, 0.0, 2.0, 2.0)));
+        page.fill_rect(&Rect::new(0.0, 0.0, 20.0, 20.0));
+        page.finish();
+
+        let pdf = String::from_utf8_lossy(&document.finish()).into_owned();
+
+        assert!(!pdf.contains("/PatternType 1"));
+        assert!(pdf.contains("/Subtype /Image"));
+    }
+}
@@ -0,0 +1,346 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The PDF document and the resources shared between its pages.
+
+use crate::PdfContext;
+use crate::writer::{Num, Ref, Writer};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use vello_common::mask::Mask;
+use vello_common::pixmap::Pixmap;
+
+/// A PDF document consisting of one or more pages.
+///
+/// Pages are drawn with a [`PdfContext`], which can be obtained via [`PdfDocument::page`].
+/// Once all pages have been drawn, [`PdfDocument::finish`] returns the serialized file.
+#[derive(Debug)]
+pub struct PdfDocument {
+    pub(crate) writer: Writer,
+    catalog: Ref,
+    page_tree: Ref,
+    /// The resource dictionary, which is shared between all pages, forms and patterns.
+    pub(crate) resources: Ref,
+    pages: Vec<Ref>,
+    ext_gstates: Vec<Ref>,
+    ext_gstate_cache: HashMap<String, usize>,
+    xobjects: Vec<Ref>,
+    patterns: Vec<Ref>,
+    /// Image `XObjects`, keyed by the address of the pixmap and whether it is interpolated.
+    image_cache: HashMap<(usize, bool), usize>,
+    /// Keeps cached images alive, so that their addresses cannot be reused.
+    retained_images: Vec<Arc<Pixmap>>,
+    /// Soft mask graphics states, keyed by the address of the mask data.
+    mask_cache: HashMap<usize, String>,
+    /// Keeps cached masks alive, so that their addresses cannot be reused.
+    retained_masks: Vec<Mask>,
+    raster_scale: f32,
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfDocument {
+    /// Create a new, empty document.
+    pub fn new() -> Self {
+        let mut writer = Writer::default();
+        let catalog = writer.alloc();
+        let page_tree = writer.alloc();
+        let resources = writer.alloc();
+
+        Self {
+            writer,
+            catalog,
+            page_tree,
+            resources,
+            pages: Vec::new(),
+            ext_gstates: Vec::new(),
+            ext_gstate_cache: HashMap::new(),
+            xobjects: Vec::new(),
+            patterns: Vec::new(),
+            image_cache: HashMap::new(),
+            retained_images: Vec::new(),
+            mask_cache: HashMap::new(),
+            retained_masks: Vec::new(),
+            raster_scale: 2.0,
+        }
+    }
+
+    /// Set the number of pixels per point used for content that cannot be represented
+    /// as vector graphics (for example filter layers and sweep gradients) and is
+    /// therefore rasterized with Vello CPU.
+    ///
+    /// The default is 2.
+    pub fn set_raster_scale(&mut self, scale: f32) {
+        self.raster_scale = scale.max(f32::EPSILON);
+    }
+
+    /// Return the number of pixels per point used for rasterized content.
+    pub fn raster_scale(&self) -> f32 {
+        self.raster_scale
+    }
+
+    /// Start a new page with the given size in points.
+    ///
+    /// The page is added to the document once [`PdfContext::finish`] is called.
+    pub fn page(&mut self, width: u16, height: u16) -> PdfContext<'_> {
+        PdfContext::new(self, width, height)
+    }
+
+    /// Return the number of finished pages.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Serialize the document.
+    pub fn finish(mut self) -> Vec<u8> {
+        let mut kids = String::new();
+        for page in &self.pages {
+            write!(kids, "{page} ").unwrap();
+        }
+        self.writer.object(
+            self.page_tree,
+            format_args!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.trim_end(),
+                self.pages.len()
+            ),
+        );
+        self.writer.object(
+            self.catalog,
+            format_args!("<< /Type /Catalog /Pages {} >>", self.page_tree),
+        );
+
+        let mut resources = String::from("<< /ProcSet [/PDF /ImageB /ImageC]");
+        write_resource_dict(&mut resources, "ExtGState", 'G', &self.ext_gstates);
+        write_resource_dict(&mut resources, "XObject", 'X', &self.xobjects);
+        write_resource_dict(&mut resources, "Pattern", 'P', &self.patterns);
+        resources.push_str(" >>");
+        self.writer.object(self.resources, resources);
+
+        self.writer.finish(self.catalog)
+    }
+
+    /// Add a page that draws the given form `XObject`, which contains the contents
+    /// in a coordinate system with the origin at the top left.
+    pub(crate) fn add_page(&mut self, width: u16, height: u16, form: &str) {
+        let page = self.writer.alloc();
+        let content = self.writer.alloc();
+        self.writer.object(
+            page,
+            format_args!(
+                "<< /Type /Page /Parent {} /MediaBox [0 0 {width} {height}] /Resources {} \
+                 /Contents {content} /Group << /Type /Group /S /Transparency /CS /DeviceRGB >> >>",
+                self.page_tree, self.resources
+            ),
+        );
+        let data = format!("1 0 0 -1 0 {height} cm {form} Do");
+        self.writer.stream(content, "", data.as_bytes());
+        self.pages.push(page);
+    }
+
+    /// Register a graphics state parameter dictionary and return its resource name.
+    pub(crate) fn ext_gstate(&mut self, dict: String) -> String {
+        let idx = if let Some(idx) = self.ext_gstate_cache.get(&dict) {
+            *idx
+        } else {
+            let id = self.writer.alloc();
+            self.writer.object(id, &dict);
+            self.ext_gstates.push(id);
+            self.ext_gstate_cache
+                .insert(dict, self.ext_gstates.len() - 1);
+            self.ext_gstates.len() - 1
+        };
+
+        format!("/G{idx}")
+    }
+
+    /// Register an `XObject` and return its resource name.
+    pub(crate) fn xobject(&mut self, id: Ref) -> String {
+        self.xobjects.push(id);
+        format!("/X{}", self.xobjects.len() - 1)
+    }
+
+    /// Register a pattern and return its resource name.
+    pub(crate) fn pattern(&mut self, id: Ref) -> String {
+        self.patterns.push(id);
+        format!("/P{}", self.patterns.len() - 1)
+    }
+
+    /// Write a form `XObject` with the given content and bounding box.
+    ///
+    /// If `group` is set, the form is an isolated transparency group with the given
+    /// blending color space.
+    pub(crate) fn form(&mut self, content: &str, bbox: [f64; 4], group: Option<&str>) -> Ref {
+        let id = self.writer.alloc();
+        let mut dict = format!(
+            "/Type /XObject /Subtype /Form /BBox [{} {} {} {}] /Resources {}",
+            Num(bbox[0]),
+            Num(bbox[1]),
+            Num(bbox[2]),
+            Num(bbox[3]),
+            self.resources
+        );
+        if let Some(cs) = group {
+            write!(dict, " /Group << /S /Transparency /I true /CS {cs} >>").unwrap();
+        }
+        self.writer.stream(id, dict, content.as_bytes());
+        id
+    }
+
+    /// Embed a shared image and return the resource name of its image `XObject`.
+    pub(crate) fn cached_image(&mut self, pixmap: &Arc<Pixmap>, interpolate: bool) -> String {
+        let key = (Arc::as_ptr(pixmap) as usize, interpolate);
+        let idx = if let Some(idx) = self.image_cache.get(&key) {
+            *idx
+        } else {
+            let id = self.write_image(pixmap, interpolate);
+            self.xobjects.push(id);
+            self.retained_images.push(Arc::clone(pixmap));
+            self.image_cache.insert(key, self.xobjects.len() - 1);
+            self.xobjects.len() - 1
+        };
+
+        format!("/X{idx}")
+    }
+
+    /// Embed an image that is only used once and return the resource name of its
+    /// image `XObject`.
+    pub(crate) fn image(&mut self, pixmap: &Pixmap, interpolate: bool) -> String {
+        let id = self.write_image(pixmap, interpolate);
+        self.xobject(id)
+    }
+
+    fn write_image(&mut self, pixmap: &Pixmap, interpolate: bool) -> Ref {
+        // Images are embedded with the `DeviceRGB` color space, which is treated as sRGB.
+        let pixmap = pixmap.to_srgb();
+        let width = pixmap.width();
+        let height = pixmap.height();
+        let mut rgb = Vec::with_capacity(pixmap.data().len() * 3);
+        let mut alpha = Vec::with_capacity(pixmap.data().len());
+        for pixel in pixmap.data() {
+            let unpremultiply = |c: u8| {
+                if pixel.a == 0 {
+                    0
+                } else {
+                    ((u16::from(c) * 255 + u16::from(pixel.a) / 2) / u16::from(pixel.a)) as u8
+                }
+            };
+            rgb.extend([
+                unpremultiply(pixel.r),
+                unpremultiply(pixel.g),
+                unpremultiply(pixel.b),
+            ]);
+            alpha.push(pixel.a);
+        }
+
+        let interpolate = if interpolate { "true" } else { "false" };
+        let mut dict = format!(
+            "/Type /XObject /Subtype /Image /Width {width} /Height {height} \
+             /ColorSpace /DeviceRGB /BitsPerComponent 8 /Interpolate {interpolate}"
+        );
+        if alpha.iter().any(|a| *a != 255) {
+            let smask = self.writer.alloc();
+            self.writer.stream(
+                smask,
+                format_args!(
+                    "/Type /XObject /Subtype /Image /Width {width} /Height {height} \
+                     /ColorSpace /DeviceGray /BitsPerComponent 8 /Interpolate {interpolate}"
+                ),
+                &alpha,
+            );
+            write!(dict, " /SMask {smask}").unwrap();
+        }
+
+        let id = self.writer.alloc();
+        self.writer.stream(id, dict, &rgb);
+        id
+    }
+
+    /// Return the resource name of a graphics state that applies the given mask as a
+    /// soft mask.
+    ///
+    /// The mask covers the area from the origin to its size in the current coordinate
+    /// system, which is expected to have the origin at the top left.
+    pub(crate) fn mask_gstate(&mut self, mask: &Mask) -> String {
+        let key = mask.data().as_ptr() as usize;
+        if let Some(name) = self.mask_cache.get(&key) {
+            return name.clone();
+        }
+
+        let width = mask.width();
+        let height = mask.height();
+        let image = self.writer.alloc();
+        self.writer.stream(
+            image,
+            format_args!(
+                "/Type /XObject /Subtype /Image /Width {width} /Height {height} \
+                 /ColorSpace /DeviceGray /BitsPerComponent 8"
+            ),
+            mask.data(),
+        );
+        let image = self.xobject(image);
+        let form = self.form(
+            &format!("{width} 0 0 -{height} 0 {height} cm {image} Do"),
+            [0.0, 0.0, f64::from(width), f64::from(height)],
+            Some("/DeviceGray"),
+        );
+        let name = self.ext_gstate(format!(
+            "<< /Type /ExtGState /SMask << /Type /Mask /S /Luminosity /G {form} >> >>"
+        ));
+
+        self.retained_masks.push(mask.clone());
+        self.mask_cache.insert(key, name.clone());
+        name
+    }
+}
+
+fn write_resource_dict(out: &mut String, key: &str, prefix: char, ids: &[Ref]) {
+    if ids.is_empty() {
+        return;
+    }
+
+    write!(out, " /{key} <<").unwrap();
+    for (idx, id) in ids.iter().enumerate() {
+        write!(out, " /{prefix}{idx} {id}").unwrap();
+    }
+    out.push_str(" >>");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PdfDocument;
+    use crate::color::palette::css;
+    use crate::kurbo::Rect;
+
+    #[test]
+    fn cross_reference_table_is_valid() {
+        let mut document = PdfDocument::new();
+        for _ in 0..2 {
+            let mut page = document.page(100, 50);
+            page.set_paint(css::GREEN);
+            page.fill_rect(&Rect::new(0.0, 0.0, 10.0, 10.0));
+            page.finish();
+        }
+        assert_eq!(document.page_count(), 2);
+
+        let pdf = document.finish();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Type /Pages /Kids [5 0 R 8 0 R] /Count 2"));
+
+        let startxref = text.rfind("startxref\n").unwrap() + "startxref\n".len();
+        let xref_offset: usize = text[startxref..].lines().next().unwrap().parse().unwrap();
+        assert!(pdf[xref_offset..].starts_with(b"xref\n"));
+
+        let entries = text[xref_offset..].lines().skip(3);
+        for (idx, entry) in entries.take_while(|l| l.ends_with(" n\r")).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            let header = format!("{} 0 obj\n", idx + 1);
+            assert!(pdf[offset..].starts_with(header.as_bytes()));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Vello PDF writes vector PDF documents using the same drawing API as Vello CPU.
+//!
+//! This makes it possible to render a scene on screen with Vello CPU and to produce
+//! a printable version of the same scene by replaying the drawing commands into a
+//! [`PdfContext`] instead of a `RenderContext`.
+//!
+//! Paths, strokes, solid colors, linear and radial gradients, images, patterns as well as
+//! clip, blend, opacity and mask layers are written as native PDF constructs. Glyph runs
+//! are drawn through [`glifo`], which means that text is embedded as glyph outlines rather
+//! than as fonts. Content that has no PDF equivalent, most notably filter layers, is
+//! rasterized with Vello CPU at the resolution set by [`PdfDocument::set_raster_scale`]
+//! and embedded as an image.
+//!
+//! ```rust
+//! use vello_pdf::PdfDocument;
+//! use vello_pdf::{color::palette::css, kurbo::Rect};
+//!
+//! let mut document = PdfDocument::new();
+//! let mut page = document.page(200, 100);
+//! page.set_paint(css::MAGENTA);
+//! page.fill_rect(&Rect::new(10.0, 10.0, 190.0, 90.0));
+//! page.finish();
+//!
+//! let pdf = document.finish();
+//! assert!(pdf.starts_with(b"%PDF-1.7"));
+//! ```
+
+#![expect(
+    clippy::cast_possible_truncation,
+    reason = "We cast clamped floats and small integers in various places"
+)]
+
+mod context;
+mod document;
+mod paint;
+mod text;
+mod writer;
+
+pub use context::PdfContext;
+pub use document::PdfDocument;
+pub use glifo::{Glyph, GlyphPrepCache};
+pub use text::{GlyphRunBuilder, PdfGlyphRunBackend};
+pub use vello_common::filter_effects::Filter;
+pub use vello_common::mask::Mask;
+pub use vello_common::paint::{Image, ImageSource, PaintType};
+pub use vello_common::pixmap::Pixmap;
+pub use vello_common::{color, kurbo, peniko};
+
+#[cfg(test)]
+mod tests {
+    /// Decompress and concatenate all streams of a serialized document.
+    pub(crate) fn streams(pdf: &[u8]) -> String {
+        let mut out = String::new();
+        let mut rest = pdf;
+        while let Some(start) = find(rest, b">>\nstream\n") {
+            let data = &rest[start + 10..];
+            let end = find(data, b"\nendstream").unwrap();
+            let decompressed = miniz_oxide::inflate::decompress_to_vec_zlib(&data[..end]).unwrap();
+            out.push_str(&String::from_utf8_lossy(&decompressed));
+            out.push('\n');
+            rest = &data[end..];
+        }
+        out
+    }
+
+    fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        haystack.windows(needle.len()).position(|w| w == needle)
+    }
+}
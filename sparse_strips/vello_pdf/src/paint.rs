@@ -0,0 +1,300 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Conversion of paints into PDF patterns.
+
+use crate::PdfDocument;
+use crate::writer::{Matrix, Num};
+use std::fmt::Write as _;
+use vello_common::color::{ColorSpaceTag, Srgb};
+use vello_common::kurbo::Affine;
+use vello_common::peniko::{Extend, Gradient, GradientKind, InterpolationAlphaSpace};
+
+/// The number of segments each pair of stops is split into when the gradient is not
+/// interpolated linearly in sRGB.
+const STOP_SUBDIVISIONS: usize = 16;
+
+/// A gradient converted into shading patterns.
+#[derive(Debug)]
+pub(crate) struct GradientPattern {
+    /// The resource name of the pattern for the color channels.
+    pub(crate) pattern: String,
+    /// The resource name of a graphics state that applies the alpha channel of the
+    /// gradient as a soft mask, if the gradient is not opaque.
+    pub(crate) alpha: Option<String>,
+}
+
+impl PdfDocument {
+    /// Convert a gradient into shading patterns, given the transform from gradient space
+    /// to the coordinate system of the current content stream.
+    ///
+    /// Returns `None` if the gradient cannot be represented natively, in which case it
+    /// needs to be rasterized instead.
+    pub(crate) fn gradient(
+        &mut self,
+        gradient: &Gradient,
+        transform: Affine,
+        bbox: [f64; 4],
+    ) -> Option<GradientPattern> {
+        if !is_native_gradient(gradient) {
+            return None;
+        }
+
+        let coords = match gradient.kind {
+            GradientKind::Linear(pos) => format!(
+                "/ShadingType 2 /Coords [{} {} {} {}]",
+                Num(pos.start.x),
+                Num(pos.start.y),
+                Num(pos.end.x),
+                Num(pos.end.y)
+            ),
+            GradientKind::Radial(pos) => format!(
+                "/ShadingType 3 /Coords [{} {} {} {} {} {}]",
+                Num(pos.start_center.x),
+                Num(pos.start_center.y),
+                Num::from(pos.start_radius),
+                Num(pos.end_center.x),
+                Num(pos.end_center.y),
+                Num::from(pos.end_radius)
+            ),
+            GradientKind::Sweep(_) => return None,
+        };
+
+        let samples = sample_stops(gradient);
+        let color_function = function(&samples, |c| vec![c[0], c[1], c[2]]);
+        let pattern = self.shading_pattern(&coords, "/DeviceRGB", &color_function, transform);
+
+        let alpha = if samples.iter().any(|(_, c)| c[3] < 1.0) {
+            let alpha_function = function(&samples, |c| vec![c[3]]);
+            let alpha_pattern =
+                self.shading_pattern(&coords, "/DeviceGray", &alpha_function, transform);
+            let form = self.form(
+                &format!(
+                    "/Pattern cs {alpha_pattern} scn {} {} {} {} re f",
+                    Num(bbox[0]),
+                    Num(bbox[1]),
+                    Num(bbox[2] - bbox[0]),
+                    Num(bbox[3] - bbox[1])
+                ),
+                bbox,
+                Some("/DeviceGray"),
+            );
+            Some(self.ext_gstate(format!(
+                "<< /Type /ExtGState /SMask << /Type /Mask /S /Luminosity /G {form} >> >>"
+            )))
+        } else {
+            None
+        };
+
+        Some(GradientPattern { pattern, alpha })
+    }
+
+    fn shading_pattern(
+        &mut self,
+        coords: &str,
+        color_space: &str,
+        function: &str,
+        transform: Affine,
+    ) -> String {
+        let id = self.writer.alloc();
+        self.writer.object(
+            id,
+            format_args!(
+                "<< /Type /Pattern /PatternType 2 /Matrix [{}] /Shading << {coords} \
+                 /ColorSpace {color_space} /Function {function} /Extend [true true] >> >>",
+                Matrix(transform)
+            ),
+        );
+        self.pattern(id)
+    }
+
+    /// Create a tiling pattern that repeats an image `XObject` of the given size, given the
+    /// transform from image space to the coordinate system of the current content stream.
+    pub(crate) fn image_pattern(
+        &mut self,
+        image: &str,
+        width: u16,
+        height: u16,
+        transform: Affine,
+    ) -> String {
+        let bbox = [0.0, 0.0, f64::from(width), f64::from(height)];
+        self.tiling_pattern(
+            &format!("{width} 0 0 -{height} 0 {height} cm {image} Do"),
+            bbox,
+            transform,
+        )
+    }
+
+    /// Create a tiling pattern that repeats the given content, clipped to `bbox`, given the
+    /// transform from pattern space to the coordinate system of the current content stream.
+    pub(crate) fn tiling_pattern(
+        &mut self,
+        content: &str,
+        bbox: [f64; 4],
+        transform: Affine,
+    ) -> String {
+        let id = self.writer.alloc();
+        self.writer.stream(
+            id,
+            format_args!(
+                "/Type /Pattern /PatternType 1 /PaintType 1 /TilingType 1 \
+                 /BBox [{} {} {} {}] /XStep {} /YStep {} /Resources {} /Matrix [{}]",
+                Num(bbox[0]),
+                Num(bbox[1]),
+                Num(bbox[2]),
+                Num(bbox[3]),
+                Num(bbox[2] - bbox[0]),
+                Num(bbox[3] - bbox[1]),
+                self.resources,
+                Matrix(transform)
+            ),
+            content.as_bytes(),
+        );
+        self.pattern(id)
+    }
+}
+
+/// Whether a gradient can be converted into shading patterns.
+pub(crate) fn is_native_gradient(gradient: &Gradient) -> bool {
+    // PDF shadings only support padding, and have no equivalent for sweep gradients.
+    gradient.extend == Extend::Pad
+        && !gradient.stops.is_empty()
+        && !matches!(gradient.kind, GradientKind::Sweep(_))
+}
+
+/// Sample the stops of a gradient, such that interpolating linearly between the
+/// unpremultiplied sRGB samples approximates the interpolation of the gradient.
+fn sample_stops(gradient: &Gradient) -> Vec<(f32, [f32; 4])> {
+    let stops = &gradient.stops;
+    let to_srgb =
+        |color: vello_common::color::DynamicColor| color.to_alpha_color::<Srgb>().components;
+
+    let mut samples = vec![(stops[0].offset, to_srgb(stops[0].color))];
+    for pair in stops.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        let start_color = to_srgb(start.color);
+        let end_color = to_srgb(end.color);
+
+        // Linear interpolation in sRGB matches the PDF interpolation exactly, unless
+        // colors with different alpha values are interpolated in premultiplied space.
+        let needs_subdivision = gradient.interpolation_cs != ColorSpaceTag::Srgb
+            || (gradient.interpolation_alpha_space == InterpolationAlphaSpace::Premultiplied
+                && start_color[3] != end_color[3]);
+        if needs_subdivision && end.offset > start.offset {
+            for i in 1..STOP_SUBDIVISIONS {
+                let t = i as f32 / STOP_SUBDIVISIONS as f32;
+                let color = match gradient.interpolation_alpha_space {
+                    InterpolationAlphaSpace::Premultiplied => start
+                        .color
+                        .interpolate(end.color, gradient.interpolation_cs, gradient.hue_direction)
+                        .eval(t),
+                    InterpolationAlphaSpace::Unpremultiplied => start
+                        .color
+                        .interpolate_unpremultiplied(
+                            end.color,
+                            gradient.interpolation_cs,
+                            gradient.hue_direction,
+                        )
+                        .eval(t),
+                };
+                let offset = start.offset + (end.offset - start.offset) * t;
+                samples.push((offset, to_srgb(color)));
+            }
+        }
+
+        samples.push((end.offset, end_color));
+    }
+
+    for (_, color) in &mut samples {
+        for component in color.iter_mut() {
+            *component = component.clamp(0.0, 1.0);
+        }
+    }
+
+    samples
+}
+
+/// Build a function that interpolates the given channels of the samples.
+fn function(samples: &[(f32, [f32; 4])], channels: impl Fn(&[f32; 4]) -> Vec<f32>) -> String {
+    let values = |color: &[f32; 4]| {
+        let mut out = String::from("[");
+        for (idx, value) in channels(color).iter().enumerate() {
+            if idx > 0 {
+                out.push(' ');
+            }
+            write!(out, "{}", Num::from(*value)).unwrap();
+        }
+        out.push(']');
+        out
+    };
+    let interpolation = |start: &[f32; 4], end: &[f32; 4]| {
+        format!(
+            "<< /FunctionType 2 /Domain [0 1] /C0 {} /C1 {} /N 1 >>",
+            values(start),
+            values(end)
+        )
+    };
+
+    // Segments of zero width (i.e. hard stops) are dropped, the stitching function
+    // switches to the next color at the shared boundary anyway.
+    let first = samples[0];
+    let last = samples[samples.len() - 1];
+    let mut segments = Vec::new();
+    if first.0 > 0.0 {
+        segments.push((0.0, first.0, first.1, first.1));
+    }
+    for pair in samples.windows(2) {
+        let start = pair[0].0.clamp(0.0, 1.0);
+        let end = pair[1].0.clamp(0.0, 1.0);
+        if end > start {
+            segments.push((start, end, pair[0].1, pair[1].1));
+        }
+    }
+    if last.0 < 1.0 {
+        segments.push((last.0.max(0.0), 1.0, last.1, last.1));
+    }
+
+    match segments.as_slice() {
+        [] => interpolation(&last.1, &last.1),
+        [(_, _, start, end)] => interpolation(start, end),
+        segments => {
+            let mut functions = String::new();
+            let mut bounds = String::new();
+            let mut encode = String::new();
+            for (idx, (start, _, start_color, end_color)) in segments.iter().enumerate() {
+                if idx > 0 {
+                    write!(bounds, "{} ", Num::from(*start)).unwrap();
+                }
+                write!(functions, "{} ", interpolation(start_color, end_color)).unwrap();
+                encode.push_str("0 1 ");
+            }
+            format!(
+                "<< /FunctionType 3 /Domain [0 1] /Functions [{}] /Bounds [{}] /Encode [{}] >>",
+                functions.trim_end(),
+                bounds.trim_end(),
+                encode.trim_end()
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::function;
+
+    #[test]
+    fn hard_stops_are_stitched() {
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        let samples = [(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)];
+        let function = function(&samples, |c| vec![c[0], c[1], c[2]]);
+
+        assert_eq!(
+            function,
+            "<< /FunctionType 3 /Domain [0 1] /Functions [\
+             << /FunctionType 2 /Domain [0 1] /C0 [1 0 0] /C1 [1 0 0] /N 1 >> \
+             << /FunctionType 2 /Domain [0 1] /C0 [0 0 1] /C1 [0 0 1] /N 1 >>] \
+             /Bounds [0.5] /Encode [0 1 0 1] >>"
+        );
+    }
+}
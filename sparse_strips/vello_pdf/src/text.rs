@@ -0,0 +1,259 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Glyph rendering for PDF output.
+//!
+//! Glyphs are drawn uncached through the regular drawing API, i.e. outline glyphs become
+//! filled paths, bitmap glyphs become images and COLR glyphs become the corresponding
+//! gradients and layers.
+
+use crate::PdfContext;
+use core::ops::RangeInclusive;
+use glifo::atlas::AtlasSlot;
+use glifo::{AtlasCacher, AtlasPaint, DrawSink, Glyph, GlyphPrepCache, GlyphRunBackend};
+use vello_common::color::palette::css::BLACK;
+use vello_common::color::{AlphaColor, Srgb};
+use vello_common::kurbo::{Affine, BezPath, Rect};
+use vello_common::paint::{Image, ImageId, ImageSource, PaintType, Tint};
+use vello_common::peniko::{BlendMode, FontData};
+use vello_common::render_state::RenderState;
+
+/// A glyph run builder.
+pub type GlyphRunBuilder<'a, 'b> = glifo::GlyphRunBuilder<'a, PdfGlyphRunBackend<'a, 'b>>;
+
+impl<'b> PdfContext<'b> {
+    /// Creates a builder for drawing a run of glyphs that have the same attributes.
+    pub fn glyph_run<'a>(
+        &'a mut self,
+        prep_cache: &'a mut GlyphPrepCache,
+        font: &FontData,
+    ) -> GlyphRunBuilder<'a, 'b> {
+        glifo::GlyphRunBuilder::new(
+            font.clone(),
+            self.state.transform,
+            self.state.paint_transform,
+            PdfGlyphRunBackend {
+                ctx: self,
+                prep_cache,
+            },
+        )
+    }
+}
+
+/// The glyph run backend of [`PdfContext`].
+#[derive(Debug)]
+pub struct PdfGlyphRunBackend<'a, 'b> {
+    ctx: &'a mut PdfContext<'b>,
+    prep_cache: &'a mut GlyphPrepCache,
+}
+
+impl<'a, 'b> PdfGlyphRunBackend<'a, 'b> {
+    fn render_glyphs<Glyphs>(
+        self,
+        run: glifo::GlyphRun<'a>,
+        glyphs: Glyphs,
+        render: impl FnOnce(&mut glifo::GlyphRunRenderer<'a, 'a, Glyphs>, &mut PdfContext<'b>),
+    ) where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        // Glyphs are vector content, so there is no point in caching rasterized versions.
+        let mut glyph_run = run.build(glyphs, self.prep_cache.as_mut(), AtlasCacher::Disabled);
+        render(&mut glyph_run, self.ctx);
+    }
+}
+
+impl<'a> GlyphRunBackend<'a> for PdfGlyphRunBackend<'a, '_> {
+    fn atlas_cache(self, _enabled: bool) -> Self {
+        self
+    }
+
+    fn fill_glyphs<Glyphs>(self, run: glifo::GlyphRun<'a>, glyphs: Glyphs)
+    where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        self.render_glyphs(run, glyphs, |glyph_run, ctx| glyph_run.fill_glyphs(ctx));
+    }
+
+    fn stroke_glyphs<Glyphs>(self, run: glifo::GlyphRun<'a>, glyphs: Glyphs)
+    where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        self.render_glyphs(run, glyphs, |glyph_run, ctx| {
+            let stroke_adjustment = glyph_run.stroke_adjustment();
+            let original_width = ctx.state.stroke.width;
+            ctx.state.stroke.width *= stroke_adjustment;
+            glyph_run.stroke_glyphs(ctx);
+            ctx.state.stroke.width = original_width;
+        });
+    }
+
+    fn render_decoration<Glyphs>(
+        self,
+        run: glifo::GlyphRun<'a>,
+        glyphs: Glyphs,
+        x_range: RangeInclusive<f32>,
+        baseline_y: f32,
+        offset: f32,
+        size: f32,
+        buffer: f32,
+    ) where
+        Glyphs: Iterator<Item = Glyph> + Clone,
+    {
+        self.render_glyphs(run, glyphs, |glyph_run, ctx| {
+            glyph_run.render_decoration(x_range, baseline_y, offset, size, buffer, ctx);
+        });
+    }
+}
+
+impl DrawSink for PdfContext<'_> {
+    #[inline]
+    fn set_transform(&mut self, t: Affine) {
+        Self::set_transform(self, t);
+    }
+
+    #[inline]
+    fn set_paint(&mut self, paint: AtlasPaint) {
+        Self::set_paint(self, paint);
+    }
+
+    #[inline]
+    fn set_paint_transform(&mut self, t: Affine) {
+        Self::set_paint_transform(self, t);
+    }
+
+    #[inline]
+    fn fill_path(&mut self, path: &BezPath) {
+        Self::fill_path(self, path);
+    }
+
+    #[inline]
+    fn fill_rect(&mut self, rect: &Rect) {
+        Self::fill_rect(self, rect);
+    }
+
+    #[inline]
+    fn push_clip_layer(&mut self, clip: &BezPath) {
+        Self::push_clip_layer(self, clip);
+    }
+
+    #[inline]
+    fn push_clip_path(&mut self, clip: &BezPath) {
+        Self::push_clip_path(self, clip);
+    }
+
+    #[inline]
+    fn push_blend_layer(&mut self, blend_mode: BlendMode) {
+        Self::push_blend_layer(self, blend_mode);
+    }
+
+    #[inline]
+    fn pop_layer(&mut self) {
+        Self::pop_layer(self);
+    }
+
+    #[inline]
+    fn pop_clip_path(&mut self) {
+        Self::pop_clip_path(self);
+    }
+
+    #[inline]
+    fn width(&self) -> u16 {
+        Self::width(self)
+    }
+
+    #[inline]
+    fn height(&self) -> u16 {
+        Self::height(self)
+    }
+}
+
+impl glifo::GlyphRenderer for PdfContext<'_> {
+    type SavedState = RenderState;
+
+    #[inline]
+    fn save_state(&mut self) -> Self::SavedState {
+        self.save_current_state()
+    }
+
+    #[inline]
+    fn restore_state(&mut self, state: Self::SavedState) {
+        Self::restore_state(self, state);
+    }
+
+    #[inline]
+    fn stroke_path(&mut self, path: &BezPath) {
+        Self::stroke_path(self, path);
+    }
+
+    #[inline]
+    fn set_paint_image(&mut self, image: Image) {
+        self.set_paint(image);
+    }
+
+    #[inline]
+    fn set_tint(&mut self, tint: Option<Tint>) {
+        Self::set_tint(self, tint);
+    }
+
+    #[inline]
+    fn get_context_color(&self) -> AlphaColor<Srgb> {
+        match self.paint() {
+            PaintType::Solid(s) => *s,
+            _ => BLACK,
+        }
+    }
+
+    #[inline]
+    fn current_paint(&self) -> &PaintType {
+        self.paint()
+    }
+
+    // Glyph runs are always built with `AtlasCacher::Disabled`, so the two methods below
+    // are only reached if they are called directly. Opaque images cannot be resolved in
+    // PDF output, so drawing with the returned source is a no-op.
+
+    fn atlas_image_source(&self, atlas_slot: &AtlasSlot) -> ImageSource {
+        ImageSource::opaque_id(ImageId::new(atlas_slot.page_index))
+    }
+
+    fn atlas_paint_transform(&self, atlas_slot: &AtlasSlot) -> Affine {
+        Affine::translate((-(atlas_slot.x as f64), -(atlas_slot.y as f64)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::PdfDocument;
+    use crate::tests::streams;
+    use glifo::{Glyph, GlyphPrepCache};
+    use std::sync::Arc;
+    use vello_common::kurbo::Affine;
+    use vello_common::peniko::{Blob, FontData};
+
+    #[test]
+    fn glyphs_are_written_as_outlines() {
+        const ROBOTO_FONT: &[u8] =
+            include_bytes!("../../../examples/assets/roboto/Roboto-Regular.ttf");
+        let font = FontData::new(Blob::new(Arc::new(ROBOTO_FONT)), 0);
+        // Glyph 0 is the notdef glyph, which is a simple rectangle in Roboto.
+        let glyphs = [Glyph {
+            id: 0,
+            x: 10.0,
+            y: 40.0,
+        }];
+
+        let mut document = PdfDocument::new();
+        let mut prep_cache = GlyphPrepCache::default();
+        let mut page = document.page(100, 50);
+        page.set_transform(Affine::translate((5.0, 0.0)));
+        page.glyph_run(&mut prep_cache, &font)
+            .font_size(32.0)
+            .fill_glyphs(glyphs.into_iter());
+        page.finish();
+
+        // The outline is filled as a regular path, in page coordinates.
+        let content = streams(&document.finish());
+        let path = content.lines().find(|l| l.ends_with(" f Q")).unwrap();
+        assert!(path.starts_with("q 0 0 0 rg 27.625 40 m 16.5625 40 l"));
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Low-level serialization of PDF objects.
+
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::io::Write as _;
+use vello_common::kurbo::Affine;
+
+/// A reference to an indirect object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Ref(u32);
+
+impl Display for Ref {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} 0 R", self.0)
+    }
+}
+
+/// A number formatted in the compact way expected in content streams and dictionaries.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Num(pub(crate) f64);
+
+impl Display for Num {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.0.is_finite() {
+            return f.write_str("0");
+        }
+
+        // Four decimal places are more than enough precision for points, colors and
+        // transform coefficients in practice.
+        let rounded = (self.0 * 10_000.0).round() / 10_000.0;
+        if rounded == 0.0 {
+            return f.write_str("0");
+        }
+
+        let mut buf = String::new();
+        write!(buf, "{rounded:.4}")?;
+        let trimmed = buf.trim_end_matches('0').trim_end_matches('.');
+        f.write_str(trimmed)
+    }
+}
+
+impl From<f32> for Num {
+    fn from(value: f32) -> Self {
+        Self(f64::from(value))
+    }
+}
+
+/// A transformation matrix, formatted as six operands.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Matrix(pub(crate) Affine);
+
+impl Display for Matrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0.as_coeffs();
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            Num(a),
+            Num(b),
+            Num(c),
+            Num(d),
+            Num(e),
+            Num(g)
+        )
+    }
+}
+
+/// A collection of indirect objects that is serialized into a complete file at the end.
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    /// The body of each object, indexed by its object number minus one.
+    objects: Vec<Option<Vec<u8>>>,
+}
+
+impl Writer {
+    /// Reserve a new object number.
+    pub(crate) fn alloc(&mut self) -> Ref {
+        self.objects.push(None);
+        Ref(u32::try_from(self.objects.len()).expect("too many PDF objects"))
+    }
+
+    /// Set the body of a non-stream object.
+    pub(crate) fn object(&mut self, id: Ref, body: impl Display) {
+        self.objects[id.0 as usize - 1] = Some(body.to_string().into_bytes());
+    }
+
+    /// Set the body of a stream object, compressing the data.
+    ///
+    /// `dict` contains the additional entries of the stream dictionary, without
+    /// the surrounding `<<` and `>>`.
+    pub(crate) fn stream(&mut self, id: Ref, dict: impl Display, data: &[u8]) {
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+        let mut body = Vec::with_capacity(compressed.len() + 128);
+        write!(
+            body,
+            "<< {dict} /Filter /FlateDecode /Length {} >>\nstream\n",
+            compressed.len()
+        )
+        .unwrap();
+        body.extend_from_slice(&compressed);
+        body.extend_from_slice(b"\nendstream");
+        self.objects[id.0 as usize - 1] = Some(body);
+    }
+
+    /// Serialize all objects, followed by the cross-reference table and the trailer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an allocated object was never written.
+    pub(crate) fn finish(self, catalog: Ref) -> Vec<u8> {
+        let mut out = Vec::new();
+        // The comment with high-bit characters marks the file as binary.
+        out.extend_from_slice(b"%PDF-1.7\n%\x80\x80\x80\x80\n");
+
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (idx, body) in self.objects.into_iter().enumerate() {
+            let body = body.expect("all allocated PDF objects should be written");
+            offsets.push(out.len());
+            writeln!(out, "{} 0 obj", idx + 1).unwrap();
+            out.extend_from_slice(&body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = out.len();
+        write!(out, "xref\n0 {}\n0000000000 65535 f\r\n", offsets.len() + 1).unwrap();
+        for offset in &offsets {
+            write!(out, "{offset:010} 00000 n\r\n").unwrap();
+        }
+        write!(
+            out,
+            "trailer\n<< /Size {} /Root {catalog} >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            offsets.len() + 1
+        )
+        .unwrap();
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Num;
+
+    #[test]
+    fn number_formatting() {
+        assert_eq!(Num(1.0).to_string(), "1");
+        assert_eq!(Num(-2.5).to_string(), "-2.5");
+        assert_eq!(Num(0.123_456).to_string(), "0.1235");
+        assert_eq!(Num(-0.000_01).to_string(), "0");
+        assert_eq!(Num(f64::NAN).to_string(), "0");
+        assert_eq!(Num(100.0).to_string(), "100");
+    }
+}
@@ -27,6 +27,9 @@ mod types;
 // TODO: Format the generated code via `rustfmt`.
 // TODO: Use `quote` instead of string concatenation to generate code.
 fn main() {
+    log::set_logger(&BUILD_SCRIPT_LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Warn);
+
     // Rerun build if the shaders directory changes
     println!("cargo:rerun-if-changed=shaders");
     let out_dir = env::var_os("OUT_DIR").unwrap();
@@ -95,3 +98,23 @@ fn generate_wgsl_shader_module(
 
     Ok(())
 }
+
+/// Forwards warnings from the shared shader preprocessor to Cargo, so that they are
+/// visible to the user.
+struct BuildScriptLog;
+
+static BUILD_SCRIPT_LOGGER: BuildScriptLog = BuildScriptLog;
+
+impl log::Log for BuildScriptLog {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        metadata.level() <= log::Level::Warn
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if self.enabled(record.metadata()) {
+            println!("cargo:warning={}: {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
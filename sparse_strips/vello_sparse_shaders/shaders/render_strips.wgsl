@@ -10,6 +10,9 @@
 // The alpha values are stored in a texture and sampled during fragment shading.
 // This approach optimizes memory usage by only storing alpha data where needed.
 //
+// The shader is preprocessed before compilation, see the `permutations` file for the
+// variants that are generated from it. If `no_blend` is defined, the shader doesn't
+// support non-default blend modes.
 //
 // `StripInstance::paint` field encodes a color source, a paint type and a paint texture id
 // Color source determines where the fragment shader gets color data from
@@ -419,8 +422,9 @@ fn fs_main(
             let image_source_kind = get_image_source_kind(image_texel0);
             let image_padding = get_image_padding(image_texel2);
             let packed_tint = image_texel2.y;
-            let has_tint = packed_tint != 0u;
-            // When packed_tint is zero (no tint), use identity color vec4(1.0) with
+            // A fully transparent tint is packed as zero with the multiply mode.
+            let has_tint = packed_tint != 0u || image_texel2.z != TINT_MODE_ALPHA_MASK;
+            // Without a tint, use identity color vec4(1.0) with
             // Multiply mode so the math reduces to sample_color * 1.0 = sample_color.
             let image_tint = select(vec4<f32>(1.0), unpack4x8unorm(packed_tint), has_tint);
             let is_multiply = !has_tint || image_texel2.z != TINT_MODE_ALPHA_MASK;
@@ -439,12 +443,16 @@ fn fs_main(
 
             var sample_color: vec4<f32>;
             if image_source_kind == IMAGE_SOURCE_EXTERNAL {
-                let final_xy = image_offset + extended_xy;
+                // Each filter tap is extended individually, like in vello_cpu. Extending the
+                // position beforehand would clamp it to the last texel with `Pad`, so that the
+                // edge would be blended with its neighbor.
+                let final_xy = sample_xy + offset;
                 sample_color = sample_external_image(
                     image_quality,
                     final_xy,
                     image_offset,
                     image_size,
+                    image_extend_modes,
                 );
             } else if image_quality == IMAGE_QUALITY_HIGH {
                 let final_xy = image_offset + extended_xy;
@@ -498,7 +506,9 @@ fn fs_main(
                 t_value,
                 get_gradient_extend_mode(gradient_texel0),
                 get_gradient_start(gradient_texel0),
-                get_gradient_texture_width(gradient_texel0)
+                get_gradient_texture_width(gradient_texel0),
+                get_gradient_dithered(gradient_texel0),
+                position.xy
             );
             final_color = alpha * gradient_color;
         } else if paint_type == PAINT_TYPE_RADIAL_GRADIENT {
@@ -518,7 +528,9 @@ fn fs_main(
                 gradient_result.x,
                 get_gradient_extend_mode(gradient_texel0),
                 get_gradient_start(gradient_texel0),
-                get_gradient_texture_width(gradient_texel0)
+                get_gradient_texture_width(gradient_texel0),
+                get_gradient_dithered(gradient_texel0),
+                position.xy
             );
             final_color = select(
                 vec4<f32>(0.0, 0.0, 0.0, 0.0),
@@ -555,7 +567,9 @@ fn fs_main(
                 t_value,
                 get_gradient_extend_mode(gradient_texel0),
                 get_gradient_start(gradient_texel0),
-                get_gradient_texture_width(gradient_texel0)
+                get_gradient_texture_width(gradient_texel0),
+                get_gradient_dithered(gradient_texel0),
+                position.xy
             );
             final_color = alpha * gradient_color;
         } else if paint_type == PAINT_TYPE_BLURRED_ROUNDED_RECT {
@@ -596,7 +610,9 @@ fn fs_main(
         let opacity = f32(paint_and_rect_flag & 0xFFu) * (1.0 / 255.0);
 
         final_color = alpha * opacity * clip_in_color;
-    } else if color_source == COLOR_SOURCE_BLEND {
+    }
+#ifndef no_blend
+    else if color_source == COLOR_SOURCE_BLEND {
         // See the comment above.
         let sample_y = select(position.y, f32(config.height) - position.y, config.ndc_y_negate != 0u);
         let opacity = f32((paint_and_rect_flag >> 16u) & 0xFFu) * (1.0 / 255.0);
@@ -618,9 +634,11 @@ fn fs_main(
 
         final_color = blend_mix_compose(dest_color, src_color * opacity * alpha, compose_mode, mix_mode);
     }
+#endif
     return final_color;
 }
 
+#ifndef no_blend
 // Apply color mixing and composition. Both input and output colors are premultiplied RGB.
 // Referenced from:
 //   <https://github.com/linebender/vello/blob/b0e2e598ac62c7b3d04d8660e7b1b7659b596970/vello_shaders/shader/shared/blend.wgsl#L288-L310>
@@ -893,7 +911,7 @@ fn blend_mix(cb: vec3<f32>, cs: vec3<f32>, mode: u32) -> vec3<f32> {
     }
     return b;
 }
-
+#endif
 
 /// Tint mode constants.
 const TINT_MODE_ALPHA_MASK: u32 = 0u;
@@ -927,8 +945,8 @@ fn load_encoded_paint_texel(paint_tex_idx: u32, texel_offset: u32) -> vec4<u32>
 // texel0.z: image_offset, packed as [x:16, y:16]
 // texel0.w/texel1.x/texel1.y/texel1.z: transform matrix [a, b, c, d]
 // texel1.w/texel2.x: translation [tx, ty]
-// texel2.y: premultiplied tint color packed as RGBA8 unorm; 0 means no tint
-// texel2.z: tint mode, only meaningful when texel2.y != 0
+// texel2.y: premultiplied tint color packed as RGBA8 unorm
+// texel2.z: tint mode; texel2.y == 0 with the alpha mask mode means no tint
 // texel2.w: transparent padding pixels around the image in the atlas
 
 /// The rendering quality of the image.
@@ -1037,20 +1055,36 @@ fn bilinear_sample(
     return mix(mix(a, b, uv_frac.y), mix(c, d, uv_frac.y), uv_frac.x);
 }
 
+// Load the texel of the external texture that contains `coords`, applying the extend modes to
+// texels outside of the image. Unlike the atlas, the external texture has no padding around
+// the sampled region, so each filter tap needs to be extended individually.
+fn external_texel(
+    coords: vec2<f32>,
+    image_offset: vec2<f32>,
+    image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
+) -> vec4<f32> {
+    // Extend the center of the texel, so that rounding down yields the extended texel.
+    let local = floor(coords - image_offset) + vec2(0.5);
+    let extended = vec2<f32>(
+        extend_mode(local.x, extend_modes.x, image_size.x),
+        extend_mode(local.y, extend_modes.y, image_size.y)
+    );
+    return textureLoad(external_texture, vec2<i32>(floor(image_offset + extended)), 0);
+}
+
 // This is the same as `bilinear_sample` above, but for external textures instead of the atlas texture array.
 fn external_bilinear_sample(
     coords: vec2<f32>,
     image_offset: vec2<f32>,
     image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
 ) -> vec4<f32> {
-    let atlas_max = image_offset + image_size - vec2(1.0);
-    let atlas_uv_clamped = clamp(coords, image_offset, atlas_max);
-    let uv_quad = vec4(floor(atlas_uv_clamped), ceil(atlas_uv_clamped));
     let uv_frac = fract(coords);
-    let a = textureLoad(external_texture, vec2<i32>(uv_quad.xy), 0);
-    let b = textureLoad(external_texture, vec2<i32>(uv_quad.xw), 0);
-    let c = textureLoad(external_texture, vec2<i32>(uv_quad.zy), 0);
-    let d = textureLoad(external_texture, vec2<i32>(uv_quad.zw), 0);
+    let a = external_texel(coords, image_offset, image_size, extend_modes);
+    let b = external_texel(coords + vec2(0.0, 1.0), image_offset, image_size, extend_modes);
+    let c = external_texel(coords + vec2(1.0, 0.0), image_offset, image_size, extend_modes);
+    let d = external_texel(coords + vec2(1.0, 1.0), image_offset, image_size, extend_modes);
     return mix(mix(a, b, uv_frac.y), mix(c, d, uv_frac.y), uv_frac.x);
 }
 
@@ -1114,33 +1148,33 @@ fn external_bicubic_sample(
     coords: vec2<f32>,
     image_offset: vec2<f32>,
     image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
 ) -> vec4<f32> {
-     let atlas_max = image_offset + image_size - vec2(1.0);
      let frac_coords = fract(coords + 0.5);
      // Get cubic weights for x and y directions
      let cx = cubic_weights(frac_coords.x);
      let cy = cubic_weights(frac_coords.y);
 
      // Sample 4x4 grid around coords
-     let s00 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-1.5, -1.5), image_offset, atlas_max)), 0);
-     let s10 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-0.5, -1.5), image_offset, atlas_max)), 0);
-     let s20 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(0.5, -1.5), image_offset, atlas_max)), 0);
-     let s30 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(1.5, -1.5), image_offset, atlas_max)), 0);
-
-     let s01 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-1.5, -0.5), image_offset, atlas_max)), 0);
-     let s11 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-0.5, -0.5), image_offset, atlas_max)), 0);
-     let s21 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(0.5, -0.5), image_offset, atlas_max)), 0);
-     let s31 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(1.5, -0.5), image_offset, atlas_max)), 0);
-
-     let s02 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-1.5, 0.5), image_offset, atlas_max)), 0);
-     let s12 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-0.5, 0.5), image_offset, atlas_max)), 0);
-     let s22 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(0.5, 0.5), image_offset, atlas_max)), 0);
-     let s32 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(1.5, 0.5), image_offset, atlas_max)), 0);
-
-     let s03 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-1.5, 1.5), image_offset, atlas_max)), 0);
-     let s13 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(-0.5, 1.5), image_offset, atlas_max)), 0);
-     let s23 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(0.5, 1.5), image_offset, atlas_max)), 0);
-     let s33 = textureLoad(external_texture, vec2<i32>(clamp(coords + vec2(1.5, 1.5), image_offset, atlas_max)), 0);
+     let s00 = external_texel(coords + vec2(-1.5, -1.5), image_offset, image_size, extend_modes);
+     let s10 = external_texel(coords + vec2(-0.5, -1.5), image_offset, image_size, extend_modes);
+     let s20 = external_texel(coords + vec2(0.5, -1.5), image_offset, image_size, extend_modes);
+     let s30 = external_texel(coords + vec2(1.5, -1.5), image_offset, image_size, extend_modes);
+
+     let s01 = external_texel(coords + vec2(-1.5, -0.5), image_offset, image_size, extend_modes);
+     let s11 = external_texel(coords + vec2(-0.5, -0.5), image_offset, image_size, extend_modes);
+     let s21 = external_texel(coords + vec2(0.5, -0.5), image_offset, image_size, extend_modes);
+     let s31 = external_texel(coords + vec2(1.5, -0.5), image_offset, image_size, extend_modes);
+
+     let s02 = external_texel(coords + vec2(-1.5, 0.5), image_offset, image_size, extend_modes);
+     let s12 = external_texel(coords + vec2(-0.5, 0.5), image_offset, image_size, extend_modes);
+     let s22 = external_texel(coords + vec2(0.5, 0.5), image_offset, image_size, extend_modes);
+     let s32 = external_texel(coords + vec2(1.5, 0.5), image_offset, image_size, extend_modes);
+
+     let s03 = external_texel(coords + vec2(-1.5, 1.5), image_offset, image_size, extend_modes);
+     let s13 = external_texel(coords + vec2(-0.5, 1.5), image_offset, image_size, extend_modes);
+     let s23 = external_texel(coords + vec2(0.5, 1.5), image_offset, image_size, extend_modes);
+     let s33 = external_texel(coords + vec2(1.5, 1.5), image_offset, image_size, extend_modes);
 
     // Interpolate in x direction for each row
     let row0 = cx.x * s00 + cx.y * s10 + cx.z * s20 + cx.w * s30;
@@ -1159,14 +1193,15 @@ fn sample_external_image(
     coords: vec2<f32>,
     image_offset: vec2<f32>,
     image_size: vec2<f32>,
+    extend_modes: vec2<u32>,
 ) -> vec4<f32> {
     if quality == IMAGE_QUALITY_HIGH {
-        return external_bicubic_sample(coords, image_offset, image_size);
+        return external_bicubic_sample(coords, image_offset, image_size, extend_modes);
     }
     if quality == IMAGE_QUALITY_MEDIUM {
-        return external_bilinear_sample(coords - vec2(0.5), image_offset, image_size);
+        return external_bilinear_sample(coords - vec2(0.5), image_offset, image_size, extend_modes);
     }
-    return textureLoad(external_texture, vec2<u32>(coords), 0);
+    return external_texel(coords, image_offset, image_size, extend_modes);
 }
 
 // Cubic resampler logic borrowed from Skia (same as CPU cubic_resampler function)
@@ -1240,25 +1275,61 @@ fn xy_to_unit_angle(x: f32, y: f32) -> f32 {
 }
 
 // Sample from the gradient texture at calculated position.
-fn sample_gradient_lut(t_value: f32, extend_mode: u32, gradient_start: u32, texture_width: u32) -> vec4<f32> {
+//
+// Dithered ramps store two texels per entry: the color rounded down to 8 bits, and the
+// remaining fraction scaled up to 8 bits. Together, they allow adding an ordered dithering
+// offset based on the pixel position, so that gradients with a low contrast don't show bands.
+fn sample_gradient_lut(
+    t_value: f32,
+    extend_mode: u32,
+    gradient_start: u32,
+    texture_width: u32,
+    dithered: bool,
+    pixel: vec2<f32>,
+) -> vec4<f32> {
     // Apply extend mode to t_value
     let clamped_t = extend_mode_normalized(t_value, extend_mode);
     // Convert t_value to texture coordinate
     let t_offset = u32(clamped_t * f32(texture_width - 1u));
-    // Calculate absolute position in flat gradient texture
-    let flat_coord = gradient_start + t_offset;
+    if !dithered {
+        return load_gradient_texel(gradient_start + t_offset);
+    }
+
+    let high = load_gradient_texel(gradient_start + 2u * t_offset);
+    let low = load_gradient_texel(gradient_start + 2u * t_offset + 1u);
+    let color = high + low / 255.0;
+    let offset = ((bayer_8x8(vec2<u32>(pixel)) + 0.5) / 64.0 - 0.5) / 255.0;
+    return vec4<f32>(clamp(color.rgb + offset, vec3<f32>(0.0), vec3<f32>(color.a)), color.a);
+}
+
+// Load the texel at the given position in the flat gradient texture.
+fn load_gradient_texel(flat_coord: u32) -> vec4<f32> {
     // Convert flat coordinate to 2D texture coordinate
     let gradient_tex_width = textureDimensions(gradient_texture).x;
     let tex_x = flat_coord % gradient_tex_width;
     let tex_y = flat_coord / gradient_tex_width;
-    // Sample from the gradient texture at calculated position
-    let gradient_color = textureLoad(gradient_texture, vec2<u32>(tex_x, tex_y), 0);
-    return gradient_color;
+    return textureLoad(gradient_texture, vec2<u32>(tex_x, tex_y), 0);
+}
+
+// The threshold of the 8x8 Bayer matrix at the given pixel, from 0 to 63.
+//
+// This matches the matrix that `vello_cpu` uses for dithering.
+fn bayer_8x8(pixel: vec2<u32>) -> f32 {
+    let x = pixel.x;
+    let y = pixel.y;
+    let v = x ^ y;
+    // Interleave the low three bits of `x ^ y` and `y`, in reverse order.
+    let threshold = ((v & 1u) << 5u) | ((y & 1u) << 4u) | ((v & 2u) << 2u)
+        | ((y & 2u) << 1u) | ((v & 4u) >> 1u) | ((y & 4u) >> 2u);
+    return f32(threshold);
 }
 
 /// Width of the gradient texture.
 fn get_gradient_texture_width(texel0: vec4<u32>) -> u32 { return texel0.x & 0x0FFFFFFFu; }
 
+/// Whether the gradient ramp is stored with extra precision and should be dithered.
+fn get_gradient_dithered(texel0: vec4<u32>) -> bool { return ((texel0.x >> 28u) & 1u) != 0u; }
+
 /// The extend mode for the gradient.
 fn get_gradient_extend_mode(texel0: vec4<u32>) -> u32 { return (texel0.x >> 30u) & 3u; }
 
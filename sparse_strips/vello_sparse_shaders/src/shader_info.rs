@@ -1,17 +1,31 @@
 // Copyright 2026 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::Path;
 
+use vello_shaders::compile::permutations;
+use vello_shaders::compile::preprocess::preprocess;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) struct ShaderInfo {
     pub(crate) name: String,
     pub(crate) wgsl_source: String,
 }
 
+/// Load all shaders in `shader_dir`.
+///
+/// Shaders which are listed in the `permutations` file of the directory are expanded
+/// into one shader per permutation.
 pub(crate) fn load_shader_infos(shader_dir: &Path) -> io::Result<Vec<ShaderInfo>> {
+    let permutation_map = match fs::read_to_string(shader_dir.join("permutations")) {
+        Ok(source) => permutations::parse(&source),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::default(),
+        Err(err) => return Err(err),
+    };
+
     let shader_paths = fs::read_dir(shader_dir)?
         .filter_map(|entry| {
             let entry = entry.ok()?;
@@ -28,10 +42,20 @@ pub(crate) fn load_shader_infos(shader_dir: &Path) -> io::Result<Vec<ShaderInfo>
     for path in shader_paths {
         let file_stem = path.file_stem().unwrap().to_str().unwrap().to_owned();
         let source = fs::read_to_string(&path)?;
-        shader_infos.push(ShaderInfo {
-            name: file_stem,
-            wgsl_source: source,
-        });
+        if let Some(permutations) = permutation_map.get(&file_stem) {
+            for permutation in permutations {
+                let defines = permutation.defines.iter().cloned().collect();
+                shader_infos.push(ShaderInfo {
+                    name: permutation.name.clone(),
+                    wgsl_source: preprocess(&source, &file_stem, &defines, &HashMap::new()),
+                });
+            }
+        } else {
+            shader_infos.push(ShaderInfo {
+                wgsl_source: preprocess(&source, &file_stem, &HashSet::new(), &HashMap::new()),
+                name: file_stem,
+            });
+        }
     }
     shader_infos.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(shader_infos)
@@ -78,6 +78,7 @@ fn render_probe_pixmap() -> Pixmap {
     let settings = RenderSettings {
         level: Level::fallback(),
         num_threads: 0,
+        ..Default::default()
     };
     let mut ctx = RenderContext::new_with(width, height, settings);
 
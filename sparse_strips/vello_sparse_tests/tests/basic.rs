@@ -545,6 +545,7 @@ fn render_src_over_with_offset() {
     let settings = RenderSettings {
         level: Level::try_detect().unwrap_or(Level::baseline()),
         num_threads: 0,
+        ..Default::default()
     };
     let rasterizer_settings = RasterizerSettings {
         render_mode: RenderMode::OptimizeQuality,
@@ -0,0 +1,71 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Custom paints are only supported by Vello CPU.
+
+use crate::renderer::Renderer;
+use std::sync::Arc;
+use vello_common::kurbo::{Affine, Point, Rect};
+use vello_common::tile::Tile;
+use vello_cpu::{CustomPaint, CustomPaintSpan};
+use vello_dev_macros::vello_test;
+
+/// A checkerboard of black and white squares with a size of one unit.
+#[derive(Debug)]
+struct Checkerboard;
+
+impl Checkerboard {
+    fn colors(span: &CustomPaintSpan<'_>) -> impl Iterator<Item = f32> {
+        let height = usize::from(Tile::HEIGHT);
+        let span = *span;
+        (0..usize::from(span.width) * height).map(move |idx| {
+            let x = f64::from(span.x) + (idx / height) as f64 + 0.5;
+            let y = f64::from(span.y) + (idx % height) as f64 + 0.5;
+            let point = span.transform * Point::new(x, y);
+            if (point.x.floor() + point.y.floor()) % 2.0 == 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
+impl CustomPaint for Checkerboard {
+    fn paint_u8(&self, span: &CustomPaintSpan<'_>, buf: &mut [u8]) {
+        for (pixel, value) in buf.chunks_exact_mut(4).zip(Self::colors(span)) {
+            let value = (value * 255.0) as u8;
+            pixel.copy_from_slice(&[value, value, value, 255]);
+        }
+    }
+
+    fn paint_f32(&self, span: &CustomPaintSpan<'_>, buf: &mut [f32]) {
+        for (pixel, value) in buf.chunks_exact_mut(4).zip(Self::colors(span)) {
+            pixel.copy_from_slice(&[value, value, value, 1.0]);
+        }
+    }
+
+    fn may_have_transparency(&self) -> bool {
+        false
+    }
+}
+
+#[vello_test(width = 16, height = 16, skip_hybrid)]
+fn custom_paint_checkerboard(ctx: &mut impl Renderer) {
+    let id = ctx.register_custom_paint(Arc::new(Checkerboard));
+    ctx.set_paint(id);
+    ctx.set_paint_transform(Affine::scale(4.0));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+}
+
+/// Destroyed custom paints are not drawn anymore.
+#[vello_test(width = 16, height = 16, skip_hybrid)]
+fn custom_paint_destroyed(ctx: &mut impl Renderer) {
+    let id = ctx.register_custom_paint(Arc::new(Checkerboard));
+    ctx.set_paint(id);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+    assert!(
+        ctx.destroy_custom_paint(id),
+        "the custom paint should be registered"
+    );
+}
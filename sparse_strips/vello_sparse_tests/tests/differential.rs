@@ -0,0 +1,543 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Property-based differential tests between the `u8` and `f32` pipelines of Vello CPU.
+//!
+//! Random scenes consisting of paths, gradients, images, layers and clips are rendered with
+//! both pipelines, and the results must agree within a small per-channel tolerance. The scenes
+//! are generated with `proptest`, which shrinks failing scenes to a (locally) minimal one.
+//!
+//! The seed is fixed so that the test is deterministic. Other seeds can be tried by setting
+//! `PROPTEST_RNG_SEED`, and the number of generated scenes with `PROPTEST_CASES`.
+
+use proptest::prelude::*;
+use proptest::test_runner::{RngSeed, TestError, TestRunner};
+use std::sync::Arc;
+use vello_common::color::{AlphaColor, ColorSpaceTag, DynamicColor, Srgb};
+use vello_common::kurbo::{Affine, BezPath, Cap, Join, PathEl, Point, Stroke};
+use vello_common::paint::{Image, ImageSource, PaintType};
+use vello_common::peniko::{
+    BlendMode, ColorStop, ColorStops, Compose, Extend, Fill, Gradient, GradientKind, ImageQuality,
+    ImageSampler, LinearGradientPosition, Mix, RadialGradientPosition, SweepGradientPosition,
+};
+use vello_common::pixmap::Pixmap;
+use vello_cpu::{RasterizerSettings, RenderContext, RenderMode, Resources};
+
+const WIDTH: u16 = 64;
+const HEIGHT: u16 = 64;
+/// The maximum allowed difference of a single premultiplied channel between the pipelines.
+///
+/// Rounding errors of the `u8` pipeline accumulate with each nested layer and blend mode,
+/// so this is a bit larger than the tolerance of the snapshot tests.
+const TOLERANCE: u8 = 8;
+/// The number of scenes that are checked if `PROPTEST_CASES` is not set.
+const DEFAULT_CASES: u32 = 64;
+/// The maximum nesting depth of layers and clip paths.
+const MAX_DEPTH: u32 = 3;
+
+#[test]
+fn differential_lowp_highp() {
+    let mut runner = TestRunner::new(config());
+    let result = runner.run(&scene(), |scene| match scene.check() {
+        Some(mismatch) => Err(TestCaseError::fail(mismatch.to_string())),
+        None => Ok(()),
+    });
+    if let Err(error) = result {
+        panic!("u8 and f32 pipelines disagree: {error}");
+    }
+}
+
+/// The default configuration, with a fixed seed and number of cases unless they are
+/// overridden by the environment.
+fn config() -> ProptestConfig {
+    let config = ProptestConfig::default();
+    let is_set = |name| std::env::var_os(name).is_some();
+    ProptestConfig {
+        cases: if is_set("PROPTEST_CASES") {
+            config.cases
+        } else {
+            DEFAULT_CASES
+        },
+        rng_seed: if is_set("PROPTEST_RNG_SEED") {
+            config.rng_seed
+        } else {
+            RngSeed::Fixed(0)
+        },
+        // The minimal failing scene is part of the panic message instead.
+        failure_persistence: None,
+        ..config
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Scene(Vec<Node>);
+
+#[derive(Clone, Debug)]
+enum Node {
+    Draw(Box<Draw>),
+    Layer {
+        clip: Option<BezPath>,
+        blend_mode: BlendMode,
+        opacity: f32,
+        children: Vec<Self>,
+    },
+    ClipPath {
+        path: BezPath,
+        children: Vec<Self>,
+    },
+}
+
+#[derive(Clone, Debug)]
+struct Draw {
+    shape: Shape,
+    paint: Paint,
+    transform: Affine,
+    paint_transform: Affine,
+}
+
+#[derive(Clone, Debug)]
+enum Shape {
+    Fill(BezPath, Fill),
+    Stroke(BezPath, Stroke),
+}
+
+#[derive(Clone, Debug)]
+enum Paint {
+    Solid(AlphaColor<Srgb>),
+    Gradient(Gradient),
+    Image {
+        /// The index into [`images`].
+        index: usize,
+        x_extend: Extend,
+        y_extend: Extend,
+        quality: ImageQuality,
+    },
+}
+
+/// The images that can be used as paints.
+fn images() -> [Pixmap; 2] {
+    let pattern = |width: u16, height: u16, translucent: bool| {
+        let mut pixmap = Pixmap::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let r = (x * 255 / width) as f32 / 255.0;
+                let g = (y * 255 / height) as f32 / 255.0;
+                let b = if (x + y) % 2 == 0 { 1.0 } else { 0.2 };
+                let a = if translucent { 0.25 + 0.75 * r } else { 1.0 };
+                let color = AlphaColor::<Srgb>::new([r, g, b, a]);
+                pixmap.set_pixel(x, y, color.premultiply().to_rgba8());
+            }
+        }
+        pixmap
+    };
+
+    [pattern(8, 8, false), pattern(5, 3, true)]
+}
+
+impl Scene {
+    /// Render the scene with both pipelines and compare the results.
+    fn check(&self) -> Option<Mismatch> {
+        let mut resources = Resources::new();
+        let images = images().map(|pixmap| {
+            let pixmap = Arc::new(pixmap);
+            let may_have_transparency = pixmap.may_have_transparency();
+            let id = resources.register_image(pixmap);
+            ImageSource::opaque_id_with_transparency_hint(id, may_have_transparency)
+        });
+
+        let mut ctx = RenderContext::new(WIDTH, HEIGHT);
+        draw_nodes(&mut ctx, &self.0, &images);
+        ctx.flush();
+
+        let mut render = |render_mode| {
+            let mut pixmap = Pixmap::new(WIDTH, HEIGHT);
+            ctx.render_with(
+                &mut pixmap,
+                &mut resources,
+                RasterizerSettings {
+                    render_mode,
+                    ..Default::default()
+                },
+            );
+            pixmap
+        };
+        let lowp = render(RenderMode::OptimizeSpeed);
+        let highp = render(RenderMode::OptimizeQuality);
+
+        let mut worst: Option<Mismatch> = None;
+        for (idx, (l, h)) in lowp.data().iter().zip(highp.data()).enumerate() {
+            let (l, h) = (l.to_u8_array(), h.to_u8_array());
+            let difference = l.iter().zip(&h).map(|(l, h)| l.abs_diff(*h)).max().unwrap();
+            if difference > TOLERANCE && worst.is_none_or(|w| difference > w.difference) {
+                worst = Some(Mismatch {
+                    x: idx % usize::from(WIDTH),
+                    y: idx / usize::from(WIDTH),
+                    lowp: l,
+                    highp: h,
+                    difference,
+                });
+            }
+        }
+        worst
+    }
+}
+
+// The strategies list the simplest alternatives first, since that is what `proptest` shrinks
+// towards.
+
+fn scene() -> impl Strategy<Value = Scene> {
+    let node = draw()
+        .prop_map(|draw| Node::Draw(Box::new(draw)))
+        .prop_recursive(MAX_DEPTH, 64, 4, |node| {
+            let children = prop::collection::vec(node, 1..5);
+            prop_oneof![
+                3 => (
+                    prop::option::weighted(0.3, path()),
+                    prop_oneof![Just(BlendMode::default()), blend_mode()],
+                    prop_oneof![Just(1.0), 0.0..1.0_f32],
+                    children.clone(),
+                )
+                    .prop_map(|(clip, blend_mode, opacity, children)| Node::Layer {
+                        clip,
+                        blend_mode,
+                        opacity,
+                        children,
+                    }),
+                1 => (path(), children)
+                    .prop_map(|(path, children)| Node::ClipPath { path, children }),
+            ]
+        });
+    prop::collection::vec(node, 1..5).prop_map(Scene)
+}
+
+fn draw() -> impl Strategy<Value = Draw> {
+    let stroke = (
+        0.5..8.0,
+        prop::sample::select(&[Join::Bevel, Join::Miter, Join::Round][..]),
+        prop::sample::select(&[Cap::Butt, Cap::Square, Cap::Round][..]),
+        prop::option::weighted(0.2, (1.0..8.0, 1.0..8.0)),
+    )
+        .prop_map(|(width, join, cap, dashes)| {
+            let mut stroke = Stroke::new(width).with_join(join).with_caps(cap);
+            if let Some((on, off)) = dashes {
+                stroke = stroke.with_dashes(0.0, [on, off]);
+            }
+            stroke
+        });
+    let shape = prop_oneof![
+        7 => (path(), prop::sample::select(&[Fill::NonZero, Fill::EvenOdd][..]))
+            .prop_map(|(path, fill_rule)| Shape::Fill(path, fill_rule)),
+        3 => (path(), stroke).prop_map(|(path, stroke)| Shape::Stroke(path, stroke)),
+    ];
+
+    (shape, paint(), transform(), transform()).prop_map(
+        |(shape, paint, transform, paint_transform)| Draw {
+            shape,
+            paint,
+            transform,
+            paint_transform,
+        },
+    )
+}
+
+fn point() -> impl Strategy<Value = Point> + Clone {
+    let margin = 8.0;
+    (
+        -margin..f64::from(WIDTH) + margin,
+        -margin..f64::from(HEIGHT) + margin,
+    )
+        .prop_map(|(x, y)| Point::new(x, y))
+}
+
+fn color() -> impl Strategy<Value = AlphaColor<Srgb>> + Clone {
+    (
+        0.0..1.0_f32,
+        0.0..1.0_f32,
+        0.0..1.0_f32,
+        prop_oneof![Just(1.0), 0.0..1.0_f32],
+    )
+        .prop_map(|(r, g, b, a)| AlphaColor::new([r, g, b, a]))
+}
+
+fn path() -> impl Strategy<Value = BezPath> + Clone {
+    let segment = prop_oneof![
+        point().prop_map(PathEl::LineTo),
+        (point(), point()).prop_map(|(p1, p2)| PathEl::QuadTo(p1, p2)),
+        (point(), point(), point()).prop_map(|(p1, p2, p3)| PathEl::CurveTo(p1, p2, p3)),
+    ];
+    (point(), prop::collection::vec(segment, 2..7), any::<bool>()).prop_map(
+        |(start, segments, close)| {
+            let mut path = BezPath::new();
+            path.move_to(start);
+            for segment in segments {
+                path.push(segment);
+            }
+            if close {
+                path.close_path();
+            }
+            path
+        },
+    )
+}
+
+fn transform() -> impl Strategy<Value = Affine> {
+    prop_oneof![
+        Just(Affine::IDENTITY),
+        (-16.0..16.0, -16.0..16.0).prop_map(|(x, y)| Affine::translate((x, y))),
+        (0.0..core::f64::consts::TAU).prop_map(|angle| Affine::rotate_about(
+            angle,
+            Point::new(f64::from(WIDTH) / 2.0, f64::from(HEIGHT) / 2.0),
+        )),
+        (
+            0.5..1.5,
+            -0.5..0.5,
+            -0.5..0.5,
+            0.5..1.5,
+            -16.0..16.0,
+            -16.0..16.0
+        )
+            .prop_map(|(a, b, c, d, e, f)| Affine::new([a, b, c, d, e, f])),
+    ]
+}
+
+fn blend_mode() -> impl Strategy<Value = BlendMode> {
+    // Some blend modes are left out, since their result can change completely due to the
+    // rounding of the `u8` pipeline: `ColorDodge` and `ColorBurn` are discontinuous for
+    // source values of 1 and 0, and `Hue` and `Saturation` take the hue of the source,
+    // which is ill-conditioned for nearly gray colors.
+    const MIXES: &[Mix] = &[
+        Mix::Normal,
+        Mix::Multiply,
+        Mix::Screen,
+        Mix::Overlay,
+        Mix::Darken,
+        Mix::Lighten,
+        Mix::HardLight,
+        Mix::SoftLight,
+        Mix::Difference,
+        Mix::Exclusion,
+        Mix::Color,
+        Mix::Luminosity,
+    ];
+    const COMPOSES: &[Compose] = &[
+        Compose::Clear,
+        Compose::Copy,
+        Compose::Dest,
+        Compose::SrcOver,
+        Compose::DestOver,
+        Compose::SrcIn,
+        Compose::DestIn,
+        Compose::SrcOut,
+        Compose::DestOut,
+        Compose::SrcAtop,
+        Compose::DestAtop,
+        Compose::Xor,
+        Compose::Plus,
+    ];
+
+    prop_oneof![
+        7 => prop::sample::select(MIXES).prop_map(|mix| BlendMode::new(mix, Compose::SrcOver)),
+        3 => prop::sample::select(COMPOSES).prop_map(|compose| BlendMode::new(Mix::Normal, compose)),
+    ]
+}
+
+fn paint() -> impl Strategy<Value = Paint> {
+    const EXTENDS: &[Extend] = &[Extend::Pad, Extend::Repeat, Extend::Reflect];
+
+    let kind: BoxedStrategy<GradientKind> = prop_oneof![
+        (point(), point()).prop_map(|(start, end)| LinearGradientPosition::new(start, end).into()),
+        (point(), 0.0..16.0_f32, point(), 0.0..48.0_f32).prop_map(
+            |(start_center, start_radius, end_center, end_radius)| RadialGradientPosition {
+                start_center,
+                start_radius,
+                end_center,
+                end_radius,
+            }
+            .into()
+        ),
+        (point(), 0.0..360.0_f32, 1.0..360.0_f32).prop_map(|(center, start_angle, sweep)| {
+            SweepGradientPosition {
+                center,
+                start_angle,
+                end_angle: start_angle + sweep,
+            }
+            .into()
+        }),
+    ]
+    .boxed();
+    let stops = prop::collection::vec((0.0..1.0_f32, color()), 2..5).prop_map(|mut stops| {
+        stops.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        stops[0].0 = 0.0;
+        stops.last_mut().unwrap().0 = 1.0;
+        ColorStops(
+            stops
+                .into_iter()
+                .map(|(offset, color)| ColorStop {
+                    offset,
+                    color: DynamicColor::from_alpha_color(color),
+                })
+                .collect(),
+        )
+    });
+    let gradient = (
+        kind,
+        stops,
+        prop::sample::select(EXTENDS),
+        prop::sample::select(
+            &[
+                ColorSpaceTag::Srgb,
+                ColorSpaceTag::LinearSrgb,
+                ColorSpaceTag::Oklab,
+            ][..],
+        ),
+    )
+        .prop_map(|(kind, stops, extend, interpolation_cs)| {
+            Paint::Gradient(Gradient {
+                kind,
+                extend,
+                interpolation_cs,
+                stops,
+                ..Default::default()
+            })
+        });
+    let image = (
+        0..images().len(),
+        prop::sample::select(EXTENDS),
+        prop::sample::select(EXTENDS),
+        prop::sample::select(&[ImageQuality::Low, ImageQuality::Medium, ImageQuality::High][..]),
+    )
+        .prop_map(|(index, x_extend, y_extend, quality)| Paint::Image {
+            index,
+            x_extend,
+            y_extend,
+            quality,
+        });
+
+    prop_oneof![
+        2 => color().prop_map(Paint::Solid),
+        2 => gradient,
+        1 => image,
+    ]
+}
+
+fn draw_nodes(ctx: &mut RenderContext, nodes: &[Node], images: &[ImageSource]) {
+    for node in nodes {
+        match node {
+            Node::Draw(draw) => {
+                ctx.set_transform(draw.transform);
+                ctx.set_paint_transform(draw.paint_transform);
+                let paint: PaintType = match &draw.paint {
+                    Paint::Solid(color) => (*color).into(),
+                    Paint::Gradient(gradient) => gradient.clone().into(),
+                    Paint::Image {
+                        index,
+                        x_extend,
+                        y_extend,
+                        quality,
+                    } => Image {
+                        image: images[*index].clone(),
+                        sampler: ImageSampler {
+                            x_extend: *x_extend,
+                            y_extend: *y_extend,
+                            quality: *quality,
+                            // Image opacity isn't supported by Vello CPU yet.
+                            alpha: 1.0,
+                        },
+                    }
+                    .into(),
+                };
+                ctx.set_paint(paint);
+                match &draw.shape {
+                    Shape::Fill(path, fill_rule) => {
+                        ctx.set_fill_rule(*fill_rule);
+                        ctx.fill_path(path);
+                    }
+                    Shape::Stroke(path, stroke) => {
+                        ctx.set_stroke(stroke.clone());
+                        ctx.stroke_path(path);
+                    }
+                }
+            }
+            Node::Layer {
+                clip,
+                blend_mode,
+                opacity,
+                children,
+            } => {
+                ctx.set_transform(Affine::IDENTITY);
+                ctx.push_layer(clip.as_ref(), Some(*blend_mode), Some(*opacity), None, None);
+                draw_nodes(ctx, children, images);
+                ctx.pop_layer();
+            }
+            Node::ClipPath { path, children } => {
+                ctx.set_transform(Affine::IDENTITY);
+                ctx.push_clip_path(path);
+                draw_nodes(ctx, children, images);
+                ctx.pop_clip_path();
+            }
+        }
+    }
+}
+
+/// The pixel with the largest difference between the two pipelines.
+#[derive(Clone, Copy, Debug)]
+struct Mismatch {
+    x: usize,
+    y: usize,
+    lowp: [u8; 4],
+    highp: [u8; 4],
+    difference: u8,
+}
+
+impl core::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "pixel ({}, {}) is {:?} with u8 and {:?} with f32",
+            self.x, self.y, self.lowp, self.highp
+        )
+    }
+}
+
+#[test]
+fn differential_shrinking() {
+    // Pretend that the pipelines disagree whenever a stroke is drawn, which should shrink
+    // the scene down to a single stroke with everything else simplified away.
+    fn draws(nodes: &[Node]) -> Vec<&Draw> {
+        nodes
+            .iter()
+            .flat_map(|node| match node {
+                Node::Draw(draw) => vec![&**draw],
+                Node::Layer { children, .. } | Node::ClipPath { children, .. } => draws(children),
+            })
+            .collect()
+    }
+
+    let mut runner = TestRunner::new(ProptestConfig {
+        cases: 256,
+        rng_seed: RngSeed::Fixed(0),
+        failure_persistence: None,
+        ..ProptestConfig::default()
+    });
+    let result = runner.run(&scene(), |scene| {
+        let has_stroke = draws(&scene.0)
+            .iter()
+            .any(|draw| matches!(draw.shape, Shape::Stroke(..)));
+        prop_assert!(!has_stroke);
+        Ok(())
+    });
+    let Err(TestError::Fail(_, scene)) = result else {
+        panic!("no scene with a stroke was generated: {result:?}");
+    };
+
+    let [draw] = draws(&scene.0)[..] else {
+        panic!("scene wasn't shrunk to a single draw: {scene:#?}");
+    };
+    let Shape::Stroke(path, _) = &draw.shape else {
+        panic!("the stroke was removed: {draw:?}");
+    };
+    assert_eq!(path.elements().len(), 3);
+    assert!(matches!(draw.paint, Paint::Solid(color) if color.components[3] == 1.0));
+    assert_eq!(draw.transform, Affine::IDENTITY);
+    assert_eq!(draw.paint_transform, Affine::IDENTITY);
+}
@@ -1,32 +1,130 @@
 // Copyright 2026 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use crate::load_image;
+use crate::renderer::Renderer;
+use crate::util::circular_star;
+use std::sync::Arc;
+use vello_common::TextureId;
+use vello_common::color::PremulRgba8;
+use vello_common::color::palette::css::REBECCA_PURPLE;
+use vello_common::geometry::RectU16;
+use vello_common::kurbo::{Affine, Point, Rect};
+use vello_common::paint::ExternalTexture;
+use vello_common::peniko::Extend;
+use vello_common::pixmap::Pixmap;
+use vello_dev_macros::vello_test;
+
+/// Regions of the sprites in the `glyphs_colr_noto` image.
+const SPRITES: [RectU16; 4] = [
+    // Checkmark.
+    RectU16::new(0, 0, 56, 56),
+    // Eyes
+    RectU16::new(56, 0, 112, 42),
+    // Confetti
+    RectU16::new(0, 56, 54, 112),
+    // Cowboy Hat Face
+    RectU16::new(56, 42, 115, 100),
+];
+
+/// Fill the whole canvas with the eyes sprite, drawn at half its size and tiled with the given
+/// extend mode.
+fn extend(ctx: &mut impl Renderer, extend: Extend) {
+    let texture_id = ctx.register_external_texture(load_image!("glyphs_colr_noto"));
+    ctx.set_paint(ExternalTexture::new(texture_id, SPRITES[1]).with_extend(extend));
+    ctx.set_paint_transform(Affine::translate((34.0, 38.0)) * Affine::scale(0.5));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 96.0, 96.0));
+}
+
+#[vello_test(width = 96, height = 96)]
+fn external_texture_pad(ctx: &mut impl Renderer) {
+    extend(ctx, Extend::Pad);
+}
+
+#[vello_test(width = 96, height = 96)]
+fn external_texture_repeat(ctx: &mut impl Renderer) {
+    extend(ctx, Extend::Repeat);
+}
+
+#[vello_test(width = 96, height = 96)]
+fn external_texture_reflect(ctx: &mut impl Renderer) {
+    extend(ctx, Extend::Reflect);
+}
+
+#[vello_test(width = 96, height = 96, cpu_u8_tolerance = 1)]
+fn external_texture_star(ctx: &mut impl Renderer) {
+    let texture_id = ctx.register_external_texture(load_image!("glyphs_colr_noto"));
+    ctx.set_paint(ExternalTexture::new(texture_id, SPRITES[3]).with_extend(Extend::Repeat));
+    ctx.set_paint_transform(Affine::rotate(0.3) * Affine::scale(0.75));
+    ctx.fill_path(&circular_star(Point::new(48.0, 48.0), 5, 20.0, 44.0));
+}
+
+#[vello_test(width = 96, height = 96)]
+fn external_texture_with_alpha(ctx: &mut impl Renderer) {
+    let texture_id = ctx.register_external_texture(load_image!("glyphs_colr_noto"));
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_rect(&Rect::new(24.0, 24.0, 72.0, 72.0));
+    ctx.set_paint(ExternalTexture::new(texture_id, SPRITES[0]).with_alpha(0.5));
+    ctx.set_paint_transform(Affine::translate((20.0, 20.0)));
+    ctx.fill_rect(&Rect::new(20.0, 20.0, 76.0, 76.0));
+}
+
+/// Repeat only a part of a texture, so that texels outside of the source region are never
+/// sampled.
+#[vello_test(width = 12, height = 4)]
+fn external_texture_source_region(ctx: &mut impl Renderer) {
+    const RED: PremulRgba8 = PremulRgba8 {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    const BLUE: PremulRgba8 = PremulRgba8 {
+        r: 0,
+        g: 0,
+        b: 255,
+        a: 255,
+    };
+
+    // A 4x2 texture whose left half is red and whose right half is blue.
+    let pixels = [RED, RED, BLUE, BLUE].repeat(2);
+    let texture_id = ctx.register_external_texture(Arc::new(Pixmap::from_parts(pixels, 4, 2)));
+    // The region starts with one red column, so every third column is red.
+    ctx.set_paint(
+        ExternalTexture::new(texture_id, RectU16::new(1, 0, 4, 2)).with_x_extend(Extend::Repeat),
+    );
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 12.0, 4.0));
+}
+
+/// Paints referring to a texture which isn't bound are not drawn.
+///
+/// `vello_hybrid` reports missing bindings as an error instead.
+#[vello_test(width = 16, height = 16, skip_hybrid)]
+fn external_texture_unbound(ctx: &mut impl Renderer) {
+    ctx.set_paint(REBECCA_PURPLE);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+    ctx.set_paint(ExternalTexture::new(
+        TextureId(u64::MAX),
+        RectU16::new(0, 0, 4, 4),
+    ));
+    ctx.fill_rect(&Rect::new(4.0, 4.0, 12.0, 12.0));
+}
+
 #[cfg(not(all(target_arch = "wasm32", feature = "webgl")))]
 mod tests {
     use vello_common::color;
     use vello_common::filter_effects::{EdgeMode, Filter, FilterPrimitive};
-    use vello_common::geometry::RectU16;
     use vello_common::kurbo::{Affine, Circle, Rect, Shape};
     use vello_common::peniko::ImageQuality;
     use vello_hybrid::SampleRect;
 
+    use super::SPRITES;
     use crate::load_image;
 
     use crate::renderer::{HybridRenderer, Renderer};
     use crate::util::{check_ref, get_ctx};
     use vello_cpu::RenderMode;
 
-    const SPRITES: [RectU16; 4] = [
-        // Checkmark.
-        RectU16::new(0, 0, 56, 56),
-        // Eyes
-        RectU16::new(56, 0, 112, 42),
-        // Confetti
-        RectU16::new(0, 56, 54, 112),
-        // Cowboy Hat Face
-        RectU16::new(56, 42, 115, 100),
-    ];
-
     /// A (temporary?) helper to test [`vello_hybrid`], where the snapshot is generated by
     /// [`vello_hybrid`] specifically.
     ///
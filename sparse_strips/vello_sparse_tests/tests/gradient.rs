@@ -4,9 +4,10 @@
 use crate::renderer::Renderer;
 use crate::util::{stops_blue_green_red_yellow, stops_green_blue};
 use smallvec::smallvec;
-use vello_common::color::palette::css::{BLACK, BLUE, GREEN, WHITE, YELLOW};
-use vello_common::color::{ColorSpaceTag, DynamicColor};
+use vello_common::color::palette::css::{BLACK, BLUE, GREEN, LIME, RED, WHITE, YELLOW};
+use vello_common::color::{AlphaColor, ColorSpaceTag, DynamicColor, Srgb};
 use vello_common::kurbo::{Point, Rect};
+use vello_common::mesh_gradient::{MeshGradient, MeshPatch};
 use vello_common::peniko::{ColorStop, ColorStops, Gradient};
 use vello_cpu::peniko::LinearGradientPosition;
 use vello_dev_macros::vello_test;
@@ -157,6 +158,45 @@ fn gradient_padded_stops(ctx: &mut impl Renderer) {
     padded_stops(ctx, 0.25, 0.75);
 }
 
+// The lookup table entry right after the hard stop used to be extrapolated to a negative
+// value, which made the `f32` pipeline darken the background behind it.
+#[vello_test(width = 40, height = 8)]
+fn gradient_hard_stop_with_opacity(ctx: &mut impl Renderer) {
+    let rect = Rect::new(0.0, 0.0, 40.0, 8.0);
+
+    let gradient = Gradient {
+        kind: LinearGradientPosition {
+            start: Point::new(0.0, 0.0),
+            end: Point::new(2107.0, 0.0),
+        }
+        .into(),
+        stops: ColorStops(smallvec![
+            ColorStop {
+                offset: 0.0,
+                color: DynamicColor::from_alpha_color(BLACK),
+            },
+            ColorStop {
+                offset: 0.005,
+                color: DynamicColor::from_alpha_color(BLACK),
+            },
+            ColorStop {
+                offset: 0.0055,
+                color: DynamicColor::from_alpha_color(WHITE),
+            },
+            ColorStop {
+                offset: 1.0,
+                color: DynamicColor::from_alpha_color(YELLOW),
+            },
+        ]),
+        ..Default::default()
+    };
+
+    ctx.set_paint(gradient);
+    ctx.push_opacity_layer(0.5);
+    ctx.fill_rect(&rect);
+    ctx.pop_layer();
+}
+
 mod linear {
     use crate::gradient::tan_45;
     use crate::renderer::Renderer;
@@ -1108,3 +1148,31 @@ mod sweep {
         gradient_with_transform(ctx, transform, 25.0, 25.0, 75.0, 75.0);
     }
 }
+
+/// A tensor-product patch with a warped interior, which leaves the bottom-right corner of the
+/// rectangle uncovered. Vello Hybrid doesn't support mesh gradients yet.
+#[vello_test(skip_hybrid)]
+fn gradient_mesh_tensor(ctx: &mut impl Renderer) {
+    let mut points: [[Point; 4]; 4] = core::array::from_fn(|i| {
+        core::array::from_fn(|j| Point::new(10.0 + i as f64 * 20.0, 10.0 + j as f64 * 20.0))
+    });
+    points[1][1] = Point::new(50.0, 50.0);
+    points[3][3] = Point::new(60.0, 60.0);
+    let patch = MeshPatch::tensor(points, [RED, LIME, BLUE, WHITE]);
+
+    ctx.set_paint(MeshGradient::new(vec![patch]));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 100.0, 100.0));
+}
+
+/// A gradient that only spans two 8-bit steps over 64 pixels, which shows visible bands
+/// without dithering.
+#[vello_test(width = 64, height = 8)]
+fn gradient_dithered(ctx: &mut impl Renderer) {
+    ctx.set_dithering(true);
+    let gradient = Gradient::new_linear(Point::new(0.0, 0.0), Point::new(64.0, 0.0)).with_stops([
+        AlphaColor::<Srgb>::from_rgb8(64, 64, 64),
+        AlphaColor::<Srgb>::from_rgb8(66, 66, 66),
+    ]);
+    ctx.set_paint(gradient);
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 64.0, 8.0));
+}
@@ -9,12 +9,15 @@ use crate::util::crossed_line_star;
 use std::f64::consts::PI;
 use std::sync::Arc;
 use vello_common::color::palette::css::REBECCA_PURPLE;
+use vello_common::color::{ColorSpaceTag, PremulRgba8};
 use vello_common::kurbo::{Affine, Point, Rect};
 use vello_common::kurbo::{Shape, Triangle};
 use vello_common::paint::{Image, ImageSource, Tint, TintMode};
 use vello_common::peniko::Color;
 use vello_common::peniko::ImageSampler;
 use vello_common::peniko::{BlendMode, Compose, Extend, ImageQuality, Mix};
+use vello_common::pixmap::Pixmap;
+use vello_common::raw_image::{RawImage, RawImageFormat, YuvEncoding};
 use vello_dev_macros::vello_test;
 
 fn rgb_img_10x10(ctx: &mut impl Renderer) -> ImageSource {
@@ -89,6 +92,26 @@ fn image_pad_x_pad_y(ctx: &mut impl Renderer) {
     repeat(ctx, Extend::Pad, Extend::Pad);
 }
 
+// The filtered image painters of the `f32` pipeline used to apply the y extend mode
+// to the x axis as well.
+#[vello_test]
+fn image_bilinear_repeat_x_pad_y(ctx: &mut impl Renderer) {
+    let rect = Rect::new(10.0, 10.0, 90.0, 90.0);
+    let image_source = rgb_img_10x10(ctx);
+
+    ctx.set_paint_transform(Affine::translate((45.0, 45.0)) * Affine::scale(2.0));
+    ctx.set_paint(Image {
+        image: image_source,
+        sampler: ImageSampler {
+            x_extend: Extend::Repeat,
+            y_extend: Extend::Pad,
+            quality: ImageQuality::Medium,
+            alpha: 1.0,
+        },
+    });
+    ctx.fill_rect(&rect);
+}
+
 fn transform(ctx: &mut impl Renderer, transform: Affine, l: f64, t: f64, r: f64, b: f64) {
     let rect = Rect::new(l, t, r, b);
     let image_source = rgb_img_10x10(ctx);
@@ -726,3 +749,97 @@ fn image_spritesheet_tinted(ctx: &mut impl Renderer) {
         cursor_x += glyph.width;
     }
 }
+
+/// A checkerboard of single black and white pixels drawn at 1/8 scale, which point sampling
+/// turns into solid black or white instead of gray.
+#[vello_test(width = 8, height = 8)]
+fn image_minified_mip_levels(ctx: &mut impl Renderer) {
+    let mut checkerboard = Pixmap::new(64, 64);
+    for y in 0..64 {
+        for x in 0..64 {
+            let value = if (x + y) % 2 == 0 { 255 } else { 0 };
+            checkerboard.set_pixel(
+                x,
+                y,
+                PremulRgba8 {
+                    r: value,
+                    g: value,
+                    b: value,
+                    a: 255,
+                },
+            );
+        }
+    }
+
+    let id = ctx.register_image(Arc::new(checkerboard));
+    ctx.set_paint(Image {
+        image: ImageSource::opaque_id(id),
+        sampler: ImageSampler::new().with_quality(ImageQuality::Medium),
+    });
+    // Offset by half a pixel of the image, so that without mipmapping all samples would hit
+    // pixel centers.
+    ctx.set_paint_transform(Affine::translate((-0.0625, -0.0625)) * Affine::scale(0.125));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 8.0, 8.0));
+}
+
+/// A 4x4 I420 image with a luma ramp and a different chroma value in each quadrant.
+fn raw_i420_4x4() -> ImageSource {
+    let mut data = (0..16).map(|i| 16 + i * 14).collect::<Vec<u8>>();
+    data.extend([90, 160, 200, 40]);
+    data.extend([240, 110, 60, 128]);
+    let raw = RawImage::new(data, RawImageFormat::I420(YuvEncoding::default()), 4, 4).unwrap();
+    ImageSource::Raw(Arc::new(raw))
+}
+
+fn image_raw(ctx: &mut impl Renderer, quality: ImageQuality) {
+    ctx.set_paint(Image {
+        image: raw_i420_4x4(),
+        sampler: ImageSampler::new().with_quality(quality),
+    });
+    ctx.set_paint_transform(Affine::scale(4.0));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 16.0));
+}
+
+#[vello_test(width = 16, height = 16)]
+fn image_raw_i420_low_quality(ctx: &mut impl Renderer) {
+    image_raw(ctx, ImageQuality::Low);
+}
+
+#[vello_test(width = 16, height = 16)]
+fn image_raw_i420_medium_quality(ctx: &mut impl Renderer) {
+    image_raw(ctx, ImageQuality::Medium);
+}
+
+/// Images in another color space are converted to sRGB when drawn, both when they are
+/// registered (top half) and when the paint carries their pixels (bottom half).
+#[vello_test(width = 16, height = 16)]
+fn image_color_space_display_p3(ctx: &mut impl Renderer) {
+    // Display P3 green, which is less saturated in sRGB.
+    let mut pixmap = Pixmap::from_parts(
+        vec![
+            PremulRgba8 {
+                r: 0,
+                g: 200,
+                b: 0,
+                a: 255,
+            };
+            16
+        ],
+        4,
+        4,
+    );
+    pixmap.set_color_space(ColorSpaceTag::DisplayP3);
+    let pixmap = Arc::new(pixmap);
+
+    let registered = ctx.get_image_source(pixmap.clone());
+    for (source, rect) in [
+        (registered, Rect::new(0.0, 0.0, 16.0, 8.0)),
+        (ImageSource::Pixmap(pixmap), Rect::new(0.0, 8.0, 16.0, 16.0)),
+    ] {
+        ctx.set_paint(Image {
+            image: source,
+            sampler: ImageSampler::new().with_quality(ImageQuality::Low),
+        });
+        ctx.fill_rect(&rect);
+    }
+}
@@ -434,6 +434,7 @@ fn multi_threading_oob_access() {
     let settings = RenderSettings {
         level: Level::try_detect().unwrap_or(Level::baseline()),
         num_threads: 4,
+        ..Default::default()
     };
     let mut ctx = RenderContext::new_with(100, 100, settings);
     let mut resources = vello_cpu::Resources::new();
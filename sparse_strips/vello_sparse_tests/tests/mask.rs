@@ -19,6 +19,7 @@ pub(crate) fn example_mask(alpha_mask: bool) -> Mask {
     let settings = RenderSettings {
         level: Level::baseline(),
         num_threads: 0,
+        ..Default::default()
     };
     let mut mask_ctx = RenderContext::new_with(100, 100, settings);
     let mut resources = vello_cpu::Resources::new();
@@ -34,7 +34,10 @@ mod basic;
 mod blurred_rounded_rect;
 mod clip;
 mod compose;
+mod custom_paint;
 mod default_blending_only_fast_path;
+#[cfg(not(target_arch = "wasm32"))]
+mod differential;
 mod external_texture;
 mod filter;
 mod glyph;
@@ -45,6 +48,7 @@ mod layer;
 mod mask;
 mod mix;
 mod opacity;
+mod pattern;
 mod renderer;
 mod scenes;
 #[macro_use]
@@ -0,0 +1,29 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::renderer::Renderer;
+use vello_common::color::palette::css::RED;
+use vello_common::kurbo::{Affine, Rect, Shape};
+use vello_common::pattern::{Pattern, PatternScene};
+use vello_common::render_state::RenderState;
+use vello_dev_macros::vello_test;
+
+/// A 2x2 tile with a red square in its top-left quarter.
+fn quarter_pattern() -> Pattern {
+    let mut scene = PatternScene::new();
+    let state = RenderState {
+        paint: RED.into(),
+        ..Default::default()
+    };
+    scene.fill_path(Rect::new(0.0, 0.0, 1.0, 1.0).to_path(0.1), &state);
+    Pattern::new(scene, Rect::new(0.0, 0.0, 2.0, 2.0))
+}
+
+/// The tile is rendered at the scale it is displayed at, so the squares have sharp edges.
+#[vello_test(width = 16, height = 16)]
+fn pattern_device_scale(ctx: &mut impl Renderer) {
+    ctx.set_paint(quarter_pattern());
+    ctx.set_paint_transform(Affine::scale(4.0));
+    ctx.fill_rect(&Rect::new(0.0, 0.0, 16.0, 8.0));
+    ctx.fill_rect(&Rect::new(0.0, 8.0, 16.0, 16.0));
+}
@@ -9,10 +9,13 @@ use glifo::GlyphRunBackend;
 use vello_common::filter_effects::Filter;
 use vello_common::kurbo::{Affine, BezPath, Rect, Stroke};
 use vello_common::mask::Mask;
-use vello_common::paint::{ImageId, ImageSource, PaintType, Tint};
+use vello_common::paint::{CustomPaintId, ImageId, ImageSource, PaintType, Tint};
 use vello_common::peniko::{BlendMode, Fill, FontData, ImageQuality};
 use vello_common::pixmap::Pixmap;
-use vello_cpu::{Level, RasterizerSettings, RenderContext, RenderMode, RenderSettings, Resources};
+use vello_cpu::{
+    CustomPaint, Level, RasterizerSettings, RenderContext, RenderMode, RenderSettings, Resources,
+    TextureBindings,
+};
 use vello_hybrid::{
     RenderSettings as HybridRenderSettings, Resources as HybridResources, SampleRect, Scene,
     SceneConstraints, TextureId,
@@ -67,6 +70,9 @@ pub(crate) trait Renderer: Sized {
     fn set_fill_rule(&mut self, fill_rule: Fill);
     fn set_transform(&mut self, transform: Affine);
     fn set_aliasing_threshold(&mut self, aliasing_threshold: Option<u8>);
+    /// Enable or disable dithering. This recreates the context, so it needs to be called
+    /// before drawing anything.
+    fn set_dithering(&mut self, dithering: bool);
     fn set_blend_mode(&mut self, blend_mode: BlendMode);
     fn set_filter_effect(&mut self, filter: Filter);
     fn reset_filter_effect(&mut self);
@@ -74,21 +80,7 @@ pub(crate) trait Renderer: Sized {
     fn render_to_pixmap(&mut self, pixmap: &mut Pixmap);
     fn width(&self) -> u16;
     fn height(&self) -> u16;
-    #[cfg_attr(
-        all(target_arch = "wasm32", feature = "webgl"),
-        expect(
-            dead_code,
-            reason = "external textures are not wired up for the WebGL backend"
-        )
-    )]
     fn register_external_texture(&mut self, pixmap: Arc<Pixmap>) -> TextureId;
-    #[cfg_attr(
-        all(target_arch = "wasm32", feature = "webgl"),
-        expect(
-            dead_code,
-            reason = "external textures are not wired up for the WebGL backend"
-        )
-    )]
     fn draw_texture_rects(
         &mut self,
         texture_id: TextureId,
@@ -97,12 +89,16 @@ pub(crate) trait Renderer: Sized {
     );
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource;
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId;
+    fn register_custom_paint(&mut self, paint: Arc<dyn CustomPaint>) -> CustomPaintId;
+    fn destroy_custom_paint(&mut self, id: CustomPaintId) -> bool;
 }
 
 pub(crate) struct CpuRenderer {
     ctx: RenderContext,
+    settings: RenderSettings,
     resources: Resources,
     render_mode: RenderMode,
+    external_textures: HashMap<TextureId, Arc<Pixmap>>,
 }
 
 impl Renderer for CpuRenderer {
@@ -116,11 +112,17 @@ impl Renderer for CpuRenderer {
         render_mode: RenderMode,
         _default_blending_only: bool,
     ) -> Self {
-        let settings = RenderSettings { level, num_threads };
+        let settings = RenderSettings {
+            level,
+            num_threads,
+            ..Default::default()
+        };
         Self {
             ctx: RenderContext::new_with(width, height, settings),
+            settings,
             resources: Resources::new(),
             render_mode,
+            external_textures: HashMap::new(),
         }
     }
 
@@ -232,6 +234,11 @@ impl Renderer for CpuRenderer {
         self.ctx.set_aliasing_threshold(aliasing_threshold);
     }
 
+    fn set_dithering(&mut self, dithering: bool) {
+        self.settings.dithering = dithering;
+        self.ctx = RenderContext::new_with(self.ctx.width(), self.ctx.height(), self.settings);
+    }
+
     fn set_blend_mode(&mut self, blend_mode: BlendMode) {
         self.ctx.set_blend_mode(blend_mode);
     }
@@ -249,11 +256,16 @@ impl Renderer for CpuRenderer {
     }
 
     fn render_to_pixmap(&mut self, pixmap: &mut Pixmap) {
+        let mut textures = TextureBindings::new();
+        for (texture_id, texture) in &self.external_textures {
+            textures.insert(*texture_id, &**texture);
+        }
         self.ctx.render_with(
             pixmap,
             &mut self.resources,
             RasterizerSettings {
                 render_mode: self.render_mode,
+                textures: Some(&textures),
                 ..Default::default()
             },
         );
@@ -267,8 +279,10 @@ impl Renderer for CpuRenderer {
         self.ctx.height()
     }
 
-    fn register_external_texture(&mut self, _: Arc<Pixmap>) -> TextureId {
-        unimplemented!("external textures are only supported by hybrid renderer tests")
+    fn register_external_texture(&mut self, pixmap: Arc<Pixmap>) -> TextureId {
+        let texture_id = TextureId(self.external_textures.len() as u64 + 1);
+        self.external_textures.insert(texture_id, pixmap);
+        texture_id
     }
 
     fn draw_texture_rects(
@@ -277,7 +291,7 @@ impl Renderer for CpuRenderer {
         _: ImageQuality,
         _: impl IntoIterator<Item = SampleRect>,
     ) {
-        unimplemented!("external textures are only supported by hybrid renderer tests")
+        unimplemented!("batches of texture rectangles are only supported by vello_hybrid")
     }
 
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource {
@@ -288,11 +302,20 @@ impl Renderer for CpuRenderer {
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
         self.resources.register_image(pixmap)
     }
+
+    fn register_custom_paint(&mut self, paint: Arc<dyn CustomPaint>) -> CustomPaintId {
+        self.resources.register_custom_paint(paint)
+    }
+
+    fn destroy_custom_paint(&mut self, id: CustomPaintId) -> bool {
+        self.resources.destroy_custom_paint(id)
+    }
 }
 
 #[cfg(not(all(target_arch = "wasm32", feature = "webgl")))]
 pub(crate) struct HybridRenderer {
     scene: Scene,
+    settings: HybridRenderSettings,
     resources: HybridResources,
     device: wgpu::Device,
     queue: wgpu::Queue,
@@ -300,10 +323,6 @@ pub(crate) struct HybridRenderer {
     texture_view: wgpu::TextureView,
     renderer: vello_hybrid::Renderer,
     external_textures: HashMap<TextureId, wgpu::TextureView>,
-    #[allow(
-        dead_code,
-        reason = "external texture snapshot scaffolding is not enabled yet"
-    )]
     next_external_texture_id: u64,
 }
 
@@ -356,6 +375,7 @@ impl HybridRenderer {
 
         Self {
             scene,
+            settings,
             resources: HybridResources::new(),
             device,
             queue,
@@ -523,6 +543,11 @@ impl Renderer for HybridRenderer {
         self.scene.set_aliasing_threshold(aliasing_threshold);
     }
 
+    fn set_dithering(&mut self, dithering: bool) {
+        self.settings.dithering = dithering;
+        self.scene = Scene::new_with(self.scene.width(), self.scene.height(), self.settings);
+    }
+
     fn set_filter_effect(&mut self, filter: Filter) {
         self.scene.set_filter_effect(filter);
     }
@@ -713,16 +738,42 @@ impl Renderer for HybridRenderer {
     }
 
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
-        self.upload_image_with_resources(&pixmap, "Register Test Image")
+        // Like the image registry of Vello CPU, registered images are sampled from their mip
+        // levels when drawn minified.
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Register Test Image"),
+            });
+        let image_id = self.renderer.upload_image_with_mip_levels(
+            &mut self.resources,
+            &self.device,
+            &self.queue,
+            &mut encoder,
+            &pixmap,
+        );
+        self.queue.submit([encoder.finish()]);
+        image_id
+    }
+
+    fn register_custom_paint(&mut self, _: Arc<dyn CustomPaint>) -> CustomPaintId {
+        unimplemented!("custom paints are only supported by the CPU renderer")
+    }
+
+    fn destroy_custom_paint(&mut self, _: CustomPaintId) -> bool {
+        unimplemented!("custom paints are only supported by the CPU renderer")
     }
 }
 
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
 pub(crate) struct HybridRenderer {
     scene: Scene,
+    settings: HybridRenderSettings,
     resources: HybridResources,
     renderer: vello_hybrid::WebGlRenderer,
     gl: WebGl2RenderingContext,
+    texture_bindings: vello_hybrid::WebGlTextureBindings,
+    next_external_texture_id: u64,
 }
 
 #[cfg(all(target_arch = "wasm32", feature = "webgl"))]
@@ -779,9 +830,12 @@ impl Renderer for HybridRenderer {
             .unwrap();
         Self {
             scene,
+            settings,
             resources: HybridResources::new(),
             renderer,
             gl,
+            texture_bindings: vello_hybrid::WebGlTextureBindings::new(),
+            next_external_texture_id: 1,
         }
     }
 
@@ -895,6 +949,11 @@ impl Renderer for HybridRenderer {
         self.scene.set_aliasing_threshold(aliasing_threshold);
     }
 
+    fn set_dithering(&mut self, dithering: bool) {
+        self.settings.dithering = dithering;
+        self.scene = Scene::new_with(self.scene.width(), self.scene.height(), self.settings);
+    }
+
     fn set_filter_effect(&mut self, filter: Filter) {
         self.scene.set_filter_effect(filter);
     }
@@ -919,7 +978,12 @@ impl Renderer for HybridRenderer {
             height: height.into(),
         };
         self.renderer
-            .render(&self.scene, &mut self.resources, &render_size)
+            .render(
+                &self.scene,
+                &mut self.resources,
+                &render_size,
+                &self.texture_bindings,
+            )
             .unwrap();
         let mut pixels = vec![0_u8; (width as usize) * (height as usize) * 4];
         self.gl
@@ -954,17 +1018,45 @@ impl Renderer for HybridRenderer {
         self.scene.height()
     }
 
-    fn register_external_texture(&mut self, _: Arc<Pixmap>) -> TextureId {
-        unimplemented!("external textures are not wired up for the WebGL test backend")
+    fn register_external_texture(&mut self, pixmap: Arc<Pixmap>) -> TextureId {
+        let texture_id = TextureId(self.next_external_texture_id);
+        self.next_external_texture_id += 1;
+
+        let texture = self.gl.create_texture().unwrap();
+        self.gl
+            .bind_texture(WebGl2RenderingContext::TEXTURE_2D, Some(&texture));
+        self.gl
+            .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+                WebGl2RenderingContext::TEXTURE_2D,
+                0,
+                WebGl2RenderingContext::RGBA8 as i32,
+                pixmap.width().into(),
+                pixmap.height().into(),
+                0,
+                WebGl2RenderingContext::RGBA,
+                WebGl2RenderingContext::UNSIGNED_BYTE,
+                Some(pixmap.data_as_u8_slice()),
+            )
+            .unwrap();
+        // Without mipmaps, the texture is only complete with a non-mipmapped filter.
+        self.gl.tex_parameteri(
+            WebGl2RenderingContext::TEXTURE_2D,
+            WebGl2RenderingContext::TEXTURE_MIN_FILTER,
+            WebGl2RenderingContext::NEAREST as i32,
+        );
+        self.gl
+            .bind_texture(WebGl2RenderingContext::TEXTURE_2D, None);
+        self.texture_bindings.insert(texture_id, texture);
+        texture_id
     }
 
     fn draw_texture_rects(
         &mut self,
-        _: TextureId,
-        _: ImageQuality,
-        _: impl IntoIterator<Item = SampleRect>,
+        texture_id: TextureId,
+        quality: ImageQuality,
+        rects: impl IntoIterator<Item = SampleRect>,
     ) {
-        unimplemented!("external textures are not wired up for the WebGL test backend")
+        self.scene.draw_texture_rects(texture_id, quality, rects);
     }
 
     fn get_image_source(&mut self, pixmap: Arc<Pixmap>) -> ImageSource {
@@ -973,6 +1065,15 @@ impl Renderer for HybridRenderer {
     }
 
     fn register_image(&mut self, pixmap: Arc<Pixmap>) -> ImageId {
-        self.upload_image(&pixmap)
+        self.renderer
+            .upload_image_with_mip_levels(&mut self.resources, &pixmap)
+    }
+
+    fn register_custom_paint(&mut self, _: Arc<dyn CustomPaint>) -> CustomPaintId {
+        unimplemented!("custom paints are only supported by the CPU renderer")
+    }
+
+    fn destroy_custom_paint(&mut self, _: CustomPaintId) -> bool {
+        unimplemented!("custom paints are only supported by the CPU renderer")
     }
 }
@@ -36,6 +36,7 @@ fn main() {
     let settings = RenderSettings {
         level: Level::new(),
         num_threads: args.num_threads as u16,
+        ..Default::default()
     };
     let mut ctx = RenderContext::new_with(width, height, settings);
     let mut resources = Resources::new();
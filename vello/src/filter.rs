@@ -0,0 +1,255 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Filter effects for filter layers, see [`Scene::push_filter_layer`](crate::Scene::push_filter_layer).
+
+use bytemuck::{Pod, Zeroable};
+use peniko::color::{AlphaColor, Srgb};
+use vello_common::filter::PreparedFilter;
+use vello_common::filter_effects::{EdgeMode, FilterPrimitive};
+use vello_common::kurbo::Affine;
+
+use crate::FullShaders;
+use crate::recording::{ImageProxy, Recording, ResourceProxy};
+
+// Keep these in sync with `vello_shaders/shader/filter.wgsl`.
+const FILTER_BLUR_H: u32 = 0;
+const FILTER_BLUR_V: u32 = 1;
+const FILTER_OFFSET: u32 = 2;
+const FILTER_FLOOD: u32 = 3;
+const FILTER_DROP_SHADOW: u32 = 4;
+
+const EDGE_MODE_NONE: u32 = 0;
+const EDGE_MODE_DUPLICATE: u32 = 1;
+const EDGE_MODE_WRAP: u32 = 2;
+const EDGE_MODE_MIRROR: u32 = 3;
+
+/// The largest blur radius (in pixels) which is supported by the filter shader.
+///
+/// Blurs with a larger standard deviation are clamped.
+const MAX_BLUR_RADIUS: u32 = 255;
+
+/// The textures which a filter layer is rendered and filtered in.
+pub(crate) struct FilterTextures {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) textures: [wgpu::Texture; 3],
+    pub(crate) views: [wgpu::TextureView; 3],
+}
+
+impl FilterTextures {
+    pub(crate) fn new(device: &wgpu::Device, width: u32, height: u32) -> Self {
+        let create_texture = |label| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                usage: wgpu::TextureUsages::STORAGE_BINDING
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC,
+                format: wgpu::TextureFormat::Rgba8Unorm,
+                view_formats: &[],
+            })
+        };
+        let textures = [
+            create_texture("vello.filter_content"),
+            create_texture("vello.filter_scratch"),
+            create_texture("vello.filter_scratch"),
+        ];
+        let views = textures
+            .each_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        Self {
+            width,
+            height,
+            textures,
+            views,
+        }
+    }
+}
+
+/// Uniform data for a single pass of the filter shader.
+#[derive(Clone, Copy, Debug, Default, Zeroable, Pod)]
+#[repr(C)]
+struct FilterConfig {
+    kind: u32,
+    /// Whether the input of the pass is premultiplied.
+    input_premultiplied: u32,
+    /// Radius of the blur kernel, in pixels.
+    radius: u32,
+    /// Standard deviation of the blur, in pixels.
+    std_dev: f32,
+    dx: f32,
+    dy: f32,
+    /// How the input is extended beyond the layer when blurring.
+    edge_mode: u32,
+    /// Whether the original input of a drop shadow is premultiplied.
+    original_premultiplied: u32,
+    /// Premultiplied flood or shadow color.
+    color: [f32; 4],
+}
+
+impl FilterConfig {
+    fn new(kind: u32) -> Self {
+        Self {
+            kind,
+            ..Default::default()
+        }
+    }
+
+    fn with_blur(mut self, std_dev: f32, edge_mode: EdgeMode) -> Self {
+        self.radius = ((std_dev * 3.0).ceil().max(0.0) as u32).min(MAX_BLUR_RADIUS);
+        self.std_dev = std_dev;
+        self.edge_mode = match edge_mode {
+            EdgeMode::None => EDGE_MODE_NONE,
+            EdgeMode::Duplicate => EDGE_MODE_DUPLICATE,
+            EdgeMode::Wrap => EDGE_MODE_WRAP,
+            EdgeMode::Mirror => EDGE_MODE_MIRROR,
+        };
+        self
+    }
+
+    fn with_offset(mut self, dx: f32, dy: f32) -> Self {
+        self.dx = dx;
+        self.dy = dy;
+        self
+    }
+
+    fn with_color(mut self, color: AlphaColor<Srgb>) -> Self {
+        self.color = color.premultiply().components;
+        self
+    }
+}
+
+/// Record the passes which apply `primitives` to the content of a filter layer.
+///
+/// The primitives are applied in order, each one to the result of the previous one.
+/// `textures[0]` contains the rendered content of the layer (with separate alpha), and the
+/// other textures are used for intermediate results. All textures are overwritten, and the
+/// returned index is the one of the texture containing the premultiplied result.
+/// All images must have the same size and be usable as storage textures.
+pub(crate) fn record_filter(
+    recording: &mut Recording,
+    shaders: &FullShaders,
+    primitives: &[FilterPrimitive],
+    transform: &Affine,
+    textures: [ImageProxy; 3],
+) -> usize {
+    let wg_size = (
+        textures[0].width.div_ceil(16),
+        textures[0].height.div_ceil(16),
+        1,
+    );
+    let mut pass = |config: FilterConfig, input: usize, original: usize, output: usize| {
+        let config_buf =
+            recording.upload_uniform("vello.filter_config", bytemuck::bytes_of(&config));
+        recording.dispatch(
+            shaders.filter,
+            wg_size,
+            [
+                ResourceProxy::from(config_buf),
+                textures[input].into(),
+                textures[original].into(),
+                textures[output].into(),
+            ],
+        );
+        recording.free_resource(config_buf.into());
+    };
+    // The texture containing the result of the previous primitive.
+    let mut current = 0;
+    let mut premultiplied = 0;
+    for primitive in primitives {
+        let next = (current + 1) % 3;
+        let last = (current + 2) % 3;
+        let Some(prepared) = PreparedFilter::from_primitive(primitive, transform) else {
+            // Unsupported primitives are rejected when the layer is pushed.
+            continue;
+        };
+        let config = FilterConfig {
+            input_premultiplied: premultiplied,
+            original_premultiplied: premultiplied,
+            ..Default::default()
+        };
+        current = match prepared {
+            PreparedFilter::Flood(flood) => {
+                pass(
+                    FilterConfig {
+                        kind: FILTER_FLOOD,
+                        ..config
+                    }
+                    .with_color(flood.color),
+                    current,
+                    current,
+                    next,
+                );
+                next
+            }
+            PreparedFilter::GaussianBlur(blur) => {
+                let config = FilterConfig {
+                    kind: FILTER_BLUR_H,
+                    ..config
+                }
+                .with_blur(blur.std_deviation, blur.edge_mode);
+                pass(config, current, current, next);
+                pass(
+                    FilterConfig {
+                        kind: FILTER_BLUR_V,
+                        input_premultiplied: 1,
+                        ..config
+                    },
+                    next,
+                    next,
+                    last,
+                );
+                last
+            }
+            PreparedFilter::Offset(offset) => {
+                pass(
+                    FilterConfig {
+                        kind: FILTER_OFFSET,
+                        ..config
+                    }
+                    .with_offset(offset.dx, offset.dy),
+                    current,
+                    current,
+                    next,
+                );
+                next
+            }
+            PreparedFilter::DropShadow(shadow) => {
+                let config = FilterConfig {
+                    kind: FILTER_BLUR_H,
+                    ..config
+                }
+                .with_blur(shadow.std_deviation, shadow.edge_mode);
+                pass(config, current, current, next);
+                pass(
+                    FilterConfig {
+                        kind: FILTER_DROP_SHADOW,
+                        input_premultiplied: 1,
+                        ..config
+                    }
+                    .with_offset(shadow.dx, shadow.dy)
+                    .with_color(shadow.color),
+                    next,
+                    current,
+                    last,
+                );
+                last
+            }
+        };
+        premultiplied = 1;
+    }
+    if premultiplied == 0 {
+        // Without any (supported) primitive, the content is only converted to premultiplied alpha.
+        pass(FilterConfig::new(FILTER_OFFSET), 0, 0, 1);
+        current = 1;
+    }
+    current
+}
@@ -107,6 +107,8 @@
 )]
 
 mod debug;
+#[cfg(all(feature = "wgpu", feature = "filter_effects"))]
+mod filter;
 mod recording;
 mod render;
 mod scene;
@@ -142,7 +144,10 @@ use peniko::ImageData;
 #[cfg(feature = "wgpu")]
 pub use wgpu;
 
-pub use scene::{DrawGlyphs, Scene};
+pub use scene::{DrawGlyphs, MaskType, Scene};
+/// Filter effects, used in [`Scene::push_filter_layer`].
+#[cfg(feature = "filter_effects")]
+pub use vello_common::filter_effects;
 pub use vello_encoding::{FontEmbolden, Glyph, NormalizedCoord};
 
 use low_level::ShaderId;
@@ -289,6 +294,11 @@ pub enum Error {
     #[error("Failed to download internal buffer '{0}' for visualization")]
     DownloadError(&'static str),
 
+    /// A filter layer is larger than the maximum texture size supported by the device.
+    #[cfg(all(feature = "wgpu", feature = "filter_effects"))]
+    #[error("Filter layer of size {0}x{1} exceeds the maximum texture size")]
+    FilterLayerTooLarge(u32, u32),
+
     #[cfg(feature = "wgpu")]
     #[error("wgpu Error from scope")]
     WgpuErrorFromScope(#[from] wgpu::Error),
@@ -331,6 +341,10 @@ pub struct Renderer {
     engine: WgpuEngine,
     resolver: Resolver,
     image_atlas: Option<recording::ImageProxy>,
+    /// The textures used for the filter layers of the last rendered scene, which are
+    /// reused for the filter layers of the same size in the next scene.
+    #[cfg(feature = "filter_effects")]
+    filter_textures: Vec<filter::FilterTextures>,
     shaders: FullShaders,
     #[cfg(feature = "debug_layers")]
     debug: debug::DebugRenderer,
@@ -447,6 +461,8 @@ impl Renderer {
             engine,
             resolver: Resolver::new(),
             image_atlas: None,
+            #[cfg(feature = "filter_effects")]
+            filter_textures: Vec::new(),
             shaders,
             #[cfg(feature = "debug_layers")]
             debug,
@@ -479,6 +495,8 @@ impl Renderer {
         texture: &TextureView,
         params: &RenderParams,
     ) -> Result<()> {
+        #[cfg(feature = "filter_effects")]
+        let filter_images = self.render_filter_layers(device, queue, scene, params)?;
         let (recording, target) = render::render_full(
             scene,
             &mut self.resolver,
@@ -499,6 +517,8 @@ impl Renderer {
             #[cfg(feature = "wgpu-profiler")]
             &mut self.profiler,
         )?;
+        #[cfg(feature = "filter_effects")]
+        self.release_filter_layers(&filter_images);
         // N.B. This is horrible; this integration of wgpu-profiler really needs some work...
         #[cfg(feature = "wgpu-profiler")]
         {
@@ -602,6 +622,117 @@ impl Renderer {
         self.override_image(&handle, None);
     }
 
+    /// Renders and filters the content of each filter layer in `scene`, and backs the
+    /// placeholder image of the layer with the result.
+    ///
+    /// Returns the placeholder images, which should be passed to
+    /// [`release_filter_layers`](Self::release_filter_layers) once the scene has been rendered.
+    #[cfg(feature = "filter_effects")]
+    fn render_filter_layers(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        scene: &Scene,
+        params: &RenderParams,
+    ) -> Result<Vec<ImageData>> {
+        let max_size = device.limits().max_texture_dimension_2d;
+        if let Some(layer) = scene
+            .filter_layers()
+            .iter()
+            .find(|layer| layer.image.width > max_size || layer.image.height > max_size)
+        {
+            return Err(Error::FilterLayerTooLarge(
+                layer.image.width,
+                layer.image.height,
+            ));
+        }
+        let mut images = Vec::with_capacity(scene.filter_layers().len());
+        // Textures which aren't reused for this scene are dropped.
+        let mut unused_textures = std::mem::take(&mut self.filter_textures);
+        for layer in scene.filter_layers() {
+            let width = layer.image.width;
+            let height = layer.image.height;
+            let textures = unused_textures
+                .iter()
+                .position(|textures| textures.width == width && textures.height == height)
+                .map(|index| unused_textures.swap_remove(index))
+                .unwrap_or_else(|| filter::FilterTextures::new(device, width, height));
+            let proxies = [(); 3]
+                .map(|_| recording::ImageProxy::new(width, height, recording::ImageFormat::Rgba8));
+
+            // Render the content of the layer, such that the top left of the layer is at the origin.
+            let mut content = vello_encoding::Encoding::new();
+            content.append(
+                &layer.content,
+                &Some(vello_encoding::Transform::from_kurbo(
+                    &kurbo::Affine::translate(-layer.bounds.origin().to_vec2()),
+                )),
+            );
+            let content_params = RenderParams {
+                base_color: peniko::Color::TRANSPARENT,
+                width,
+                height,
+                antialiasing_method: params.antialiasing_method,
+            };
+            let (mut recording, target) = render::render_encoding_full(
+                &content,
+                &mut self.resolver,
+                &self.shaders,
+                &mut self.image_atlas,
+                &content_params,
+            );
+            let primitives = layer
+                .filter
+                .as_ref()
+                .map_or(&[][..], |filter| &filter.graph.primitives);
+            let result = filter::record_filter(
+                &mut recording,
+                &self.shaders,
+                primitives,
+                &layer.transform,
+                proxies,
+            );
+            let views = &textures.views;
+            let external_resources = [
+                ExternalResource::Image(*target.as_image().unwrap(), &views[0]),
+                ExternalResource::Image(proxies[0], &views[0]),
+                ExternalResource::Image(proxies[1], &views[1]),
+                ExternalResource::Image(proxies[2], &views[2]),
+            ];
+            self.engine.run_recording(
+                device,
+                queue,
+                &recording,
+                &external_resources,
+                "render_filter_layers",
+                #[cfg(feature = "wgpu-profiler")]
+                &mut self.profiler,
+            )?;
+
+            self.override_image(
+                &layer.image,
+                Some(wgpu::TexelCopyTextureInfoBase {
+                    texture: textures.textures[result].clone(),
+                    mip_level: 0,
+                    origin: wgpu::Origin3d::ZERO,
+                    aspect: wgpu::TextureAspect::All,
+                }),
+            );
+            images.push(layer.image.clone());
+            self.filter_textures.push(textures);
+        }
+        Ok(images)
+    }
+
+    /// Removes the textures backing the placeholder images of filter layers, after the scene
+    /// which uses them has been rendered.
+    #[cfg(feature = "filter_effects")]
+    fn release_filter_layers(&mut self, images: &[ImageData]) {
+        for image in images {
+            self.override_image(image, None);
+        }
+    }
+
     /// Reload the shaders. This should only be used during `vello` development
     #[cfg(feature = "hot_reload")]
     #[doc(hidden)] // End-users of Vello should not have `hot_reload` enabled.
@@ -722,6 +853,8 @@ impl Renderer {
         texture: &TextureView,
         params: &RenderParams,
     ) -> Result<RenderResult> {
+        #[cfg(feature = "filter_effects")]
+        let filter_images = self.render_filter_layers(device, queue, scene, params)?;
         let mut render = Render::new();
         let encoding = scene.encoding();
         // TODO: turn this on; the download feature interacts with CPU dispatch.
@@ -775,6 +908,8 @@ impl Renderer {
             #[cfg(feature = "wgpu-profiler")]
             &mut self.profiler,
         )?;
+        #[cfg(feature = "filter_effects")]
+        self.release_filter_layers(&filter_images);
         Ok(RenderResult {
             bump,
             #[cfg(feature = "debug_layers")]
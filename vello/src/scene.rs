@@ -3,6 +3,8 @@
 
 use std::io::Cursor;
 use std::sync::Arc;
+#[cfg(feature = "filter_effects")]
+use std::sync::OnceLock;
 
 use peniko::{
     BlendMode, Blob, Brush, BrushRef, Color, ColorStop, ColorStops, ColorStopsSource, Compose,
@@ -20,8 +22,12 @@ use skrifa::{
     prelude::Size,
     raw::{TableProvider, tables::cpal::Cpal},
 };
+#[cfg(feature = "filter_effects")]
+use vello_common::filter_effects::{Filter, FilterPrimitive};
 #[cfg(feature = "bump_estimate")]
 use vello_encoding::BumpAllocatorMemory;
+#[cfg(feature = "filter_effects")]
+use vello_encoding::DrawImage;
 use vello_encoding::{
     DrawBeginClip, Encoding, FontEmbolden, Glyph, GlyphRun, NormalizedCoord, Patch, Transform,
 };
@@ -46,6 +52,11 @@ pub struct Scene {
     encoding: Encoding,
     #[cfg(feature = "bump_estimate")]
     estimator: vello_encoding::BumpEstimator,
+    /// For each open layer, the state needed to finish it when it is popped.
+    layers: Vec<LayerState>,
+    /// The filter layers drawn in this scene, in the order in which they need to be rendered.
+    #[cfg(feature = "filter_effects")]
+    filter_layers: Vec<FilterLayer>,
 }
 static_assertions::assert_impl_all!(Scene: Send, Sync);
 
@@ -60,6 +71,9 @@ impl Scene {
         self.encoding.reset();
         #[cfg(feature = "bump_estimate")]
         self.estimator.reset();
+        self.layers.clear();
+        #[cfg(feature = "filter_effects")]
+        self.filter_layers.clear();
     }
 
     /// Tally up the bump allocator estimate for the current state of the encoding,
@@ -116,6 +130,7 @@ impl Scene {
             clip_style.into(),
             transform,
             clip,
+            LayerState::Plain,
         );
     }
 
@@ -163,6 +178,106 @@ impl Scene {
             clip_style.into(),
             transform,
             clip,
+            LayerState::Plain,
+        );
+    }
+
+    /// Pushes a new layer clipped by the specified shape and masked by the content
+    /// of `mask`.
+    ///
+    /// Every drawing command after this call will be clipped by the shape and, once the
+    /// layer is [popped](Self::pop_layer), multiplied by the alpha or luminance of `mask`
+    /// (as selected by `mask_type`) before being composited with previous layers.
+    /// Areas not covered by `mask` are fully masked out.
+    ///
+    /// The `mask_transform` is applied to every transform in `mask`, as in [`Self::append`].
+    ///
+    /// The `clip_style` controls how the `clip` shape is interpreted.
+    ///
+    /// - Use [`Fill`] to clip to the interior of the shape, with the chosen fill rule.
+    /// - Use [`Stroke`] (via `&Stroke`) to clip to the stroked outline of the shape.
+    ///
+    /// **However, the transforms are *not* saved or modified by the layer stack.**
+    /// That is, the `transform` argument to this function only applies a transform to the `clip` shape.
+    #[expect(
+        single_use_lifetimes,
+        reason = "False positive: https://github.com/rust-lang/rust/issues/129255"
+    )]
+    pub fn push_mask_layer<'a>(
+        &mut self,
+        clip_style: impl Into<StyleRef<'a>>,
+        mask_type: MaskType,
+        mask: &Self,
+        mask_transform: Affine,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        let clip_style = clip_style.into();
+        let mut mask_scene = Self::new();
+        mask_scene.push_layer_inner(
+            mask_type.begin_clip(),
+            clip_style,
+            transform,
+            clip,
+            LayerState::Plain,
+        );
+        mask_scene.append(mask, Some(mask_transform));
+        mask_scene.pop_layer();
+        self.push_mask_layer_inner(mask_scene, clip_style, alpha, transform, clip);
+    }
+
+    /// Pushes a new layer clipped by the specified shape and masked by an image.
+    ///
+    /// This behaves like [`Self::push_mask_layer`], where the mask content is `image`
+    /// drawn at its natural size with `mask_transform` (see [`Self::draw_image`]).
+    #[expect(
+        single_use_lifetimes,
+        reason = "False positive: https://github.com/rust-lang/rust/issues/129255"
+    )]
+    pub fn push_image_mask_layer<'a, 'b>(
+        &mut self,
+        clip_style: impl Into<StyleRef<'a>>,
+        mask_type: MaskType,
+        image: impl Into<ImageBrushRef<'b>>,
+        mask_transform: Affine,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        let clip_style = clip_style.into();
+        let mut mask_scene = Self::new();
+        mask_scene.push_layer_inner(
+            mask_type.begin_clip(),
+            clip_style,
+            transform,
+            clip,
+            LayerState::Plain,
+        );
+        mask_scene.draw_image(image, mask_transform);
+        mask_scene.pop_layer();
+        self.push_mask_layer_inner(mask_scene, clip_style, alpha, transform, clip);
+    }
+
+    /// Helper for logic shared between [`Self::push_mask_layer`] and [`Self::push_image_mask_layer`].
+    fn push_mask_layer_inner(
+        &mut self,
+        mask_scene: Self,
+        clip_style: StyleRef<'_>,
+        alpha: f32,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        // The content of the layer is collected in a regular layer, and the mask is
+        // applied to it as a mask layer nested at the end of it (see `Self::pop_layer`).
+        // Note that this can't be a clip layer, as tiles fully covered by a clip layer
+        // don't get their own blend stack entry.
+        self.push_layer_inner(
+            DrawBeginClip::new(BlendMode::default(), alpha.clamp(0.0, 1.0)),
+            clip_style,
+            transform,
+            clip,
+            LayerState::Masked(Box::new(mask_scene)),
         );
     }
 
@@ -195,7 +310,147 @@ impl Scene {
         transform: Affine,
         clip: &impl Shape,
     ) {
-        self.push_layer_inner(DrawBeginClip::clip(), clip_style.into(), transform, clip);
+        self.push_layer_inner(
+            DrawBeginClip::clip(),
+            clip_style.into(),
+            transform,
+            clip,
+            LayerState::Plain,
+        );
+    }
+
+    /// Pushes a new layer clipped by the specified `clip` shape, to which `filter` is
+    /// applied when it is popped.
+    ///
+    /// The `clip_style` controls how the `clip` shape is interpreted.
+    ///
+    /// - Use [`Fill`] to clip to the interior of the shape, with the chosen fill rule.
+    /// - Use [`Stroke`] (via `&Stroke`) to clip to the stroked outline of the shape.
+    ///
+    /// Every drawing command after this call will be clipped by the shape until the layer
+    /// is [popped](Self::pop_layer). The filter is then applied to the content of the layer,
+    /// and its result is composited with previous layers. The filtered result may extend
+    /// beyond the clip shape, for example due to a blur or an offset.
+    /// The parameters of the filter (such as the standard deviation of a blur) are scaled
+    /// by `transform`.
+    ///
+    /// The content of the layer is rendered into an intermediate texture, which is filtered
+    /// before the scene itself is rendered. When the scene is [appended](Self::append) with a
+    /// transform, the transform is applied to the content and the filter parameters before
+    /// filtering, as for the `transform` argument.
+    ///
+    /// Currently, filters made of Gaussian blur, offset, flood and drop shadow primitives are
+    /// supported, where each primitive is applied to the result of the previous one. Layers
+    /// with other filters are drawn without a filter, and a warning is logged. The same applies
+    /// to layers whose filtered result is larger than 8192 pixels in either direction.
+    ///
+    /// Filter layers are applied by [`Renderer`](crate::Renderer). When rendering the
+    /// [encoding](Self::encoding) of the scene in another way, for example using
+    /// [`low_level::Render`](crate::low_level::Render), filter layers are drawn transparent.
+    ///
+    /// **However, the transforms are *not* saved or modified by the layer stack.**
+    /// That is, the `transform` argument to this function only applies a transform to the `clip` shape.
+    #[cfg(feature = "filter_effects")]
+    #[expect(
+        single_use_lifetimes,
+        reason = "False positive: https://github.com/rust-lang/rust/issues/129255"
+    )]
+    pub fn push_filter_layer<'a>(
+        &mut self,
+        clip_style: impl Into<StyleRef<'a>>,
+        filter: Filter,
+        transform: Affine,
+        clip: &impl Shape,
+    ) {
+        let clip_style = clip_style.into();
+        let mut bbox = clip.bounding_box();
+        if let StyleRef::Stroke(stroke) = clip_style {
+            bbox = bbox.inflate(stroke.width * 0.5, stroke.width * 0.5);
+        }
+        let bbox = transform.transform_rect_bbox(bbox);
+        let filter = if is_supported_filter(&filter) {
+            Some(filter)
+        } else {
+            log::warn!("unsupported filter in filter layer, drawing it without a filter");
+            None
+        };
+        let (filter_expansion, source_expansion) =
+            filter.as_ref().map_or_else(Default::default, |filter| {
+                (
+                    filter.filter_expansion(&transform),
+                    filter.source_expansion(&transform),
+                )
+            });
+        let expand = |expansion: Rect| {
+            Rect::new(
+                bbox.x0 + expansion.x0,
+                bbox.y0 + expansion.y0,
+                bbox.x1 + expansion.x1,
+                bbox.y1 + expansion.y1,
+            )
+        };
+        let bounds = expand(filter_expansion)
+            .union(expand(source_expansion))
+            .expand();
+        if !bounds.is_finite()
+            || bounds.width() > MAX_FILTER_LAYER_SIZE
+            || bounds.height() > MAX_FILTER_LAYER_SIZE
+        {
+            log::warn!(
+                "filter layer of size {}x{} is too large, drawing it without a filter",
+                bounds.width(),
+                bounds.height()
+            );
+            self.push_clip_layer(clip_style, transform, clip);
+            return;
+        }
+        let parent = std::mem::take(&mut self.encoding);
+        self.push_layer_inner(
+            DrawBeginClip::clip(),
+            clip_style,
+            transform,
+            clip,
+            LayerState::Filter(Box::new(OpenFilterLayer {
+                parent,
+                filter,
+                transform,
+                bounds,
+                first_layer: self.filter_layers.len(),
+            })),
+        );
+    }
+
+    /// Returns the filter layers in this scene, in the order in which they need to be rendered.
+    #[cfg(feature = "filter_effects")]
+    pub(crate) fn filter_layers(&self) -> &[FilterLayer] {
+        &self.filter_layers
+    }
+
+    /// Draws the placeholder `image` of a filter layer, covering `bounds`.
+    #[cfg(feature = "filter_effects")]
+    fn draw_filter_placeholder(&mut self, image: &ImageData, bounds: Rect) -> FilterPlaceholder {
+        // The transforms of the placeholder are always encoded, even if they match the previous
+        // one, so that they can be replaced when the scene is appended (see `Self::append`).
+        let placeholder = FilterPlaceholder {
+            container: None,
+            transform: self.encoding.transforms.len(),
+            patch: self.encoding.resources.patches.len(),
+        };
+        let (transform, brush_transform) = placeholder_transforms(bounds);
+        let rect = Rect::new(0.0, 0.0, 1.0, 1.0);
+        self.encoding.flags |= Encoding::FORCE_NEXT_TRANSFORM;
+        self.encoding.encode_transform(transform);
+        self.encoding.encode_fill_style(Fill::NonZero);
+        self.encoding.encode_shape(&rect, true);
+        self.encoding.flags |= Encoding::FORCE_NEXT_TRANSFORM;
+        self.encoding.encode_transform(brush_transform);
+        self.encoding.swap_last_path_tags();
+        self.encoding
+            .encode_image(&ImageBrush::new(image.clone()), 1.0);
+        #[cfg(feature = "bump_estimate")]
+        self.estimator
+            .count_path(rect.path_elements(0.1), &transform, None);
+        placeholder
     }
 
     /// Helper for logic shared between [`Self::push_layer`] and [`Self::push_luminance_mask_layer`]
@@ -205,6 +460,7 @@ impl Scene {
         clip_style: StyleRef<'a>,
         transform: Affine,
         clip: &impl Shape,
+        state: LayerState,
     ) {
         // The logic for encoding the clip shape differs between fill and stroke style clips, but
         // the logic is otherwise similar.
@@ -245,11 +501,54 @@ impl Scene {
             }
         }
         self.encoding.encode_begin_clip(parameters);
+        self.layers.push(state);
     }
 
     /// Pops the current layer.
     pub fn pop_layer(&mut self) {
-        self.encoding.encode_end_clip();
+        match self.layers.pop() {
+            Some(LayerState::Masked(mask)) => {
+                self.append(&mask, None);
+                self.encoding.encode_end_clip();
+            }
+            #[cfg(feature = "filter_effects")]
+            Some(LayerState::Filter(layer)) => {
+                self.encoding.encode_end_clip();
+                let OpenFilterLayer {
+                    parent,
+                    filter,
+                    transform,
+                    bounds,
+                    first_layer,
+                } = *layer;
+                let content = std::mem::replace(&mut self.encoding, parent);
+                if bounds.is_zero_area() {
+                    // The content isn't drawn, and neither are the filter layers inside of it.
+                    self.filter_layers.truncate(first_layer);
+                    return;
+                }
+                // The placeholders of the filter layers directly inside of this layer are drawn
+                // in its content.
+                let index = self.filter_layers.len();
+                for layer in &mut self.filter_layers[first_layer..] {
+                    layer.placeholder.container.get_or_insert(index);
+                }
+                // The filtered result is drawn as an image, whose content is provided by the
+                // renderer (see `Renderer::render_to_texture`). Its own pixels are transparent,
+                // for render paths which don't apply filter layers.
+                let image = placeholder_image(bounds);
+                let placeholder = self.draw_filter_placeholder(&image, bounds);
+                self.filter_layers.push(FilterLayer {
+                    content,
+                    filter,
+                    transform,
+                    bounds,
+                    image,
+                    placeholder,
+                });
+            }
+            Some(LayerState::Plain) | None => self.encoding.encode_end_clip(),
+        }
     }
 
     /// Draw a rounded rectangle blurred with a gaussian filter.
@@ -461,8 +760,45 @@ impl Scene {
     ///
     /// The given transform is applied to every transform in the child.
     /// This is an O(N) operation.
+    ///
+    /// Layers which are still open in the child stay open in this scene and are
+    /// finished by later calls to [`Scene::pop_layer`].
     pub fn append(&mut self, other: &Self, transform: Option<Affine>) {
         let t = transform.as_ref().map(Transform::from_kurbo);
+        #[cfg(feature = "filter_effects")]
+        let mut filter_layers = FilterLayerAppend::new(self, other, transform, t);
+        for layer in &other.layers {
+            let layer = match layer {
+                LayerState::Masked(mask) => {
+                    let mut transformed = Self::new();
+                    transformed.append(mask, transform);
+                    LayerState::Masked(Box::new(transformed))
+                }
+                // The content of an open filter layer is encoded separately from everything
+                // before it, so that is appended first, and the layer is opened again here.
+                #[cfg(feature = "filter_effects")]
+                LayerState::Filter(layer) => {
+                    filter_layers.append_encoding(self, &layer.parent, layer.first_layer);
+                    let transform = transform.unwrap_or_default();
+                    let parent = std::mem::take(&mut self.encoding);
+                    LayerState::Filter(Box::new(OpenFilterLayer {
+                        parent,
+                        filter: layer.filter.clone(),
+                        transform: transform * layer.transform,
+                        bounds: transformed_bounds(layer.bounds, transform),
+                        first_layer: filter_layers.base + layer.first_layer,
+                    }))
+                }
+                LayerState::Plain => LayerState::Plain,
+            };
+            self.layers.push(layer);
+        }
+        #[cfg(feature = "filter_effects")]
+        {
+            filter_layers.append_encoding(self, &other.encoding, other.filter_layers.len());
+            filter_layers.finish(self);
+        }
+        #[cfg(not(feature = "filter_effects"))]
         self.encoding.append(&other.encoding, &t);
         #[cfg(feature = "bump_estimate")]
         self.estimator.append(&other.estimator, t.as_ref());
@@ -477,6 +813,282 @@ impl From<Encoding> for Scene {
             encoding,
             #[cfg(feature = "bump_estimate")]
             estimator: vello_encoding::BumpEstimator::default(),
+            layers: Vec::new(),
+            #[cfg(feature = "filter_effects")]
+            filter_layers: Vec::new(),
+        }
+    }
+}
+
+/// The state of an open layer, which is needed to finish it in [`Scene::pop_layer`].
+#[derive(Clone)]
+enum LayerState {
+    /// A layer which doesn't need any extra work.
+    Plain,
+    /// A layer whose content is masked by the given mask scene.
+    Masked(Box<Scene>),
+    /// A filter layer, whose content is encoded separately from its parent.
+    #[cfg(feature = "filter_effects")]
+    Filter(Box<OpenFilterLayer>),
+}
+
+/// A filter layer which hasn't been popped yet.
+#[cfg(feature = "filter_effects")]
+#[derive(Clone)]
+struct OpenFilterLayer {
+    /// The encoding of the enclosing scene, which is restored when the layer is popped.
+    parent: Encoding,
+    /// The filter of the layer, or `None` if it isn't supported.
+    filter: Option<Filter>,
+    transform: Affine,
+    bounds: Rect,
+    /// The number of filter layers in the scene when the layer was pushed, i.e. the index
+    /// of the first filter layer inside of it.
+    first_layer: usize,
+}
+
+/// Where the placeholder image of a filter layer is drawn.
+#[cfg(feature = "filter_effects")]
+#[derive(Clone, Copy)]
+struct FilterPlaceholder {
+    /// The index of the filter layer whose content the placeholder is drawn in, or `None` if it
+    /// is drawn in the encoding the layer was popped into, and no enclosing filter layer has been
+    /// popped yet.
+    container: Option<usize>,
+    /// The index of the transform of the placeholder rectangle, which is followed by the
+    /// transform of its image.
+    transform: usize,
+    /// The index of the image patch of the placeholder.
+    patch: usize,
+}
+
+/// A filter layer whose content is rendered and filtered separately from the scene.
+#[cfg(feature = "filter_effects")]
+#[derive(Clone)]
+pub(crate) struct FilterLayer {
+    /// The unfiltered content of the layer.
+    pub(crate) content: Encoding,
+    /// The filter of the layer, or `None` if it isn't supported.
+    pub(crate) filter: Option<Filter>,
+    /// The transform of the layer, which the filter parameters are scaled by.
+    pub(crate) transform: Affine,
+    /// The pixel-aligned region covered by the filtered result.
+    pub(crate) bounds: Rect,
+    /// The image which is drawn in place of the layer.
+    ///
+    /// This is only a placeholder, which the renderer backs with the filtered result.
+    pub(crate) image: ImageData,
+    /// Where the placeholder image is drawn.
+    placeholder: FilterPlaceholder,
+}
+
+/// Appends the filter layers of a scene to another one, see [`Scene::append`].
+#[cfg(feature = "filter_effects")]
+struct FilterLayerAppend {
+    /// The appended filter layers, which have been transformed and have new placeholder images.
+    layers: Vec<FilterLayer>,
+    /// The number of filter layers in the scene before the new layers are appended.
+    base: usize,
+    /// The number of layers whose placeholder has been appended, if it is drawn in an encoding
+    /// of the scene rather than in the content of another layer.
+    appended: usize,
+    transform: Option<Transform>,
+}
+
+#[cfg(feature = "filter_effects")]
+impl FilterLayerAppend {
+    fn new(scene: &Scene, other: &Scene, transform: Option<Affine>, t: Option<Transform>) -> Self {
+        let base = scene.filter_layers.len();
+        let layers = other
+            .filter_layers
+            .iter()
+            .map(|layer| {
+                let content = if t.is_some() {
+                    let mut content = Encoding::new();
+                    content.append(&layer.content, &t);
+                    content
+                } else {
+                    layer.content.clone()
+                };
+                let transform = transform.unwrap_or_default();
+                let bounds = transformed_bounds(layer.bounds, transform);
+                // Every layer gets a new placeholder image, as the renderer tells them apart by
+                // their image, and the same scene may be appended multiple times.
+                FilterLayer {
+                    content,
+                    filter: layer.filter.clone(),
+                    transform: transform * layer.transform,
+                    bounds,
+                    image: placeholder_image(bounds),
+                    placeholder: FilterPlaceholder {
+                        container: layer.placeholder.container.map(|index| base + index),
+                        ..layer.placeholder
+                    },
+                }
+            })
+            .collect();
+        Self {
+            layers,
+            base,
+            appended: 0,
+            transform: t,
+        }
+    }
+
+    /// Appends `encoding` to the encoding of `scene`, where `encoding` contains the placeholders
+    /// of the layers before `end` which haven't been appended yet.
+    fn append_encoding(&mut self, scene: &mut Scene, encoding: &Encoding, end: usize) {
+        let transforms = scene.encoding.transforms.len();
+        let patches = scene.encoding.resources.patches.len();
+        scene.encoding.append(encoding, &self.transform);
+        for layer in &mut self.layers[self.appended..end] {
+            if layer.placeholder.container.is_none() {
+                layer.placeholder.transform += transforms;
+                layer.placeholder.patch += patches;
+                move_placeholder(&mut scene.encoding, layer);
+            }
+        }
+        self.appended = end;
+    }
+
+    /// Moves the placeholders drawn in the content of other layers, and adds the layers to `scene`.
+    fn finish(mut self, scene: &mut Scene) {
+        for index in 0..self.layers.len() {
+            if let Some(container) = self.layers[index].placeholder.container {
+                // Layers are always contained in layers which come after them.
+                let (layers, containers) = self.layers.split_at_mut(container - self.base);
+                move_placeholder(&mut containers[0].content, &layers[index]);
+            }
+        }
+        scene.filter_layers.append(&mut self.layers);
+    }
+}
+
+/// Creates the placeholder image of a filter layer covering `bounds`.
+#[cfg(feature = "filter_effects")]
+fn placeholder_image(bounds: Rect) -> ImageData {
+    let width = bounds.width() as u32;
+    let height = bounds.height() as u32;
+    ImageData {
+        data: Blob::new(Arc::new(TransparentPixels::new(width, height))),
+        format: peniko::ImageFormat::Rgba8,
+        alpha_type: peniko::ImageAlphaType::AlphaPremultiplied,
+        width,
+        height,
+    }
+}
+
+/// Returns the transform of the unit square drawn as the placeholder of a filter layer covering
+/// `bounds`, and the transform of its image.
+#[cfg(feature = "filter_effects")]
+fn placeholder_transforms(bounds: Rect) -> (Transform, Transform) {
+    let origin = Affine::translate(bounds.origin().to_vec2());
+    (
+        Transform::from_kurbo(
+            &(origin * Affine::scale_non_uniform(bounds.width(), bounds.height())),
+        ),
+        Transform::from_kurbo(&origin),
+    )
+}
+
+/// Returns the pixel-aligned bounds of a filter layer with `bounds` after applying `transform`.
+#[cfg(feature = "filter_effects")]
+fn transformed_bounds(bounds: Rect, transform: Affine) -> Rect {
+    let bounds = transform.transform_rect_bbox(bounds).expand();
+    // The placeholder of the layer is already drawn, so it needs to cover at least one pixel.
+    Rect::from_origin_size(
+        bounds.origin(),
+        (bounds.width().max(1.0), bounds.height().max(1.0)),
+    )
+}
+
+/// Replaces the placeholder of `layer` drawn in `encoding`, so that it covers the bounds of the
+/// layer with its image.
+#[cfg(feature = "filter_effects")]
+fn move_placeholder(encoding: &mut Encoding, layer: &FilterLayer) {
+    let placeholder = &layer.placeholder;
+    let (transform, brush_transform) = placeholder_transforms(layer.bounds);
+    encoding.transforms[placeholder.transform] = transform;
+    encoding.transforms[placeholder.transform + 1] = brush_transform;
+    if let Patch::Image {
+        image,
+        draw_data_offset,
+    } = &mut encoding.resources.patches[placeholder.patch]
+    {
+        *image = layer.image.clone();
+        let draw_data = &mut encoding.draw_data[*draw_data_offset..][..size_of::<DrawImage>() / 4];
+        let draw: &mut DrawImage = bytemuck::from_bytes_mut(bytemuck::cast_slice_mut(draw_data));
+        draw.width_height = (image.width << 16) | (image.height & 0xFFFF);
+    }
+}
+
+/// The largest width and height of a filter layer, which is the largest image that fits into
+/// the image atlas.
+#[cfg(feature = "filter_effects")]
+const MAX_FILTER_LAYER_SIZE: f64 = 8192.0;
+
+/// The transparent pixels of the placeholder image of a filter layer.
+///
+/// They are only allocated once they are read, i.e. when the scene is rendered in a way
+/// which doesn't apply filter layers, as [`Renderer`](crate::Renderer) replaces the image.
+#[cfg(feature = "filter_effects")]
+struct TransparentPixels {
+    len: usize,
+    data: OnceLock<Box<[u8]>>,
+}
+
+#[cfg(feature = "filter_effects")]
+impl TransparentPixels {
+    fn new(width: u32, height: u32) -> Self {
+        Self {
+            len: width as usize * height as usize * 4,
+            data: OnceLock::new(),
+        }
+    }
+}
+
+#[cfg(feature = "filter_effects")]
+impl AsRef<[u8]> for TransparentPixels {
+    fn as_ref(&self) -> &[u8] {
+        self.data
+            .get_or_init(|| vec![0; self.len].into_boxed_slice())
+    }
+}
+
+/// Whether filter layers support `filter`, i.e. whether it is a chain of primitives
+/// which are each applied to the result of the previous one, and which are all supported.
+#[cfg(feature = "filter_effects")]
+fn is_supported_filter(filter: &Filter) -> bool {
+    let graph = &filter.graph;
+    graph.inputs.iter().all(Option::is_none)
+        && usize::from(graph.output.0) + 1 == graph.primitives.len()
+        && graph.primitives.iter().all(|primitive| {
+            matches!(
+                primitive,
+                FilterPrimitive::Flood { .. }
+                    | FilterPrimitive::GaussianBlur { .. }
+                    | FilterPrimitive::Offset { .. }
+                    | FilterPrimitive::DropShadow { .. }
+            )
+        })
+}
+
+/// Which channel of the mask content is used by [`Scene::push_mask_layer`]
+/// and [`Scene::push_image_mask_layer`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum MaskType {
+    /// The alpha of the mask content is used as the mask.
+    #[default]
+    Alpha,
+    /// The luminance of the mask content, multiplied by its alpha, is used as the mask.
+    Luminance,
+}
+
+impl MaskType {
+    fn begin_clip(self) -> DrawBeginClip {
+        match self {
+            Self::Alpha => DrawBeginClip::alpha_mask(1.0),
+            Self::Luminance => DrawBeginClip::luminance_mask(1.0),
         }
     }
 }
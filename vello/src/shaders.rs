@@ -39,6 +39,8 @@ pub struct FullShaders {
     pub fine_area: Option<ShaderId>,
     pub fine_msaa8: Option<ShaderId>,
     pub fine_msaa16: Option<ShaderId>,
+    #[cfg(feature = "filter_effects")]
+    pub filter: ShaderId,
     // 2-level dispatch works for CPU pathtag scan even for large
     // inputs, 3-level is not yet implemented.
     pub pathtag_is_cpu: bool,
@@ -246,6 +248,20 @@ pub(crate) fn full_shaders(
         None
     };
 
+    // Like the fine shaders, the filter shader only reads and writes images, which CPU shaders
+    // can't bind. It therefore always runs on the GPU, also when `use_cpu` is set.
+    #[cfg(feature = "filter_effects")]
+    let filter = add_shader!(
+        filter,
+        [
+            Uniform,
+            ImageRead(ImageFormat::Rgba8),
+            ImageRead(ImageFormat::Rgba8),
+            Image(ImageFormat::Rgba8),
+        ],
+        CpuShaderType::Missing
+    );
+
     Ok(FullShaders {
         pathtag_reduce,
         pathtag_reduce2,
@@ -269,6 +285,8 @@ pub(crate) fn full_shaders(
         fine_area,
         fine_msaa8,
         fine_msaa16,
+        #[cfg(feature = "filter_effects")]
+        filter,
         pathtag_is_cpu: options.use_cpu,
     })
 }
@@ -203,6 +203,18 @@ impl DrawBeginClip {
     /// combinations.
     pub const LUMINANCE_MASK_BLEND_MODE: u32 = 0x10000;
     /// The `blend_mode` used to indicate that a layer should be
+    /// treated as an alpha mask.
+    ///
+    /// The least significant 16 bits are reserved for Mix + Compose
+    /// combinations.
+    ///
+    /// Like [`Self::LUMINANCE_MASK_BLEND_MODE`], mask layers are encoded as
+    /// clips with a sentinel blend mode rather than with their own draw tags.
+    /// A mask is applied when the layer ends, exactly where a blend mode is,
+    /// so mask layers go through the same clip, coarse and blend stack
+    /// handling as any other layer, and only `fine` needs to know about them.
+    pub const ALPHA_MASK_BLEND_MODE: u32 = 0x20000;
+    /// The `blend_mode` used to indicate that a layer should be
     /// treated as a clip.
     ///
     /// This is equivalent to `Compose::SrcOver` with a `Mix` of 128,
@@ -226,6 +238,14 @@ impl DrawBeginClip {
         }
     }
 
+    /// Creates a new clip draw data for an alpha mask.
+    pub fn alpha_mask(alpha: f32) -> Self {
+        Self {
+            blend_mode: Self::ALPHA_MASK_BLEND_MODE,
+            alpha,
+        }
+    }
+
     /// Creates the clip draw data for a clip-only layer.
     pub fn clip() -> Self {
         Self {
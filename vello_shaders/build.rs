@@ -100,6 +100,12 @@ fn write_shaders(buf: &mut String, shaders: &[(String, ShaderInfo)]) -> Result<(
         if cfg!(feature = "msl") {
             write_msl(buf, info)?;
         }
+        if cfg!(feature = "spirv") {
+            write_spirv(buf, info)?;
+        }
+        if cfg!(feature = "hlsl") {
+            write_hlsl(buf, info)?;
+        }
         writeln!(buf, "        }},")?;
     }
     writeln!(buf, "    }};")?;
@@ -134,6 +140,59 @@ fn write_msl(buf: &mut String, info: &ShaderInfo) -> Result<(), fmt::Error> {
     Ok(())
 }
 
+#[cfg(not(feature = "spirv"))]
+fn write_spirv(_: &mut String, _: &ShaderInfo) -> Result<(), fmt::Error> {
+    Ok(())
+}
+
+#[cfg(feature = "spirv")]
+fn write_spirv(buf: &mut String, info: &ShaderInfo) -> Result<(), fmt::Error> {
+    let indices = info
+        .bindings
+        .iter()
+        .map(|binding| binding.location.1)
+        .collect::<Vec<_>>();
+    writeln!(buf, "            spirv: SpirvSource {{")?;
+    writeln!(
+        buf,
+        "                code: Cow::Borrowed(&{:?}),",
+        compile::spirv::translate(info).unwrap()
+    )?;
+    writeln!(
+        buf,
+        "                binding_indices : Cow::Borrowed(&{indices:?}),",
+    )?;
+    writeln!(buf, "            }},")?;
+    Ok(())
+}
+
+#[cfg(not(feature = "hlsl"))]
+fn write_hlsl(_: &mut String, _: &ShaderInfo) -> Result<(), fmt::Error> {
+    Ok(())
+}
+
+#[cfg(feature = "hlsl")]
+fn write_hlsl(buf: &mut String, info: &ShaderInfo) -> Result<(), fmt::Error> {
+    let mut index_iter = compile::hlsl::BindingIndexIterator::default();
+    let indices = info
+        .bindings
+        .iter()
+        .map(|binding| index_iter.next(binding.ty))
+        .collect::<Vec<_>>();
+    writeln!(buf, "            hlsl: HlslSource {{")?;
+    writeln!(
+        buf,
+        "                code: Cow::Borrowed({:?}),",
+        compile::hlsl::translate(info).unwrap()
+    )?;
+    writeln!(
+        buf,
+        "                binding_indices : Cow::Borrowed(&{indices:?}),",
+    )?;
+    writeln!(buf, "            }},")?;
+    Ok(())
+}
+
 /// A very simple logger for build scripts, which ensures that warnings and above
 /// are visible to the user.
 ///
@@ -0,0 +1,167 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT OR Unlicense
+
+// Filter effects applied to the rendered content of a filter layer.
+//
+// A filter is applied as a sequence of passes, each reading from `input` (and possibly
+// `original`, the unfiltered layer content) and writing the result to `output`.
+// All textures have the same size, which is the size of the filter layer.
+// Pixels outside of the layer are transparent black, except for blurs, which
+// extend their input according to the edge mode.
+
+// Keep these in sync with `vello/src/filter.rs`.
+const FILTER_BLUR_H = 0u;
+const FILTER_BLUR_V = 1u;
+const FILTER_OFFSET = 2u;
+const FILTER_FLOOD = 3u;
+const FILTER_DROP_SHADOW = 4u;
+
+const EDGE_MODE_NONE = 0u;
+const EDGE_MODE_DUPLICATE = 1u;
+const EDGE_MODE_WRAP = 2u;
+const EDGE_MODE_MIRROR = 3u;
+
+struct FilterConfig {
+    kind: u32,
+    // Whether `input` contains premultiplied colors. The layer content itself
+    // is stored with separate alpha.
+    input_premultiplied: u32,
+    // The radius of the blur kernel, in pixels.
+    radius: u32,
+    // The standard deviation of the blur, in pixels.
+    std_dev: f32,
+    dx: f32,
+    dy: f32,
+    // How `input` is extended beyond the layer when blurring.
+    edge_mode: u32,
+    // Whether `original` contains premultiplied colors.
+    original_premultiplied: u32,
+    // Premultiplied flood or shadow color.
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> config: FilterConfig;
+
+@group(0) @binding(1)
+var input: texture_2d<f32>;
+
+@group(0) @binding(2)
+var original: texture_2d<f32>;
+
+@group(0) @binding(3)
+var output: texture_storage_2d<rgba8unorm, write>;
+
+fn premul_alpha(rgba: vec4<f32>) -> vec4<f32> {
+    return vec4(rgba.rgb * rgba.a, rgba.a);
+}
+
+// Map a coordinate outside of `0..size` into it, according to `edge_mode`.
+fn extend(coord: i32, size: i32, edge_mode: u32) -> i32 {
+    switch edge_mode {
+        case EDGE_MODE_DUPLICATE: {
+            return clamp(coord, 0, size - 1);
+        }
+        case EDGE_MODE_WRAP: {
+            let c = coord % size;
+            return select(c, c + size, c < 0);
+        }
+        case EDGE_MODE_MIRROR: {
+            let period = size * 2;
+            var c = coord % period;
+            if c < 0 {
+                c += period;
+            }
+            return select(c, period - c - 1, c >= size);
+        }
+        default: {
+            return coord;
+        }
+    }
+}
+
+fn load_input_extended(coords_in: vec2<i32>, edge_mode: u32) -> vec4<f32> {
+    let dims = vec2<i32>(textureDimensions(input));
+    let coords = vec2(extend(coords_in.x, dims.x, edge_mode), extend(coords_in.y, dims.y, edge_mode));
+    if any(coords < vec2(0)) || any(coords >= dims) {
+        return vec4(0.0);
+    }
+    let rgba = textureLoad(input, coords, 0);
+    if config.input_premultiplied != 0u {
+        return rgba;
+    }
+    return premul_alpha(rgba);
+}
+
+fn load_input(coords: vec2<i32>) -> vec4<f32> {
+    return load_input_extended(coords, EDGE_MODE_NONE);
+}
+
+fn load_original(coords: vec2<i32>) -> vec4<f32> {
+    let rgba = textureLoad(original, coords, 0);
+    if config.original_premultiplied != 0u {
+        return rgba;
+    }
+    return premul_alpha(rgba);
+}
+
+// One-dimensional gaussian blur of `input` along `dir`, centered at `coords`.
+fn blur(coords: vec2<i32>, dir: vec2<i32>) -> vec4<f32> {
+    let edge_mode = config.edge_mode;
+    if config.radius == 0u {
+        return load_input_extended(coords, edge_mode);
+    }
+    let scale = -0.5 / (config.std_dev * config.std_dev);
+    var sum = load_input_extended(coords, edge_mode);
+    var total_weight = 1.0;
+    for (var i = 1u; i <= config.radius; i += 1u) {
+        let x = f32(i);
+        let weight = exp(x * x * scale);
+        let d = dir * i32(i);
+        sum += weight * (load_input_extended(coords - d, edge_mode)
+            + load_input_extended(coords + d, edge_mode));
+        total_weight += 2.0 * weight;
+    }
+    return sum / total_weight;
+}
+
+@compute @workgroup_size(16, 16)
+fn main(
+    @builtin(global_invocation_id) global_id: vec3<u32>,
+) {
+    let dims = textureDimensions(output);
+    if global_id.x >= dims.x || global_id.y >= dims.y {
+        return;
+    }
+    let coords = vec2<i32>(global_id.xy);
+    let shift = vec2<i32>(round(vec2(config.dx, config.dy)));
+    var rgba = vec4(0.0);
+    switch config.kind {
+        case FILTER_BLUR_H: {
+            rgba = blur(coords, vec2(1, 0));
+        }
+        case FILTER_BLUR_V: {
+            rgba = blur(coords, vec2(0, 1));
+        }
+        case FILTER_OFFSET: {
+            rgba = load_input(coords - shift);
+        }
+        case FILTER_FLOOD: {
+            rgba = config.color;
+        }
+        case FILTER_DROP_SHADOW: {
+            // `input` contains the horizontally blurred content, so we only need
+            // to finish the blur at the offset position. The shadow is shifted after
+            // blurring, so it doesn't extend beyond the layer at the shifted edges.
+            let shadow_coords = coords - shift;
+            var shadow = vec4(0.0);
+            if all(shadow_coords >= vec2(0)) && all(shadow_coords < vec2<i32>(dims)) {
+                shadow = config.color * blur(shadow_coords, vec2(0, 1)).a;
+            }
+            let fg = load_original(coords);
+            rgba = fg + shadow * (1.0 - fg.a);
+        }
+        default: {}
+    }
+    textureStore(output, coords, rgba);
+}
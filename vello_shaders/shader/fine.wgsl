@@ -30,6 +30,7 @@ const IMAGE_QUALITY_MEDIUM = 1u;
 const IMAGE_QUALITY_HIGH = 2u;
 
 const LUMINANCE_MASK_LAYER = 0x10000u;
+const ALPHA_MASK_LAYER = 0x20000u;
 
 @group(0) @binding(2)
 var<storage> ptcl: array<u32>;
@@ -1161,6 +1162,14 @@ fn main(
                         }
                         let luminance = clamp(svg_lum(unpremultiply(fg)) * fg.a, 0.0, 1.0);
                         rgba[i] = bg * luminance;
+                    } else if end_clip.blend == ALPHA_MASK_LAYER {
+                        // As for luminance masks, pixels outside of the mask layer's clip
+                        // are not affected by the mask.
+                        if area[i] == 0f {
+                            rgba[i] = bg;
+                            continue;
+                        }
+                        rgba[i] = bg * fg.a;
                     } else {
                         rgba[i] = blend_mix_compose(bg, fg, end_clip.blend);
                     }
@@ -0,0 +1,98 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::{BindType, ShaderInfo};
+use crate::types::hlsl::BindingIndex;
+use naga::back::hlsl as naga_hlsl;
+
+/// Translate `shader` to HLSL, targeting shader model 6.0.
+///
+/// The entry point of the generated shader is called `main`. All resources live in
+/// register space 0, see [`BindingIndexIterator`] for how registers are assigned.
+pub fn translate(shader: &ShaderInfo) -> Result<String, naga_hlsl::Error> {
+    let mut idx_iter = BindingIndexIterator::default();
+    let mut binding_map = naga_hlsl::BindingMap::default();
+    for resource in &shader.bindings {
+        let binding = naga::ResourceBinding {
+            group: resource.location.0,
+            binding: resource.location.1,
+        };
+        let register = match idx_iter.next(resource.ty) {
+            BindingIndex::ConstantBuffer(idx)
+            | BindingIndex::ShaderResource(idx)
+            | BindingIndex::UnorderedAccess(idx) => u32::from(idx),
+        };
+        binding_map.insert(
+            binding,
+            naga_hlsl::BindTarget {
+                space: 0,
+                register,
+                binding_array_size: None,
+                dynamic_storage_buffer_offsets_index: None,
+                restrict_indexing: false,
+            },
+        );
+    }
+    let options = naga_hlsl::Options {
+        shader_model: naga_hlsl::ShaderModel::V6_0,
+        binding_map,
+        fake_missing_bindings: false,
+        zero_initialize_workgroup_memory: true,
+        force_loop_bounding: true,
+        ..Default::default()
+    };
+    let pipeline_options = naga_hlsl::PipelineOptions {
+        entry_point: Some((naga::ShaderStage::Compute, "main".to_string())),
+    };
+    let mut source = String::new();
+    let mut writer = naga_hlsl::Writer::new(&mut source, &options, &pipeline_options);
+    let reflection = writer.write(&shader.module, &shader.module_info, None)?;
+    match reflection.entry_point_names.into_iter().next() {
+        Some(Ok(name)) if name == "main" => Ok(source),
+        Some(Ok(name)) => Err(naga_hlsl::Error::Custom(format!(
+            "entry point was renamed to `{name}`"
+        ))),
+        Some(Err(err)) => Err(naga_hlsl::Error::Custom(err.to_string())),
+        None => Err(naga_hlsl::Error::EntryPointNotFound(
+            naga::ShaderStage::Compute,
+            "main".to_string(),
+        )),
+    }
+}
+
+/// Assigns registers to the resources of a shader.
+///
+/// Uniforms are bound to `b` registers, read-only resources to `t` registers and
+/// read-write resources to `u` registers. Each register type is numbered separately,
+/// in the order in which the resources are declared.
+#[derive(Default)]
+pub struct BindingIndexIterator {
+    constant_buffer_idx: u8,
+    shader_resource_idx: u8,
+    unordered_access_idx: u8,
+}
+
+impl BindingIndexIterator {
+    pub fn next(&mut self, ty: BindType) -> BindingIndex {
+        match ty {
+            BindType::Uniform => {
+                let idx = self.constant_buffer_idx;
+                self.constant_buffer_idx += 1;
+                assert!(self.constant_buffer_idx > 0);
+                BindingIndex::ConstantBuffer(idx)
+            }
+            BindType::BufReadOnly | BindType::ImageRead => {
+                let idx = self.shader_resource_idx;
+                self.shader_resource_idx += 1;
+                assert!(self.shader_resource_idx > 0);
+                BindingIndex::ShaderResource(idx)
+            }
+            BindType::Buffer | BindType::Image => {
+                let idx = self.unordered_access_idx;
+                self.unordered_access_idx += 1;
+                assert!(self.unordered_access_idx > 0);
+                BindingIndex::UnorderedAccess(idx)
+            }
+        }
+    }
+}
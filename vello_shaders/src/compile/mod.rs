@@ -13,8 +13,12 @@ use thiserror::Error;
 pub mod permutations;
 pub mod preprocess;
 
+#[cfg(feature = "hlsl")]
+pub mod hlsl;
 #[cfg(feature = "msl")]
 pub mod msl;
+#[cfg(feature = "spirv")]
+pub mod spirv;
 
 use crate::types::{BindType, BindingInfo, WorkgroupBufferInfo};
 
@@ -0,0 +1,45 @@
+// Copyright 2026 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use super::ShaderInfo;
+use naga::back::spv as naga_spv;
+
+/// Translate `shader` to SPIR-V, targeting SPIR-V 1.0 (and therefore Vulkan 1.0).
+///
+/// Each resource is bound to descriptor set 0, using the same binding index as in the
+/// WGSL source.
+pub fn translate(shader: &ShaderInfo) -> Result<Vec<u32>, naga_spv::Error> {
+    let mut binding_map = naga_spv::BindingMap::default();
+    for resource in &shader.bindings {
+        binding_map.insert(
+            naga::ResourceBinding {
+                group: resource.location.0,
+                binding: resource.location.1,
+            },
+            naga_spv::BindingInfo {
+                descriptor_set: 0,
+                binding: resource.location.1,
+                binding_array_size: None,
+            },
+        );
+    }
+    let options = naga_spv::Options {
+        lang_version: (1, 0),
+        flags: naga_spv::WriterFlags::empty(),
+        fake_missing_bindings: false,
+        binding_map,
+        // Don't rely on `VK_KHR_zero_initialize_workgroup_memory`.
+        zero_initialize_workgroup_memory: naga_spv::ZeroInitializeWorkgroupMemoryMode::Polyfill,
+        force_loop_bounding: true,
+        ..Default::default()
+    };
+    naga_spv::write_vec(
+        &shader.module,
+        &shader.module_info,
+        &options,
+        Some(&naga_spv::PipelineOptions {
+            shader_stage: naga::ShaderStage::Compute,
+            entry_point: "main".to_string(),
+        }),
+    )
+}
@@ -15,6 +15,7 @@
 mod backdrop;
 mod bbox_clear;
 mod binning;
+mod blend;
 mod clip_leaf;
 mod clip_reduce;
 mod coarse;
@@ -0,0 +1,212 @@
+// Copyright 2022 the Vello Authors
+// SPDX-License-Identifier: Apache-2.0 OR MIT OR Unlicense
+
+//! Color mixing and composition, ported from `shared/blend.wgsl`.
+
+// Color mixing modes
+
+const MIX_NORMAL: u32 = 0;
+const MIX_MULTIPLY: u32 = 1;
+const MIX_SCREEN: u32 = 2;
+const MIX_OVERLAY: u32 = 3;
+const MIX_DARKEN: u32 = 4;
+const MIX_LIGHTEN: u32 = 5;
+const MIX_COLOR_DODGE: u32 = 6;
+const MIX_COLOR_BURN: u32 = 7;
+const MIX_HARD_LIGHT: u32 = 8;
+const MIX_SOFT_LIGHT: u32 = 9;
+const MIX_DIFFERENCE: u32 = 10;
+const MIX_EXCLUSION: u32 = 11;
+const MIX_HUE: u32 = 12;
+const MIX_SATURATION: u32 = 13;
+const MIX_COLOR: u32 = 14;
+const MIX_LUMINOSITY: u32 = 15;
+
+fn screen(cb: f32, cs: f32) -> f32 {
+    cb + cs - (cb * cs)
+}
+
+fn color_dodge(cb: f32, cs: f32) -> f32 {
+    if cb == 0.0 {
+        0.0
+    } else if cs == 1.0 {
+        1.0
+    } else {
+        (cb / (1.0 - cs)).min(1.0)
+    }
+}
+
+fn color_burn(cb: f32, cs: f32) -> f32 {
+    if cb == 1.0 {
+        1.0
+    } else if cs == 0.0 {
+        0.0
+    } else {
+        1.0 - ((1.0 - cb) / cs).min(1.0)
+    }
+}
+
+fn hard_light(cb: f32, cs: f32) -> f32 {
+    if cs <= 0.5 {
+        cb * 2.0 * cs
+    } else {
+        screen(cb, 2.0 * cs - 1.0)
+    }
+}
+
+fn soft_light(cb: f32, cs: f32) -> f32 {
+    let d = if cb <= 0.25 {
+        ((16.0 * cb - 12.0) * cb + 4.0) * cb
+    } else {
+        cb.sqrt()
+    };
+    if cs <= 0.5 {
+        cb - (1.0 - 2.0 * cs) * cb * (1.0 - cb)
+    } else {
+        cb + (2.0 * cs - 1.0) * (d - cb)
+    }
+}
+
+fn sat(c: [f32; 3]) -> f32 {
+    c[0].max(c[1].max(c[2])) - c[0].min(c[1].min(c[2]))
+}
+
+fn lum(c: [f32; 3]) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn clip_color(mut c: [f32; 3]) -> [f32; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1].min(c[2]));
+    let x = c[0].max(c[1].max(c[2]));
+    if n < 0.0 {
+        c = c.map(|c| l + (((c - l) * l) / (l - n)));
+    }
+    if x > 1.0 {
+        c = c.map(|c| l + (((c - l) * (1.0 - l)) / (x - l)));
+    }
+    c
+}
+
+fn set_lum(c: [f32; 3], l: f32) -> [f32; 3] {
+    let d = l - lum(c);
+    clip_color(c.map(|c| c + d))
+}
+
+fn set_sat(c: [f32; 3], s: f32) -> [f32; 3] {
+    // Sort the channel indices by their value, to find the minimum, middle and maximum.
+    let mut order = [0, 1, 2];
+    order.sort_by(|&a, &b| c[a].total_cmp(&c[b]));
+    let [min, mid, max] = order;
+    let mut result = [0.0; 3];
+    if c[max] > c[min] {
+        result[mid] = ((c[mid] - c[min]) * s) / (c[max] - c[min]);
+        result[max] = s;
+    }
+    result
+}
+
+/// Blends two RGB colors together. The colors are assumed to be in sRGB
+/// color space, and this function does not take alpha into account.
+fn blend_mix(cb: [f32; 3], cs: [f32; 3], mode: u32) -> [f32; 3] {
+    let per_channel = |f: fn(f32, f32) -> f32| [0, 1, 2].map(|i| f(cb[i], cs[i]));
+    match mode {
+        MIX_MULTIPLY => per_channel(|cb, cs| cb * cs),
+        MIX_SCREEN => per_channel(screen),
+        MIX_OVERLAY => per_channel(|cb, cs| hard_light(cs, cb)),
+        MIX_DARKEN => per_channel(f32::min),
+        MIX_LIGHTEN => per_channel(f32::max),
+        MIX_COLOR_DODGE => per_channel(color_dodge),
+        MIX_COLOR_BURN => per_channel(color_burn),
+        MIX_HARD_LIGHT => per_channel(hard_light),
+        MIX_SOFT_LIGHT => per_channel(soft_light),
+        MIX_DIFFERENCE => per_channel(|cb, cs| (cb - cs).abs()),
+        MIX_EXCLUSION => per_channel(|cb, cs| cb + cs - 2.0 * cb * cs),
+        MIX_HUE => set_lum(set_sat(cs, sat(cb)), lum(cb)),
+        MIX_SATURATION => set_lum(set_sat(cb, sat(cs)), lum(cb)),
+        MIX_COLOR => set_lum(cs, lum(cb)),
+        MIX_LUMINOSITY => set_lum(cb, lum(cs)),
+        _ => cs,
+    }
+}
+
+// Composition modes
+
+const COMPOSE_CLEAR: u32 = 0;
+const COMPOSE_COPY: u32 = 1;
+const COMPOSE_DEST: u32 = 2;
+const COMPOSE_SRC_OVER: u32 = 3;
+const COMPOSE_DEST_OVER: u32 = 4;
+const COMPOSE_SRC_IN: u32 = 5;
+const COMPOSE_DEST_IN: u32 = 6;
+const COMPOSE_SRC_OUT: u32 = 7;
+const COMPOSE_DEST_OUT: u32 = 8;
+const COMPOSE_SRC_ATOP: u32 = 9;
+const COMPOSE_DEST_ATOP: u32 = 10;
+const COMPOSE_XOR: u32 = 11;
+const COMPOSE_PLUS: u32 = 12;
+const COMPOSE_PLUS_LIGHTER: u32 = 13;
+
+/// Apply general compositing operation.
+/// Inputs are separated colors and alpha, output is premultiplied.
+fn blend_compose(cb: [f32; 3], cs: [f32; 3], ab: f32, as_: f32, compose_mode: u32) -> [f32; 4] {
+    let (fa, fb) = match compose_mode {
+        COMPOSE_CLEAR => (0.0, 0.0),
+        COMPOSE_COPY => (1.0, 0.0),
+        COMPOSE_DEST => (0.0, 1.0),
+        COMPOSE_SRC_OVER => (1.0, 1.0 - as_),
+        COMPOSE_DEST_OVER => (1.0 - ab, 1.0),
+        COMPOSE_SRC_IN => (ab, 0.0),
+        COMPOSE_DEST_IN => (0.0, as_),
+        COMPOSE_SRC_OUT => (1.0 - ab, 0.0),
+        COMPOSE_DEST_OUT => (0.0, 1.0 - as_),
+        COMPOSE_SRC_ATOP => (ab, 1.0 - as_),
+        COMPOSE_DEST_ATOP => (1.0 - ab, as_),
+        COMPOSE_XOR => (1.0 - ab, 1.0 - as_),
+        COMPOSE_PLUS => (1.0, 1.0),
+        COMPOSE_PLUS_LIGHTER => {
+            let co = [0, 1, 2].map(|i| (as_ * cs[i] + ab * cb[i]).min(1.0));
+            return [co[0], co[1], co[2], (as_ + ab).min(1.0)];
+        }
+        _ => (0.0, 0.0),
+    };
+    let as_fa = as_ * fa;
+    let ab_fb = ab * fb;
+    let co = [0, 1, 2].map(|i| as_fa * cs[i] + ab_fb * cb[i]);
+    // Modes like COMPOSE_PLUS can generate alpha > 1.0, so clamp.
+    [co[0], co[1], co[2], (as_fa + ab_fb).min(1.0)]
+}
+
+fn unpremultiply(color: [f32; 4]) -> [f32; 3] {
+    const EPSILON: f32 = 1e-15;
+    // Max with a small epsilon to avoid NaNs.
+    let inv_alpha = 1.0 / color[3].max(EPSILON);
+    [
+        color[0] * inv_alpha,
+        color[1] * inv_alpha,
+        color[2] * inv_alpha,
+    ]
+}
+
+/// Apply color mixing and composition. Both input and output colors are
+/// premultiplied RGB.
+pub(super) fn blend_mix_compose(backdrop: [f32; 4], src: [f32; 4], mode: u32) -> [f32; 4] {
+    const BLEND_DEFAULT: u32 = (MIX_NORMAL << 8) | COMPOSE_SRC_OVER;
+    if (mode & 0x7fff) == BLEND_DEFAULT {
+        // Both normal+src_over blend and clip case
+        return [0, 1, 2, 3].map(|i| backdrop[i] * (1.0 - src[3]) + src[i]);
+    }
+    // Un-premultiply colors for blending.
+    let cs = unpremultiply(src);
+    let cb = unpremultiply(backdrop);
+    let mix_mode = mode >> 8;
+    let mixed = blend_mix(cb, cs, mix_mode);
+    let cs = [0, 1, 2].map(|i| cs[i] + (mixed[i] - cs[i]) * backdrop[3]);
+    let compose_mode = mode & 0xff;
+    if compose_mode == COMPOSE_SRC_OVER {
+        let co = [0, 1, 2].map(|i| backdrop[i] + (cs[i] - backdrop[i]) * src[3]);
+        [co[0], co[1], co[2], src[3] + backdrop[3] * (1.0 - src[3])]
+    } else {
+        blend_compose(cb, cs, backdrop[3], src[3], compose_mode)
+    }
+}
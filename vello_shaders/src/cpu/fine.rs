@@ -1,9 +1,13 @@
 // Copyright 2023 the Vello Authors
 // SPDX-License-Identifier: Apache-2.0 OR MIT OR Unlicense
 
-use vello_encoding::{ConfigUniform, PathSegment, Tile};
+use vello_encoding::{ConfigUniform, DrawBeginClip, PathSegment, Tile};
 
-use super::{CMD_COLOR, CMD_END, CMD_FILL, CMD_JUMP, CMD_SOLID, CpuTexture, PTCL_INITIAL_ALLOC};
+use super::blend::blend_mix_compose;
+use super::{
+    CMD_BEGIN_CLIP, CMD_COLOR, CMD_END, CMD_END_CLIP, CMD_FILL, CMD_JUMP, CMD_SOLID, CpuTexture,
+    PTCL_INITIAL_ALLOC,
+};
 
 // These should also move into a common area
 const TILE_WIDTH: usize = 16;
@@ -31,6 +35,23 @@ fn read_fill(ptcl: &[u32], offset: u32) -> CmdFill {
     }
 }
 
+struct CmdEndClip {
+    blend: u32,
+    alpha: f32,
+}
+
+fn read_end_clip(ptcl: &[u32], offset: u32) -> CmdEndClip {
+    let blend = ptcl[(offset + 1) as usize];
+    let alpha = f32::from_bits(ptcl[(offset + 2) as usize]);
+    CmdEndClip { blend, alpha }
+}
+
+fn svg_lum(c: [f32; 4]) -> f32 {
+    // Max with a small epsilon to avoid NaNs.
+    let inv_alpha = 1.0 / c[3].max(1e-15);
+    (0.2125 * c[0] + 0.7154 * c[1] + 0.0721 * c[2]) * inv_alpha
+}
+
 fn unpack4x8unorm(x: u32) -> [f32; 4] {
     let mut result = [0.0; 4];
     for i in 0..4 {
@@ -117,13 +138,13 @@ fn fine_main(
     ptcl: &[u32],
     info: &[u32],
     // TODO: image texture resources
-    // TODO: masks?
 ) {
     let width_in_tiles = config.width_in_tiles;
     let height_in_tiles = config.height_in_tiles;
     let n_tiles = width_in_tiles * height_in_tiles;
     let mut area = vec![0.0_f32; TILE_SIZE];
     let mut rgba = vec![[0.0_f32; 4]; TILE_SIZE];
+    let mut blend_stack: Vec<Vec<u32>> = vec![];
     for tile_ix in 0..n_tiles {
         rgba.fill([0.0; 4]);
         area.fill(0.0);
@@ -153,7 +174,6 @@ fn fine_main(
                 CMD_COLOR => {
                     let color = read_color(ptcl, cmd_ix);
                     let fg = unpack4x8unorm(color);
-                    let fg = [fg[3], fg[2], fg[1], fg[0]];
                     for i in 0..TILE_SIZE {
                         let ai = area[i];
                         let fg_i = [fg[0] * ai, fg[1] * ai, fg[2] * ai, fg[3] * ai];
@@ -163,6 +183,37 @@ fn fine_main(
                     }
                     cmd_ix += 2;
                 }
+                CMD_BEGIN_CLIP => {
+                    // Like the GPU version, the blend stack is stored at 8 bits per channel.
+                    blend_stack.push(rgba.iter().map(|c| pack4x8unorm(*c)).collect::<Vec<_>>());
+                    rgba.fill([0.0; 4]);
+                    cmd_ix += 1;
+                }
+                CMD_END_CLIP => {
+                    let end_clip = read_end_clip(ptcl, cmd_ix);
+                    let bg_rgba = blend_stack.pop().unwrap();
+                    for i in 0..TILE_SIZE {
+                        let bg = unpack4x8unorm(bg_rgba[i]);
+                        let scale = area[i] * end_clip.alpha;
+                        let fg = rgba[i].map(|c| c * scale);
+                        rgba[i] = match end_clip.blend {
+                            // Pixels outside of the mask layer's clip are not affected by the mask.
+                            DrawBeginClip::LUMINANCE_MASK_BLEND_MODE
+                            | DrawBeginClip::ALPHA_MASK_BLEND_MODE
+                                if area[i] == 0.0 =>
+                            {
+                                bg
+                            }
+                            DrawBeginClip::LUMINANCE_MASK_BLEND_MODE => {
+                                let luminance = (svg_lum(fg) * fg[3]).clamp(0.0, 1.0);
+                                bg.map(|c| c * luminance)
+                            }
+                            DrawBeginClip::ALPHA_MASK_BLEND_MODE => bg.map(|c| c * fg[3]),
+                            _ => blend_mix_compose(bg, fg, end_clip.blend),
+                        };
+                    }
+                    cmd_ix += 3;
+                }
                 CMD_JUMP => {
                     cmd_ix = ptcl[(cmd_ix + 1) as usize];
                 }
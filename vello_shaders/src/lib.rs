@@ -7,7 +7,7 @@
 //! (such as resource management and command encoding) up to the client.
 //!
 //! The shaders can be pre-compiled to any target shading language at build time based on
-//! feature flags. Currently only WGSL and Metal Shading Language are supported.
+//! feature flags. Currently WGSL, Metal Shading Language, SPIR-V and HLSL are supported.
 //!
 //! Your first choice should be to use the build time generated [`SHADERS`].
 //!
@@ -49,6 +49,8 @@ pub mod compile;
 #[cfg(feature = "cpu")]
 pub mod cpu;
 
+#[cfg(feature = "hlsl")]
+pub use types::hlsl;
 #[cfg(feature = "msl")]
 pub use types::msl;
 pub use types::{BindType, BindingInfo, WorkgroupBufferInfo};
@@ -67,6 +69,12 @@ pub struct ComputeShader<'a> {
 
     #[cfg(feature = "msl")]
     pub msl: MslSource<'a>,
+
+    #[cfg(feature = "spirv")]
+    pub spirv: SpirvSource<'a>,
+
+    #[cfg(feature = "hlsl")]
+    pub hlsl: HlslSource<'a>,
 }
 
 #[cfg(feature = "wgsl")]
@@ -136,6 +144,60 @@ pub struct MslSource<'a> {
     pub binding_indices: Cow<'a, [msl::BindingIndex]>,
 }
 
+#[cfg(feature = "spirv")]
+#[derive(Clone, Debug)]
+pub struct SpirvSource<'a> {
+    /// The SPIR-V module, as a sequence of 32-bit words.
+    ///
+    /// The module targets SPIR-V 1.0, and its compute entry point is called `main`.
+    pub code: Cow<'a, [u32]>,
+
+    /// Contains the binding index of each resource listed in [`ComputeShader::bindings`].
+    /// This is guaranteed to have the same element count as `ComputeShader::bindings`.
+    ///
+    /// As in WGSL, each index corresponds to the value of the corresponding `@binding(..)`
+    /// declaration in the shader source. The descriptor set is always 0.
+    pub binding_indices: Cow<'a, [u8]>,
+}
+
+#[cfg(feature = "hlsl")]
+#[derive(Clone, Debug)]
+pub struct HlslSource<'a> {
+    /// The HLSL source, targeting shader model 6.0. The compute entry point is called `main`.
+    pub code: Cow<'a, str>,
+
+    /// Contains the register of each resource listed in [`ComputeShader::bindings`].
+    /// This is guaranteed to have the same element count as `ComputeShader::bindings`.
+    ///
+    /// In HLSL, each index is scoped to the register type of the corresponding resource,
+    /// and the register space is always 0.
+    ///
+    /// Example:
+    /// --------
+    /// ```wgsl
+    /// // An unused binding (i.e. declaration is not reachable from the entry-point)
+    /// @group(0) @binding(0) var<uniform> foo: Foo;
+    ///
+    /// // Used bindings:
+    /// @group(0) @binding(1) var<storage> buffer: Buffer;
+    /// @group(0) @binding(2) var tex: texture_2d<f32>;
+    /// ```
+    /// This results in the following bindings:
+    /// ```rust,ignore
+    ///   bindings: [BindType::BufReadOnly, BindType::ImageRead],
+    ///   // ...
+    ///   hlsl: HlslSource {
+    ///       code: /* ... */,
+    ///       // In HLSL these would be declared as `register(t0)` and `register(t1)`.
+    ///       binding_indices: [
+    ///           hlsl::BindingIndex::ShaderResource(0),
+    ///           hlsl::BindingIndex::ShaderResource(1),
+    ///       ],
+    ///   },
+    /// ```
+    pub binding_indices: Cow<'a, [hlsl::BindingIndex]>,
+}
+
 include!(concat!(env!("OUT_DIR"), "/shaders.rs"));
 
 pub use generated::SHADERS;
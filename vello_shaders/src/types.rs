@@ -58,3 +58,29 @@ pub mod msl {
         }
     }
 }
+
+#[cfg(feature = "hlsl")]
+pub mod hlsl {
+    use std::fmt;
+
+    /// The register which a resource is bound to, in register space 0.
+    #[derive(Clone)]
+    pub enum BindingIndex {
+        /// A `b` register, used for uniforms.
+        ConstantBuffer(u8),
+        /// A `t` register, used for read-only buffers and images.
+        ShaderResource(u8),
+        /// A `u` register, used for read-write buffers and images.
+        UnorderedAccess(u8),
+    }
+
+    impl fmt::Debug for BindingIndex {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match *self {
+                Self::ConstantBuffer(i) => write!(f, "hlsl::BindingIndex::ConstantBuffer({i})"),
+                Self::ShaderResource(i) => write!(f, "hlsl::BindingIndex::ShaderResource({i})"),
+                Self::UnorderedAccess(i) => write!(f, "hlsl::BindingIndex::UnorderedAccess({i})"),
+            }
+        }
+    }
+}
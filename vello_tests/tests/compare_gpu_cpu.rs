@@ -107,3 +107,11 @@ fn compare_large_bin_count() {
     let params = TestParams::new("compare_large_bin_count", 8192, 2304);
     compare_test_scene(test_scene, params);
 }
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn compare_filter_layers() {
+    let test_scene = test_scenes::filter_layer_chains();
+    let params = TestParams::new("compare_filter_layers", 400, 400);
+    compare_test_scene(test_scene, params);
+}
@@ -171,3 +171,27 @@ fn image_luminance_mask() {
     let params = TestParams::new("image_luminance_mask", 350, 250);
     snapshot_test_scene(test_scene, params);
 }
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn snapshot_mask_layers() {
+    let test_scene = test_scenes::mask_layers();
+    let params = TestParams::new("mask_layers", 400, 200);
+    snapshot_test_scene(test_scene, params);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn snapshot_filter_layers() {
+    let test_scene = test_scenes::filter_layers();
+    let params = TestParams::new("filter_layers", 400, 400);
+    snapshot_test_scene(test_scene, params);
+}
+
+#[test]
+#[cfg_attr(skip_gpu_tests, ignore)]
+fn snapshot_filter_layer_chains() {
+    let test_scene = test_scenes::filter_layer_chains();
+    let params = TestParams::new("filter_layer_chains", 400, 400);
+    snapshot_test_scene(test_scene, params);
+}